@@ -18,6 +18,9 @@ use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
 use deno_core::ModuleSpecifier;
+use deno_runtime::permissions::ModuleScopeOptions;
+use deno_runtime::permissions::PermissionsOptions;
+use deno_runtime::permissions::ROOT_SCOPE;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -461,6 +464,95 @@ pub enum LockConfig {
   PathBuf(PathBuf),
 }
 
+/// A permission of a module scope in the "permissions" configuration. `true`
+/// keeps what the command line flags grant, `false` or an empty list revokes
+/// the permission and a list only keeps the given entries.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum PermissionConfig {
+  Bool(bool),
+  List(Vec<String>),
+}
+
+impl PermissionConfig {
+  fn into_allowlist<T>(self, map: impl Fn(String) -> T) -> Option<Vec<T>> {
+    match self {
+      PermissionConfig::Bool(true) => Some(vec![]),
+      PermissionConfig::Bool(false) => None,
+      PermissionConfig::List(list) if list.is_empty() => None,
+      PermissionConfig::List(list) => Some(list.into_iter().map(map).collect()),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SerializedPermissionScopeConfig {
+  pub env: Option<PermissionConfig>,
+  pub ffi: Option<PermissionConfig>,
+  pub hrtime: Option<bool>,
+  pub net: Option<PermissionConfig>,
+  pub read: Option<PermissionConfig>,
+  pub run: Option<PermissionConfig>,
+  pub sys: Option<PermissionConfig>,
+  pub write: Option<PermissionConfig>,
+}
+
+impl SerializedPermissionScopeConfig {
+  /// Permissions that aren't configured are inherited from `parent`.
+  pub fn into_resolved(
+    self,
+    parent: &PermissionsOptions,
+    config_dir: Option<&Path>,
+  ) -> PermissionsOptions {
+    let path = |raw: String| resolve_permission_path(config_dir, raw);
+    let string = |raw: String| raw;
+    PermissionsOptions {
+      allow_env: self
+        .env
+        .map_or_else(|| parent.allow_env.clone(), |c| c.into_allowlist(string)),
+      allow_hrtime: self.hrtime.unwrap_or(parent.allow_hrtime),
+      allow_net: self
+        .net
+        .map_or_else(|| parent.allow_net.clone(), |c| c.into_allowlist(string)),
+      allow_ffi: self
+        .ffi
+        .map_or_else(|| parent.allow_ffi.clone(), |c| c.into_allowlist(path)),
+      allow_read: self
+        .read
+        .map_or_else(|| parent.allow_read.clone(), |c| c.into_allowlist(path)),
+      allow_run: self
+        .run
+        .map_or_else(|| parent.allow_run.clone(), |c| c.into_allowlist(string)),
+      allow_sys: self
+        .sys
+        .map_or_else(|| parent.allow_sys.clone(), |c| c.into_allowlist(string)),
+      allow_write: self
+        .write
+        .map_or_else(|| parent.allow_write.clone(), |c| c.into_allowlist(path)),
+      ..Default::default()
+    }
+  }
+}
+
+/// Resolves a relative path of a "permissions" scope against the directory
+/// of the config file, keeping a leading `!` and paths that start with an
+/// environment variable.
+fn resolve_permission_path(config_dir: Option<&Path>, raw: String) -> PathBuf {
+  let (excluded, path) = match raw.strip_prefix('!') {
+    Some(path) => ("!", path),
+    None => ("", raw.as_str()),
+  };
+  match config_dir {
+    Some(config_dir)
+      if !path.starts_with('$') && Path::new(path).is_relative() =>
+    {
+      PathBuf::from(format!("{excluded}{}", config_dir.join(path).display()))
+    }
+    _ => PathBuf::from(raw),
+  }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigFileJson {
@@ -474,6 +566,8 @@ pub struct ConfigFileJson {
   pub test: Option<Value>,
   pub bench: Option<Value>,
   pub lock: Option<Value>,
+  pub permissions: Option<Value>,
+  pub conditions: Option<Value>,
 }
 
 #[derive(Clone, Debug)]
@@ -822,6 +916,82 @@ impl ConfigFile {
     }
   }
 
+  /// Resolves the "permissions" configuration, which maps module specifier
+  /// prefixes (or `"*"` for all modules) to the permissions the modules in
+  /// that scope are restricted to. Relative prefixes are resolved against the
+  /// config file. Permissions that a scope doesn't mention are inherited from
+  /// the closest enclosing scope.
+  pub fn to_permission_scopes(
+    &self,
+  ) -> Result<Vec<ModuleScopeOptions>, AnyError> {
+    let config = match self.json.permissions.clone() {
+      Some(config) => config,
+      None => return Ok(Vec::new()),
+    };
+    let scopes: BTreeMap<String, SerializedPermissionScopeConfig> =
+      serde_json::from_value(config)
+        .context("Failed to parse \"permissions\" configuration")?;
+    let config_dir_specifier = specifier_parent(&self.specifier);
+    let config_dir = specifier_to_file_path(&config_dir_specifier).ok();
+
+    let mut scopes = scopes
+      .into_iter()
+      .map(|(key, scope)| {
+        if key == ROOT_SCOPE {
+          return Ok((key, scope));
+        }
+        let prefix = config_dir_specifier.join(&key).with_context(|| {
+          format!("Invalid scope \"{key}\" in \"permissions\" configuration")
+        })?;
+        Ok((prefix.to_string(), scope))
+      })
+      .collect::<Result<Vec<_>, AnyError>>()?;
+    // Enclosing scopes have to be resolved first.
+    scopes.sort_by_key(|(prefix, _)| {
+      if prefix == ROOT_SCOPE {
+        0
+      } else {
+        prefix.len()
+      }
+    });
+
+    let unrestricted = PermissionsOptions {
+      allow_env: Some(vec![]),
+      allow_hrtime: true,
+      allow_net: Some(vec![]),
+      allow_ffi: Some(vec![]),
+      allow_read: Some(vec![]),
+      allow_run: Some(vec![]),
+      allow_sys: Some(vec![]),
+      allow_write: Some(vec![]),
+      ..Default::default()
+    };
+    let mut resolved: Vec<ModuleScopeOptions> = Vec::new();
+    for (prefix, scope) in scopes {
+      let parent = resolved
+        .iter()
+        .rev()
+        .find(|s| s.prefix == ROOT_SCOPE || prefix.starts_with(&s.prefix))
+        .map_or(&unrestricted, |s| &s.permissions);
+      let permissions = scope.into_resolved(parent, config_dir.as_deref());
+      resolved.push(ModuleScopeOptions {
+        prefix,
+        permissions,
+      });
+    }
+    Ok(resolved)
+  }
+
+  /// Returns the custom conditions to resolve the "exports" and "imports" of
+  /// npm packages with.
+  pub fn to_conditions(&self) -> Result<Vec<String>, AnyError> {
+    match self.json.conditions.clone() {
+      Some(config) => serde_json::from_value(config)
+        .context("Failed to parse \"conditions\" configuration"),
+      None => Ok(Vec::new()),
+    }
+  }
+
   pub fn to_lock_config(&self) -> Result<Option<LockConfig>, AnyError> {
     if let Some(config) = self.json.lock.clone() {
       let lock_config: LockConfig = serde_json::from_value(config)
@@ -1130,6 +1300,59 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_parse_config_permissions() {
+    let config_text = r#"{
+      "permissions": {
+        "*": { "write": false, "net": ["deno.land"] },
+        "./app/": { "write": true },
+        "https://deno.land/x/somelib/": { "net": ["example.com"] },
+        "https://deno.land/x/somelib/internal/": { "env": [] }
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let scopes = config_file.to_permission_scopes().unwrap();
+    let prefixes = scopes.iter().map(|s| s.prefix.as_str()).collect::<Vec<_>>();
+    assert_eq!(
+      prefixes,
+      vec![
+        "*",
+        "file:///deno/app/",
+        "https://deno.land/x/somelib/",
+        "https://deno.land/x/somelib/internal/",
+      ]
+    );
+
+    let root = &scopes[0].permissions;
+    assert_eq!(root.allow_write, None);
+    assert_eq!(root.allow_net, Some(vec!["deno.land".to_string()]));
+    assert_eq!(root.allow_read, Some(vec![]));
+    assert!(root.allow_hrtime);
+
+    let app = &scopes[1].permissions;
+    assert_eq!(app.allow_write, Some(vec![]));
+    assert_eq!(app.allow_net, Some(vec!["deno.land".to_string()]));
+
+    let somelib = &scopes[2].permissions;
+    assert_eq!(somelib.allow_write, None);
+    assert_eq!(somelib.allow_net, Some(vec!["example.com".to_string()]));
+
+    let internal = &scopes[3].permissions;
+    assert_eq!(internal.allow_env, None);
+    assert_eq!(internal.allow_net, Some(vec!["example.com".to_string()]));
+  }
+
+  #[test]
+  fn test_parse_config_permissions_invalid() {
+    let config_text = r#"{ "permissions": { "*": { "unknown": true } } }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert!(config_file.to_permission_scopes().is_err());
+  }
+
   #[test]
   fn test_parse_config_with_empty_file() {
     let config_text = "";
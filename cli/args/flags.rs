@@ -1,6 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use clap::Arg;
+use clap::ArgGroup;
 use clap::ArgMatches;
 use clap::ColorChoice;
 use clap::Command;
@@ -155,6 +156,13 @@ pub struct InstallFlags {
   pub force: bool,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JupyterFlags {
+  pub install: bool,
+  pub kernel: bool,
+  pub conn_file: Option<PathBuf>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UninstallFlags {
   pub name: String,
@@ -172,6 +180,15 @@ pub struct LintFlags {
   pub compact: bool,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PermissionsFlags {
+  List,
+  Revoke {
+    name: Option<String>,
+    values: Vec<String>,
+  },
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ReplFlags {
   pub eval_files: Option<Vec<String>>,
@@ -240,9 +257,11 @@ pub enum DenoSubcommand {
   Init(InitFlags),
   Info(InfoFlags),
   Install(InstallFlags),
+  Jupyter(JupyterFlags),
   Uninstall(UninstallFlags),
   Lsp,
   Lint(LintFlags),
+  Permissions(PermissionsFlags),
   Repl(ReplFlags),
   Run(RunFlags),
   Task(TaskFlags),
@@ -314,6 +333,7 @@ pub struct Flags {
   pub allow_hrtime: bool,
   pub allow_net: Option<Vec<String>>,
   pub allow_ffi: Option<Vec<PathBuf>>,
+  pub allow_import: Option<Vec<String>>,
   pub allow_read: Option<Vec<PathBuf>>,
   pub allow_run: Option<Vec<String>>,
   pub allow_sys: Option<Vec<String>>,
@@ -326,11 +346,16 @@ pub struct Flags {
   pub cache_path: Option<PathBuf>,
   pub cached_only: bool,
   pub type_check_mode: TypeCheckMode,
+  pub conditions: Vec<String>,
   pub config_flag: ConfigFlag,
   pub node_modules_dir: Option<bool>,
   pub coverage_dir: Option<String>,
+  pub cpu_prof_dir: Option<String>,
   pub enable_testing_features: bool,
+  pub heap_snapshot_near_limit_dir: Option<String>,
+  pub heap_snapshot_on_exit_dir: Option<String>,
   pub ignore: Vec<PathBuf>,
+  pub import: Vec<String>,
   pub import_map_path: Option<String>,
   pub inspect_brk: Option<SocketAddr>,
   pub inspect_wait: Option<SocketAddr>,
@@ -339,17 +364,21 @@ pub struct Flags {
   pub lock_write: bool,
   pub lock: Option<PathBuf>,
   pub log_level: Option<Level>,
+  pub max_cpu_time: Option<u64>,
+  pub max_heap_size: Option<usize>,
   pub no_remote: bool,
   pub no_lock: bool,
   pub no_npm: bool,
   pub no_prompt: bool,
   pub reload: bool,
   pub seed: Option<u64>,
+  pub trace_ops_timeline: Option<String>,
   pub unstable: bool,
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
   pub v8_flags: Vec<String>,
   pub version: bool,
   pub watch: Option<Vec<PathBuf>>,
+  pub watch_hmr: bool,
   pub no_clear_screen: bool,
 }
 
@@ -463,6 +492,17 @@ impl Flags {
       _ => {}
     }
 
+    match &self.allow_import {
+      Some(import_allowlist) if import_allowlist.is_empty() => {
+        args.push("--allow-import".to_string());
+      }
+      Some(import_allowlist) => {
+        let s = format!("--allow-import={}", import_allowlist.join(","));
+        args.push(s);
+      }
+      _ => {}
+    }
+
     if self.allow_hrtime {
       args.push("--allow-hrtime".to_string());
     }
@@ -535,6 +575,7 @@ impl Flags {
       || self.allow_hrtime
       || self.allow_env.is_some()
       || self.allow_ffi.is_some()
+      || self.allow_import.is_some()
       || self.allow_net.is_some()
       || self.allow_read.is_some()
       || self.allow_run.is_some()
@@ -548,6 +589,7 @@ impl Flags {
         || arg == "--allow-hrtime"
         || arg.starts_with("--allow-env")
         || arg.starts_with("--allow-ffi")
+        || arg.starts_with("--allow-import")
         || arg.starts_with("--allow-net")
         || arg.starts_with("--allow-read")
         || arg.starts_with("--allow-run")
@@ -647,7 +689,9 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::Result<Flags> {
     Some(("init", m)) => init_parse(&mut flags, m),
     Some(("info", m)) => info_parse(&mut flags, m),
     Some(("install", m)) => install_parse(&mut flags, m),
+    Some(("jupyter", m)) => jupyter_parse(&mut flags, m),
     Some(("lint", m)) => lint_parse(&mut flags, m),
+    Some(("permissions", m)) => permissions_parse(&mut flags, m),
     Some(("lsp", m)) => lsp_parse(&mut flags, m),
     Some(("repl", m)) => repl_parse(&mut flags, m),
     Some(("run", m)) => run_parse(&mut flags, m),
@@ -680,6 +724,7 @@ fn handle_repl_flags(flags: &mut Flags, repl_flags: ReplFlags) {
     flags.allow_sys = Some(vec![]);
     flags.allow_write = Some(vec![]);
     flags.allow_ffi = Some(vec![]);
+    flags.allow_import = Some(vec![]);
     flags.allow_hrtime = true;
   }
   flags.subcommand = DenoSubcommand::Repl(repl_flags);
@@ -728,9 +773,11 @@ fn clap_root(version: &str) -> Command {
     .subcommand(init_subcommand())
     .subcommand(info_subcommand())
     .subcommand(install_subcommand())
+    .subcommand(jupyter_subcommand())
     .subcommand(uninstall_subcommand())
     .subcommand(lsp_subcommand())
     .subcommand(lint_subcommand())
+    .subcommand(permissions_subcommand())
     .subcommand(repl_subcommand())
     .subcommand(run_subcommand())
     .subcommand(task_subcommand())
@@ -1323,6 +1370,7 @@ TypeScript compiler cache: Subdirectory containing TS compiler output.",
     .arg(config_arg())
     .arg(import_map_arg())
     .arg(local_npm_arg())
+    .arg(conditions_arg())
     .arg(
       Arg::new("json")
         .long("json")
@@ -1387,6 +1435,45 @@ The installation root is determined, in order of precedence:
 These must be added to the path manually if required.")
 }
 
+fn jupyter_subcommand<'a>() -> Command<'a> {
+  runtime_args(Command::new("jupyter"), true, false)
+    .arg(
+      Arg::new("install")
+        .long("install")
+        .help("Install the kernelspec so Jupyter can start Deno")
+        .conflicts_with("kernel"),
+    )
+    .arg(
+      Arg::new("kernel")
+        .long("kernel")
+        .help("Start the kernel")
+        .conflicts_with("install")
+        .requires("conn"),
+    )
+    .arg(
+      Arg::new("conn")
+        .long("conn")
+        .help("Path to JSON file describing connection parameters, provided by Jupyter")
+        .takes_value(true)
+        .requires("kernel")
+        .value_hint(ValueHint::FilePath),
+    )
+    .about("Deno kernel for Jupyter notebooks")
+    .long_about(
+      "Deno kernel for Jupyter notebooks.
+
+Install the kernelspec so Jupyter can find Deno:
+
+  deno jupyter --install
+
+Without flags, checks whether the kernelspec is installed.
+
+The kernel is started by Jupyter with '--kernel --conn <FILE>'. Cells can't
+prompt for permissions, grant them by adding permission flags like
+'--allow-net' to the \"argv\" of the installed kernel.json.",
+    )
+}
+
 fn uninstall_subcommand<'a>() -> Command<'a> {
   Command::new("uninstall")
     .trailing_var_arg(true)
@@ -1535,6 +1622,56 @@ Ignore linting a file by adding an ignore comment at the top of the file:
     .arg(no_clear_screen_arg())
 }
 
+fn permissions_subcommand<'a>() -> Command<'a> {
+  Command::new("permissions")
+    .about("Manage the permissions remembered for the current project")
+    .long_about(
+      "Manage the permissions remembered for the current project.
+
+Answering a permission prompt with 'a' or 'b' (always allow) remembers the
+grant for the project of the configuration file, and applies it to every
+following run.
+
+List the remembered permissions:
+
+  deno permissions list
+
+Revoke all remembered permissions, all remembered read permissions, or a
+single one:
+
+  deno permissions revoke
+  deno permissions revoke read
+  deno permissions revoke read ./data",
+    )
+    .subcommand_required(true)
+    .arg_required_else_help(true)
+    .subcommand(
+      Command::new("list")
+        .about("List the remembered permissions")
+        .arg(config_arg())
+        .arg(no_config_arg()),
+    )
+    .subcommand(
+      Command::new("revoke")
+        .about("Revoke remembered permissions")
+        .arg(
+          Arg::new("permission")
+            .help("The permission to revoke, all permissions if omitted")
+            .possible_values([
+              "env", "ffi", "import", "net", "read", "run", "sys", "write",
+            ]),
+        )
+        .arg(
+          Arg::new("values")
+            .help("The values to revoke, all values if omitted")
+            .multiple_values(true)
+            .requires("permission"),
+        )
+        .arg(config_arg())
+        .arg(no_config_arg()),
+    )
+}
+
 fn repl_subcommand<'a>() -> Command<'a> {
   runtime_args(Command::new("repl"), true, true)
     .about("Read Eval Print Loop")
@@ -1558,14 +1695,42 @@ fn repl_subcommand<'a>() -> Command<'a> {
 }
 
 fn run_subcommand<'a>() -> Command<'a> {
-  runtime_args(Command::new("run"), true, true)
+  profiling_args(runtime_args(Command::new("run"), true, true))
     .arg(
       watch_arg(true)
         .conflicts_with("inspect")
         .conflicts_with("inspect-wait")
         .conflicts_with("inspect-brk"),
     )
-    .arg(no_clear_screen_arg())
+    .arg(
+      watch_hmr_arg()
+        .conflicts_with("watch")
+        .conflicts_with("inspect")
+        .conflicts_with("inspect-wait")
+        .conflicts_with("inspect-brk"),
+    )
+    .group(ArgGroup::new("watch-mode").args(&["watch", "watch-hmr"]))
+    .arg(
+      Arg::new("no-clear-screen")
+        .requires("watch-mode")
+        .long("no-clear-screen")
+        .help("Do not clear terminal screen when under watch mode"),
+    )
+    .arg(
+      Arg::new("trace-ops-timeline")
+        .long("trace-ops-timeline")
+        .value_name("FILE")
+        .help("Write a timeline of op calls to FILE")
+        .long_help(
+          "Record every op call with its timing and the resources it used, \
+and write them to FILE when the program finishes. The file uses the Chrome \
+trace event format and can be opened in Perfetto or chrome://tracing.",
+        )
+        .require_equals(true)
+        .takes_value(true)
+        .conflicts_with("watch")
+        .value_hint(ValueHint::FilePath),
+    )
     .trailing_var_arg(true)
     .arg(script_arg().required(true))
     .about("Run a JavaScript or TypeScript program")
@@ -1625,7 +1790,7 @@ fn task_subcommand<'a>() -> Command<'a> {
 }
 
 fn test_subcommand<'a>() -> Command<'a> {
-  runtime_args(Command::new("test"), true, true)
+  profiling_args(runtime_args(Command::new("test"), true, true))
     .trailing_var_arg(true)
     .arg(
       Arg::new("ignore")
@@ -1873,6 +2038,7 @@ fn compile_args(app: Command) -> Command {
     .arg(no_remote_arg())
     .arg(no_npm_arg())
     .arg(local_npm_arg())
+    .arg(conditions_arg())
     .arg(no_config_arg())
     .arg(config_arg())
     .arg(no_check_arg())
@@ -1890,6 +2056,7 @@ fn compile_args_without_check_args(app: Command) -> Command {
     .arg(no_remote_arg())
     .arg(no_npm_arg())
     .arg(local_npm_arg())
+    .arg(conditions_arg())
     .arg(config_arg())
     .arg(no_config_arg())
     .arg(reload_arg())
@@ -1909,6 +2076,12 @@ fn permission_args(app: Command) -> Command {
         .use_value_delimiter(true)
         .require_equals(true)
         .help("Allow file system read access")
+        .long_help(
+          "Allow file system read access. Entries prefixed with glob: may \
+          contain glob patterns and environment variables \
+          (e.g. glob:$HOME/src/**/*.json), and entries prefixed with ! are \
+          excluded.",
+        )
         .value_hint(ValueHint::AnyPath),
     )
     .arg(
@@ -1919,6 +2092,12 @@ fn permission_args(app: Command) -> Command {
         .use_value_delimiter(true)
         .require_equals(true)
         .help("Allow file system write access")
+        .long_help(
+          "Allow file system write access. Entries prefixed with glob: may \
+          contain glob patterns and environment variables \
+          (e.g. glob:$HOME/src/**/*.json), and entries prefixed with ! are \
+          excluded.",
+        )
         .value_hint(ValueHint::AnyPath),
     )
     .arg(
@@ -1983,6 +2162,20 @@ fn permission_args(app: Command) -> Command {
         .help("Allow loading dynamic libraries")
         .value_hint(ValueHint::AnyPath),
     )
+    .arg(
+      Arg::new("allow-import")
+        .long("allow-import")
+        .min_values(0)
+        .takes_value(true)
+        .use_value_delimiter(true)
+        .require_equals(true)
+        .help("Allow importing from remote hosts")
+        .long_help(
+          "Allow importing from remote hosts. Optionally specify allowed \
+          hosts, defaults to deno.land, esm.sh and registry.npmjs.org.",
+        )
+        .validator(flags_allow_net::validator),
+    )
     .arg(
       Arg::new("allow-hrtime")
         .long("allow-hrtime")
@@ -2023,8 +2216,11 @@ fn runtime_args(
   app
     .arg(cached_only_arg())
     .arg(location_arg())
+    .arg(import_arg())
     .arg(v8_flags_arg())
     .arg(seed_arg())
+    .arg(max_heap_size_arg())
+    .arg(max_cpu_time_arg())
     .arg(enable_testing_features_arg())
 }
 
@@ -2046,7 +2242,7 @@ fn inspect_args(app: Command) -> Command {
         .long("inspect-brk")
         .value_name("HOST_AND_PORT")
         .help(
-          "Activate inspector on host:port, wait for debugger to connect and break at the start of user script",
+          "Activate inspector on host:port, wait for debugger to connect and break at the start of user script and of every worker",
         )
         .min_values(0)
         .max_values(1)
@@ -2059,7 +2255,7 @@ fn inspect_args(app: Command) -> Command {
         .long("inspect-wait")
         .value_name("HOST_AND_PORT")
         .help(
-          "Activate inspector on host:port and wait for debugger to connect before running user code",
+          "Activate inspector on host:port and wait for debugger to connect before running user code and the code of every worker",
         )
         .min_values(0)
         .max_values(1)
@@ -2172,6 +2368,19 @@ fn v8_flags_arg<'a>() -> Arg<'a> {
     Any flags set with this flag are appended after the DENO_V8_FLAGS environmental variable")
 }
 
+fn import_arg<'a>() -> Arg<'a> {
+  Arg::new("import")
+    .long("import")
+    .takes_value(true)
+    .multiple_occurrences(true)
+    .value_name("MODULE")
+    .help("Preload a module before the main module")
+    .long_help("Preload a module before the main module, like the --import flag of Node.js. The modules are evaluated in order, e.g. to register hooks with `module.register()` of \"node:module\".
+
+  --import=./register_hooks.js")
+    .value_hint(ValueHint::FilePath)
+}
+
 fn seed_arg<'a>() -> Arg<'a> {
   Arg::new("seed")
     .long("seed")
@@ -2184,6 +2393,91 @@ fn seed_arg<'a>() -> Arg<'a> {
     })
 }
 
+fn profiling_args(app: Command) -> Command {
+  app
+    .arg(
+      Arg::new("cpu-prof")
+        .long("cpu-prof")
+        .value_name("DIR")
+        .help("Write a CPU profile of the program to DIR (default: .)")
+        .long_help(
+          "Profile the CPU usage of the program and write the profile to DIR \
+(default: the current directory) when it finishes. The .cpuprofile file can \
+be opened in the Performance tab of Chrome DevTools.",
+        )
+        .min_values(0)
+        .max_values(1)
+        .require_equals(true)
+        .takes_value(true)
+        .conflicts_with("watch")
+        .value_hint(ValueHint::DirPath),
+    )
+    .arg(
+      Arg::new("heap-snapshot-on-exit")
+        .long("heap-snapshot-on-exit")
+        .value_name("DIR")
+        .help("Write a heap snapshot to DIR (default: .) when the program finishes")
+        .min_values(0)
+        .max_values(1)
+        .require_equals(true)
+        .takes_value(true)
+        .conflicts_with("watch")
+        .value_hint(ValueHint::DirPath),
+    )
+    .arg(
+      Arg::new("heap-snapshot-near-limit")
+        .long("heap-snapshot-near-limit")
+        .value_name("DIR")
+        .help("Write a heap snapshot to DIR (default: .) when the heap is about to run out of memory")
+        .long_help(
+          "Write a heap snapshot to DIR (default: the current directory) the \
+first time the heap of the program gets close to its size limit, which is \
+either the V8 default or the one set with --max-heap-size.",
+        )
+        .min_values(0)
+        .max_values(1)
+        .require_equals(true)
+        .takes_value(true)
+        .conflicts_with("watch")
+        .value_hint(ValueHint::DirPath),
+    )
+}
+
+fn max_heap_size_arg<'a>() -> Arg<'a> {
+  Arg::new("max-heap-size")
+    .long("max-heap-size")
+    .value_name("MB")
+    .help("Terminate the program if its heap grows beyond the given size")
+    .long_help(
+      "Terminate the program if its heap grows beyond the given size in \
+megabytes. Web workers inherit the limit and can only be given a lower one.",
+    )
+    .takes_value(true)
+    .require_equals(true)
+    .validator(|val| match val.parse::<usize>() {
+      Ok(size) if size > 0 => Ok(()),
+      _ => Err("Heap size should be a positive number".to_string()),
+    })
+}
+
+fn max_cpu_time_arg<'a>() -> Arg<'a> {
+  Arg::new("max-cpu-time")
+    .long("max-cpu-time")
+    .value_name("MS")
+    .help("Terminate the program once it used the given CPU time")
+    .long_help(
+      "Terminate the program once its main thread used the given CPU time \
+in milliseconds. Web workers inherit the limit and can only be given a lower \
+one.",
+    )
+    .takes_value(true)
+    .require_equals(true)
+    .validator(|val| match val.parse::<u64>() {
+      Ok(time) if time > 0 => Ok(()),
+      _ => Err("CPU time should be a positive number".to_string()),
+    })
+}
+
 fn watch_arg<'a>(takes_files: bool) -> Arg<'a> {
   let arg = Arg::new("watch")
     .long("watch")
@@ -2210,6 +2504,27 @@ Additional paths might be watched by passing them as arguments to this flag.",
   }
 }
 
+fn watch_hmr_arg<'a>() -> Arg<'a> {
+  Arg::new("watch-hmr")
+    .long("watch-hmr")
+    .help("Watch for file changes and hot replace modules")
+    .value_name("FILES")
+    .min_values(0)
+    .takes_value(true)
+    .use_value_delimiter(true)
+    .require_equals(true)
+    .long_help(
+      "Watch for file changes and replace the changed modules in the running \
+process, keeping its state. Changed functions are patched through the \
+inspector and a \"hmr\" event is dispatched on globalThis with the path of the \
+changed file. When a change can't be applied in place, e.g. because it changes \
+top-level module code, the process is restarted like with --watch.
+Local files from entry point module graph are watched by default.
+Additional paths might be watched by passing them as arguments to this flag.",
+    )
+    .value_hint(ValueHint::AnyPath)
+}
+
 fn no_clear_screen_arg<'a>() -> Arg<'a> {
   Arg::new("no-clear-screen")
     .requires("watch")
@@ -2344,6 +2659,20 @@ fn local_npm_arg<'a>() -> Arg<'a> {
     .help("Creates a local node_modules folder. This option is implicitly true when a package.json is auto-discovered.")
 }
 
+fn conditions_arg<'a>() -> Arg<'a> {
+  Arg::new("conditions")
+    .long("conditions")
+    .takes_value(true)
+    .multiple_occurrences(true)
+    .use_value_delimiter(true)
+    .require_equals(true)
+    .value_name("CONDITIONS")
+    .help("Additional conditions to resolve the \"exports\" and \"imports\" of npm packages with")
+    .long_help("Additional conditions to resolve the \"exports\" and \"imports\" of npm packages with, like the --conditions flag of Node.js. They're used in addition to the default conditions, e.g. \"deno\", \"node\" and \"import\".
+
+  --conditions=development,worker")
+}
+
 fn unsafely_ignore_certificate_errors_arg<'a>() -> Arg<'a> {
   Arg::new("unsafely-ignore-certificate-errors")
     .long("unsafely-ignore-certificate-errors")
@@ -2568,6 +2897,7 @@ fn eval_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   flags.allow_sys = Some(vec![]);
   flags.allow_write = Some(vec![]);
   flags.allow_ffi = Some(vec![]);
+  flags.allow_import = Some(vec![]);
   flags.allow_hrtime = true;
   // TODO(@satyarohith): remove this flag in 2.0.
   let as_typescript = matches.is_present("ts");
@@ -2661,6 +2991,7 @@ fn info_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   location_arg_parse(flags, matches);
   ca_file_arg_parse(flags, matches);
   local_npm_args_parse(flags, matches);
+  conditions_arg_parse(flags, matches);
   let json = matches.is_present("json");
   flags.subcommand = DenoSubcommand::Info(InfoFlags {
     file: matches.value_of("file").map(|f| f.to_string()),
@@ -2693,6 +3024,20 @@ fn install_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   });
 }
 
+fn jupyter_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  runtime_args_parse(flags, matches, true, false);
+
+  let conn_file = matches.value_of("conn").map(PathBuf::from);
+  let kernel = matches.is_present("kernel");
+  let install = matches.is_present("install");
+
+  flags.subcommand = DenoSubcommand::Jupyter(JupyterFlags {
+    install,
+    kernel,
+    conn_file,
+  });
+}
+
 fn uninstall_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   let root = matches.value_of("root").map(PathBuf::from);
 
@@ -2745,6 +3090,27 @@ fn lint_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   });
 }
 
+fn permissions_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  let permissions_flags = match matches.subcommand() {
+    Some(("revoke", m)) => {
+      config_args_parse(flags, m);
+      PermissionsFlags::Revoke {
+        name: m.value_of("permission").map(ToString::to_string),
+        values: m
+          .values_of("values")
+          .map(|v| v.map(ToString::to_string).collect())
+          .unwrap_or_default(),
+      }
+    }
+    Some(("list", m)) => {
+      config_args_parse(flags, m);
+      PermissionsFlags::List
+    }
+    _ => unreachable!(),
+  };
+  flags.subcommand = DenoSubcommand::Permissions(permissions_flags);
+}
+
 fn repl_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   runtime_args_parse(flags, matches, true, true);
   unsafely_ignore_certificate_errors_parse(flags, matches);
@@ -2765,6 +3131,7 @@ fn repl_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
 fn run_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   runtime_args_parse(flags, matches, true, true);
+  profiling_args_parse(flags, matches);
 
   let mut script: Vec<String> = matches
     .values_of("script_arg")
@@ -2779,6 +3146,12 @@ fn run_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   }
 
   watch_arg_parse(flags, matches, true);
+  if let Some(f) = matches.values_of("watch-hmr") {
+    flags.watch = Some(f.map(PathBuf::from).collect());
+    flags.watch_hmr = true;
+  }
+  flags.trace_ops_timeline =
+    matches.value_of("trace-ops-timeline").map(String::from);
   flags.subcommand = DenoSubcommand::Run(RunFlags { script });
 }
 
@@ -2847,6 +3220,7 @@ fn task_parse(
 fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   flags.type_check_mode = TypeCheckMode::Local;
   runtime_args_parse(flags, matches, true, true);
+  profiling_args_parse(flags, matches);
   // NOTE: `deno test` always uses `--no-prompt`, tests shouldn't ever do
   // interactive prompts, unless done by user code
   flags.no_prompt = true;
@@ -2993,6 +3367,7 @@ fn compile_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   no_remote_arg_parse(flags, matches);
   no_npm_arg_parse(flags, matches);
   local_npm_args_parse(flags, matches);
+  conditions_arg_parse(flags, matches);
   config_args_parse(flags, matches);
   no_check_arg_parse(flags, matches);
   check_arg_parse(flags, matches);
@@ -3009,6 +3384,7 @@ fn compile_args_without_no_check_parse(
   no_remote_arg_parse(flags, matches);
   no_npm_arg_parse(flags, matches);
   local_npm_args_parse(flags, matches);
+  conditions_arg_parse(flags, matches);
   config_args_parse(flags, matches);
   reload_arg_parse(flags, matches);
   lock_args_parse(flags, matches);
@@ -3066,6 +3442,14 @@ fn permission_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     debug!("ffi allowlist: {:#?}", &flags.allow_ffi);
   }
 
+  if let Some(import_wl) = matches.values_of("allow-import") {
+    let import_allowlist: Vec<String> =
+      flags_allow_net::parse(import_wl.map(ToString::to_string).collect())
+        .unwrap();
+    flags.allow_import = Some(import_allowlist);
+    debug!("import allowlist: {:#?}", &flags.allow_import);
+  }
+
   if matches.is_present("allow-hrtime") {
     flags.allow_hrtime = true;
   }
@@ -3078,6 +3462,7 @@ fn permission_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     flags.allow_write = Some(vec![]);
     flags.allow_sys = Some(vec![]);
     flags.allow_ffi = Some(vec![]);
+    flags.allow_import = Some(vec![]);
     flags.allow_hrtime = true;
   }
   if matches.is_present("no-prompt") {
@@ -3109,8 +3494,10 @@ fn runtime_args_parse(
     inspect_arg_parse(flags, matches);
   }
   location_arg_parse(flags, matches);
+  import_arg_parse(flags, matches);
   v8_flags_arg_parse(flags, matches);
   seed_arg_parse(flags, matches);
+  resource_limits_arg_parse(flags, matches);
   enable_testing_features_arg_parse(flags, matches);
 }
 
@@ -3197,6 +3584,12 @@ fn v8_flags_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
   }
 }
 
+fn import_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  if let Some(import) = matches.values_of("import") {
+    flags.import = import.map(String::from).collect();
+  }
+}
+
 fn seed_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
   if matches.is_present("seed") {
     let seed_string = matches.value_of("seed").unwrap();
@@ -3207,6 +3600,26 @@ fn seed_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
   }
 }
 
+fn profiling_args_parse(flags: &mut Flags, matches: &ArgMatches) {
+  let dir = |name| {
+    matches
+      .is_present(name)
+      .then(|| matches.value_of(name).unwrap_or(".").to_string())
+  };
+  flags.cpu_prof_dir = dir("cpu-prof");
+  flags.heap_snapshot_on_exit_dir = dir("heap-snapshot-on-exit");
+  flags.heap_snapshot_near_limit_dir = dir("heap-snapshot-near-limit");
+}
+
+fn resource_limits_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  if let Some(max_heap_size) = matches.value_of("max-heap-size") {
+    flags.max_heap_size = Some(max_heap_size.parse().unwrap());
+  }
+  if let Some(max_cpu_time) = matches.value_of("max-cpu-time") {
+    flags.max_cpu_time = Some(max_cpu_time.parse().unwrap());
+  }
+}
+
 fn no_check_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   if let Some(cache_type) = matches.value_of("no-check") {
     match cache_type {
@@ -3282,6 +3695,12 @@ fn local_npm_args_parse(flags: &mut Flags, matches: &ArgMatches) {
   flags.node_modules_dir = optional_bool_parse(matches, "node-modules-dir");
 }
 
+fn conditions_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  if let Some(conditions) = matches.values_of("conditions") {
+    flags.conditions = conditions.map(String::from).collect();
+  }
+}
+
 fn inspect_arg_validate(val: &str) -> Result<(), String> {
   match val.parse::<SocketAddr>() {
     Ok(_) => Ok(()),
@@ -3469,6 +3888,58 @@ mod tests {
     );
   }
 
+  #[test]
+  fn run_watch_hmr() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch-hmr",
+      "--no-clear-screen",
+      "script.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        watch: Some(vec![]),
+        watch_hmr: true,
+        no_clear_screen: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch-hmr=file1,file2",
+      "script.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        watch: Some(vec![PathBuf::from("file1"), PathBuf::from("file2")]),
+        watch_hmr: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch",
+      "--watch-hmr",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn run_reload_allow_write() {
     let r =
@@ -3576,6 +4047,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_import: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -3597,6 +4069,26 @@ mod tests {
     );
   }
 
+  #[test]
+  fn allow_import_allowlist() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-import=deno.land,localhost:4545",
+      "gist.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "gist.ts".to_string(),
+        }),
+        allow_import: Some(svec!["deno.land", "localhost:4545"]),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn allow_hrtime() {
     let r = flags_from_vec(svec!["deno", "run", "--allow-hrtime", "gist.ts"]);
@@ -4315,6 +4807,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_import: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4339,6 +4832,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_import: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4364,6 +4858,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_import: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4402,6 +4897,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_import: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4433,6 +4929,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_import: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4458,6 +4955,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_import: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4497,6 +4995,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_import: Some(vec![]),
         allow_hrtime: true,
         unsafely_ignore_certificate_errors: Some(vec![]),
         ..Flags::default()
@@ -5106,6 +5605,82 @@ mod tests {
     );
   }
 
+  #[test]
+  fn run_resource_limits() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--max-heap-size=64",
+      "--max-cpu-time=1000",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        max_heap_size: Some(64),
+        max_cpu_time: Some(1000),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--max-heap-size=0", "script.ts"]);
+    assert!(r.is_err());
+    let r =
+      flags_from_vec(svec!["deno", "run", "--max-cpu-time=abc", "script.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn run_profiling() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--cpu-prof",
+      "--heap-snapshot-on-exit=snapshots",
+      "--heap-snapshot-near-limit",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        cpu_prof_dir: Some(".".to_string()),
+        heap_snapshot_on_exit_dir: Some("snapshots".to_string()),
+        heap_snapshot_near_limit_dir: Some(".".to_string()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--cpu-prof=prof"]);
+    assert_eq!(r.unwrap().cpu_prof_dir, Some("prof".to_string()));
+  }
+
+  #[test]
+  fn run_trace_ops_timeline() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--trace-ops-timeline=ops.json",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        trace_ops_timeline: Some("ops.json".to_string()),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn install() {
     let r = flags_from_vec(svec![
@@ -5162,6 +5737,67 @@ mod tests {
     );
   }
 
+  #[test]
+  fn jupyter() {
+    let r = flags_from_vec(svec!["deno", "jupyter", "--install"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
+          install: true,
+          kernel: false,
+          conn_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "jupyter",
+      "--kernel",
+      "--conn",
+      "path/to/conn/file"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
+          install: false,
+          kernel: true,
+          conn_file: Some(PathBuf::from("path/to/conn/file")),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "jupyter",
+      "--kernel",
+      "--conn",
+      "path/to/conn/file",
+      "--allow-net"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
+          install: false,
+          kernel: true,
+          conn_file: Some(PathBuf::from("path/to/conn/file")),
+        }),
+        allow_net: Some(vec![]),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "jupyter", "--kernel"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "jupyter", "--install", "--kernel"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn uninstall() {
     let r = flags_from_vec(svec!["deno", "uninstall", "file_server"]);
@@ -5183,6 +5819,52 @@ mod tests {
     assert_eq!(r.err().unwrap().kind(), clap::ErrorKind::DisplayHelp);
   }
 
+  #[test]
+  fn permissions() {
+    let r = flags_from_vec(svec!["deno", "permissions", "list"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Permissions(PermissionsFlags::List),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "permissions",
+      "revoke",
+      "--config",
+      "deno.json",
+      "read",
+      "./data",
+      "./cache"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Permissions(PermissionsFlags::Revoke {
+          name: Some("read".to_string()),
+          values: svec!["./data", "./cache"],
+        }),
+        config_flag: ConfigFlag::Path("deno.json".to_string()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "permissions", "revoke"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Permissions(PermissionsFlags::Revoke {
+        name: None,
+        values: vec![],
+      })
+    );
+
+    let r = flags_from_vec(svec!["deno", "permissions", "revoke", "hrtime"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn log_level() {
     let r =
@@ -5501,6 +6183,67 @@ mod tests {
     );
   }
 
+  #[test]
+  fn conditions() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--conditions=development,worker",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        conditions: svec!["development", "worker"],
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "info",
+      "--conditions=development",
+      "--conditions=worker",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          file: Some("script.ts".to_string()),
+          json: false,
+        }),
+        conditions: svec!["development", "worker"],
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn import() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--import=./hooks.js",
+      "--import",
+      "https://example.com/register.ts",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        import: svec!["./hooks.js", "https://example.com/register.ts"],
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn cached_only() {
     let r = flags_from_vec(svec!["deno", "run", "--cached-only", "script.ts"]);
@@ -6377,7 +7120,7 @@ mod tests {
           force: true,
           output_path: Some(PathBuf::from("out_dir")),
         }),
-        config_flag: ConfigFlag::Path("deno.json".to_owned()),
+        config_flag: ConfigFlag::Path("deno.json".to_string()),
         import_map_path: Some("import_map.json".to_string()),
         lock: Some(PathBuf::from("lock.json")),
         reload: true,
@@ -6446,7 +7189,7 @@ mod tests {
           task: "build".to_string(),
         }),
         argv: svec!["--", "hello", "world"],
-        config_flag: ConfigFlag::Path("deno.json".to_owned()),
+        config_flag: ConfigFlag::Path("deno.json".to_string()),
         ..Flags::default()
       }
     );
@@ -48,6 +48,7 @@ use deno_runtime::deno_tls::rustls_pemfile;
 use deno_runtime::deno_tls::webpki_roots;
 use deno_runtime::inspector_server::InspectorServer;
 use deno_runtime::permissions::PermissionsOptions;
+use deno_runtime::resource_limits::ResourceLimits;
 use once_cell::sync::Lazy;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
@@ -64,6 +65,7 @@ use std::sync::Arc;
 use crate::cache::DenoDir;
 use crate::file_fetcher::FileFetcher;
 use crate::npm::NpmProcessState;
+use crate::util::checksum;
 use crate::util::fs::canonicalize_path_maybe_not_exists;
 use crate::version;
 
@@ -608,6 +610,10 @@ impl CliOptions {
 
   pub fn from_flags(flags: Flags) -> Result<Self, AnyError> {
     let maybe_config_file = ConfigFile::discover(&flags)?;
+    if let Some(config_file) = &maybe_config_file {
+      // Surface errors early, `permissions_options()` can't report them.
+      config_file.to_permission_scopes()?;
+    }
 
     let mut maybe_package_json = None;
     if let Some(config_file) = &maybe_config_file {
@@ -809,14 +815,20 @@ impl CliOptions {
     }
   }
 
-  pub fn resolve_inspector_server(&self) -> Option<InspectorServer> {
+  pub fn resolve_inspector_server(
+    &self,
+  ) -> Result<Option<InspectorServer>, AnyError> {
     let maybe_inspect_host = self
       .flags
       .inspect
       .or(self.flags.inspect_brk)
       .or(self.flags.inspect_wait);
     maybe_inspect_host
-      .map(|host| InspectorServer::new(host, version::get_user_agent()))
+      .map(|host| {
+        InspectorServer::new(host, version::get_user_agent())
+          .context("Cannot start inspector server")
+      })
+      .transpose()
   }
 
   pub fn maybe_lock_file(&self) -> Option<Arc<Mutex<Lockfile>>> {
@@ -962,10 +974,30 @@ impl CliOptions {
     }
   }
 
+  pub fn cpu_prof_dir(&self) -> Option<PathBuf> {
+    self.flags.cpu_prof_dir.as_ref().map(PathBuf::from)
+  }
+
   pub fn enable_testing_features(&self) -> bool {
     self.flags.enable_testing_features
   }
 
+  pub fn heap_snapshot_near_limit_dir(&self) -> Option<PathBuf> {
+    self
+      .flags
+      .heap_snapshot_near_limit_dir
+      .as_ref()
+      .map(PathBuf::from)
+  }
+
+  pub fn heap_snapshot_on_exit_dir(&self) -> Option<PathBuf> {
+    self
+      .flags
+      .heap_snapshot_on_exit_dir
+      .as_ref()
+      .map(PathBuf::from)
+  }
+
   /// If the --inspect or --inspect-brk flags are used.
   pub fn is_inspecting(&self) -> bool {
     self.flags.inspect.is_some()
@@ -1020,24 +1052,77 @@ impl CliOptions {
     self.flags.no_npm
   }
 
+  /// The custom conditions to resolve the "exports" and "imports" of npm
+  /// packages with. The `--conditions` flag overrides the "conditions" of the
+  /// configuration file.
+  pub fn node_conditions(&self) -> Result<Vec<String>, AnyError> {
+    if !self.flags.conditions.is_empty() {
+      return Ok(self.flags.conditions.clone());
+    }
+    match &self.maybe_config_file {
+      Some(config_file) => config_file.to_conditions(),
+      None => Ok(Vec::new()),
+    }
+  }
+
+  /// The modules of `--import`, evaluated before the main module.
+  pub fn preload_modules(&self) -> Result<Vec<ModuleSpecifier>, AnyError> {
+    self
+      .flags
+      .import
+      .iter()
+      .map(|specifier| {
+        deno_core::resolve_url_or_path(specifier).with_context(|| {
+          format!("Bad URL (\"{specifier}\") for the preloaded module.")
+        })
+      })
+      .collect()
+  }
+
   pub fn permissions_options(&self) -> PermissionsOptions {
     PermissionsOptions {
       allow_env: self.flags.allow_env.clone(),
       allow_hrtime: self.flags.allow_hrtime,
       allow_net: self.flags.allow_net.clone(),
       allow_ffi: self.flags.allow_ffi.clone(),
+      allow_import: self.flags.allow_import.clone(),
       allow_read: self.flags.allow_read.clone(),
       allow_run: self.flags.allow_run.clone(),
       allow_sys: self.flags.allow_sys.clone(),
       allow_write: self.flags.allow_write.clone(),
       prompt: !self.no_prompt(),
+      module_scopes: self
+        .maybe_config_file
+        .as_ref()
+        .and_then(|config_file| config_file.to_permission_scopes().ok())
+        .unwrap_or_default(),
+      grants_file: self.grants_file_path(),
+    }
+  }
+
+  /// The file that permission prompt answers are remembered in. Answers are
+  /// only remembered for projects with a local configuration file.
+  pub fn grants_file_path(&self) -> Option<PathBuf> {
+    let config_file = self.maybe_config_file.as_ref()?;
+    if config_file.specifier.scheme() != "file" {
+      return None;
     }
+    let deno_dir = self.resolve_deno_dir().ok()?;
+    let name = checksum::gen(&[config_file.specifier.as_str()]);
+    Some(deno_dir.grants_folder_path().join(format!("{name}.json")))
   }
 
   pub fn reload_flag(&self) -> bool {
     self.flags.reload
   }
 
+  pub fn resource_limits(&self) -> ResourceLimits {
+    ResourceLimits {
+      max_heap_size: self.flags.max_heap_size,
+      max_cpu_time: self.flags.max_cpu_time,
+    }
+  }
+
   pub fn seed(&self) -> Option<u64> {
     self.flags.seed
   }
@@ -1053,6 +1138,10 @@ impl CliOptions {
     }
   }
 
+  pub fn trace_ops_timeline(&self) -> Option<PathBuf> {
+    self.flags.trace_ops_timeline.as_ref().map(PathBuf::from)
+  }
+
   pub fn shuffle_tests(&self) -> Option<u64> {
     match self.sub_command() {
       DenoSubcommand::Test(flags) => flags.shuffle,
@@ -1079,6 +1168,12 @@ impl CliOptions {
   pub fn watch_paths(&self) -> &Option<Vec<PathBuf>> {
     &self.flags.watch
   }
+
+  /// Whether changed modules are hot replaced instead of restarting the
+  /// process under `--watch-hmr`.
+  pub fn has_hmr(&self) -> bool {
+    self.flags.watch_hmr
+  }
 }
 
 fn resolve_import_map_specifier(
@@ -140,6 +140,14 @@ mod ts {
       "cargo:rerun-if-changed={}",
       cwd.join("tsc").join("99_main_compiler.js").display()
     );
+    println!(
+      "cargo:rerun-if-changed={}",
+      cwd.join("js").join("40_jupyter.js").display()
+    );
+    println!(
+      "cargo:rerun-if-changed={}",
+      cwd.join("js").join("40_module_hooks.js").display()
+    );
     println!(
       "cargo:rerun-if-changed={}",
       cwd.join("js").join("40_testing.js").display()
@@ -356,6 +364,8 @@ fn create_cli_snapshot(snapshot_path: PathBuf) {
 
   let mut esm_files = include_js_files!(
     dir "js",
+    "40_jupyter.js",
+    "40_module_hooks.js",
     "40_testing.js",
   );
   esm_files.push(ExtensionFileSource {
@@ -113,6 +113,11 @@ impl DenoDir {
     self.root.join("deno_history.txt")
   }
 
+  /// Folder used for the permission grants remembered per project.
+  pub fn grants_folder_path(&self) -> PathBuf {
+    self.root.join("permission_grants")
+  }
+
   /// Folder path used for downloading new versions of deno.
   pub fn dl_folder_path(&self) -> PathBuf {
     self.root.join("dl")
@@ -121,7 +126,7 @@ impl DenoDir {
 
 /// To avoid the poorly managed dirs crate
 #[cfg(not(windows))]
-mod dirs {
+pub mod dirs {
   use std::path::PathBuf;
 
   pub fn cache_dir() -> Option<PathBuf> {
@@ -134,6 +139,16 @@ mod dirs {
     }
   }
 
+  pub fn data_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+      home_dir().map(|h| h.join("Library/Application Support"))
+    } else {
+      std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|h| h.join(".local/share")))
+    }
+  }
+
   pub fn home_dir() -> Option<PathBuf> {
     std::env::var_os("HOME")
       .and_then(|h| if h.is_empty() { None } else { Some(h) })
@@ -179,7 +194,7 @@ mod dirs {
 // https://github.com/dirs-dev/dirs-sys-rs/blob/ec7cee0b3e8685573d847f0a0f60aae3d9e07fa2/src/lib.rs#L140-L164
 // MIT license. Copyright (c) 2018-2019 dirs-rs contributors
 #[cfg(windows)]
-mod dirs {
+pub mod dirs {
   use std::ffi::OsString;
   use std::os::windows::ffi::OsStringExt;
   use std::path::PathBuf;
@@ -217,6 +232,10 @@ mod dirs {
     known_folder(&knownfolders::FOLDERID_LocalAppData)
   }
 
+  pub fn data_dir() -> Option<PathBuf> {
+    known_folder(&knownfolders::FOLDERID_RoamingAppData)
+  }
+
   pub fn home_dir() -> Option<PathBuf> {
     known_folder(&knownfolders::FOLDERID_Profile)
   }
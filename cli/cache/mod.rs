@@ -2,7 +2,11 @@
 
 use crate::errors::get_error_class_name;
 use crate::file_fetcher::FileFetcher;
+use crate::graph_util::asset_to_json;
+use crate::graph_util::AssetKind;
 
+use deno_ast::MediaType;
+use deno_core::anyhow::Context;
 use deno_core::futures;
 use deno_core::futures::FutureExt;
 use deno_core::ModuleSpecifier;
@@ -11,6 +15,7 @@ use deno_graph::source::LoadFuture;
 use deno_graph::source::LoadResponse;
 use deno_graph::source::Loader;
 use deno_runtime::permissions::PermissionsContainer;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 mod check;
@@ -25,6 +30,7 @@ mod parsed_source;
 
 pub use check::TypeCheckCache;
 pub use common::FastInsecureHasher;
+pub use deno_dir::dirs;
 pub use deno_dir::DenoDir;
 pub use disk_cache::DiskCache;
 pub use emit::EmitCache;
@@ -135,6 +141,7 @@ impl Loader for FetchCacher {
       self.root_permissions.clone()
     };
     let file_fetcher = self.file_fetcher.clone();
+    let maybe_asset_kind = AssetKind::from_graph_specifier(&specifier);
 
     async move {
       file_fetcher
@@ -152,9 +159,41 @@ impl Loader for FetchCacher {
             Err(err)
           },
           |file| {
+            if let Some(kind) = maybe_asset_kind {
+              // Assets are in the graph as JSON modules of their contents.
+              let bytes = if matches!(file.specifier.scheme(), "data" | "blob")
+              {
+                file.source.as_bytes().to_vec()
+              } else {
+                std::fs::read(&file.local).with_context(|| {
+                  format!("Unable to load {}", file.local.display())
+                })?
+              };
+              let content = asset_to_json(kind, &bytes).with_context(|| {
+                format!("Unable to load {} as text", file.specifier)
+              })?;
+              return Ok(Some(LoadResponse::Module {
+                // Redirects keep the fragment, so this is still the
+                // specifier of the asset.
+                specifier: file.specifier,
+                maybe_headers: Some(HashMap::from([(
+                  "content-type".to_string(),
+                  "application/json".to_string(),
+                )])),
+                content: content.into(),
+              }));
+            }
+            let mut maybe_headers = file.maybe_headers;
+            if file.media_type == MediaType::Wasm {
+              // The source of a Wasm module is its type declarations.
+              maybe_headers.get_or_insert_with(HashMap::new).insert(
+                "content-type".to_string(),
+                "application/typescript".to_string(),
+              );
+            }
             Ok(Some(LoadResponse::Module {
               specifier: file.specifier,
-              maybe_headers: file.maybe_headers,
+              maybe_headers,
               content: file.source,
             }))
           },
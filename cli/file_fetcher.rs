@@ -5,6 +5,7 @@ use crate::auth_tokens::AuthToken;
 use crate::auth_tokens::AuthTokens;
 use crate::cache::HttpCache;
 use crate::colors;
+use crate::graph_util::AssetKind;
 use crate::http_util;
 use crate::http_util::resolve_redirect_from_response;
 use crate::http_util::CacheSemantics;
@@ -13,6 +14,7 @@ use crate::http_util::HttpClient;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::UpdateGuard;
 use crate::util::text_encoding;
+use crate::util::wasm::wasm_module_to_dts;
 
 use data_url::DataUrl;
 use deno_ast::MediaType;
@@ -59,7 +61,8 @@ pub struct File {
   pub maybe_types: Option<String>,
   /// The resolved media type for the file.
   pub media_type: MediaType,
-  /// The source of the file as a string.
+  /// The source of the file as a string. For Wasm modules, these are the
+  /// TypeScript declarations of their exports.
   pub source: Arc<str>,
   /// The _final_ specifier for the file.  The requested specifier and the final
   /// specifier maybe different for remote files that have been redirected.
@@ -91,9 +94,10 @@ fn fetch_local(specifier: &ModuleSpecifier) -> Result<File, AnyError> {
     uri_error(format!("Invalid file path.\n  Specifier: {specifier}"))
   })?;
   let bytes = fs::read(&local)?;
-  let charset = text_encoding::detect_charset(&bytes).to_string();
-  let source = get_source_from_bytes(bytes, Some(charset))?;
   let media_type = MediaType::from(specifier);
+  let charset = text_encoding::detect_charset(&bytes).to_string();
+  let source =
+    get_source_for_specifier(specifier, bytes, media_type, Some(charset))?;
 
   Ok(File {
     local,
@@ -135,6 +139,36 @@ pub fn get_source_from_bytes(
   Ok(source)
 }
 
+/// Given the bytes of a file, get its source for the specifier. Wasm modules
+/// and assets, which may be binary, are read again from the local file, so
+/// assets are not decoded.
+fn get_source_for_specifier(
+  specifier: &ModuleSpecifier,
+  bytes: Vec<u8>,
+  media_type: MediaType,
+  maybe_charset: Option<String>,
+) -> Result<String, AnyError> {
+  if AssetKind::from_graph_specifier(specifier).is_some() {
+    Ok(String::new())
+  } else if media_type == MediaType::Wasm {
+    wasm_module_to_dts(&bytes)
+  } else {
+    get_source_from_bytes(bytes, maybe_charset)
+  }
+}
+
+/// A redirect keeps the fragment of the requested URL, unless it has one of
+/// its own. <https://www.rfc-editor.org/rfc/rfc9110#section-10.2.2>
+fn redirect_with_fragment(
+  mut redirect: ModuleSpecifier,
+  specifier: &ModuleSpecifier,
+) -> ModuleSpecifier {
+  if redirect.fragment().is_none() {
+    redirect.set_fragment(specifier.fragment());
+  }
+  redirect
+}
+
 /// Return a validated scheme for a given module specifier.
 fn get_validated_scheme(
   specifier: &ModuleSpecifier,
@@ -228,7 +262,8 @@ impl FileFetcher {
     let maybe_content_type = headers.get("content-type");
     let (media_type, maybe_charset) =
       map_content_type(specifier, maybe_content_type);
-    let source = get_source_from_bytes(bytes, maybe_charset)?;
+    let source =
+      get_source_for_specifier(specifier, bytes, media_type, maybe_charset)?;
     let maybe_types = match media_type {
       MediaType::JavaScript
       | MediaType::Cjs
@@ -272,8 +307,10 @@ impl FileFetcher {
       Ok(cache) => cache,
     };
     if let Some(redirect_to) = headers.get("location") {
-      let redirect =
-        deno_core::resolve_import(redirect_to, specifier.as_str())?;
+      let redirect = redirect_with_fragment(
+        deno_core::resolve_import(redirect_to, specifier.as_str())?,
+        specifier,
+      );
       return self.fetch_cached(&redirect, redirect_limit - 1);
     }
     let mut bytes = Vec::new();
@@ -478,6 +515,7 @@ impl FileFetcher {
         }
         FetchOnceResult::Redirect(redirect_url, headers) => {
           file_fetcher.http_cache.set(&specifier, headers, &[])?;
+          let redirect_url = redirect_with_fragment(redirect_url, &specifier);
           file_fetcher
             .fetch_remote(
               &redirect_url,
@@ -660,7 +698,7 @@ async fn fetch_once<'a>(
   http_client: &HttpClient,
   args: FetchOnceArgs<'a>,
 ) -> Result<FetchOnceResult, AnyError> {
-  let mut request = http_client.get_no_redirect(args.url.clone());
+  let mut request = http_client.get_no_redirect(args.url.clone())?;
 
   if let Some(etag) = args.maybe_etag {
     let if_none_match_val = HeaderValue::from_str(&etag)?;
@@ -13,16 +13,29 @@ use crate::proc_state::ProcState;
 use crate::resolver::CliGraphResolver;
 use crate::tools::check;
 
+use deno_ast::MediaType;
 use deno_core::anyhow::bail;
 use deno_core::error::custom_error;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
 use deno_core::ModuleSpecifier;
+use deno_graph::source::Resolver;
+use deno_graph::DependencyDescriptor;
+use deno_graph::DynamicArgument;
+use deno_graph::ImportAssertion;
+use deno_graph::ImportAssertions;
+use deno_graph::ModuleAnalyzer;
 use deno_graph::ModuleGraph;
 use deno_graph::ModuleGraphError;
+use deno_graph::ModuleInfo;
 use deno_graph::ResolutionError;
 use deno_graph::SpecifierError;
+use deno_graph::SpecifierWithRange;
 use deno_runtime::permissions::PermissionsContainer;
 use import_map::ImportMapError;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
 
 #[derive(Clone, Copy)]
@@ -157,7 +170,8 @@ pub async fn create_graph_and_maybe_check(
     maybe_package_json_deps,
   );
   let graph_resolver = cli_resolver.as_graph_resolver();
-  let analyzer = ps.parsed_source_cache.as_analyzer();
+  let asset_imports =
+    AssetImports::new(ps.parsed_source_cache.as_analyzer(), graph_resolver);
   let mut graph = ModuleGraph::default();
   graph
     .build(
@@ -166,8 +180,8 @@ pub async fn create_graph_and_maybe_check(
       deno_graph::BuildOptions {
         is_dynamic: false,
         imports: maybe_imports,
-        resolver: Some(graph_resolver),
-        module_analyzer: Some(&*analyzer),
+        resolver: Some(&asset_imports),
+        module_analyzer: Some(&asset_imports),
         reporter: None,
       },
     )
@@ -242,6 +256,226 @@ pub fn error_for_any_npm_specifier(
   }
 }
 
+/// How a module is imported when it is imported as an asset rather than as
+/// code, with a `"text"` or `"bytes"` type assertion.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AssetKind {
+  Text,
+  Bytes,
+}
+
+impl AssetKind {
+  fn from_assert_type(assert_type: &str) -> Option<Self> {
+    match assert_type {
+      "text" => Some(Self::Text),
+      "bytes" => Some(Self::Bytes),
+      _ => None,
+    }
+  }
+
+  fn fragment(&self) -> &'static str {
+    match self {
+      Self::Text => "deno-asset-text",
+      Self::Bytes => "deno-asset-bytes",
+    }
+  }
+
+  /// The specifier of an asset in the module graph. It has the kind of the
+  /// asset as its fragment, so that the same module can be in the graph as
+  /// code, as text and as bytes.
+  pub fn to_graph_specifier(
+    self,
+    specifier: &ModuleSpecifier,
+  ) -> ModuleSpecifier {
+    let mut specifier = specifier.clone();
+    specifier.set_fragment(Some(self.fragment()));
+    specifier
+  }
+
+  /// Gets the kind of the asset of a specifier in the module graph, if it is
+  /// one.
+  pub fn from_graph_specifier(specifier: &ModuleSpecifier) -> Option<Self> {
+    [Self::Text, Self::Bytes]
+      .into_iter()
+      .find(|kind| specifier.fragment() == Some(kind.fragment()))
+  }
+
+  /// A module declaring the type of the asset, used as the types dependency
+  /// of its imports.
+  fn types_specifier(&self) -> String {
+    let types = match self {
+      Self::Text => "declare const text: string;\nexport default text;\n",
+      Self::Bytes => {
+        "declare const bytes: Uint8Array;\nexport default bytes;\n"
+      }
+    };
+    format!(
+      "data:application/typescript;base64,{}",
+      base64::encode(types)
+    )
+  }
+}
+
+/// Gets the source of an asset in the module graph. deno_graph only knows
+/// about JSON modules besides code, so assets are in the module graph as JSON
+/// strings, which records them in the lockfile and in `deno compile` output
+/// like any other dependency. Bytes are encoded with base64.
+pub fn asset_to_json(
+  kind: AssetKind,
+  bytes: &[u8],
+) -> Result<String, AnyError> {
+  let json = match kind {
+    AssetKind::Text => serde_json::to_string(std::str::from_utf8(bytes)?)?,
+    AssetKind::Bytes => serde_json::to_string(&base64::encode(bytes))?,
+  };
+  Ok(json)
+}
+
+/// Gets the contents of an asset from its source in the module graph.
+pub fn asset_from_json(
+  kind: AssetKind,
+  source: &str,
+) -> Result<Vec<u8>, AnyError> {
+  let source: String = serde_json::from_str(source)?;
+  Ok(match kind {
+    AssetKind::Text => source.into_bytes(),
+    AssetKind::Bytes => base64::decode(source)?,
+  })
+}
+
+/// Analyzes modules with `inner` and changes the dependencies imported as
+/// assets into JSON imports, so that deno_graph accepts them. As the resolver
+/// of the graph, it resolves these imports to the specifiers of the assets in
+/// the graph.
+pub struct AssetImports<'a> {
+  inner: Box<dyn ModuleAnalyzer>,
+  resolver: &'a dyn Resolver,
+  /// The kinds of the asset imports by referrer and import specifier.
+  kinds: RefCell<HashMap<(ModuleSpecifier, String), AssetKind>>,
+}
+
+impl<'a> AssetImports<'a> {
+  pub fn new(
+    inner: Box<dyn ModuleAnalyzer>,
+    resolver: &'a dyn Resolver,
+  ) -> Self {
+    Self {
+      inner,
+      resolver,
+      kinds: Default::default(),
+    }
+  }
+
+  /// Changes the type assertion of an asset import to `"json"` and returns
+  /// the kind of the asset.
+  fn take_asset_kind(
+    import_assertions: &mut ImportAssertions,
+  ) -> Option<AssetKind> {
+    let assertions = match import_assertions {
+      ImportAssertions::Known(assertions) => assertions,
+      _ => return None,
+    };
+    match assertions.get_mut("type") {
+      Some(ImportAssertion::Known(assert_type)) => {
+        let kind = AssetKind::from_assert_type(assert_type)?;
+        *assert_type = "json".to_string();
+        Some(kind)
+      }
+      _ => None,
+    }
+  }
+}
+
+impl<'a> fmt::Debug for AssetImports<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("AssetImports")
+      .field("resolver", &self.resolver)
+      .field("kinds", &self.kinds)
+      .finish()
+  }
+}
+
+impl<'a> ModuleAnalyzer for AssetImports<'a> {
+  fn analyze(
+    &self,
+    specifier: &ModuleSpecifier,
+    source: Arc<str>,
+    media_type: MediaType,
+  ) -> Result<ModuleInfo, deno_ast::Diagnostic> {
+    let mut module_info = self.inner.analyze(specifier, source, media_type)?;
+    for dependency in &mut module_info.dependencies {
+      let (maybe_kind, maybe_asset_specifier) = match dependency {
+        DependencyDescriptor::Static(dependency) => {
+          let maybe_kind =
+            Self::take_asset_kind(&mut dependency.import_assertions);
+          if let Some(kind) = maybe_kind {
+            dependency.types_specifier = Some(SpecifierWithRange {
+              text: kind.types_specifier(),
+              range: dependency.specifier_range.clone(),
+            });
+          }
+          (maybe_kind, Some(&dependency.specifier))
+        }
+        DependencyDescriptor::Dynamic(dependency) => {
+          let maybe_kind =
+            Self::take_asset_kind(&mut dependency.import_assertions);
+          // Unless the specifier is a string, the module can't be known until
+          // the import happens, so it's not in the graph.
+          let maybe_asset_specifier = match &dependency.argument {
+            DynamicArgument::String(text) => Some(text),
+            _ => None,
+          };
+          (maybe_kind, maybe_asset_specifier)
+        }
+      };
+      if let (Some(kind), Some(asset_specifier)) =
+        (maybe_kind, maybe_asset_specifier)
+      {
+        self
+          .kinds
+          .borrow_mut()
+          .insert((specifier.clone(), asset_specifier.clone()), kind);
+      }
+    }
+    Ok(module_info)
+  }
+}
+
+impl<'a> Resolver for AssetImports<'a> {
+  fn default_jsx_import_source(&self) -> Option<String> {
+    self.resolver.default_jsx_import_source()
+  }
+
+  fn jsx_import_source_module(&self) -> &str {
+    self.resolver.jsx_import_source_module()
+  }
+
+  fn resolve(
+    &self,
+    specifier: &str,
+    referrer: &ModuleSpecifier,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    let resolved = self.resolver.resolve(specifier, referrer)?;
+    let kinds = self.kinds.borrow();
+    let maybe_kind = kinds.get(&(referrer.clone(), specifier.to_string()));
+    Ok(match maybe_kind {
+      // npm packages and built-in modules are not loaded through the graph.
+      Some(kind) if !matches!(resolved.scheme(), "npm" | "node") => {
+        kind.to_graph_specifier(&resolved)
+      }
+      _ => resolved,
+    })
+  }
+
+  fn resolve_types(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Result<Option<(ModuleSpecifier, Option<deno_graph::Range>)>, AnyError>
+  {
+    self.resolver.resolve_types(specifier)
+  }
+}
+
 /// Adds more explanatory information to a resolution error.
 pub fn enhanced_resolution_error_message(error: &ResolutionError) -> String {
   let mut message = format!("{error}");
@@ -295,7 +529,10 @@ mod test {
   use deno_graph::ResolutionError;
   use deno_graph::SpecifierError;
 
+  use crate::graph_util::asset_from_json;
+  use crate::graph_util::asset_to_json;
   use crate::graph_util::get_resolution_error_bare_node_specifier;
+  use crate::graph_util::AssetKind;
 
   #[test]
   fn import_map_node_resolution_error() {
@@ -335,4 +572,41 @@ mod test {
       assert_eq!(get_resolution_error_bare_node_specifier(&err), output,);
     }
   }
+
+  #[test]
+  fn asset_json() {
+    let text = asset_to_json(AssetKind::Text, b"SELECT 1;\n").unwrap();
+    assert_eq!(text, r#""SELECT 1;\n""#);
+    assert_eq!(
+      asset_from_json(AssetKind::Text, &text).unwrap(),
+      b"SELECT 1;\n"
+    );
+
+    let bytes = asset_to_json(AssetKind::Bytes, &[0, 159, 255]).unwrap();
+    assert_eq!(bytes, r#""AJ//""#);
+    assert_eq!(
+      asset_from_json(AssetKind::Bytes, &bytes).unwrap(),
+      vec![0, 159, 255]
+    );
+
+    assert!(asset_to_json(AssetKind::Text, &[0, 159, 255]).is_err());
+    assert!(asset_from_json(AssetKind::Text, "{}").is_err());
+  }
+
+  #[test]
+  fn asset_graph_specifier() {
+    let specifier = ModuleSpecifier::parse("file:///data.bin").unwrap();
+    let text = AssetKind::Text.to_graph_specifier(&specifier);
+    let bytes = AssetKind::Bytes.to_graph_specifier(&specifier);
+    assert_ne!(text, bytes);
+    assert_eq!(
+      AssetKind::from_graph_specifier(&text),
+      Some(AssetKind::Text)
+    );
+    assert_eq!(
+      AssetKind::from_graph_specifier(&bytes),
+      Some(AssetKind::Bytes)
+    );
+    assert_eq!(AssetKind::from_graph_specifier(&specifier), None);
+  }
 }
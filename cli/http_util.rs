@@ -10,6 +10,7 @@ use deno_core::error::custom_error;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::futures::StreamExt;
+use deno_core::parking_lot::Mutex;
 use deno_core::url::Url;
 use deno_runtime::deno_fetch::create_http_client;
 use deno_runtime::deno_fetch::reqwest;
@@ -17,6 +18,8 @@ use deno_runtime::deno_fetch::reqwest::header::LOCATION;
 use deno_runtime::deno_fetch::reqwest::Response;
 use deno_runtime::deno_tls::rustls::RootCertStore;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread::ThreadId;
 use std::time::Duration;
 use std::time::SystemTime;
 
@@ -216,34 +219,82 @@ impl CacheSemantics {
   }
 }
 
-#[derive(Debug, Clone)]
-pub struct HttpClient(reqwest::Client);
+type CreateClient = dyn Fn() -> Result<reqwest::Client, AnyError> + Send + Sync;
+
+/// The HTTP client of the CLI. Each thread gets a `reqwest::Client` of its
+/// own, as the connections of a client are driven by the runtime of the thread
+/// that opened them, which may be blocked waiting for another thread, e.g. for
+/// the module hooks.
+#[derive(Clone)]
+pub struct HttpClient(Arc<HttpClientInner>);
+
+struct HttpClientInner {
+  create_client: Box<CreateClient>,
+  clients: Mutex<HashMap<ThreadId, reqwest::Client>>,
+}
+
+impl std::fmt::Debug for HttpClient {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("HttpClient")
+      .field("clients", &self.0.clients.lock().len())
+      .finish()
+  }
+}
 
 impl HttpClient {
   pub fn new(
     root_cert_store: Option<RootCertStore>,
     unsafely_ignore_certificate_errors: Option<Vec<String>>,
   ) -> Result<Self, AnyError> {
-    Ok(HttpClient::from_client(create_http_client(
-      get_user_agent(),
-      root_cert_store,
-      vec![],
-      None,
-      unsafely_ignore_certificate_errors,
-      None,
-    )?))
+    let client = Self::with_factory(move || {
+      create_http_client(
+        get_user_agent(),
+        root_cert_store.clone(),
+        vec![],
+        None,
+        unsafely_ignore_certificate_errors.clone(),
+        None,
+      )
+    });
+    // fail early if the options are invalid
+    client.client()?;
+    Ok(client)
   }
 
+  /// A client that uses `client` on every thread.
   pub fn from_client(client: reqwest::Client) -> Self {
-    Self(client)
+    Self::with_factory(move || Ok(client.clone()))
+  }
+
+  fn with_factory(
+    create_client: impl Fn() -> Result<reqwest::Client, AnyError>
+      + Send
+      + Sync
+      + 'static,
+  ) -> Self {
+    Self(Arc::new(HttpClientInner {
+      create_client: Box::new(create_client),
+      clients: Default::default(),
+    }))
+  }
+
+  fn client(&self) -> Result<reqwest::Client, AnyError> {
+    let mut clients = self.0.clients.lock();
+    let thread_id = std::thread::current().id();
+    if let Some(client) = clients.get(&thread_id) {
+      return Ok(client.clone());
+    }
+    let client = (self.0.create_client)()?;
+    clients.insert(thread_id, client.clone());
+    Ok(client)
   }
 
   /// Do a GET request without following redirects.
   pub fn get_no_redirect<U: reqwest::IntoUrl>(
     &self,
     url: U,
-  ) -> reqwest::RequestBuilder {
-    self.0.get(url)
+  ) -> Result<reqwest::RequestBuilder, AnyError> {
+    Ok(self.client()?.get(url))
   }
 
   pub async fn download_text<U: reqwest::IntoUrl>(
@@ -305,12 +356,13 @@ impl HttpClient {
     url: U,
   ) -> Result<Response, AnyError> {
     let mut url = url.into_url()?;
-    let mut response = self.get_no_redirect(url.clone()).send().await?;
+    let mut response = self.get_no_redirect(url.clone())?.send().await?;
     let status = response.status();
     if status.is_redirection() {
       for _ in 0..5 {
         let new_url = resolve_redirect_from_response(&url, &response)?;
-        let new_response = self.get_no_redirect(new_url.clone()).send().await?;
+        let new_response =
+          self.get_no_redirect(new_url.clone())?.send().await?;
         let status = new_response.status();
         if status.is_redirection() {
           response = new_response;
@@ -0,0 +1,139 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+const core = globalThis.Deno.core;
+const ops = core.ops;
+const internals = globalThis.__bootstrap.internals;
+import { inspectArgs } from "internal:deno_console/02_console.js";
+import { forgivingBase64Encode } from "internal:deno_web/00_infra.js";
+import { readFileSync } from "internal:runtime/js/40_read_file.js";
+const primordials = globalThis.__bootstrap.primordials;
+const {
+  ArrayPrototypeJoin,
+  ArrayPrototypePush,
+  ObjectDefineProperty,
+  ObjectFreeze,
+  StringPrototypeTrim,
+  SymbolFor,
+  TypeError,
+} = primordials;
+
+const $display = SymbolFor("Jupyter.display");
+
+function makeDisplayable(bundle) {
+  return {
+    [$display]: () => bundle,
+  };
+}
+
+function joinTemplate(strings, values) {
+  const parts = [];
+  for (let i = 0; i < strings.length; i++) {
+    ArrayPrototypePush(parts, strings[i]);
+    if (i < values.length) {
+      ArrayPrototypePush(parts, values[i]);
+    }
+  }
+  return ArrayPrototypeJoin(parts, "");
+}
+
+/**
+ * Creates a displayable HTML object from a template literal.
+ */
+function html(strings, ...values) {
+  return makeDisplayable({ "text/html": joinTemplate(strings, values) });
+}
+
+/**
+ * Creates a displayable Markdown object from a template literal.
+ */
+function md(strings, ...values) {
+  return makeDisplayable({ "text/markdown": joinTemplate(strings, values) });
+}
+
+/**
+ * Creates a displayable SVG object from a template literal.
+ */
+function svg(strings, ...values) {
+  return makeDisplayable({
+    "image/svg+xml": StringPrototypeTrim(joinTemplate(strings, values)),
+  });
+}
+
+function isPng(data) {
+  return data.length > 8 && data[0] === 0x89 && data[1] === 0x50 &&
+    data[2] === 0x4E && data[3] === 0x47 && data[4] === 0x0D &&
+    data[5] === 0x0A && data[6] === 0x1A && data[7] === 0x0A;
+}
+
+function isJpeg(data) {
+  return data.length > 3 && data[0] === 0xFF && data[1] === 0xD8 &&
+    data[2] === 0xFF;
+}
+
+/**
+ * Creates a displayable image from the bytes of a PNG or JPEG image, or from
+ * the path of such an image.
+ */
+function image(obj) {
+  const data = typeof obj === "string" ? readFileSync(obj) : obj;
+  let mimeType;
+  if (isPng(data)) {
+    mimeType = "image/png";
+  } else if (isJpeg(data)) {
+    mimeType = "image/jpeg";
+  } else {
+    throw new TypeError("Object is not a valid PNG or JPEG image.");
+  }
+  return makeDisplayable({
+    [mimeType]: forgivingBase64Encode(data),
+  });
+}
+
+/**
+ * Formats a value as a MIME bundle, using `[Deno.jupyter.$display]()` if the
+ * value implements it and plain text otherwise.
+ */
+async function format(obj) {
+  if (
+    obj !== null && obj !== undefined && typeof obj[$display] === "function"
+  ) {
+    return await obj[$display]();
+  }
+  return { "text/plain": inspectArgs(["%o", obj], { colors: true }) };
+}
+
+/**
+ * Sends a `display_data` message for `obj`, or an `update_display_data`
+ * message with `update: true`. With `raw: true`, `obj` is sent as the MIME
+ * bundle itself.
+ */
+async function display(obj, options = {}) {
+  const data = options.raw ? obj : await format(obj);
+  const transient = {};
+  if (options.display_id !== undefined) {
+    transient.display_id = options.display_id;
+  }
+  ops.op_jupyter_broadcast(
+    options.update ? "update_display_data" : "display_data",
+    { data, metadata: {}, transient },
+  );
+}
+
+function enableJupyter() {
+  ObjectDefineProperty(globalThis.Deno, "jupyter", {
+    value: ObjectFreeze({
+      $display,
+      display,
+      html,
+      md,
+      svg,
+      image,
+    }),
+    writable: false,
+    enumerable: true,
+    configurable: false,
+  });
+}
+
+internals.enableJupyter = enableJupyter;
+internals.jupyter = { format };
@@ -0,0 +1,212 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+// The worker of the hooks registered with `module.register()` of
+// "node:module". It serves the requests of the module loaders of the other
+// workers, by running the `resolve` and `load` hooks like Node.js does.
+// https://nodejs.org/api/module.html#customization-hooks
+
+const core = globalThis.Deno.core;
+const ops = core.ops;
+const internals = globalThis.__bootstrap.internals;
+import {
+  fileURLToPath,
+  pathToFileURL,
+} from "internal:deno_node/polyfills/url.ts";
+const primordials = globalThis.__bootstrap.primordials;
+const {
+  ArrayBufferIsView,
+  ArrayBufferPrototype,
+  ArrayPrototypeIncludes,
+  ArrayPrototypePush,
+  Error,
+  ObjectPrototypeIsPrototypeOf,
+  String,
+  StringPrototypeEndsWith,
+  StringPrototypeStartsWith,
+  TypeError,
+  Uint8Array,
+} = primordials;
+
+// The hooks in the order they were registered. The last ones run first, and
+// call the previous ones with `nextResolve()` and `nextLoad()`.
+const resolveHooks = [];
+const loadHooks = [];
+
+const importConditions = ["node", "import"];
+const requireConditions = ["node", "require"];
+
+function isRequire(context) {
+  return ArrayPrototypeIncludes(context.conditions, "require");
+}
+
+function defaultResolve(specifier, context) {
+  if (!isRequire(context)) {
+    const url = ops.op_module_hooks_default_resolve(
+      specifier,
+      context.parentURL ?? null,
+    );
+    return { url };
+  }
+
+  const { Module } = internals.require;
+  // Only the main module is required without a parent, by its absolute path.
+  const parentURL = context.parentURL ?? pathToFileURL(specifier).href;
+  const filename = Module.createRequire(parentURL).resolve(specifier);
+  if (StringPrototypeStartsWith(filename, "node:")) {
+    return { url: filename, format: "builtin" };
+  }
+  if (ArrayPrototypeIncludes(Module.builtinModules, filename)) {
+    return { url: `node:${filename}`, format: "builtin" };
+  }
+  return {
+    url: pathToFileURL(filename).href,
+    format: StringPrototypeEndsWith(filename, ".json") ? "json" : "commonjs",
+  };
+}
+
+// `id` is the one of the load request, whose permissions are checked.
+async function defaultLoad(url, context, id, isDynamic) {
+  if (isRequire(context)) {
+    return {
+      format: context.format ?? "commonjs",
+      source: ops.op_require_read_file(fileURLToPath(url)),
+    };
+  }
+  return await core.opAsync(
+    "op_module_hooks_default_load",
+    id,
+    url,
+    isDynamic,
+  );
+}
+
+// Returns the function that runs the hook at `index` of the chain, or the
+// default one after the first hook.
+function chain(name, hooks, index, defaultHook) {
+  return async (input, context) => {
+    if (index < 0) {
+      return await defaultHook(input, context);
+    }
+    const next = chain(name, hooks, index - 1, defaultHook);
+    let isNextCalled = false;
+    const result = await hooks[index](
+      input,
+      context,
+      (nextInput, nextContext) => {
+        isNextCalled = true;
+        return next(nextInput, { ...context, ...nextContext });
+      },
+    );
+    if (result === null || typeof result !== "object") {
+      throw new TypeError(`Expected the "${name}" hook to return an object`);
+    }
+    if (!isNextCalled && result.shortCircuit !== true) {
+      throw new Error(
+        `"${name}" hook did not call the next hook in its chain and did not explicitly signal a short circuit. If this is intentional, include \`shortCircuit: true\` in the hook's return.`,
+      );
+    }
+    return result;
+  };
+}
+
+async function register(specifier, parentURL, data) {
+  const url = ops.op_module_hooks_default_resolve(specifier, parentURL);
+  const hooks = await import(url);
+  if (typeof hooks.initialize === "function") {
+    await hooks.initialize(data);
+  }
+  if (typeof hooks.resolve === "function") {
+    ArrayPrototypePush(resolveHooks, hooks.resolve);
+  }
+  if (typeof hooks.load === "function") {
+    ArrayPrototypePush(loadHooks, hooks.load);
+  }
+}
+
+async function resolve(request) {
+  const resolveChain = chain(
+    "resolve",
+    resolveHooks,
+    resolveHooks.length - 1,
+    defaultResolve,
+  );
+  const { url } = await resolveChain(request.specifier, {
+    conditions: request.isRequire ? requireConditions : importConditions,
+    importAssertions: {},
+    parentURL: request.parentURL ?? undefined,
+  });
+  return String(url);
+}
+
+async function load(id, request) {
+  const loadChain = chain(
+    "load",
+    loadHooks,
+    loadHooks.length - 1,
+    (url, context) => defaultLoad(url, context, id, request.isDynamic),
+  );
+  const { format, source } = await loadChain(request.url, {
+    conditions: request.isRequire ? requireConditions : importConditions,
+    format: request.format ?? undefined,
+    importAssertions: {},
+  });
+  if (source === null || source === undefined) {
+    throw new TypeError(
+      `The "load" hook returned no source for ${request.url}`,
+    );
+  }
+  let buffer;
+  if (ObjectPrototypeIsPrototypeOf(ArrayBufferPrototype, source)) {
+    buffer = new Uint8Array(source);
+  } else if (ArrayBufferIsView(source)) {
+    buffer = new Uint8Array(
+      source.buffer,
+      source.byteOffset,
+      source.byteLength,
+    );
+  } else {
+    buffer = String(source);
+  }
+  return [format ?? "module", buffer];
+}
+
+async function handleRequest(id, request) {
+  try {
+    switch (request.kind) {
+      case "register":
+        await register(request.specifier, request.parentURL, request.data);
+        ops.op_module_hooks_registered(id);
+        break;
+      case "resolve":
+        ops.op_module_hooks_resolved(id, await resolve(request));
+        break;
+      case "load": {
+        const { 0: format, 1: source } = await load(id, request);
+        ops.op_module_hooks_loaded(id, format, source);
+        break;
+      }
+    }
+  } catch (error) {
+    ops.op_module_hooks_failed(id, String(error?.message ?? error));
+  }
+}
+
+// Resolves once nothing can request the hooks anymore. The requests are
+// handled concurrently, hooks may wait on each other, except for the
+// registrations: the hooks are in place for the requests that follow them.
+async function run() {
+  while (true) {
+    const next = await core.opAsync("op_module_hooks_next_request");
+    if (next === null) {
+      return;
+    }
+    const { 0: id, 1: request } = next;
+    if (request.kind === "register") {
+      await handleRequest(id, request);
+    } else {
+      handleRequest(id, request);
+    }
+  }
+}
+
+internals.moduleHooks = { run };
@@ -774,6 +774,7 @@ impl Inner {
     self.maybe_config_file = None;
     self.fmt_options = Default::default();
     self.lint_options = Default::default();
+    self.npm_resolver.set_conditions(Vec::new());
 
     if let Some(config_file) = self.get_config_file()? {
       let lint_options = config_file
@@ -792,10 +793,14 @@ impl Inner {
         .map_err(|err| {
           anyhow!("Unable to update formatter configuration: {:?}", err)
         })?;
+      let conditions = config_file.to_conditions().map_err(|err| {
+        anyhow!("Unable to update conditions configuration: {:?}", err)
+      })?;
 
       self.maybe_config_file = Some(config_file);
       self.lint_options = lint_options;
       self.fmt_options = fmt_options;
+      self.npm_resolver.set_conditions(conditions);
     }
 
     Ok(())
@@ -18,8 +18,11 @@ use tower_lsp::lsp_types::CompletionTriggerKind;
 use tower_lsp::lsp_types::DidChangeTextDocumentParams;
 use tower_lsp::lsp_types::DidCloseTextDocumentParams;
 use tower_lsp::lsp_types::DidOpenTextDocumentParams;
+use tower_lsp::lsp_types::HoverContents;
+use tower_lsp::lsp_types::HoverParams;
 use tower_lsp::lsp_types::InitializeParams;
 use tower_lsp::lsp_types::InitializedParams;
+use tower_lsp::lsp_types::MarkedString;
 use tower_lsp::lsp_types::PartialResultParams;
 use tower_lsp::lsp_types::Position;
 use tower_lsp::lsp_types::Range;
@@ -183,6 +186,55 @@ impl ReplLanguageServer {
       .collect()
   }
 
+  /// Gets the type of an expression, as inferred by the type checker in the
+  /// context of the text committed so far.
+  pub async fn type_of(&mut self, expression: &str) -> Option<String> {
+    const DECLARATION: &str = "const $deno$type";
+    self
+      .did_change(&format!("{DECLARATION} = ({expression});"))
+      .await;
+    let text_info = deno_ast::SourceTextInfo::from_string(format!(
+      "{}{}",
+      self.document_text, self.pending_text
+    ));
+    let position =
+      text_info.range().start + self.document_text.len() + DECLARATION.len();
+    let line_and_column = text_info.line_and_column_index(position);
+    let hover = self
+      .language_server
+      .hover(HoverParams {
+        text_document_position_params: TextDocumentPositionParams {
+          text_document: TextDocumentIdentifier {
+            uri: self.get_document_specifier(),
+          },
+          position: Position {
+            line: line_and_column.line_index as u32,
+            character: line_and_column.column_index as u32,
+          },
+        },
+        work_done_progress_params: WorkDoneProgressParams {
+          work_done_token: None,
+        },
+      })
+      .await
+      .ok()??;
+
+    // The quick info starts with the declaration, like
+    // "const $deno$type: number".
+    let declaration = match hover.contents {
+      HoverContents::Array(parts) => match parts.into_iter().next()? {
+        MarkedString::LanguageString(code) => code.value,
+        MarkedString::String(text) => text,
+      },
+      HoverContents::Scalar(MarkedString::LanguageString(code)) => code.value,
+      _ => return None,
+    };
+    declaration
+      .strip_prefix(DECLARATION)
+      .and_then(|type_text| type_text.strip_prefix(": "))
+      .map(ToString::to_string)
+  }
+
   async fn did_change(&mut self, new_text: &str) {
     self.check_cwd_change().await;
     let new_text = if new_text.ends_with('\n') {
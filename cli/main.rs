@@ -11,6 +11,7 @@ mod graph_util;
 mod http_util;
 mod js;
 mod lsp;
+mod module_hooks;
 mod module_loader;
 mod napi;
 mod node;
@@ -105,6 +106,10 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
       tools::installer::install_command(flags, install_flags).await?;
       Ok(0)
     }
+    DenoSubcommand::Jupyter(jupyter_flags) => {
+      tools::jupyter::kernel(flags, jupyter_flags).await?;
+      Ok(0)
+    }
     DenoSubcommand::Uninstall(uninstall_flags) => {
       tools::installer::uninstall(uninstall_flags.name, uninstall_flags.root)?;
       Ok(0)
@@ -123,6 +128,10 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
       }
       Ok(0)
     }
+    DenoSubcommand::Permissions(permissions_flags) => {
+      tools::permissions::permissions(flags, permissions_flags)?;
+      Ok(0)
+    }
     DenoSubcommand::Repl(repl_flags) => {
       tools::repl::run(flags, repl_flags).await
     }
@@ -0,0 +1,310 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! The customization hooks of `node:module`, registered with
+//! `module.register()`. Like in Node.js, the hooks run in a worker of their
+//! own, so the module loaders of the other workers can wait for them, even
+//! synchronously to resolve a specifier.
+
+use std::rc::Rc;
+use std::sync::Arc;
+use std::thread::ThreadId;
+
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::futures::channel::oneshot;
+use deno_core::futures::executor::block_on;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json::Value;
+use deno_core::ModuleSpecifier;
+use deno_runtime::colors;
+use deno_runtime::deno_node::NodeModuleHooks;
+use deno_runtime::permissions::Permissions;
+use deno_runtime::permissions::PermissionsContainer;
+use deno_runtime::tokio_util::run_local;
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use crate::ops;
+use crate::proc_state::ProcState;
+use crate::worker::create_main_worker_with_extensions;
+
+/// A request to the hooks, as it's passed to them.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ModuleHooksRequest {
+  Register {
+    specifier: String,
+    #[serde(rename = "parentURL")]
+    parent_url: String,
+    data: Option<Value>,
+  },
+  #[serde(rename_all = "camelCase")]
+  Resolve {
+    specifier: String,
+    #[serde(rename = "parentURL")]
+    parent_url: Option<String>,
+    is_require: bool,
+  },
+  #[serde(rename_all = "camelCase")]
+  Load {
+    url: String,
+    format: Option<String>,
+    is_require: bool,
+    is_dynamic: bool,
+    /// The permissions of the requesting module loader, checked by the
+    /// default `load` hook. `None` for `require()`, which checks them itself.
+    #[serde(skip)]
+    permissions: Option<LoaderPermissions>,
+  },
+}
+
+/// The root and dynamic permissions of a module loader.
+pub type LoaderPermissions = (PermissionsContainer, PermissionsContainer);
+
+pub enum ModuleHooksResponse {
+  Registered,
+  Resolved(String),
+  Loaded { format: String, source: Vec<u8> },
+}
+
+pub type ModuleHooksResult = Result<ModuleHooksResponse, AnyError>;
+
+pub type ModuleHooksMessage =
+  (ModuleHooksRequest, oneshot::Sender<ModuleHooksResult>);
+
+struct HooksWorker {
+  thread_id: ThreadId,
+  sender: mpsc::UnboundedSender<ModuleHooksMessage>,
+  /// The responses to the registrations not waited for yet.
+  registrations: Vec<oneshot::Receiver<ModuleHooksResult>>,
+}
+
+impl HooksWorker {
+  fn send(
+    &self,
+    request: ModuleHooksRequest,
+  ) -> Result<oneshot::Receiver<ModuleHooksResult>, AnyError> {
+    let (sender, receiver) = oneshot::channel();
+    self
+      .sender
+      .send((request, sender))
+      .map_err(|_| worker_terminated_error())?;
+    Ok(receiver)
+  }
+}
+
+/// The hooks of the process, shared by all its workers. The worker of the
+/// hooks is started when the first ones are registered.
+///
+/// Registering hooks doesn't block, the requests that follow are handled once
+/// the hooks are registered. Resolving blocks the requesting worker, which
+/// never deadlocks as the worker of the hooks doesn't need its runtime: the
+/// state they share is behind locks that are never held while waiting, and
+/// the worker of the hooks has HTTP connections and an npm resolution of its
+/// own.
+#[derive(Clone, Default)]
+pub struct ModuleHooks(Arc<Mutex<Option<HooksWorker>>>);
+
+impl ModuleHooks {
+  /// Whether hooks were registered. The modules of the worker of the hooks
+  /// aren't customized, including the hooks themselves.
+  pub fn is_active(&self) -> bool {
+    match &*self.0.lock() {
+      Some(worker) => worker.thread_id != std::thread::current().id(),
+      None => false,
+    }
+  }
+
+  pub fn register(
+    &self,
+    ps: &ProcState,
+    specifier: &str,
+    parent_url: &str,
+    data: Option<Value>,
+  ) -> Result<(), AnyError> {
+    let mut worker = self.0.lock();
+    if worker.is_none() {
+      let main_module = ModuleSpecifier::parse(parent_url)?;
+      *worker = Some(spawn_hooks_worker(ps.for_module_hooks(), main_module));
+    }
+    let worker = worker.as_mut().unwrap();
+    if worker.thread_id == std::thread::current().id() {
+      return Err(generic_error(
+        "module.register() can't be called by the hooks themselves",
+      ));
+    }
+    let registration = worker.send(ModuleHooksRequest::Register {
+      specifier: specifier.to_string(),
+      parent_url: parent_url.to_string(),
+      data,
+    })?;
+    worker.registrations.push(registration);
+    Ok(())
+  }
+
+  /// Waits for the hooks registered so far, and fails if any of them couldn't
+  /// be imported or initialized.
+  pub async fn wait_for_registrations(&self) -> Result<(), AnyError> {
+    let registrations = match &mut *self.0.lock() {
+      Some(worker) => std::mem::take(&mut worker.registrations),
+      None => return Ok(()),
+    };
+    for registration in registrations {
+      registration
+        .await
+        .unwrap_or_else(|_| Err(worker_terminated_error()))?;
+    }
+    Ok(())
+  }
+
+  /// Resolves `specifier` with the `resolve` hooks, blocking until they're
+  /// done. `parent_url` is `None` for the main module.
+  ///
+  /// The pending registrations are waited for first, so that hooks
+  /// registered right before, e.g. by `register()` followed by `require()`,
+  /// apply and their errors are reported.
+  pub fn resolve(
+    &self,
+    specifier: &str,
+    parent_url: Option<&str>,
+    is_require: bool,
+  ) -> Result<String, AnyError> {
+    let response = block_on(async {
+      self.wait_for_registrations().await?;
+      self
+        .request(ModuleHooksRequest::Resolve {
+          specifier: specifier.to_string(),
+          parent_url: parent_url.map(ToString::to_string),
+          is_require,
+        })
+        .await
+    })?;
+    match response {
+      ModuleHooksResponse::Resolved(url) => Ok(url),
+      _ => unreachable!(),
+    }
+  }
+
+  /// Loads the module at `url` with the `load` hooks, which resolves with its
+  /// format and source. The default `load` hook checks `permissions`.
+  pub async fn load(
+    &self,
+    url: String,
+    format: Option<String>,
+    is_require: bool,
+    is_dynamic: bool,
+    permissions: Option<LoaderPermissions>,
+  ) -> Result<(String, Vec<u8>), AnyError> {
+    let response = self
+      .request(ModuleHooksRequest::Load {
+        url,
+        format,
+        is_require,
+        is_dynamic,
+        permissions,
+      })
+      .await?;
+    match response {
+      ModuleHooksResponse::Loaded { format, source } => Ok((format, source)),
+      _ => unreachable!(),
+    }
+  }
+
+  async fn request(&self, request: ModuleHooksRequest) -> ModuleHooksResult {
+    let receiver = match &*self.0.lock() {
+      Some(worker) => worker.send(request)?,
+      None => return Err(worker_terminated_error()),
+    };
+    receiver
+      .await
+      .unwrap_or_else(|_| Err(worker_terminated_error()))
+  }
+}
+
+fn worker_terminated_error() -> AnyError {
+  generic_error("The worker of the module hooks terminated")
+}
+
+fn spawn_hooks_worker(
+  ps: ProcState,
+  main_module: ModuleSpecifier,
+) -> HooksWorker {
+  let (sender, receiver) = mpsc::unbounded_channel();
+  let handle = std::thread::Builder::new()
+    .name("module hooks".to_string())
+    .spawn(move || {
+      let result = run_local(run_hooks_worker(ps, main_module, receiver));
+      if let Err(err) = result {
+        eprintln!("{}: {:?}", colors::red_bold("error"), err);
+      }
+    })
+    .unwrap();
+  HooksWorker {
+    thread_id: handle.thread().id(),
+    sender,
+    registrations: Vec::new(),
+  }
+}
+
+async fn run_hooks_worker(
+  ps: ProcState,
+  main_module: ModuleSpecifier,
+  receiver: mpsc::UnboundedReceiver<ModuleHooksMessage>,
+) -> Result<(), AnyError> {
+  let permissions = PermissionsContainer::new(Permissions::from_options(
+    &ps.options.permissions_options(),
+  )?);
+  let extension = ops::module_hooks::init(receiver);
+  let mut worker = create_main_worker_with_extensions(
+    &ps,
+    main_module,
+    permissions,
+    vec![extension],
+  )
+  .await?;
+  worker.run_module_hooks().await
+}
+
+/// The hooks of the process, as seen by the `require()` of a worker.
+struct RequireModuleHooks(ProcState);
+
+impl NodeModuleHooks for RequireModuleHooks {
+  fn register(
+    &self,
+    specifier: &str,
+    parent_url: &str,
+    data: Option<Value>,
+  ) -> Result<(), AnyError> {
+    self
+      .0
+      .module_hooks
+      .register(&self.0, specifier, parent_url, data)
+  }
+
+  fn is_active(&self) -> bool {
+    self.0.module_hooks.is_active()
+  }
+
+  fn resolve_require(
+    &self,
+    specifier: &str,
+    parent_url: Option<&str>,
+  ) -> Result<String, AnyError> {
+    self.0.module_hooks.resolve(specifier, parent_url, true)
+  }
+
+  fn load_require(&self, url: &str, format: &str) -> Result<String, AnyError> {
+    let (_, source) = block_on(self.0.module_hooks.load(
+      url.to_string(),
+      Some(format.to_string()),
+      true,
+      false,
+      None,
+    ))?;
+    Ok(String::from_utf8(source)?)
+  }
+}
+
+pub fn node_module_hooks(ps: ProcState) -> Rc<dyn NodeModuleHooks> {
+  Rc::new(RequireModuleHooks(ps))
+}
@@ -2,6 +2,8 @@
 
 use crate::args::TsTypeLib;
 use crate::emit::emit_parsed_source;
+use crate::graph_util::asset_from_json;
+use crate::graph_util::AssetKind;
 use crate::node;
 use crate::proc_state::ProcState;
 use crate::util::text_encoding::code_without_source_map;
@@ -10,6 +12,7 @@ use crate::util::text_encoding::source_map_from_code;
 use deno_ast::MediaType;
 use deno_core::anyhow::anyhow;
 use deno_core::anyhow::Context;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::futures::future::FutureExt;
 use deno_core::futures::Future;
@@ -23,6 +26,7 @@ use deno_core::ResolutionKind;
 use deno_core::SourceMapGetter;
 use deno_runtime::permissions::PermissionsContainer;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::str;
@@ -43,6 +47,10 @@ pub struct CliModuleLoader {
   /// "root permissions" for Web Worker.
   dynamic_permissions: PermissionsContainer,
   pub ps: ProcState,
+  /// The specifiers resolved by the hooks of `module.register()`, by specifier
+  /// and referrer. Modules are resolved again when they're instantiated, and
+  /// the hooks are asked only once.
+  hooks_resolutions: RefCell<HashMap<(String, String), ModuleSpecifier>>,
 }
 
 impl CliModuleLoader {
@@ -56,6 +64,7 @@ impl CliModuleLoader {
       root_permissions,
       dynamic_permissions,
       ps,
+      hooks_resolutions: Default::default(),
     })
   }
 
@@ -69,6 +78,7 @@ impl CliModuleLoader {
       root_permissions,
       dynamic_permissions,
       ps,
+      hooks_resolutions: Default::default(),
     })
   }
 
@@ -136,12 +146,68 @@ impl CliModuleLoader {
     }
   }
 
+  /// Wasm modules are in the graph as their type declarations, so they are
+  /// read again from the file they were fetched from.
+  fn load_wasm_module(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<Result<ModuleSource, AnyError>> {
+    let found_url = self.ps.graph().get(specifier)?.specifier.clone();
+    let file = self.ps.file_fetcher.get_source(&found_url)?;
+    if file.media_type != MediaType::Wasm {
+      return None;
+    }
+    let result = std::fs::read(&file.local)
+      .with_context(|| format!("Unable to load {}", file.local.display()))
+      .map(|code| ModuleSource {
+        code: code.into_boxed_slice(),
+        module_url_specified: specifier.to_string(),
+        module_url_found: found_url.to_string(),
+        module_type: ModuleType::Wasm,
+      });
+    Some(result)
+  }
+
+  /// Modules imported as text or bytes are in the graph as JSON of their
+  /// contents, except for those in npm packages, which are read as they are.
+  fn load_raw_sync(
+    &self,
+    specifier: &ModuleSpecifier,
+    maybe_referrer: Option<ModuleSpecifier>,
+  ) -> Result<ModuleSource, AnyError> {
+    let (code, found_url) = if self.ps.npm_resolver.in_npm_package(specifier) {
+      let file_path = specifier.to_file_path().unwrap();
+      let code = std::fs::read(&file_path)
+        .with_context(|| format!("Unable to load {}", file_path.display()))?;
+      (code, specifier.clone())
+    } else {
+      let kind = AssetKind::from_graph_specifier(specifier).ok_or_else(|| {
+        generic_error(format!(
+          "Unable to load {specifier} as an asset, only imports with a string literal specifier are supported"
+        ))
+      })?;
+      let code_source = self.load_prepared_module(specifier, maybe_referrer)?;
+      let code = asset_from_json(kind, &code_source.code)
+        .with_context(|| format!("Unable to load {specifier} as an asset"))?;
+      (code, code_source.found_url)
+    };
+    Ok(ModuleSource {
+      code: code.into_boxed_slice(),
+      module_url_specified: specifier.to_string(),
+      module_url_found: found_url.to_string(),
+      module_type: ModuleType::Bytes,
+    })
+  }
+
   fn load_sync(
     &self,
     specifier: &ModuleSpecifier,
     maybe_referrer: Option<ModuleSpecifier>,
     is_dynamic: bool,
   ) -> Result<ModuleSource, AnyError> {
+    if let Some(result) = self.load_wasm_module(specifier) {
+      return result;
+    }
     let code_source = if self.ps.npm_resolver.in_npm_package(specifier) {
       let file_path = specifier.to_file_path().unwrap();
       let code = std::fs::read_to_string(&file_path).with_context(|| {
@@ -205,6 +271,82 @@ impl CliModuleLoader {
       },
     })
   }
+
+  fn resolve_with_hooks(
+    &self,
+    specifier: &str,
+    referrer: &str,
+    permissions: &mut PermissionsContainer,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    let key = (specifier.to_string(), referrer.to_string());
+    if let Some(resolved) = self.hooks_resolutions.borrow().get(&key) {
+      return Ok(resolved.clone());
+    }
+    // The main module is resolved from ".", without a parent.
+    let parent_url = if referrer == "." {
+      None
+    } else {
+      Some(referrer)
+    };
+    let url = self.ps.module_hooks.resolve(specifier, parent_url, false)?;
+    let resolved = if url.starts_with("node:") {
+      // Built-in modules are resolved to their polyfill.
+      self.ps.resolve(&url, referrer, permissions)?
+    } else {
+      ModuleSpecifier::parse(&url)?
+    };
+    self
+      .hooks_resolutions
+      .borrow_mut()
+      .insert(key, resolved.clone());
+    Ok(resolved)
+  }
+
+  fn load_with_hooks(
+    &self,
+    specifier: &ModuleSpecifier,
+    is_dynamic: bool,
+  ) -> Pin<Box<deno_core::ModuleSourceFuture>> {
+    let ps = self.ps.clone();
+    let specifier = specifier.clone();
+    let permissions = (
+      self.root_permissions.clone(),
+      self.dynamic_permissions.clone(),
+    );
+    async move {
+      ps.module_hooks.wait_for_registrations().await?;
+      let (format, source) = ps
+        .module_hooks
+        .load(
+          specifier.to_string(),
+          None,
+          false,
+          is_dynamic,
+          Some(permissions),
+        )
+        .await?;
+      // The hooks don't know about redirects, the default `load` hook
+      // followed them while preparing the module.
+      let found_url = ps.graph().resolve(&specifier);
+      let module_type = match format.as_str() {
+        "module" => ModuleType::JavaScript,
+        "json" => ModuleType::Json,
+        "wasm" => ModuleType::Wasm,
+        _ => {
+          return Err(anyhow!(
+            "Unsupported module format \"{format}\" of {specifier}"
+          ))
+        }
+      };
+      Ok(ModuleSource {
+        code: source.into_boxed_slice(),
+        module_url_specified: specifier.to_string(),
+        module_url_found: found_url.to_string(),
+        module_type,
+      })
+    }
+    .boxed_local()
+  }
 }
 
 impl ModuleLoader for CliModuleLoader {
@@ -219,6 +361,10 @@ impl ModuleLoader for CliModuleLoader {
     } else {
       self.root_permissions.clone()
     };
+    // The polyfills of built-in modules aren't customized.
+    if self.ps.module_hooks.is_active() && !referrer.starts_with("internal:") {
+      return self.resolve_with_hooks(specifier, referrer, &mut permissions);
+    }
     self.ps.resolve(specifier, referrer, &mut permissions)
   }
 
@@ -228,6 +374,9 @@ impl ModuleLoader for CliModuleLoader {
     maybe_referrer: Option<ModuleSpecifier>,
     is_dynamic: bool,
   ) -> Pin<Box<deno_core::ModuleSourceFuture>> {
+    if self.ps.module_hooks.is_active() {
+      return self.load_with_hooks(specifier, is_dynamic);
+    }
     // NOTE: this block is async only because of `deno_core` interface
     // requirements; module was already loaded when constructing module graph
     // during call to `prepare_load` so we can load it synchronously.
@@ -238,6 +387,17 @@ impl ModuleLoader for CliModuleLoader {
     )))
   }
 
+  fn load_raw(
+    &self,
+    specifier: &ModuleSpecifier,
+    maybe_referrer: Option<ModuleSpecifier>,
+    _is_dynamic: bool,
+  ) -> Pin<Box<deno_core::ModuleSourceFuture>> {
+    Box::pin(deno_core::futures::future::ready(
+      self.load_raw_sync(specifier, maybe_referrer),
+    ))
+  }
+
   fn prepare_load(
     &self,
     _op_state: Rc<RefCell<OpState>>,
@@ -245,7 +405,10 @@ impl ModuleLoader for CliModuleLoader {
     _maybe_referrer: Option<String>,
     is_dynamic: bool,
   ) -> Pin<Box<dyn Future<Output = Result<(), AnyError>>>> {
-    if self.ps.npm_resolver.in_npm_package(specifier) {
+    // With hooks, the default `load` hook prepares the modules it loads.
+    if self.ps.module_hooks.is_active()
+      || self.ps.npm_resolver.in_npm_package(specifier)
+    {
       // nothing to prepare
       return Box::pin(deno_core::futures::future::ready(Ok(())));
     }
@@ -26,6 +26,7 @@ use deno_runtime::deno_node::package_exports_resolve;
 use deno_runtime::deno_node::package_imports_resolve;
 use deno_runtime::deno_node::package_resolve;
 use deno_runtime::deno_node::path_to_declaration_path;
+use deno_runtime::deno_node::with_custom_conditions;
 use deno_runtime::deno_node::NodeModuleKind;
 use deno_runtime::deno_node::NodePermissions;
 use deno_runtime::deno_node::NodeResolutionMode;
@@ -211,7 +212,7 @@ pub fn node_resolve(
   let url = module_resolve(
     specifier,
     referrer,
-    DEFAULT_CONDITIONS,
+    &with_custom_conditions(DEFAULT_CONDITIONS, npm_resolver.conditions()),
     mode,
     npm_resolver,
     permissions,
@@ -257,7 +258,7 @@ pub fn node_resolve_npm_reference(
       .unwrap_or_else(|| ".".to_string()),
     &package_folder,
     node_module_kind,
-    DEFAULT_CONDITIONS,
+    &with_custom_conditions(DEFAULT_CONDITIONS, npm_resolver.conditions()),
     mode,
     npm_resolver,
     permissions,
@@ -672,7 +673,10 @@ pub fn translate_cjs_to_esm(
       &referrer,
       // FIXME(bartlomieju): check if these conditions are okay, probably
       // should be `deno-require`, because `deno` is already used in `esm_resolver.rs`
-      &["deno", "require", "default"],
+      &with_custom_conditions(
+        &["deno", "require", "default"],
+        npm_resolver.conditions(),
+      ),
       NodeResolutionMode::Execution,
       npm_resolver,
       permissions,
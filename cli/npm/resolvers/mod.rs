@@ -49,6 +49,7 @@ pub struct NpmPackageResolver {
   api: NpmRegistryApi,
   cache: NpmCache,
   maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
+  conditions: Vec<String>,
 }
 
 impl std::fmt::Debug for NpmPackageResolver {
@@ -57,13 +58,14 @@ impl std::fmt::Debug for NpmPackageResolver {
       .field("no_npm", &self.no_npm)
       .field("inner", &"<omitted>")
       .field("local_node_modules_path", &self.local_node_modules_path)
+      .field("conditions", &self.conditions)
       .finish()
   }
 }
 
 impl NpmPackageResolver {
   pub fn new(cache: NpmCache, api: NpmRegistryApi) -> Self {
-    Self::new_inner(cache, api, false, None, None, None)
+    Self::new_inner(cache, api, false, None, None, None, vec![])
   }
 
   pub async fn new_with_maybe_lockfile(
@@ -73,6 +75,7 @@ impl NpmPackageResolver {
     local_node_modules_path: Option<PathBuf>,
     initial_snapshot: Option<NpmResolutionSnapshot>,
     maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
+    conditions: Vec<String>,
   ) -> Result<Self, AnyError> {
     let mut initial_snapshot = initial_snapshot;
 
@@ -100,6 +103,7 @@ impl NpmPackageResolver {
       local_node_modules_path,
       initial_snapshot,
       maybe_lockfile,
+      conditions,
     ))
   }
 
@@ -110,6 +114,7 @@ impl NpmPackageResolver {
     local_node_modules_path: Option<PathBuf>,
     maybe_snapshot: Option<NpmResolutionSnapshot>,
     maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
+    conditions: Vec<String>,
   ) -> Self {
     let inner: Arc<dyn InnerNpmPackageResolver> = match &local_node_modules_path
     {
@@ -132,9 +137,16 @@ impl NpmPackageResolver {
       api,
       cache,
       maybe_lockfile,
+      conditions,
     }
   }
 
+  /// Sets the custom conditions to resolve the "exports" and "imports" of
+  /// packages with.
+  pub fn set_conditions(&mut self, conditions: Vec<String>) {
+    self.conditions = conditions;
+  }
+
   /// Resolves an npm package folder path from a Deno module.
   pub fn resolve_package_folder_from_deno_module(
     &self,
@@ -272,6 +284,21 @@ impl NpmPackageResolver {
       self.local_node_modules_path.clone(),
       Some(self.snapshot()),
       None,
+      self.conditions.clone(),
+    )
+  }
+
+  /// Gets a new resolver starting from the current snapshot, which resolves
+  /// packages on its own, but still updates the lockfile.
+  pub fn forked(&self) -> Self {
+    Self::new_inner(
+      self.cache.clone(),
+      self.api.clone(),
+      self.no_npm,
+      self.local_node_modules_path.clone(),
+      Some(self.snapshot()),
+      self.maybe_lockfile.clone(),
+      self.conditions.clone(),
     )
   }
 
@@ -334,6 +361,10 @@ impl RequireNpmResolver for NpmPackageResolver {
   ) -> Result<(), AnyError> {
     self.inner.ensure_read_permission(permissions, path)
   }
+
+  fn conditions(&self) -> &[String] {
+    &self.conditions
+  }
 }
 
 fn path_to_specifier(path: &Path) -> Result<ModuleSpecifier, AnyError> {
@@ -0,0 +1,73 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::futures::channel::mpsc::UnboundedSender;
+use deno_core::op;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::Extension;
+use deno_core::OpState;
+
+/// A message from the cell being executed, which the kernel publishes on its
+/// IOPub socket.
+#[derive(Debug)]
+pub struct JupyterBroadcast {
+  pub msg_type: String,
+  pub content: Value,
+}
+
+pub fn init(sender: UnboundedSender<JupyterBroadcast>) -> Extension {
+  Extension::builder("deno_jupyter")
+    .ops(vec![op_jupyter_broadcast::decl()])
+    .middleware(|op| match op.name {
+      "op_print" => op_print::decl(),
+      _ => op,
+    })
+    .state(move |state| {
+      state.put(sender.clone());
+      Ok(())
+    })
+    .build()
+}
+
+#[op]
+fn op_jupyter_broadcast(
+  state: &mut OpState,
+  msg_type: String,
+  content: Value,
+) -> Result<(), AnyError> {
+  if !matches!(
+    msg_type.as_str(),
+    "display_data" | "update_display_data" | "clear_output"
+  ) {
+    return Err(type_error(format!(
+      "Unsupported Jupyter message type \"{msg_type}\""
+    )));
+  }
+  broadcast(state, msg_type, content);
+  Ok(())
+}
+
+/// Replaces the builtin `op_print`, so the output of `console.log()` shows
+/// up in the notebook.
+#[op]
+fn op_print(
+  state: &mut OpState,
+  msg: String,
+  is_err: bool,
+) -> Result<(), AnyError> {
+  let name = if is_err { "stderr" } else { "stdout" };
+  broadcast(
+    state,
+    "stream".to_string(),
+    json!({ "name": name, "text": msg }),
+  );
+  Ok(())
+}
+
+fn broadcast(state: &mut OpState, msg_type: String, content: Value) {
+  let sender = state.borrow::<UnboundedSender<JupyterBroadcast>>();
+  // The receiver is only dropped once the kernel shuts down.
+  let _ = sender.unbounded_send(JupyterBroadcast { msg_type, content });
+}
@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::module_hooks::node_module_hooks;
 use crate::proc_state::ProcState;
 use deno_core::error::AnyError;
 use deno_core::op;
@@ -7,6 +8,8 @@ use deno_core::Extension;
 use deno_core::OpState;
 
 pub mod bench;
+pub mod jupyter;
+pub mod module_hooks;
 pub mod testing;
 
 pub fn cli_exts(ps: ProcState) -> Vec<Extension> {
@@ -17,6 +20,7 @@ fn init_proc_state(ps: ProcState) -> Extension {
   Extension::builder("deno_cli")
     .ops(vec![op_npm_process_state::decl()])
     .state(move |state| {
+      state.put(node_module_hooks(ps.clone()));
       state.put(ps.clone());
       Ok(())
     })
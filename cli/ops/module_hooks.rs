@@ -0,0 +1,200 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Ops of the worker of the `node:module` hooks, which serve the requests of
+//! the other workers and back the default `resolve` and `load` hooks.
+
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::futures::channel::oneshot;
+use deno_core::op;
+use deno_core::AsyncRefCell;
+use deno_core::Extension;
+use deno_core::ModuleLoader;
+use deno_core::ModuleSpecifier;
+use deno_core::ModuleType;
+use deno_core::OpState;
+use deno_core::RcRef;
+use deno_core::StringOrBuffer;
+use deno_core::ZeroCopyBuf;
+use deno_runtime::deno_node::SUPPORTED_BUILTIN_NODE_MODULES;
+use deno_runtime::permissions::PermissionsContainer;
+use serde::Serialize;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::module_hooks::LoaderPermissions;
+use crate::module_hooks::ModuleHooksMessage;
+use crate::module_hooks::ModuleHooksRequest;
+use crate::module_hooks::ModuleHooksResponse;
+use crate::module_hooks::ModuleHooksResult;
+use crate::module_loader::CliModuleLoader;
+use crate::proc_state::ProcState;
+
+pub fn init(receiver: UnboundedReceiver<ModuleHooksMessage>) -> Extension {
+  let requests = Rc::new(ModuleHooksRequests {
+    receiver: AsyncRefCell::new(receiver),
+    pending: Default::default(),
+    permissions: Default::default(),
+    next_id: Default::default(),
+  });
+  Extension::builder("deno_module_hooks")
+    .ops(vec![
+      op_module_hooks_next_request::decl(),
+      op_module_hooks_registered::decl(),
+      op_module_hooks_resolved::decl(),
+      op_module_hooks_loaded::decl(),
+      op_module_hooks_failed::decl(),
+      op_module_hooks_default_resolve::decl(),
+      op_module_hooks_default_load::decl(),
+    ])
+    .state(move |state| {
+      state.put(requests.clone());
+      Ok(())
+    })
+    .build()
+}
+
+struct ModuleHooksRequests {
+  receiver: AsyncRefCell<UnboundedReceiver<ModuleHooksMessage>>,
+  /// The senders of the responses to the requests being handled.
+  pending: RefCell<HashMap<u32, oneshot::Sender<ModuleHooksResult>>>,
+  /// The permissions of the loaders requesting the loads being handled.
+  permissions: RefCell<HashMap<u32, LoaderPermissions>>,
+  next_id: Cell<u32>,
+}
+
+/// Waits for the next request to the hooks, or `None` once nothing can
+/// request them anymore.
+#[op]
+async fn op_module_hooks_next_request(
+  state: Rc<RefCell<OpState>>,
+) -> Option<(u32, ModuleHooksRequest)> {
+  let requests = state.borrow().borrow::<Rc<ModuleHooksRequests>>().clone();
+  let mut receiver = RcRef::map(&requests, |r| &r.receiver).borrow_mut().await;
+  let (mut request, sender) = receiver.recv().await?;
+  let id = requests.next_id.get();
+  requests.next_id.set(id.wrapping_add(1));
+  requests.pending.borrow_mut().insert(id, sender);
+  if let ModuleHooksRequest::Load { permissions, .. } = &mut request {
+    if let Some(permissions) = permissions.take() {
+      requests.permissions.borrow_mut().insert(id, permissions);
+    }
+  }
+  Some((id, request))
+}
+
+fn respond(state: &mut OpState, id: u32, result: ModuleHooksResult) {
+  let requests = state.borrow::<Rc<ModuleHooksRequests>>();
+  requests.permissions.borrow_mut().remove(&id);
+  if let Some(sender) = requests.pending.borrow_mut().remove(&id) {
+    // The requester may have gone away in the meantime.
+    let _ = sender.send(result);
+  }
+}
+
+#[op]
+fn op_module_hooks_registered(state: &mut OpState, id: u32) {
+  respond(state, id, Ok(ModuleHooksResponse::Registered));
+}
+
+#[op]
+fn op_module_hooks_resolved(state: &mut OpState, id: u32, url: String) {
+  respond(state, id, Ok(ModuleHooksResponse::Resolved(url)));
+}
+
+#[op]
+fn op_module_hooks_loaded(
+  state: &mut OpState,
+  id: u32,
+  format: String,
+  source: StringOrBuffer,
+) {
+  let source = source.to_vec();
+  respond(
+    state,
+    id,
+    Ok(ModuleHooksResponse::Loaded { format, source }),
+  );
+}
+
+#[op]
+fn op_module_hooks_failed(state: &mut OpState, id: u32, message: String) {
+  respond(state, id, Err(generic_error(message)));
+}
+
+/// Resolves an imported specifier as the main worker does. Built-in modules
+/// are resolved to their `node:` specifier, rather than to their polyfill.
+#[op]
+fn op_module_hooks_default_resolve(
+  state: &mut OpState,
+  specifier: String,
+  parent_url: Option<String>,
+) -> Result<String, AnyError> {
+  let ps = state.borrow::<ProcState>().clone();
+  let mut permissions = state.borrow::<PermissionsContainer>().clone();
+  let resolved = ps.resolve(
+    &specifier,
+    parent_url.as_deref().unwrap_or("."),
+    &mut permissions,
+  )?;
+  let builtin = SUPPORTED_BUILTIN_NODE_MODULES
+    .iter()
+    .find(|module| module.specifier == resolved.as_str());
+  Ok(match builtin {
+    Some(module) => format!("node:{}", module.name),
+    None => resolved.to_string(),
+  })
+}
+
+#[derive(Serialize)]
+struct LoadedModule {
+  format: &'static str,
+  source: ZeroCopyBuf,
+}
+
+/// Loads an imported module as the main worker does, without hooks, but with
+/// the permissions of the loader that requested the load `id`. TypeScript is
+/// emitted as JavaScript and CommonJS of npm packages translated to ES modules.
+#[op]
+async fn op_module_hooks_default_load(
+  state: Rc<RefCell<OpState>>,
+  id: u32,
+  url: String,
+  is_dynamic: bool,
+) -> Result<LoadedModule, AnyError> {
+  let specifier = ModuleSpecifier::parse(&url)?;
+  let loader = {
+    let state = state.borrow();
+    let requests = state.borrow::<Rc<ModuleHooksRequests>>();
+    let permissions = requests.permissions.borrow();
+    let (root_permissions, dynamic_permissions) =
+      permissions.get(&id).ok_or_else(|| {
+        generic_error(format!("The load of {url} isn't pending anymore"))
+      })?;
+    CliModuleLoader::new(
+      state.borrow::<ProcState>().clone(),
+      root_permissions.clone(),
+      dynamic_permissions.clone(),
+    )
+  };
+  loader
+    .prepare_load(state.clone(), &specifier, None, is_dynamic)
+    .await?;
+  let source = loader.load(&specifier, None, is_dynamic).await?;
+  let format = match source.module_type {
+    ModuleType::JavaScript => "module",
+    ModuleType::Json => "json",
+    ModuleType::Wasm => "wasm",
+    ModuleType::Text | ModuleType::Bytes => {
+      return Err(generic_error(format!("Unsupported module type of {url}")))
+    }
+  };
+  Ok(LoadedModule {
+    format,
+    source: source.code.into_vec().into(),
+  })
+}
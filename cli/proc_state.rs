@@ -19,7 +19,9 @@ use crate::emit::emit_parsed_source;
 use crate::file_fetcher::FileFetcher;
 use crate::graph_util::graph_lock_or_exit;
 use crate::graph_util::graph_valid_with_cli_options;
+use crate::graph_util::AssetImports;
 use crate::http_util::HttpClient;
+use crate::module_hooks::ModuleHooks;
 use crate::node;
 use crate::node::NodeResolution;
 use crate::npm::resolve_graph_npm_info;
@@ -95,6 +97,7 @@ pub struct Inner {
   pub npm_cache: NpmCache,
   pub npm_resolver: NpmPackageResolver,
   pub cjs_resolutions: Mutex<HashSet<ModuleSpecifier>>,
+  pub module_hooks: ModuleHooks,
   progress_bar: ProgressBar,
 }
 
@@ -156,11 +159,45 @@ impl ProcState {
       npm_cache: self.npm_cache.clone(),
       npm_resolver: self.npm_resolver.clone(),
       cjs_resolutions: Default::default(),
+      module_hooks: Default::default(),
       progress_bar: self.progress_bar.clone(),
     });
     self.init_watcher();
   }
 
+  /// The state of the worker of the module hooks. It resolves npm packages on
+  /// its own, as the other workers may be blocked waiting for the hooks while
+  /// resolving some.
+  pub fn for_module_hooks(&self) -> Self {
+    Self(Arc::new(Inner {
+      dir: self.dir.clone(),
+      options: self.options.clone(),
+      emit_cache: self.emit_cache.clone(),
+      emit_options_hash: self.emit_options_hash,
+      emit_options: self.emit_options.clone(),
+      file_fetcher: self.file_fetcher.clone(),
+      http_client: self.http_client.clone(),
+      graph_data: self.graph_data.clone(),
+      lockfile: self.lockfile.clone(),
+      maybe_import_map: self.maybe_import_map.clone(),
+      maybe_inspector_server: self.maybe_inspector_server.clone(),
+      root_cert_store: self.root_cert_store.clone(),
+      blob_store: self.blob_store.clone(),
+      broadcast_channel: self.broadcast_channel.clone(),
+      shared_array_buffer_store: self.shared_array_buffer_store.clone(),
+      compiled_wasm_module_store: self.compiled_wasm_module_store.clone(),
+      parsed_source_cache: self.parsed_source_cache.clone(),
+      resolver: self.resolver.clone(),
+      maybe_file_watcher_reporter: self.maybe_file_watcher_reporter.clone(),
+      node_analysis_cache: self.node_analysis_cache.clone(),
+      npm_cache: self.npm_cache.clone(),
+      npm_resolver: self.npm_resolver.forked(),
+      cjs_resolutions: Default::default(),
+      module_hooks: self.module_hooks.clone(),
+      progress_bar: self.progress_bar.clone(),
+    }))
+  }
+
   // Add invariant files like the import map and explicit watch flag list to
   // the watcher. Dedup for build_for_file_watcher and reset_for_file_watcher.
   fn init_watcher(&self) {
@@ -214,7 +251,7 @@ impl ProcState {
       .await?
       .map(Arc::new);
     let maybe_inspector_server =
-      cli_options.resolve_inspector_server().map(Arc::new);
+      cli_options.resolve_inspector_server()?.map(Arc::new);
 
     let maybe_package_json_deps = cli_options.maybe_package_json_deps()?;
     let package_json_reqs = if let Some(deps) = &maybe_package_json_deps {
@@ -266,6 +303,7 @@ impl ProcState {
         .with_context(|| "Resolving local node_modules folder.")?,
       cli_options.get_npm_resolution_snapshot(),
       lockfile.as_ref().cloned(),
+      cli_options.node_conditions()?,
     )
     .await?;
     npm_resolver.add_package_reqs(package_json_reqs).await?;
@@ -299,6 +337,7 @@ impl ProcState {
       npm_cache,
       npm_resolver,
       cjs_resolutions: Default::default(),
+      module_hooks: Default::default(),
       progress_bar,
     })))
   }
@@ -334,7 +373,8 @@ impl ProcState {
         None
       };
 
-    let analyzer = self.parsed_source_cache.as_analyzer();
+    let asset_imports =
+      AssetImports::new(self.parsed_source_cache.as_analyzer(), resolver);
 
     log::debug!("Creating module graph.");
     let mut graph = self.graph_data.read().graph_inner_clone();
@@ -351,8 +391,8 @@ impl ProcState {
         deno_graph::BuildOptions {
           is_dynamic,
           imports: maybe_imports,
-          resolver: Some(resolver),
-          module_analyzer: Some(&*analyzer),
+          resolver: Some(&asset_imports),
+          module_analyzer: Some(&asset_imports),
           reporter: maybe_file_watcher_reporter,
         },
       )
@@ -655,7 +695,8 @@ impl ProcState {
       None,
     );
     let graph_resolver = cli_resolver.as_graph_resolver();
-    let analyzer = self.parsed_source_cache.as_analyzer();
+    let asset_imports =
+      AssetImports::new(self.parsed_source_cache.as_analyzer(), graph_resolver);
 
     let mut graph = ModuleGraph::default();
     graph
@@ -665,8 +706,8 @@ impl ProcState {
         deno_graph::BuildOptions {
           is_dynamic: false,
           imports: maybe_imports,
-          resolver: Some(graph_resolver),
-          module_analyzer: Some(&*analyzer),
+          resolver: Some(&asset_imports),
+          module_analyzer: Some(&asset_imports),
           reporter: None,
         },
       )
@@ -4,6 +4,8 @@ use crate::args::CaData;
 use crate::args::Flags;
 use crate::colors;
 use crate::file_fetcher::get_source_from_data_url;
+use crate::graph_util::asset_from_json;
+use crate::graph_util::AssetKind;
 use crate::ops;
 use crate::proc_state::ProcState;
 use crate::util::v8::construct_v8_flags;
@@ -32,6 +34,7 @@ use deno_runtime::fmt_errors::format_js_error;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsContainer;
 use deno_runtime::permissions::PermissionsOptions;
+use deno_runtime::resource_limits::ResourceLimits;
 use deno_runtime::worker::MainWorker;
 use deno_runtime::worker::WorkerOptions;
 use deno_runtime::BootstrapOptions;
@@ -49,6 +52,7 @@ pub struct Metadata {
   pub unstable: bool,
   pub seed: Option<u64>,
   pub permissions: PermissionsOptions,
+  pub resource_limits: ResourceLimits,
   pub location: Option<Url>,
   pub v8_flags: Vec<String>,
   pub log_level: Option<Level>,
@@ -195,6 +199,41 @@ impl ModuleLoader for EmbeddedModuleLoader {
     }
     .boxed_local()
   }
+
+  fn load_raw(
+    &self,
+    module_specifier: &ModuleSpecifier,
+    _maybe_referrer: Option<ModuleSpecifier>,
+    _is_dynamic: bool,
+  ) -> Pin<Box<deno_core::ModuleSourceFuture>> {
+    // Assets are embedded under their specifiers in the module graph, and
+    // either kind has the contents of the module.
+    let module = [AssetKind::Bytes, AssetKind::Text]
+      .into_iter()
+      .find_map(|kind| {
+        let specifier = kind.to_graph_specifier(module_specifier);
+        Some((kind, self.eszip.get_module(specifier.as_str())?))
+      })
+      .ok_or_else(|| type_error("Module not found"));
+
+    let module_specifier = module_specifier.clone();
+    async move {
+      // Modules imported as text or bytes are JSON of their contents.
+      let (kind, module) = module?;
+      let code = module.source().await;
+      let code = std::str::from_utf8(&code)
+        .map_err(|_| type_error("Module source is not utf-8"))?;
+      let code = asset_from_json(kind, code)?;
+
+      Ok(deno_core::ModuleSource {
+        code: code.into_boxed_slice(),
+        module_type: deno_core::ModuleType::Bytes,
+        module_url_specified: module_specifier.to_string(),
+        module_url_found: module_specifier.to_string(),
+      })
+    }
+    .boxed_local()
+  }
 }
 
 fn metadata_to_flags(metadata: &Metadata) -> Flags {
@@ -299,6 +338,9 @@ pub async fn run(
     shared_array_buffer_store: None,
     compiled_wasm_module_store: None,
     stdio: Default::default(),
+    resource_limits: metadata.resource_limits,
+    heap_snapshot_near_limit: None,
+    ops_timeline: false,
   };
   let mut worker = MainWorker::bootstrap_from_options(
     main_module.clone(),
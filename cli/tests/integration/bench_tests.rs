@@ -148,13 +148,13 @@ itest!(no_prompt_with_denied_perms {
 });
 
 itest!(check_local_by_default {
-  args: "bench --quiet bench/check_local_by_default.ts",
+  args: "bench --allow-import --quiet bench/check_local_by_default.ts",
   output: "bench/check_local_by_default.out",
   http_server: true,
 });
 
 itest!(check_local_by_default2 {
-  args: "bench --quiet bench/check_local_by_default2.ts",
+  args: "bench --allow-import --quiet bench/check_local_by_default2.ts",
   output: "bench/check_local_by_default2.out",
   http_server: true,
   exit_code: 1,
@@ -249,6 +249,7 @@ fn bundle_dynamic_import() {
     .current_dir(util::testdata_path())
     .arg("run")
     .arg("--allow-net")
+    .arg("--allow-import")
     .arg("--quiet")
     .arg(&output_path)
     .output()
@@ -66,7 +66,7 @@ itest!(ip_address_unsafe_ssl {
 
 itest!(localhost_unsafe_ssl {
     args:
-      "run --quiet --reload --allow-net --unsafely-ignore-certificate-errors=deno.land cert/cafile_url_imports.ts",
+      "run --allow-import --quiet --reload --allow-net --unsafely-ignore-certificate-errors=deno.land cert/cafile_url_imports.ts",
     output: "cert/localhost_unsafe_ssl.ts.out",
     http_server: true,
     exit_code: 1,
@@ -166,6 +166,7 @@ fn cafile_install_remote_module() {
     .arg(temp_dir.path())
     .arg("-n")
     .arg("echo_test")
+    .arg("--allow-import")
     .arg("https://localhost:5545/echo.ts")
     .output()
     .expect("Failed to spawn script");
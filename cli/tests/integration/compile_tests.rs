@@ -194,6 +194,37 @@ fn standalone_load_datauri() {
   assert_eq!(output.stdout, b"Hello Deno!\n");
 }
 
+#[test]
+fn standalone_text_and_bytes_imports() {
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir.path().join("text_bytes_import.exe")
+  } else {
+    dir.path().join("text_bytes_import")
+  };
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("compile")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./import_assertions/text_bytes_import.ts")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let output = Command::new(exe)
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(output.stdout, b"SELECT 1;\nUint8Array(3) [ 0, 159, 255 ]\n");
+}
+
 // https://github.com/denoland/deno/issues/13704
 #[test]
 fn standalone_follow_redirects() {
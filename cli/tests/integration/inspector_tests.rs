@@ -1382,3 +1382,141 @@ async fn inspector_wait() {
   assert!(temp_dir.path().join("hello.txt").exists());
   tester.child.kill().unwrap();
 }
+
+#[tokio::test]
+async fn inspector_auto_attach_worker() {
+  let script = util::testdata_path().join("inspector/inspect_worker.js");
+  let child = util::deno_cmd()
+    .arg("run")
+    .arg("--quiet")
+    .arg("-A")
+    .arg(inspect_flag_with_unique_port("--inspect-wait"))
+    .arg(script)
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+
+  let mut tester = InspectorTester::create(child, ignore_script_parsed).await;
+
+  tester.assert_stderr_for_inspect_brk();
+  tester
+    .send_many(&[
+      json!({
+        "id": 1,
+        "method": "Target.setAutoAttach",
+        "params": {
+          "autoAttach": true,
+          "waitForDebuggerOnStart": true,
+          "flatten": true,
+        },
+      }),
+      json!({"id":2,"method":"Runtime.runIfWaitingForDebugger"}),
+    ])
+    .await;
+
+  // The worker might be created before or after auto-attach is enabled.
+  let event = loop {
+    let msg = tester.recv_as_json().await;
+    if msg["method"] == "Target.attachedToTarget" {
+      break msg;
+    }
+    assert_eq!(msg["result"], json!({}), "{msg}");
+  };
+  assert_eq!(event["params"]["targetInfo"]["type"], "worker");
+  assert_eq!(event["params"]["waitingForDebugger"], true);
+  let session_id = event["params"]["sessionId"].as_str().unwrap().to_string();
+
+  tester
+    .send(json!({
+      "id": 3,
+      "sessionId": session_id,
+      "method": "Runtime.runIfWaitingForDebugger",
+    }))
+    .await;
+  let response = loop {
+    let msg = tester.recv_as_json().await;
+    if msg["id"] == 3 {
+      break msg;
+    }
+  };
+  assert_eq!(response["sessionId"], session_id.as_str());
+  assert_eq!(response["result"], json!({}));
+  assert_eq!(&tester.stdout_line(), "hello from worker");
+  tester.child.kill().unwrap();
+}
+
+#[tokio::test]
+async fn inspector_network_fetch() {
+  let _server = http_server();
+  let script = util::testdata_path().join("inspector/inspect_fetch.js");
+  let child = util::deno_cmd()
+    .arg("run")
+    .arg("--quiet")
+    .arg("-A")
+    .arg(inspect_flag_with_unique_port("--inspect-wait"))
+    .arg(script)
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+
+  let mut tester = InspectorTester::create(child, ignore_script_parsed).await;
+
+  tester.assert_stderr_for_inspect_brk();
+  tester
+    .send_many(&[
+      json!({"id":1,"method":"Network.enable"}),
+      json!({"id":2,"method":"Runtime.runIfWaitingForDebugger"}),
+    ])
+    .await;
+
+  let mut request_id = None;
+  let mut status = None;
+  loop {
+    let msg = tester.recv_as_json().await;
+    match msg["method"].as_str() {
+      Some("Network.requestWillBeSent") => {
+        assert_eq!(
+          msg["params"]["request"]["url"],
+          "http://localhost:4545/run/001_hello.js"
+        );
+        assert_eq!(msg["params"]["request"]["method"], "GET");
+        request_id = Some(msg["params"]["requestId"].clone());
+      }
+      Some("Network.responseReceived") => {
+        assert_eq!(msg["params"]["requestId"], request_id.clone().unwrap());
+        status = Some(msg["params"]["response"]["status"].clone());
+      }
+      Some("Network.loadingFinished") => {
+        assert_eq!(msg["params"]["requestId"], request_id.clone().unwrap());
+        break;
+      }
+      _ => {}
+    }
+  }
+  assert_eq!(status, Some(json!(200)));
+  assert_eq!(&tester.stdout_line(), "fetched");
+
+  tester
+    .send(json!({
+      "id": 3,
+      "method": "Network.getResponseBody",
+      "params": { "requestId": request_id.unwrap() },
+    }))
+    .await;
+  let response = loop {
+    let msg = tester.recv_as_json().await;
+    if msg["id"] == 3 {
+      break msg;
+    }
+  };
+  assert_eq!(
+    response["result"],
+    json!({
+      "body": "console.log(\"Hello World\");\n",
+      "base64Encoded": false,
+    })
+  );
+  tester.child.kill().unwrap();
+}
@@ -177,6 +177,7 @@ fn installer_test_remote_module_run() {
     .arg("echo_test")
     .arg("--root")
     .arg(temp_dir.path())
+    .arg("--allow-import")
     .arg("http://localhost:4545/echo.ts")
     .arg("hello")
     .spawn()
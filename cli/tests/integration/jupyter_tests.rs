@@ -0,0 +1,185 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use bytes::Bytes;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use std::net::TcpListener;
+use test_util as util;
+use test_util::TempDir;
+use zeromq::DealerSocket;
+use zeromq::Socket;
+use zeromq::SocketRecv;
+use zeromq::SocketSend;
+use zeromq::ZmqMessage;
+
+#[test]
+fn jupyter_install_kernelspec() {
+  let temp_dir = TempDir::new();
+  let data_dir = temp_dir.path().join("jupyter");
+
+  let output = util::deno_cmd()
+    .current_dir(temp_dir.path())
+    .arg("jupyter")
+    .arg("--install")
+    .env("JUPYTER_DATA_DIR", &data_dir)
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+
+  let kernel_json = std::fs::read_to_string(
+    data_dir.join("kernels").join("deno").join("kernel.json"),
+  )
+  .unwrap();
+  let kernel_json: Value = serde_json::from_str(&kernel_json).unwrap();
+  assert_eq!(kernel_json["display_name"], "Deno");
+  assert_eq!(kernel_json["language"], "typescript");
+  assert_eq!(
+    kernel_json["argv"].as_array().unwrap()[1..],
+    ["jupyter", "--kernel", "--conn", "{connection_file}"]
+  );
+}
+
+fn unused_port() -> u16 {
+  TcpListener::bind("127.0.0.1:0")
+    .unwrap()
+    .local_addr()
+    .unwrap()
+    .port()
+}
+
+/// Connects to a socket of the kernel, which may still be starting up.
+async fn connect(port: u16) -> DealerSocket {
+  let endpoint = format!("tcp://127.0.0.1:{port}");
+  for _ in 0..100 {
+    let mut socket = DealerSocket::new();
+    if socket.connect(&endpoint).await.is_ok() {
+      return socket;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+  }
+  panic!("Could not connect to {endpoint}");
+}
+
+async fn request(
+  socket: &mut DealerSocket,
+  msg_type: &str,
+  content: Value,
+) -> (String, Value) {
+  let header = json!({
+    "msg_id": msg_type,
+    "session": "test",
+    "username": "test",
+    "date": "",
+    "msg_type": msg_type,
+    "version": "5.3",
+  });
+  let frames = vec![
+    Bytes::from("<IDS|MSG>"),
+    // Unsigned, the connection file has no key.
+    Bytes::from(""),
+    Bytes::from(header.to_string()),
+    Bytes::from("{}"),
+    Bytes::from("{}"),
+    Bytes::from(content.to_string()),
+  ];
+  socket
+    .send(ZmqMessage::try_from(frames).unwrap())
+    .await
+    .unwrap();
+  receive(socket).await
+}
+
+async fn receive(socket: &mut DealerSocket) -> (String, Value) {
+  let frames = socket.recv().await.unwrap().into_vec();
+  let delimiter = frames
+    .iter()
+    .position(|frame| frame.as_ref() == b"<IDS|MSG>")
+    .unwrap();
+  let header: Value = serde_json::from_slice(&frames[delimiter + 2]).unwrap();
+  let content: Value = serde_json::from_slice(&frames[delimiter + 5]).unwrap();
+  (header["msg_type"].as_str().unwrap().to_string(), content)
+}
+
+fn execute_request(code: &str) -> Value {
+  json!({
+    "code": code,
+    "silent": false,
+    "store_history": true,
+    "user_expressions": {},
+    "allow_stdin": false,
+  })
+}
+
+#[tokio::test]
+async fn jupyter_kernel_execute_request() {
+  let temp_dir = TempDir::new();
+  let shell_port = unused_port();
+  let control_port = unused_port();
+  let conn_file = temp_dir.path().join("connection.json");
+  std::fs::write(
+    &conn_file,
+    json!({
+      "ip": "127.0.0.1",
+      "transport": "tcp",
+      "control_port": control_port,
+      "shell_port": shell_port,
+      "stdin_port": unused_port(),
+      "hb_port": unused_port(),
+      "iopub_port": unused_port(),
+      "signature_scheme": "hmac-sha256",
+      "key": "",
+    })
+    .to_string(),
+  )
+  .unwrap();
+  let mut child = util::deno_cmd()
+    .current_dir(temp_dir.path())
+    .arg("jupyter")
+    .arg("--kernel")
+    .arg("--conn")
+    .arg(&conn_file)
+    .spawn()
+    .unwrap();
+  let mut shell = connect(shell_port).await;
+  let mut control = connect(control_port).await;
+
+  let (msg_type, content) =
+    request(&mut shell, "execute_request", execute_request("1 + 2")).await;
+  assert_eq!(msg_type, "execute_reply");
+  assert_eq!(content["status"], "ok");
+  assert_eq!(content["execution_count"], 1);
+
+  // Cells only have the permissions granted by flags.
+  let code = "Deno.readTextFileSync('connection.json')";
+  let (_, content) =
+    request(&mut shell, "execute_request", execute_request(code)).await;
+  assert_eq!(content["status"], "error");
+  assert_eq!(content["ename"], "PermissionDenied");
+
+  // A cell that never returns is interrupted through the control socket.
+  let code = execute_request("while (true) {}");
+  let running = request(&mut shell, "execute_request", code);
+  let interrupt = async {
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    request(&mut control, "interrupt_request", json!({})).await
+  };
+  let ((_, content), (msg_type, _)) = tokio::join!(running, interrupt);
+  assert_eq!(msg_type, "interrupt_reply");
+  assert_eq!(content["status"], "error");
+  assert_eq!(content["ename"], "Interrupted");
+
+  let (_, content) =
+    request(&mut shell, "execute_request", execute_request("3")).await;
+  assert_eq!(content["status"], "ok");
+  assert_eq!(content["execution_count"], 4);
+
+  let (msg_type, _) = request(
+    &mut control,
+    "shutdown_request",
+    json!({ "restart": false }),
+  )
+  .await;
+  assert_eq!(msg_type, "shutdown_reply");
+  assert!(child.wait().unwrap().success());
+}
@@ -66,6 +66,8 @@ mod inspector;
 mod install;
 #[path = "js_unit_tests.rs"]
 mod js_unit_tests;
+#[path = "jupyter_tests.rs"]
+mod jupyter;
 #[path = "lint_tests.rs"]
 mod lint;
 #[path = "lsp_tests.rs"]
@@ -76,6 +78,8 @@ mod node_compat_tests;
 mod node_unit_tests;
 #[path = "npm_tests.rs"]
 mod npm;
+#[path = "permissions_tests.rs"]
+mod permissions;
 #[path = "repl_tests.rs"]
 mod repl;
 #[path = "run_tests.rs"]
@@ -107,6 +107,27 @@ itest!(conditional_exports_node_modules_dir {
     temp_cwd: true,
   });
 
+itest!(custom_conditions {
+  args: "run --allow-read --quiet npm/custom_conditions/main.js",
+  output: "npm/custom_conditions/production.out",
+  envs: env_vars_for_npm_tests(),
+  http_server: true,
+});
+
+itest!(custom_conditions_flag {
+  args: "run --allow-read --quiet --conditions=development npm/custom_conditions/main.js",
+  output: "npm/custom_conditions/development.out",
+  envs: env_vars_for_npm_tests(),
+  http_server: true,
+});
+
+itest!(custom_conditions_config {
+  args: "run --allow-read --quiet --config npm/custom_conditions/deno.json npm/custom_conditions/main.js",
+  output: "npm/custom_conditions/development.out",
+  envs: env_vars_for_npm_tests(),
+  http_server: true,
+});
+
 itest!(dual_cjs_esm {
   args: "run -A --quiet npm/dual_cjs_esm/main.ts",
   output: "npm/dual_cjs_esm/main.out",
@@ -217,7 +238,7 @@ itest!(sub_paths {
 });
 
 itest!(remote_npm_specifier {
-  args: "run --quiet npm/remote_npm_specifier/main.ts",
+  args: "run --allow-import --quiet npm/remote_npm_specifier/main.ts",
   output: "npm/remote_npm_specifier/main.out",
   envs: env_vars_for_npm_tests(),
   http_server: true,
@@ -0,0 +1,117 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::process::Stdio;
+use test_util as util;
+use test_util::TempDir;
+use util::assert_contains;
+
+fn run_permissions_subcommand(
+  cwd: &TempDir,
+  deno_dir: &TempDir,
+  args: &[&str],
+) -> (bool, String) {
+  let output = util::deno_cmd_with_deno_dir(deno_dir)
+    .current_dir(cwd.path())
+    .env("NO_COLOR", "1")
+    .arg("permissions")
+    .args(args)
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  (
+    output.status.success(),
+    String::from_utf8(output.stderr).unwrap(),
+  )
+}
+
+#[test]
+fn permissions_list_and_revoke() {
+  let temp_dir = TempDir::new();
+  let deno_dir = util::new_deno_dir();
+  std::fs::write(temp_dir.path().join("deno.json"), "{}").unwrap();
+
+  let (success, stderr) =
+    run_permissions_subcommand(&temp_dir, &deno_dir, &["list"]);
+  assert!(success);
+  assert_contains!(stderr, "No permissions are remembered for this project.");
+
+  let (success, stderr) =
+    run_permissions_subcommand(&temp_dir, &deno_dir, &["revoke", "read"]);
+  assert!(success);
+  assert_contains!(stderr, "Revoked 0 remembered permissions");
+}
+
+#[test]
+fn permissions_without_config_file() {
+  let temp_dir = TempDir::new();
+  let deno_dir = util::new_deno_dir();
+
+  let (success, stderr) =
+    run_permissions_subcommand(&temp_dir, &deno_dir, &["list", "--no-config"]);
+  assert!(!success);
+  assert_contains!(
+    stderr,
+    "Permissions are only remembered for projects with a configuration file"
+  );
+}
+
+#[test]
+fn permissions_always_allow_is_remembered() {
+  if !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stderr) {
+    eprintln!("Ignoring non-tty environment.");
+    return;
+  }
+
+  let temp_dir = TempDir::new();
+  let deno_dir = util::new_deno_dir();
+  temp_dir.write("deno.json", "{}");
+  temp_dir.write("data.txt", "data");
+  temp_dir.write("other.txt", "other");
+  temp_dir.write(
+    "main.js",
+    r#"console.log(Deno.readTextFileSync("data.txt"));
+try {
+  Deno.readTextFileSync("other.txt");
+} catch (err) {
+  console.log(err.name);
+}
+"#,
+  );
+
+  let mut env_vars = std::collections::HashMap::new();
+  env_vars.insert("NO_COLOR".to_string(), "1".to_string());
+  env_vars.insert(
+    "DENO_DIR".to_string(),
+    deno_dir.path().to_string_lossy().to_string(),
+  );
+  let mut console = util::pty::create_pty(
+    util::deno_exe_path(),
+    &["run", "--quiet", "main.js"],
+    temp_dir.path(),
+    Some(env_vars),
+  );
+  console.write_line("a");
+  let output = console.read_all_output();
+  assert_contains!(output, "a = always allow in this project");
+  assert_contains!(output, "b = always allow all of");
+  assert_contains!(output, "also for future runs of this project");
+  assert_contains!(output, "data");
+  drop(console);
+
+  // Only the requested file was remembered, not its directory. Without a tty
+  // the other file is denied instead of prompted for.
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(temp_dir.path())
+    .args(["run", "--quiet", "main.js"])
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert_eq!(stdout, "data\nPermissionDenied\n");
+}
@@ -980,3 +980,79 @@ fn npm_packages() {
     assert!(err.is_empty());
   }
 }
+
+#[test]
+fn save_and_load_commands() {
+  let temp_dir = TempDir::new();
+  let session_path = temp_dir.path().join("session.ts");
+  let save_command = format!(".save {}", session_path.display());
+  let (out, err) = util::run_and_collect_output(
+    true,
+    "repl",
+    Some(vec![
+      "const a: number = 1;",
+      "throw new Error('not saved')",
+      "let b = a + 1;",
+      &save_command,
+    ]),
+    Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+    false,
+  );
+  assert_contains!(out, "Saved 2 inputs to");
+  assert!(err.is_empty());
+  assert_eq!(
+    std::fs::read_to_string(&session_path).unwrap(),
+    "const a: number = 1;\nlet b = a + 1;\n"
+  );
+
+  let load_command = format!(".load {}", session_path.display());
+  let (out, err) = util::run_and_collect_output(
+    true,
+    "repl",
+    Some(vec![&load_command, "a + b"]),
+    Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+    false,
+  );
+  assert_ends_with!(out, "undefined\n3\n");
+  assert!(err.is_empty());
+}
+
+#[test]
+fn type_command() {
+  let (out, err) = util::run_and_collect_output(
+    true,
+    "repl",
+    Some(vec![
+      "const a = { foo: 'bar' };",
+      ".type a.foo",
+      ".type [1, 2]",
+    ]),
+    Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+    false,
+  );
+  assert_ends_with!(out, "string\nnumber[]\n");
+  assert!(err.is_empty());
+}
+
+#[test]
+fn check_flag() {
+  let (out, err) = util::run_and_collect_output(
+    true,
+    "repl --check",
+    Some(vec![
+      "const a: number = 1;",
+      "const b: string = a;",
+      "let c = 2;",
+      "let c = 3;",
+      "a + c",
+    ]),
+    Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+    false,
+  );
+  assert_contains!(
+    out,
+    "TS2322 [ERROR]: Type 'number' is not assignable to type 'string'."
+  );
+  assert_ends_with!(out, "4\n");
+  assert!(err.is_empty());
+}
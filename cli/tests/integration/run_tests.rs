@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
 use deno_core::url;
 use deno_runtime::deno_fetch::reqwest;
 use std::io::Read;
@@ -56,7 +57,7 @@ itest!(_005_more_imports {
 });
 
 itest!(_006_url_imports {
-  args: "run --quiet --reload run/006_url_imports.ts",
+  args: "run --allow-import --quiet --reload run/006_url_imports.ts",
   output: "run/006_url_imports.ts.out",
   http_server: true,
 });
@@ -108,7 +109,7 @@ itest!(_018_async_catch {
 });
 
 itest!(_019_media_types {
-  args: "run --reload run/019_media_types.ts",
+  args: "run --allow-import --reload run/019_media_types.ts",
   output: "run/019_media_types.ts.out",
   http_server: true,
 });
@@ -141,13 +142,13 @@ itest!(_025_reload_js_type_error {
 });
 
 itest!(_026_redirect_javascript {
-  args: "run --quiet --reload run/026_redirect_javascript.js",
+  args: "run --allow-import --quiet --reload run/026_redirect_javascript.js",
   output: "run/026_redirect_javascript.js.out",
   http_server: true,
 });
 
 itest!(_027_redirect_typescript {
-  args: "run --quiet --reload run/027_redirect_typescript.ts",
+  args: "run --allow-import --quiet --reload run/027_redirect_typescript.ts",
   output: "run/027_redirect_typescript.ts.out",
   http_server: true,
 });
@@ -177,14 +178,14 @@ itest!(_033_import_map_in_flag_has_precedence {
 
 itest!(_033_import_map_remote {
   args:
-    "run --quiet --reload --import-map=http://127.0.0.1:4545/import_maps/import_map_remote.json --unstable import_maps/test_remote.ts",
+    "run --allow-import --quiet --reload --import-map=http://127.0.0.1:4545/import_maps/import_map_remote.json --unstable import_maps/test_remote.ts",
   output: "run/033_import_map_remote.out",
   http_server: true,
 });
 
 itest!(_033_import_map_data_uri {
   args:
-    "run --quiet --reload --import-map=data:application/json;charset=utf-8;base64,ewogICJpbXBvcnRzIjogewogICAgInRlc3Rfc2VydmVyLyI6ICJodHRwOi8vbG9jYWxob3N0OjQ1NDUvIgogIH0KfQ== run/import_maps/test_data.ts",
+    "run --allow-import --quiet --reload --import-map=data:application/json;charset=utf-8;base64,ewogICJpbXBvcnRzIjogewogICAgInRlc3Rfc2VydmVyLyI6ICJodHRwOi8vbG9jYWxob3N0OjQ1NDUvIgogIH0KfQ== run/import_maps/test_data.ts",
   output: "run/import_maps/test_data.ts.out",
   http_server: true,
 });
@@ -195,7 +196,7 @@ itest!(onload {
 });
 
 itest!(_035_cached_only_flag {
-  args: "run --reload --check --cached-only http://127.0.0.1:4545/run/019_media_types.ts",
+  args: "run --allow-import --reload --check --cached-only http://127.0.0.1:4545/run/019_media_types.ts",
   output: "run/035_cached_only_flag.out",
   exit_code: 1,
   http_server: true,
@@ -238,14 +239,14 @@ itest!(_047_jsx {
 });
 
 itest!(_048_media_types_jsx {
-  args: "run  --reload run/048_media_types_jsx.ts",
+  args: "run --allow-import  --reload run/048_media_types_jsx.ts",
   output: "run/048_media_types_jsx.ts.out",
   http_server: true,
 });
 
 itest!(_052_no_remote_flag {
   args:
-    "run --reload --check --no-remote http://127.0.0.1:4545/run/019_media_types.ts",
+    "run --allow-import --reload --check --no-remote http://127.0.0.1:4545/run/019_media_types.ts",
   output: "run/052_no_remote_flag.out",
   exit_code: 1,
   http_server: true,
@@ -523,6 +524,7 @@ fn _083_legacy_external_source_map() {
     .env("DENO_DIR", deno_dir.path())
     .current_dir(util::testdata_path())
     .arg("run")
+    .arg("--allow-import")
     .arg(module_url.to_string())
     .output()
     .unwrap();
@@ -654,87 +656,89 @@ itest!(lock_write_fetch {
 
 itest!(lock_check_ok {
   args:
-    "run --lock=run/lock_check_ok.json http://127.0.0.1:4545/run/003_relative_import.ts",
+    "run --allow-import --lock=run/lock_check_ok.json http://127.0.0.1:4545/run/003_relative_import.ts",
   output: "run/003_relative_import.ts.out",
   http_server: true,
 });
 
 itest!(lock_check_ok2 {
-  args: "run --lock=run/lock_check_ok2.json run/019_media_types.ts",
+  args:
+    "run --allow-import --lock=run/lock_check_ok2.json run/019_media_types.ts",
   output: "run/019_media_types.ts.out",
   http_server: true,
 });
 
 itest!(lock_dynamic_imports {
-  args: "run --lock=run/lock_dynamic_imports.json --allow-read --allow-net http://127.0.0.1:4545/run/013_dynamic_import.ts",
+  args: "run --allow-import --lock=run/lock_dynamic_imports.json --allow-read --allow-net http://127.0.0.1:4545/run/013_dynamic_import.ts",
   output: "run/lock_dynamic_imports.out",
   exit_code: 10,
   http_server: true,
 });
 
 itest!(lock_check_err {
-  args: "run --lock=run/lock_check_err.json http://127.0.0.1:4545/run/003_relative_import.ts",
+  args: "run --allow-import --lock=run/lock_check_err.json http://127.0.0.1:4545/run/003_relative_import.ts",
   output: "run/lock_check_err.out",
   exit_code: 10,
   http_server: true,
 });
 
 itest!(lock_check_err2 {
-  args: "run --lock=run/lock_check_err2.json run/019_media_types.ts",
+  args:
+    "run --allow-import --lock=run/lock_check_err2.json run/019_media_types.ts",
   output: "run/lock_check_err2.out",
   exit_code: 10,
   http_server: true,
 });
 
 itest!(config_file_lock_path {
-  args: "run --config=run/config_file_lock_path.json run/019_media_types.ts",
+  args: "run --allow-import --config=run/config_file_lock_path.json run/019_media_types.ts",
   output: "run/config_file_lock_path.out",
   exit_code: 10,
   http_server: true,
 });
 
 itest!(lock_flag_overrides_config_file_lock_path {
-     args: "run --lock=run/lock_check_ok2.json --config=run/config_file_lock_path.json run/019_media_types.ts",
+     args: "run --allow-import --lock=run/lock_check_ok2.json --config=run/config_file_lock_path.json run/019_media_types.ts",
     output: "run/019_media_types.ts.out",
     http_server: true,
   });
 
 itest!(lock_v2_check_ok {
   args:
-    "run --lock=run/lock_v2_check_ok.json http://127.0.0.1:4545/run/003_relative_import.ts",
+    "run --allow-import --lock=run/lock_v2_check_ok.json http://127.0.0.1:4545/run/003_relative_import.ts",
   output: "run/003_relative_import.ts.out",
   http_server: true,
 });
 
 itest!(lock_v2_check_ok2 {
-  args: "run --lock=run/lock_v2_check_ok2.json run/019_media_types.ts",
+  args: "run --allow-import --lock=run/lock_v2_check_ok2.json run/019_media_types.ts",
   output: "run/019_media_types.ts.out",
   http_server: true,
 });
 
 itest!(lock_v2_dynamic_imports {
-  args: "run --lock=run/lock_v2_dynamic_imports.json --allow-read --allow-net http://127.0.0.1:4545/run/013_dynamic_import.ts",
+  args: "run --allow-import --lock=run/lock_v2_dynamic_imports.json --allow-read --allow-net http://127.0.0.1:4545/run/013_dynamic_import.ts",
   output: "run/lock_v2_dynamic_imports.out",
   exit_code: 10,
   http_server: true,
 });
 
 itest!(lock_v2_check_err {
-  args: "run --lock=run/lock_v2_check_err.json http://127.0.0.1:4545/run/003_relative_import.ts",
+  args: "run --allow-import --lock=run/lock_v2_check_err.json http://127.0.0.1:4545/run/003_relative_import.ts",
   output: "run/lock_v2_check_err.out",
   exit_code: 10,
   http_server: true,
 });
 
 itest!(lock_v2_check_err2 {
-  args: "run --lock=run/lock_v2_check_err2.json run/019_media_types.ts",
+  args: "run --allow-import --lock=run/lock_v2_check_err2.json run/019_media_types.ts",
   output: "run/lock_v2_check_err2.out",
   exit_code: 10,
   http_server: true,
 });
 
 itest!(lock_only_http_and_https {
-  args: "run --lock=run/lock_only_http_and_https/deno.lock run/lock_only_http_and_https/main.ts",
+  args: "run --allow-import --lock=run/lock_only_http_and_https/deno.lock run/lock_only_http_and_https/main.ts",
   output: "run/lock_only_http_and_https/main.out",
   http_server: true,
 });
@@ -769,7 +773,7 @@ itest!(config_types {
 
 itest!(config_types_remote {
     http_server: true,
-    args: "run --reload --quiet --config run/config_types/remote.tsconfig.json run/config_types/main.ts",
+    args: "run --allow-import --reload --quiet --config run/config_types/remote.tsconfig.json run/config_types/main.ts",
     output: "run/config_types/main.out",
   });
 
@@ -874,9 +878,39 @@ itest!(error_015_dynamic_import_permissions {
   http_server: true,
 });
 
+itest!(import_permission_static {
+  args: "run --reload --quiet run/import_permission_static.ts",
+  output: "run/import_permission_static.ts.out",
+  exit_code: 1,
+  http_server: true,
+});
+
+itest!(import_permission_static_allowed {
+  args: "run --reload --quiet --allow-import=localhost:4545 run/import_permission_static.ts",
+  output: "run/import_permission_static_allowed.out",
+  http_server: true,
+});
+
+itest!(permission_scopes {
+  args: "run --quiet --allow-read --config run/permission_scopes/deno.json run/permission_scopes/main.ts",
+  output: "run/permission_scopes/main.out",
+});
+
+itest!(max_heap_size {
+  args: "run --quiet --max-heap-size=16 run/max_heap_size.js",
+  output: "run/max_heap_size.js.out",
+  exit_code: 1,
+});
+
+itest!(max_cpu_time {
+  args: "run --quiet --max-cpu-time=100 run/max_cpu_time.js",
+  output: "run/max_cpu_time.js.out",
+  exit_code: 1,
+});
+
 // We have an allow-net flag but not allow-read, it should still result in error.
 itest!(error_016_dynamic_import_permissions2 {
-  args: "run --reload --allow-net run/error_016_dynamic_import_permissions2.js",
+  args: "run --allow-import --reload --allow-net run/error_016_dynamic_import_permissions2.js",
   output: "run/error_016_dynamic_import_permissions2.out",
   exit_code: 1,
   http_server: true,
@@ -937,7 +971,7 @@ itest!(error_025_tab_indent {
 });
 
 itest!(error_026_remote_import_error {
-  args: "run run/error_026_remote_import_error.ts",
+  args: "run --allow-import run/error_026_remote_import_error.ts",
   output: "run/error_026_remote_import_error.ts.out",
   exit_code: 1,
   http_server: true,
@@ -980,14 +1014,14 @@ itest!(error_type_definitions {
 });
 
 itest!(error_local_static_import_from_remote_ts {
-    args: "run --reload http://localhost:4545/run/error_local_static_import_from_remote.ts",
+    args: "run --allow-import --reload http://localhost:4545/run/error_local_static_import_from_remote.ts",
     exit_code: 1,
     http_server: true,
     output: "run/error_local_static_import_from_remote.ts.out",
   });
 
 itest!(error_local_static_import_from_remote_js {
-    args: "run --reload http://localhost:4545/run/error_local_static_import_from_remote.js",
+    args: "run --allow-import --reload http://localhost:4545/run/error_local_static_import_from_remote.js",
     exit_code: 1,
     http_server: true,
     output: "run/error_local_static_import_from_remote.js.out",
@@ -1041,7 +1075,7 @@ itest!(finalization_registry {
 });
 
 itest!(https_import {
-  args: "run --quiet --reload --cert tls/RootCA.pem run/https_import.ts",
+  args: "run --allow-import --quiet --reload --cert tls/RootCA.pem run/https_import.ts",
   output: "run/https_import.ts.out",
   http_server: true,
 });
@@ -1062,7 +1096,7 @@ itest!(main_module {
 });
 
 itest!(no_check {
-  args: "run --quiet --reload --no-check run/006_url_imports.ts",
+  args: "run --allow-import --quiet --reload --no-check run/006_url_imports.ts",
   output: "run/006_url_imports.ts.out",
   http_server: true,
 });
@@ -1073,14 +1107,15 @@ itest!(no_check_decorators {
 });
 
 itest!(check_remote {
-  args: "run --quiet --reload --check=all run/no_check_remote.ts",
+  args:
+    "run --allow-import --quiet --reload --check=all run/no_check_remote.ts",
   output: "run/no_check_remote.ts.disabled.out",
   exit_code: 1,
   http_server: true,
 });
 
 itest!(no_check_remote {
-  args: "run --quiet --reload --no-check=remote run/no_check_remote.ts",
+  args: "run --allow-import --quiet --reload --no-check=remote run/no_check_remote.ts",
   output: "run/no_check_remote.ts.enabled.out",
   http_server: true,
 });
@@ -1107,7 +1142,8 @@ itest!(type_definitions_for_export {
 });
 
 itest!(type_directives_01 {
-  args: "run --reload --check=all -L debug run/type_directives_01.ts",
+  args:
+    "run --allow-import --reload --check=all -L debug run/type_directives_01.ts",
   output: "run/type_directives_01.ts.out",
   http_server: true,
 });
@@ -1124,13 +1160,13 @@ itest!(type_directives_js_main {
 });
 
 itest!(type_directives_redirect {
-  args: "run --reload --check run/type_directives_redirect.ts",
+  args: "run --allow-import --reload --check run/type_directives_redirect.ts",
   output: "run/type_directives_redirect.ts.out",
   http_server: true,
 });
 
 itest!(type_headers_deno_types {
-  args: "run --reload --check run/type_headers_deno_types.ts",
+  args: "run --allow-import --reload --check run/type_headers_deno_types.ts",
   output: "run/type_headers_deno_types.ts.out",
   http_server: true,
 });
@@ -1223,6 +1259,17 @@ itest!(wasm_url {
   http_server: true,
 });
 
+itest!(wasm_module_import {
+  args: "run --quiet --check run/wasm_module/main.ts",
+  output: "run/wasm_module/main.out",
+});
+
+itest!(wasm_module_import_type_error {
+  args: "run --quiet --check run/wasm_module/type_error.ts",
+  output: "run/wasm_module/type_error.out",
+  exit_code: 1,
+});
+
 itest!(weakref {
   args: "run --quiet --reload run/weakref.ts",
   output: "run/weakref.ts.out",
@@ -1303,20 +1350,20 @@ itest!(unstable_worker {
 });
 
 itest!(import_compression {
-  args: "run --quiet --reload --allow-net run/import_compression/main.ts",
+  args: "run --allow-import --quiet --reload --allow-net run/import_compression/main.ts",
   output: "run/import_compression/main.out",
   http_server: true,
 });
 
 itest!(disallow_http_from_https_js {
-  args: "run --quiet --reload --cert tls/RootCA.pem https://localhost:5545/run/disallow_http_from_https.js",
+  args: "run --allow-import --quiet --reload --cert tls/RootCA.pem https://localhost:5545/run/disallow_http_from_https.js",
   output: "run/disallow_http_from_https_js.out",
   http_server: true,
   exit_code: 1,
 });
 
 itest!(disallow_http_from_https_ts {
-  args: "run --quiet --reload --cert tls/RootCA.pem https://localhost:5545/run/disallow_http_from_https.ts",
+  args: "run --allow-import --quiet --reload --cert tls/RootCA.pem https://localhost:5545/run/disallow_http_from_https.ts",
   output: "run/disallow_http_from_https_ts.out",
   http_server: true,
   exit_code: 1,
@@ -1373,7 +1420,7 @@ itest!(cjs_imports {
 });
 
 itest!(ts_import_from_js {
-  args: "run --quiet --reload run/ts_import_from_js/main.js",
+  args: "run --allow-import --quiet --reload run/ts_import_from_js/main.js",
   output: "run/ts_import_from_js/main.out",
   http_server: true,
 });
@@ -1417,37 +1464,37 @@ itest!(jsx_import_source_no_pragma_dev {
 });
 
 itest!(jsx_import_source_pragma_import_map {
-  args: "run --reload --import-map jsx/import-map.json run/jsx_import_source_pragma_import_map.tsx",
+  args: "run --allow-import --reload --import-map jsx/import-map.json run/jsx_import_source_pragma_import_map.tsx",
   output: "run/jsx_import_source_import_map.out",
   http_server: true,
 });
 
 itest!(jsx_import_source_pragma_import_map_dev {
-  args: "run --reload --import-map jsx/import-map.json --config jsx/deno-jsxdev-import-map.jsonc run/jsx_import_source_pragma_import_map.tsx",
+  args: "run --allow-import --reload --import-map jsx/import-map.json --config jsx/deno-jsxdev-import-map.jsonc run/jsx_import_source_pragma_import_map.tsx",
   output: "run/jsx_import_source_import_map_dev.out",
   http_server: true,
 });
 
 itest!(jsx_import_source_import_map {
-  args: "run --reload --import-map jsx/import-map.json --no-lock --config jsx/deno-jsx-import-map.jsonc run/jsx_import_source_no_pragma.tsx",
+  args: "run --allow-import --reload --import-map jsx/import-map.json --no-lock --config jsx/deno-jsx-import-map.jsonc run/jsx_import_source_no_pragma.tsx",
   output: "run/jsx_import_source_import_map.out",
   http_server: true,
 });
 
 itest!(jsx_import_source_import_map_dev {
-  args: "run --reload --import-map jsx/import-map.json --no-lock --config jsx/deno-jsxdev-import-map.jsonc run/jsx_import_source_no_pragma.tsx",
+  args: "run --allow-import --reload --import-map jsx/import-map.json --no-lock --config jsx/deno-jsxdev-import-map.jsonc run/jsx_import_source_no_pragma.tsx",
   output: "run/jsx_import_source_import_map_dev.out",
   http_server: true,
 });
 
 itest!(jsx_import_source_import_map_scoped {
-  args: "run --reload --import-map jsx/import-map-scoped.json --no-lock --config jsx/deno-jsx-import-map.jsonc subdir/jsx_import_source_no_pragma.tsx",
+  args: "run --allow-import --reload --import-map jsx/import-map-scoped.json --no-lock --config jsx/deno-jsx-import-map.jsonc subdir/jsx_import_source_no_pragma.tsx",
   output: "run/jsx_import_source_import_map.out",
   http_server: true,
 });
 
 itest!(jsx_import_source_import_map_scoped_dev {
-  args: "run --reload --import-map jsx/import-map-scoped.json --no-lock --config jsx/deno-jsxdev-import-map.jsonc subdir/jsx_import_source_no_pragma.tsx",
+  args: "run --allow-import --reload --import-map jsx/import-map-scoped.json --no-lock --config jsx/deno-jsxdev-import-map.jsonc subdir/jsx_import_source_no_pragma.tsx",
   output: "run/jsx_import_source_import_map_dev.out",
   http_server: true,
 });
@@ -1472,13 +1519,13 @@ itest!(jsx_import_source_no_pragma_no_check {
 });
 
 itest!(jsx_import_source_pragma_import_map_no_check {
-  args: "run --reload --import-map jsx/import-map.json --no-check run/jsx_import_source_pragma_import_map.tsx",
+  args: "run --allow-import --reload --import-map jsx/import-map.json --no-check run/jsx_import_source_pragma_import_map.tsx",
   output: "run/jsx_import_source_import_map.out",
   http_server: true,
 });
 
 itest!(jsx_import_source_import_map_no_check {
-  args: "run --reload --import-map jsx/import-map.json --no-lock --config jsx/deno-jsx-import-map.jsonc --no-check run/jsx_import_source_no_pragma.tsx",
+  args: "run --allow-import --reload --import-map jsx/import-map.json --no-lock --config jsx/deno-jsx-import-map.jsonc --no-check run/jsx_import_source_no_pragma.tsx",
   output: "run/jsx_import_source_import_map.out",
   http_server: true,
 });
@@ -1507,7 +1554,7 @@ itest!(reference_types {
 
 itest!(references_types_remote {
   http_server: true,
-  args: "run --reload --quiet run/reference_types_remote.ts",
+  args: "run --allow-import --reload --quiet run/reference_types_remote.ts",
   output: "run/reference_types_remote.ts.out",
 });
 
@@ -1541,7 +1588,7 @@ itest!(import_data_url_import_map {
   });
 
 itest!(import_data_url_imports {
-  args: "run --quiet --reload run/import_data_url_imports.ts",
+  args: "run --allow-import --quiet --reload run/import_data_url_imports.ts",
   output: "run/import_data_url_imports.ts.out",
   http_server: true,
 });
@@ -1575,7 +1622,7 @@ itest!(import_blob_url_import_relative {
 
 itest!(import_blob_url_imports {
   args:
-    "run --quiet --reload --allow-net=localhost:4545 run/import_blob_url_imports.ts",
+    "run --allow-import --quiet --reload --allow-net=localhost:4545 run/import_blob_url_imports.ts",
   output: "run/import_blob_url_imports.ts.out",
   http_server: true,
 });
@@ -1591,13 +1638,13 @@ itest!(import_blob_url {
 });
 
 itest!(import_file_with_colon {
-  args: "run --quiet --reload run/import_file_with_colon.ts",
+  args: "run --allow-import --quiet --reload run/import_file_with_colon.ts",
   output: "run/import_file_with_colon.ts.out",
   http_server: true,
 });
 
 itest!(import_extensionless {
-  args: "run --quiet --reload run/import_extensionless.ts",
+  args: "run --allow-import --quiet --reload run/import_extensionless.ts",
   output: "run/import_extensionless.ts.out",
   http_server: true,
 });
@@ -1643,7 +1690,7 @@ itest!(inline_js_source_map_2_with_inline_contents {
 // was not commented out. The source line is remapped using source contents that
 // from the module graph.
 itest!(inline_js_source_map_with_contents_from_graph {
-  args: "run --quiet run/inline_js_source_map_with_contents_from_graph.js",
+  args: "run --allow-import --quiet run/inline_js_source_map_with_contents_from_graph.js",
   output: "run/inline_js_source_map_with_contents_from_graph.js.out",
   exit_code: 1,
   http_server: true,
@@ -2413,35 +2460,35 @@ mod permissions {
   }
 
   itest!(dynamic_import_permissions_remote_remote {
-    args: "run --quiet --reload --allow-net=localhost:4545 dynamic_import/permissions_remote_remote.ts",
+    args: "run --quiet --reload --allow-import=localhost:4545 dynamic_import/permissions_remote_remote.ts",
     output: "dynamic_import/permissions_remote_remote.ts.out",
     http_server: true,
     exit_code: 1,
   });
 
   itest!(dynamic_import_permissions_data_remote {
-    args: "run --quiet --reload --allow-net=localhost:4545 dynamic_import/permissions_data_remote.ts",
+    args: "run --quiet --reload --allow-import=localhost:4545 dynamic_import/permissions_data_remote.ts",
     output: "dynamic_import/permissions_data_remote.ts.out",
     http_server: true,
     exit_code: 1,
   });
 
   itest!(dynamic_import_permissions_blob_remote {
-    args: "run --quiet --reload --allow-net=localhost:4545 dynamic_import/permissions_blob_remote.ts",
+    args: "run --quiet --reload --allow-import=localhost:4545 dynamic_import/permissions_blob_remote.ts",
     output: "dynamic_import/permissions_blob_remote.ts.out",
     http_server: true,
     exit_code: 1,
   });
 
   itest!(dynamic_import_permissions_data_local {
-    args: "run --quiet --reload --allow-net=localhost:4545 dynamic_import/permissions_data_local.ts",
+    args: "run --quiet --reload --allow-import=localhost:4545 dynamic_import/permissions_data_local.ts",
     output: "dynamic_import/permissions_data_local.ts.out",
     http_server: true,
     exit_code: 1,
   });
 
   itest!(dynamic_import_permissions_blob_local {
-    args: "run --quiet --reload --allow-net=localhost:4545 dynamic_import/permissions_blob_local.ts",
+    args: "run --quiet --reload --allow-import=localhost:4545 dynamic_import/permissions_blob_local.ts",
     output: "dynamic_import/permissions_blob_local.ts.out",
     http_server: true,
     exit_code: 1,
@@ -2661,6 +2708,27 @@ itest!(import_assertions_type_check {
   exit_code: 1,
 });
 
+itest!(import_assertions_text_bytes_import {
+  args: "run --check import_assertions/text_bytes_import.ts",
+  output: "import_assertions/text_bytes_import.out",
+});
+
+itest!(import_assertions_same_file_import {
+  args: "run --check import_assertions/same_file_import.ts",
+  output: "import_assertions/same_file_import.out",
+});
+
+itest!(import_assertions_text_bytes_dynamic_import {
+  args: "run --allow-read import_assertions/text_bytes_dynamic_import.ts",
+  output: "import_assertions/text_bytes_dynamic_import.out",
+});
+
+itest!(import_assertions_text_bytes_type_check {
+  args: "run --check import_assertions/text_bytes_type_check.ts",
+  output: "import_assertions/text_bytes_type_check.out",
+  exit_code: 1,
+});
+
 itest!(delete_window {
   args: "run run/delete_window.js",
   output_str: Some("true\n"),
@@ -2688,7 +2756,7 @@ itest!(no_config_auto_discovery_for_local_script {
 });
 
 itest!(config_not_auto_discovered_for_remote_script {
-  args: "run --quiet http://127.0.0.1:4545/run/with_config/server_side_work.ts",
+  args: "run --allow-import --quiet http://127.0.0.1:4545/run/with_config/server_side_work.ts",
   output_str: Some("ok\n"),
   http_server: true,
 });
@@ -2899,6 +2967,7 @@ fn check_local_then_remote() {
   let output = util::deno_cmd_with_deno_dir(&deno_dir)
     .current_dir(util::testdata_path())
     .arg("run")
+    .arg("--allow-import")
     .arg("--check")
     .arg("run/remote_type_error/main.ts")
     .spawn()
@@ -2909,6 +2978,7 @@ fn check_local_then_remote() {
   let output = util::deno_cmd_with_deno_dir(&deno_dir)
     .current_dir(util::testdata_path())
     .arg("run")
+    .arg("--allow-import")
     .arg("--check=all")
     .arg("run/remote_type_error/main.ts")
     .env("NO_COLOR", "1")
@@ -2995,7 +3065,8 @@ itest!(custom_inspect_url {
 });
 
 itest!(config_json_import {
-  args: "run --quiet -c jsx/deno-jsx.json run/config_json_import.ts",
+  args:
+    "run --allow-import --quiet -c jsx/deno-jsx.json run/config_json_import.ts",
   output: "run/config_json_import.ts.out",
   http_server: true,
 });
@@ -3089,6 +3160,7 @@ fn cache_test() {
     .env("NO_COLOR", "1")
     .current_dir(util::testdata_path())
     .arg("run")
+    .arg("--allow-import")
     .arg(module_url.to_string())
     .output()
     .expect("Failed to spawn script");
@@ -3262,6 +3334,7 @@ fn basic_auth_tokens() {
   let output = util::deno_cmd()
     .current_dir(util::root_path())
     .arg("run")
+    .arg("--allow-import")
     .arg("http://127.0.0.1:4554/run/001_hello.js")
     .stdout(std::process::Stdio::piped())
     .stderr(std::process::Stdio::piped())
@@ -3284,6 +3357,7 @@ fn basic_auth_tokens() {
   let output = util::deno_cmd()
     .current_dir(util::root_path())
     .arg("run")
+    .arg("--allow-import")
     .arg("http://127.0.0.1:4554/run/001_hello.js")
     .env("DENO_AUTH_TOKENS", "testuser123:testpassabc@127.0.0.1:4554")
     .stdout(std::process::Stdio::piped())
@@ -3773,28 +3847,28 @@ fn websocket_server_idletimeout() {
 }
 
 itest!(auto_discover_lockfile {
-  args: "run run/auto_discover_lockfile/main.ts",
+  args: "run --allow-import run/auto_discover_lockfile/main.ts",
   output: "run/auto_discover_lockfile/main.out",
   http_server: true,
   exit_code: 10,
 });
 
 itest!(no_lock_flag {
-  args: "run --no-lock run/no_lock_flag/main.ts",
+  args: "run --allow-import --no-lock run/no_lock_flag/main.ts",
   output: "run/no_lock_flag/main.out",
   http_server: true,
   exit_code: 0,
 });
 
 itest!(config_file_lock_false {
-  args: "run --config=run/config_file_lock_boolean/false.json run/config_file_lock_boolean/main.ts",
+  args: "run --allow-import --config=run/config_file_lock_boolean/false.json run/config_file_lock_boolean/main.ts",
   output: "run/config_file_lock_boolean/false.main.out",
   http_server: true,
   exit_code: 0,
 });
 
 itest!(config_file_lock_true {
-  args: "run --config=run/config_file_lock_boolean/true.json run/config_file_lock_boolean/main.ts",
+  args: "run --allow-import --config=run/config_file_lock_boolean/true.json run/config_file_lock_boolean/main.ts",
   output: "run/config_file_lock_boolean/true.main.out",
   http_server: true,
   exit_code: 10,
@@ -3857,6 +3931,63 @@ fn stdio_streams_are_locked_in_permission_prompt() {
   });
 }
 
+#[test]
+fn permission_prompt_allow_parent_dir() {
+  util::with_pty(
+    &["run", "--quiet", "run/permission_prompt_parent_dir/main.js"],
+    |mut console| {
+      console.write_line("p");
+      let output = console.read_all_output();
+      assert_contains!(output, "p = allow all of");
+      assert_contains!(output, "Granted read access to all of");
+      assert_contains!(output, "from a");
+      assert_contains!(output, "from b");
+    },
+  );
+}
+
+#[test]
+fn cpu_prof_and_heap_snapshot_on_exit() {
+  let temp_dir = TempDir::new();
+  temp_dir.write(
+    "main.js",
+    "let n = 0; for (let i = 0; i < 1e6; i++) n += i;",
+  );
+  let output = util::deno_cmd_with_deno_dir(&temp_dir)
+    .current_dir(temp_dir.path())
+    .args([
+      "run",
+      "--cpu-prof=profiles",
+      "--heap-snapshot-on-exit=profiles",
+      "main.js",
+    ])
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+
+  let files = std::fs::read_dir(temp_dir.path().join("profiles"))
+    .unwrap()
+    .map(|entry| entry.unwrap().path())
+    .collect::<Vec<_>>();
+  let find = |extension: &str| {
+    files
+      .iter()
+      .find(|path| path.extension().unwrap() == extension)
+      .unwrap()
+  };
+  let profile: serde_json::Value =
+    serde_json::from_str(&std::fs::read_to_string(find("cpuprofile")).unwrap())
+      .unwrap();
+  assert!(profile["nodes"].is_array());
+  let snapshot: serde_json::Value = serde_json::from_str(
+    &std::fs::read_to_string(find("heapsnapshot")).unwrap(),
+  )
+  .unwrap();
+  assert!(snapshot["snapshot"].is_object());
+}
+
 itest!(node_builtin_modules_ts {
   args: "run --quiet --allow-read run/node_builtin_modules/mod.ts hello there",
   output: "run/node_builtin_modules/mod.ts.out",
@@ -3889,3 +4020,33 @@ itest!(internal_dynamic_import {
   output: "run/internal_dynamic_import.ts.out",
   exit_code: 1,
 });
+
+itest!(module_hooks {
+  args: "run --quiet --allow-read --import=./run/module_hooks/register.mjs run/module_hooks/main.mjs",
+  output: "run/module_hooks/main.out",
+  exit_code: 0,
+});
+
+itest!(module_hooks_register_require {
+  args: "run --quiet --allow-read run/module_hooks/register_require.mjs",
+  output: "run/module_hooks/register_require.out",
+  exit_code: 0,
+});
+
+itest!(module_hooks_worker_permissions {
+  args: "run --quiet --allow-read run/module_hooks/worker_permissions.mjs",
+  output: "run/module_hooks/worker_permissions.out",
+  exit_code: 0,
+});
+
+itest!(module_hooks_errors {
+  args: "run --quiet --allow-read run/module_hooks/errors.mjs",
+  output: "run/module_hooks/errors.out",
+  exit_code: 0,
+});
+
+itest!(module_hooks_register_failed {
+  args: "run --quiet --allow-read run/module_hooks/register_failed.mjs",
+  output: "run/module_hooks/register_failed.out",
+  exit_code: 0,
+});
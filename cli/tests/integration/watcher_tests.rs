@@ -1223,3 +1223,60 @@ fn run_watch_dynamic_imports() {
 
   check_alive_then_kill(child);
 }
+
+#[test]
+fn run_watch_hmr() {
+  let t = TempDir::new();
+  let file_to_watch = t.path().join("file_to_watch.js");
+  let mod_file = t.path().join("mod.js");
+  write(
+    &file_to_watch,
+    r#"
+    import { greet } from "./mod.js";
+    addEventListener("hmr", (e) => {
+      console.log("hmr event", e.detail.path.endsWith("mod.js"));
+    });
+    setInterval(() => console.log(greet()), 100);
+    "#,
+  )
+  .unwrap();
+  write(&mod_file, r#"export function greet() { return "hello"; }"#).unwrap();
+
+  let mut child = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("run")
+    .arg("--watch-hmr")
+    .arg(&file_to_watch)
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+
+  wait_contains("Process started", &mut stderr_lines);
+  wait_contains("hello", &mut stdout_lines);
+
+  // A changed function body is replaced without restarting.
+  write(
+    &mod_file,
+    r#"export function greet() { return "goodbye"; }"#,
+  )
+  .unwrap();
+  wait_contains("HMR Replaced", &mut stderr_lines);
+  wait_contains("hmr event true", &mut stdout_lines);
+  wait_contains("goodbye", &mut stdout_lines);
+
+  // Top-level changes can't be replaced and restart the process.
+  write(
+    &mod_file,
+    r#"export function greet() { return "restarted"; }
+    console.log("top-level");"#,
+  )
+  .unwrap();
+  wait_contains("Restarting", &mut stderr_lines);
+  wait_contains("top-level", &mut stdout_lines);
+  wait_contains("restarted", &mut stdout_lines);
+
+  check_alive_then_kill(child);
+}
@@ -25,6 +25,16 @@ itest!(worker_async_error {
   exit_code: 1,
 });
 
+itest!(worker_resource_limits {
+  args: "run --quiet --reload --unstable workers/resource_limits.ts",
+  output: "workers/resource_limits.ts.out",
+});
+
+itest!(worker_resource_limits_inherited {
+  args: "run --quiet --reload --unstable --max-cpu-time=1000 workers/resource_limits_inherited.ts",
+  output: "workers/resource_limits_inherited.ts.out",
+});
+
 itest!(worker_message_handler_error {
   args: "run -A --quiet --reload workers/worker_message_handler_error.ts",
   output: "workers/worker_message_handler_error.ts.out",
@@ -57,42 +67,42 @@ itest!(error_worker_permissions_remote {
 });
 
 itest!(worker_permissions_remote_remote {
-    args: "run --quiet --reload --allow-net=localhost:4545 workers/permissions_remote_remote.ts",
+    args: "run --quiet --reload --allow-import=localhost:4545 workers/permissions_remote_remote.ts",
     output: "workers/permissions_remote_remote.ts.out",
     http_server: true,
     exit_code: 1,
   });
 
 itest!(worker_permissions_dynamic_remote {
-    args: "run --quiet --reload --allow-net --unstable workers/permissions_dynamic_remote.ts",
+    args: "run --quiet --reload --allow-net --allow-import=localhost:4545 --unstable workers/permissions_dynamic_remote.ts",
     output: "workers/permissions_dynamic_remote.ts.out",
     http_server: true,
     exit_code: 1,
   });
 
 itest!(worker_permissions_data_remote {
-    args: "run --quiet --reload --allow-net=localhost:4545 workers/permissions_data_remote.ts",
+    args: "run --quiet --reload --allow-import=localhost:4545 workers/permissions_data_remote.ts",
     output: "workers/permissions_data_remote.ts.out",
     http_server: true,
     exit_code: 1,
   });
 
 itest!(worker_permissions_blob_remote {
-    args: "run --quiet --reload --allow-net=localhost:4545 workers/permissions_blob_remote.ts",
+    args: "run --quiet --reload --allow-import=localhost:4545 workers/permissions_blob_remote.ts",
     output: "workers/permissions_blob_remote.ts.out",
     http_server: true,
     exit_code: 1,
   });
 
 itest!(worker_permissions_data_local {
-    args: "run --quiet --reload --allow-net=localhost:4545 workers/permissions_data_local.ts",
+    args: "run --quiet --reload --allow-import=localhost:4545 workers/permissions_data_local.ts",
     output: "workers/permissions_data_local.ts.out",
     http_server: true,
     exit_code: 1,
   });
 
 itest!(worker_permissions_blob_local {
-    args: "run --quiet --reload --allow-net=localhost:4545 workers/permissions_blob_local.ts",
+    args: "run --quiet --reload --allow-import=localhost:4545 workers/permissions_blob_local.ts",
     output: "workers/permissions_blob_local.ts.out",
     http_server: true,
     exit_code: 1,
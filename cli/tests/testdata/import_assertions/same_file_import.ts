@@ -0,0 +1,7 @@
+import data from "./data.json" assert { type: "json" };
+import text from "./data.json" assert { type: "text" };
+import bytes from "./data.json" assert { type: "bytes" };
+
+console.log(data.c.d);
+console.log(text === JSON.stringify(data, null, 2) + "\n");
+console.log(bytes.length === text.length);
@@ -0,0 +1,9 @@
+const { default: query } = await import("./query.sql", {
+  assert: { type: "text" },
+});
+const { default: data } = await import("./data.bin", {
+  assert: { type: "bytes" },
+});
+
+console.log(query.trim());
+console.log(data);
@@ -0,0 +1,7 @@
+import query from "./query.sql" assert { type: "text" };
+import data from "./data.bin" assert { type: "bytes" };
+
+const text: string = query;
+const bytes: Uint8Array = data;
+console.log(text.trim());
+console.log(bytes);
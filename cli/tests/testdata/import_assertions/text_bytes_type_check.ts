@@ -0,0 +1,4 @@
+import data from "./data.bin" assert { type: "bytes" };
+
+const text: string = data;
+console.log(text);
@@ -0,0 +1,3 @@
+const res = await fetch("http://localhost:4545/run/001_hello.js");
+await res.text();
+console.log("fetched");
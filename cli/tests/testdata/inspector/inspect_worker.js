@@ -0,0 +1,3 @@
+new Worker(new URL("./inspect_worker_child.js", import.meta.url), {
+  type: "module",
+});
@@ -0,0 +1,2 @@
+console.log("hello from worker");
+self.close();
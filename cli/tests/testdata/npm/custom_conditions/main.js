@@ -0,0 +1,5 @@
+import { mode } from "npm:@denotest/custom-conditions";
+import required from "npm:@denotest/custom-conditions/required";
+
+console.log(mode);
+console.log(required.mode);
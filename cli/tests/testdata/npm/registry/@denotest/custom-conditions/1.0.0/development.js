@@ -0,0 +1 @@
+export const mode = "development";
@@ -0,0 +1 @@
+export declare const mode: "development" | "production";
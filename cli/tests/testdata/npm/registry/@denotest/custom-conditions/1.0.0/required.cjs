@@ -0,0 +1 @@
+module.exports = require("@denotest/custom-conditions/mode.cjs");
@@ -0,0 +1,3 @@
+import { printHello } from "http://localhost:4545/subdir/print_hello.ts";
+
+printHello();
@@ -0,0 +1,3 @@
+while (true) {
+  // Spin until the program is terminated.
+}
@@ -0,0 +1,4 @@
+let s = "";
+while (true) {
+  s += "Hello";
+}
@@ -0,0 +1,13 @@
+import { register } from "node:module";
+
+register("./hooks_errors.mjs", import.meta.url);
+
+for (const specifier of ["virtual:throw", "virtual:no_short_circuit"]) {
+  try {
+    await import(specifier);
+  } catch (err) {
+    console.log(err.message);
+  }
+}
+const { default: data } = await import("./data.mjs");
+console.log(data);
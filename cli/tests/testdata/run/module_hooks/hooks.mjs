@@ -0,0 +1,32 @@
+let name;
+
+export function initialize(data) {
+  name = data.name;
+  console.log(`initialize ${name}`);
+}
+
+export async function resolve(specifier, context, nextResolve) {
+  if (specifier === "virtual:greeting") {
+    return { url: specifier, shortCircuit: true };
+  }
+  if (specifier === "virtual:cjs") {
+    return nextResolve("./cjs.js", context);
+  }
+  return nextResolve(specifier, context);
+}
+
+export async function load(url, context, nextLoad) {
+  if (url === "virtual:greeting") {
+    return {
+      format: "module",
+      source: `export default "hello from ${name}";`,
+      shortCircuit: true,
+    };
+  }
+  const result = await nextLoad(url, context);
+  if (url.endsWith("/cjs.js")) {
+    const source = String(result.source).replace("commonjs", "the hooks");
+    return { ...result, source };
+  }
+  return result;
+}
@@ -0,0 +1,16 @@
+export async function resolve(specifier, context, nextResolve) {
+  if (specifier === "virtual:throw") {
+    throw new Error("the resolve hook failed");
+  }
+  if (specifier === "virtual:no_short_circuit") {
+    return { url: specifier, shortCircuit: true };
+  }
+  return nextResolve(specifier, context);
+}
+
+export async function load(url, context, nextLoad) {
+  if (url === "virtual:no_short_circuit") {
+    return { format: "module", source: "export default 1;" };
+  }
+  return nextLoad(url, context);
+}
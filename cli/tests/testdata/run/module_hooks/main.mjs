@@ -0,0 +1,6 @@
+import { createRequire } from "node:module";
+import greeting from "virtual:greeting";
+
+console.log(greeting);
+const require = createRequire(import.meta.url);
+console.log(require("virtual:cjs"));
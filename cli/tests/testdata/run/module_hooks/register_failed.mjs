@@ -0,0 +1,9 @@
+import { register } from "node:module";
+
+register("./missing_hooks.mjs", import.meta.url);
+
+try {
+  await import("./data.mjs");
+} catch (err) {
+  console.log(err.message);
+}
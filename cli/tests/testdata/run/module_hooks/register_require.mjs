@@ -0,0 +1,5 @@
+import { createRequire, register } from "node:module";
+
+const require = createRequire(import.meta.url);
+register("./hooks.mjs", import.meta.url, { data: { name: "greeter" } });
+console.log(require("virtual:cjs"));
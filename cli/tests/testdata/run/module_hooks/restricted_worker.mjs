@@ -0,0 +1,11 @@
+import greeting from "virtual:greeting";
+
+// The worker can't read files on its own, even if the hooks can.
+let result;
+try {
+  const { default: data } = await import("./data.mjs");
+  result = data;
+} catch (err) {
+  result = err.message;
+}
+postMessage(`${greeting}\n${result}`);
@@ -0,0 +1,12 @@
+import { register } from "node:module";
+
+register("./hooks.mjs", import.meta.url, { data: { name: "greeter" } });
+
+const worker = new Worker(
+  new URL("./restricted_worker.mjs", import.meta.url),
+  { type: "module", deno: { permissions: "none" } },
+);
+worker.onmessage = (e) => {
+  console.log(e.data);
+  worker.terminate();
+};
@@ -0,0 +1,4 @@
+const data = new URL("./data/", import.meta.url);
+console.log(Deno.readTextFileSync(new URL("a.txt", data)).trim());
+// Covered by the parent directory granted at the first prompt.
+console.log(Deno.readTextFileSync(new URL("b.txt", data)).trim());
@@ -0,0 +1,30 @@
+export function readFromLib(path: URL): string {
+  return Deno.readTextFileSync(path);
+}
+
+export function callWith(fn: () => string): string {
+  return fn();
+}
+
+export function readWithEval(path: URL): string {
+  return eval("Deno.readTextFileSync(path)");
+}
+
+export function readWithFunction(path: URL): string {
+  return new Function("path", "return Deno.readTextFileSync(path);")(path);
+}
+
+// Code created from a string and called by the event loop has no module on
+// its stack, nor does the name it claims with a `sourceURL` count.
+export function readLater(path: URL, sourceUrl = ""): Promise<string> {
+  const read = new Function(
+    "path",
+    "resolve",
+    "reject",
+    "try { resolve(Deno.readTextFileSync(path)); } catch (e) { reject(e); }" +
+      (sourceUrl ? `\n//# sourceURL=${sourceUrl}` : ""),
+  );
+  return new Promise((resolve, reject) =>
+    setTimeout(read, 0, path, resolve, reject)
+  );
+}
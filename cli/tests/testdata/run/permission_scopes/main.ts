@@ -0,0 +1,39 @@
+import {
+  callWith,
+  readFromLib,
+  readLater,
+  readWithEval,
+  readWithFunction,
+} from "./lib/mod.ts";
+
+const data = new URL("./data.txt", import.meta.url);
+console.log(Deno.readTextFileSync(data).trim());
+
+try {
+  readFromLib(data);
+} catch (e) {
+  console.log(`${e.name}: ${e.message}`);
+}
+
+// The library is still on the stack when it calls back into the app.
+try {
+  callWith(() => Deno.readTextFileSync(data));
+} catch (e) {
+  console.log(`${e.name}: ${e.message}`);
+}
+
+for (const read of [readWithEval, readWithFunction]) {
+  try {
+    read(data);
+  } catch (e) {
+    console.log(`${e.name}: ${e.message}`);
+  }
+}
+
+for (const sourceUrl of ["", import.meta.url]) {
+  try {
+    await readLater(data, sourceUrl);
+  } catch (e) {
+    console.log(`${e.name}: ${e.message}`);
+  }
+}
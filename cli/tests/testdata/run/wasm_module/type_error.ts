@@ -0,0 +1,4 @@
+import { add } from "./add.wasm";
+
+const result: string = add(1, 2);
+console.log(result);
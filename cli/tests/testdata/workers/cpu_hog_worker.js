@@ -0,0 +1,3 @@
+while (true) {
+  // Spin until the worker is terminated.
+}
@@ -0,0 +1,20 @@
+const cases = [
+  ["./heap_hog_worker.js", { maxHeapSize: 16 }],
+  ["./cpu_hog_worker.js", { maxCpuTime: 100 }],
+] as const;
+
+for (const [specifier, limits] of cases) {
+  const worker = new Worker(import.meta.resolve(specifier), {
+    type: "module",
+    deno: { limits },
+  });
+  await new Promise<void>((resolve) => {
+    worker.onerror = (e) => {
+      e.preventDefault();
+      console.log(e.message);
+      resolve();
+    };
+  });
+  worker.terminate();
+}
+console.log("done");
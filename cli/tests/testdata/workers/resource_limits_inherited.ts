@@ -0,0 +1,10 @@
+const worker = new Worker(import.meta.resolve("./cpu_hog_worker.js"), {
+  type: "module",
+  // The limit set on the command line can't be raised.
+  deno: { limits: { maxCpuTime: 60000 } },
+});
+worker.onerror = (e) => {
+  e.preventDefault();
+  console.log(e.message);
+  worker.terminate();
+};
@@ -865,3 +865,16 @@ Deno.test(
     }
   },
 );
+
+Deno.test(
+  { permissions: { read: true, run: true } },
+  async function commandIgnoresIpcOption() {
+    const child = new Deno.Command(Deno.execPath(), {
+      args: ["eval", "console.log(Deno.env.get('DENO_CHANNEL_FD'))"],
+      stdout: "piped",
+      ipc: true,
+    } as Deno.CommandOptions).spawn();
+    const { stdout } = await child.output();
+    assertEquals(new TextDecoder().decode(stdout), "undefined\n");
+  },
+);
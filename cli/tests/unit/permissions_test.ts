@@ -32,6 +32,17 @@ Deno.test(function permissionNetInvalidHostSync() {
   }, URIError);
 });
 
+Deno.test(async function permissionImportHost() {
+  const status = await Deno.permissions.query({
+    name: "import",
+    host: "localhost:4545",
+  });
+  assertEquals(status.state, "granted");
+  await assertRejects(async () => {
+    await Deno.permissions.query({ name: "import", host: ":" });
+  }, URIError);
+});
+
 Deno.test(async function permissionSysValidKind() {
   await Deno.permissions.query({ name: "sys", kind: "loadavg" });
   await Deno.permissions.query({ name: "sys", kind: "osRelease" });
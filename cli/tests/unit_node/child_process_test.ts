@@ -551,6 +551,33 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "[node/child_process] child_process.fork with an IPC channel",
+  ignore: Deno.build.os === "windows",
+  async fn() {
+    const script = path.join(
+      path.dirname(path.fromFileUrl(import.meta.url)),
+      "testdata",
+      "child_process_ipc.js",
+    );
+    const cp = CP.fork(script);
+    assert(cp.connected);
+
+    const message = deferred<unknown>();
+    const disconnected = deferred();
+    const exited = deferred();
+    cp.on("message", (data) => message.resolve(data));
+    cp.on("disconnect", () => disconnected.resolve());
+    cp.on("exit", () => exited.resolve());
+    assert(cp.send!({ hello: "world" }));
+
+    assertEquals(await message, { echo: { hello: "world" } });
+    await disconnected;
+    assert(!cp.connected);
+    await exited;
+  },
+});
+
 Deno.test("[node/child_process execFileSync] 'inherit' stdout and stderr", () => {
   execFileSync(Deno.execPath(), ["--help"], { stdio: "inherit" });
 });
@@ -0,0 +1,88 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+import cluster from "node:cluster";
+import type { Worker } from "node:cluster";
+import {
+  assert,
+  assertEquals,
+  assertStringIncludes,
+} from "../../../test_util/std/testing/asserts.ts";
+import { deferred } from "../../../test_util/std/async/deferred.ts";
+import * as path from "../../../test_util/std/path/mod.ts";
+
+const exec = path.join(
+  path.dirname(path.fromFileUrl(import.meta.url)),
+  "testdata",
+  "cluster_worker.js",
+);
+
+Deno.test("[node/cluster] the main process is the primary", () => {
+  assert(cluster.isPrimary);
+  assert(cluster.isMaster);
+  assert(!cluster.isWorker);
+  assertEquals(cluster.worker, undefined);
+  assertEquals(cluster.schedulingPolicy, cluster.SCHED_NONE);
+});
+
+Deno.test({
+  name: "[node/cluster] workers share a listening port",
+  // Windows can't share ports between sockets.
+  ignore: Deno.build.os === "windows",
+  async fn() {
+    const port = 4560;
+    cluster.setupPrimary({ exec });
+    assertEquals(cluster.settings.exec, exec);
+
+    const workers: Worker[] = [];
+    const listening = [deferred(), deferred()];
+    const exited = [deferred(), deferred()];
+    for (let i = 0; i < 2; i++) {
+      const worker = cluster.fork({ PORT: port });
+      worker.on("listening", (address) => {
+        assertEquals(address.port, port);
+        listening[i].resolve();
+      });
+      worker.on("exit", (code) => exited[i].resolve(code));
+      workers.push(worker);
+    }
+    assert(cluster.workers![workers[0].id] === workers[0]);
+    await Promise.all(listening);
+    assertEquals(workers[1].state, "listening");
+
+    const res = await fetch(`http://127.0.0.1:${port}/`);
+    assertStringIncludes(await res.text(), "worker ");
+
+    for (const worker of workers) {
+      worker.send("shutdown");
+    }
+    assertEquals(await Promise.all(exited), [0, 0]);
+    assert(workers[0].exitedAfterDisconnect);
+    assert(workers[0].isDead());
+    assertEquals(Object.keys(cluster.workers!).length, 0);
+  },
+});
+
+Deno.test({
+  name: "[node/cluster] the primary and the workers exchange messages",
+  ignore: Deno.build.os === "windows",
+  async fn() {
+    cluster.setupPrimary({ exec });
+    const worker = cluster.fork({ PORT: 4561 });
+    const online = deferred();
+    const message = deferred<unknown>();
+    const exited = deferred();
+    cluster.once("online", (w) => {
+      assert(w === worker);
+      online.resolve();
+    });
+    worker.on("message", (data) => message.resolve(data));
+    worker.on("exit", () => exited.resolve());
+
+    await online;
+    worker.send({ hello: "world" });
+    assertEquals(await message, { echo: { hello: "world" } });
+
+    worker.kill();
+    await exited;
+    assert(worker.isDead());
+  },
+});
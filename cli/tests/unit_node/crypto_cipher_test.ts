@@ -48,3 +48,139 @@ Deno.test({
     );
   },
 });
+
+Deno.test({
+  name: "aes-128-cbc matches the NIST test vector",
+  fn() {
+    const key = Buffer.from("2b7e151628aed2a6abf7158809cf4f3c", "hex");
+    const iv = Buffer.from("000102030405060708090a0b0c0d0e0f", "hex");
+    const plaintext = Buffer.from(
+      "6bc1bee22e409f96e93d7e117393172a",
+      "hex",
+    );
+    const cipher = crypto.createCipheriv("aes-128-cbc", key, iv)
+      .setAutoPadding(false);
+    const encrypted = Buffer.concat([
+      cipher.update(plaintext),
+      cipher.final(),
+    ]);
+    assertEquals(
+      encrypted.toString("hex"),
+      "7649abac8119b246cee98e9b12e9197d",
+    );
+
+    const decipher = crypto.createDecipheriv("aes-128-cbc", key, iv)
+      .setAutoPadding(false);
+    assertEquals(
+      Buffer.concat([decipher.update(encrypted), decipher.final()]),
+      plaintext,
+    );
+  },
+});
+
+Deno.test({
+  name: "aes-256-cbc round trips with padding",
+  fn() {
+    const key = crypto.randomBytes(32);
+    const iv = crypto.randomBytes(16);
+    const cipher = crypto.createCipheriv("aes-256-cbc", key, iv);
+    const encrypted = cipher.update("hello world", "utf8", "hex") +
+      cipher.final("hex");
+    assertEquals(encrypted.length, 32);
+
+    const decipher = crypto.createDecipheriv("aes-256-cbc", key, iv);
+    assertEquals(
+      decipher.update(encrypted, "hex", "utf8") + decipher.final("utf8"),
+      "hello world",
+    );
+
+    // a final block of zeroes is not padded
+    const unpadded = crypto.createCipheriv("aes-256-cbc", key, iv)
+      .setAutoPadding(false)
+      .update(Buffer.alloc(16));
+    const badPadding = crypto.createDecipheriv("aes-256-cbc", key, iv);
+    badPadding.update(unpadded);
+    assertThrows(() => badPadding.final(), TypeError, "bad decrypt");
+  },
+});
+
+Deno.test({
+  name: "aes-256-gcm authenticates the data",
+  fn() {
+    const key = crypto.randomBytes(32);
+    const iv = crypto.randomBytes(12);
+    const aad = Buffer.from("header");
+    const cipher = crypto.createCipheriv("aes-256-gcm", key, iv);
+    cipher.setAAD(aad);
+    const encrypted = Buffer.concat([cipher.update(input), cipher.final()]);
+    const tag = cipher.getAuthTag();
+    assertEquals(tag.byteLength, 16);
+
+    const decipher = crypto.createDecipheriv("aes-256-gcm", key, iv);
+    decipher.setAAD(aad);
+    decipher.setAuthTag(tag);
+    assertEquals(
+      new Uint8Array(
+        Buffer.concat([decipher.update(encrypted), decipher.final()]),
+      ),
+      input,
+    );
+
+    const tampered = crypto.createDecipheriv("aes-256-gcm", key, iv);
+    tampered.setAuthTag(tag);
+    tampered.update(encrypted);
+    assertThrows(
+      () => tampered.final(),
+      TypeError,
+      "Unsupported state or unable to authenticate data",
+    );
+  },
+});
+
+Deno.test({
+  name: "cipheriv rejects invalid keys and ivs",
+  fn() {
+    assertThrows(
+      () =>
+        crypto.createCipheriv(
+          "aes-128-cbc",
+          Buffer.alloc(15),
+          Buffer.alloc(16),
+        ),
+      TypeError,
+      "Invalid key length",
+    );
+    assertThrows(
+      () =>
+        crypto.createCipheriv(
+          "aes-128-cbc",
+          Buffer.alloc(16),
+          Buffer.alloc(8),
+        ),
+      TypeError,
+      "Invalid initialization vector",
+    );
+  },
+});
+
+Deno.test({
+  name: "cipheriv is a stream",
+  async fn() {
+    const key = crypto.randomBytes(16);
+    const iv = crypto.randomBytes(16);
+    const cipher = crypto.createCipheriv("aes-128-ctr", key, iv);
+    const chunks: Buffer[] = [];
+    cipher.on("data", (chunk: Buffer) => chunks.push(chunk));
+    const finished = new Promise((resolve) => cipher.on("end", resolve));
+    cipher.write("hello ");
+    cipher.end("world");
+    await finished;
+
+    const decipher = crypto.createDecipheriv("aes-128-ctr", key, iv);
+    const decrypted = Buffer.concat([
+      decipher.update(Buffer.concat(chunks)),
+      decipher.final(),
+    ]);
+    assertEquals(decrypted.toString(), "hello world");
+  },
+});
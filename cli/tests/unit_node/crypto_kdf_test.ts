@@ -0,0 +1,101 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+import crypto from "node:crypto";
+import { Buffer } from "node:buffer";
+import {
+  assertEquals,
+  assertThrows,
+} from "../../../test_util/std/testing/asserts.ts";
+import { deferred } from "../../../test_util/std/async/deferred.ts";
+
+Deno.test("[node/crypto] hmac", () => {
+  assertEquals(
+    crypto.createHmac("sha256", "key")
+      .update("The quick brown fox jumps over the lazy dog")
+      .digest("hex"),
+    "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8",
+  );
+  const key = crypto.createSecretKey(Buffer.from("key"));
+  assertEquals(
+    crypto.createHmac("sha256", key)
+      .update("The quick brown fox jumps over the lazy dog")
+      .digest("hex"),
+    "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8",
+  );
+});
+
+Deno.test("[node/crypto] pbkdf2", async () => {
+  const expected = "0c60c80f961f0e71f3a9b524af6012062fe037a6";
+  assertEquals(
+    crypto.pbkdf2Sync("password", "salt", 1, 20, "sha1").toString("hex"),
+    expected,
+  );
+
+  const promise = deferred<Buffer>();
+  crypto.pbkdf2("password", "salt", 1, 20, "sha1", (err, key) => {
+    if (err) promise.reject(err);
+    else promise.resolve(key!);
+  });
+  assertEquals((await promise).toString("hex"), expected);
+
+  assertThrows(
+    // @ts-ignore: the callback is required
+    () => crypto.pbkdf2("password", "salt", 1, 20, "sha1"),
+    TypeError,
+    'The "callback" argument must be of type function',
+  );
+});
+
+Deno.test("[node/crypto] scrypt", async () => {
+  const expected =
+    "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442" +
+    "fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906";
+  const options = { N: 16, r: 1, p: 1 };
+  assertEquals(
+    crypto.scryptSync("", "", 64, options).toString("hex"),
+    expected,
+  );
+
+  const promise = deferred<Buffer>();
+  crypto.scrypt("", "", 64, options, (err, key) => {
+    if (err) promise.reject(err);
+    else promise.resolve(key);
+  });
+  assertEquals((await promise).toString("hex"), expected);
+
+  // needs at least 128 * N * r bytes
+  const tooLarge = { N: 2048, r: 8, p: 1, maxmem: 1024 * 1024 };
+  for (
+    const fn of [
+      () => crypto.scryptSync("", "", 64, tooLarge),
+      () => crypto.scrypt("", "", 64, tooLarge, () => {}),
+    ]
+  ) {
+    const err = assertThrows(fn, RangeError, "Invalid scrypt parameter");
+    assertEquals(
+      (err as { code?: string }).code,
+      "ERR_CRYPTO_INVALID_SCRYPT_PARAMS",
+    );
+  }
+});
+
+Deno.test("[node/crypto] hkdf", async () => {
+  const ikm = Buffer.alloc(22, 0x0b);
+  const salt = Buffer.from("000102030405060708090a0b0c", "hex");
+  const info = Buffer.from("f0f1f2f3f4f5f6f7f8f9", "hex");
+  const expected =
+    "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf" +
+    "34007208d5b887185865";
+  assertEquals(
+    Buffer.from(crypto.hkdfSync("sha256", ikm, salt, info, 42)).toString(
+      "hex",
+    ),
+    expected,
+  );
+
+  const promise = deferred<ArrayBuffer>();
+  crypto.hkdf("sha256", ikm, salt, info, 42, (err, key) => {
+    if (err) promise.reject(err);
+    else promise.resolve(key);
+  });
+  assertEquals(Buffer.from(await promise).toString("hex"), expected);
+});
@@ -0,0 +1,142 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+import {
+  assertEquals,
+  assertThrows,
+} from "../../../test_util/std/testing/asserts.ts";
+import { deferred } from "../../../test_util/std/async/deferred.ts";
+import * as http2 from "node:http2";
+import { once } from "node:events";
+import type { AddressInfo } from "node:net";
+
+Deno.test("[node/http2] packed settings round trip", () => {
+  const packed = http2.getPackedSettings({
+    enablePush: false,
+    initialWindowSize: 1024,
+    maxFrameSize: 20000,
+  });
+  assertEquals(packed.byteLength, 18);
+  assertEquals(http2.getUnpackedSettings(packed), {
+    enablePush: false,
+    initialWindowSize: 1024,
+    maxFrameSize: 20000,
+  });
+  assertThrows(() => http2.getUnpackedSettings(new Uint8Array(5)));
+});
+
+Deno.test("[node/http2] connect rejects unsupported protocols", () => {
+  assertThrows(
+    () => http2.connect("ftp://localhost"),
+    Error,
+    "protocol \"ftp:\" is unsupported.",
+  );
+});
+
+Deno.test("[node/http2] client and server streams", async () => {
+  const server = http2.createServer();
+  server.on("stream", (stream, headers) => {
+    assertEquals(headers[":method"], "POST");
+    assertEquals(headers[":path"], "/echo");
+    const chunks: Uint8Array[] = [];
+    stream.on("data", (chunk: Uint8Array) => chunks.push(chunk));
+    stream.on("end", () => {
+      stream.respond({
+        ":status": 201,
+        "content-type": "text/plain",
+      }, { waitForTrailers: true });
+      stream.on("wantTrailers", () => stream.sendTrailers({ "x-done": "1" }));
+      stream.end(new TextDecoder().decode(
+        new Uint8Array(chunks.flatMap((chunk) => [...chunk])),
+      ).toUpperCase());
+    });
+  });
+  server.listen(0);
+  await once(server, "listening");
+  const { port } = server.address() as AddressInfo;
+
+  const client = http2.connect(`http://localhost:${port}`);
+  const req = client.request({ ":method": "POST", ":path": "/echo" });
+  req.setEncoding("utf8");
+  const response = deferred<Record<string, unknown>>();
+  const trailers = deferred<Record<string, unknown>>();
+  req.on("response", response.resolve);
+  req.on("trailers", trailers.resolve);
+  let body = "";
+  req.on("data", (chunk: string) => body += chunk);
+  req.end("hello http2");
+  await once(req, "end");
+
+  const headers = await response;
+  assertEquals(headers[":status"], 201);
+  assertEquals(headers["content-type"], "text/plain");
+  assertEquals((await trailers)["x-done"], "1");
+  assertEquals(body, "HELLO HTTP2");
+
+  const closed = deferred();
+  client.close(closed.resolve);
+  await closed;
+  server.close();
+  await once(server, "close");
+});
+
+Deno.test("[node/http2] compat request and response", async () => {
+  const server = http2.createServer((req, res) => {
+    res.setHeader("x-path", req.url);
+    res.end(`${req.method} ${req.httpVersion}`);
+  });
+  server.listen(0);
+  await once(server, "listening");
+  const { port } = server.address() as AddressInfo;
+
+  const client = http2.connect(`http://localhost:${port}`);
+  const pong = deferred<Error | null>();
+  client.ping((err) => pong.resolve(err));
+  assertEquals(await pong, null);
+
+  const req = client.request({ ":path": "/compat" });
+  req.setEncoding("utf8");
+  const response = deferred<Record<string, unknown>>();
+  req.on("response", response.resolve);
+  let body = "";
+  req.on("data", (chunk: string) => body += chunk);
+  await once(req, "end");
+
+  const headers = await response;
+  assertEquals(headers[":status"], 200);
+  assertEquals(headers["x-path"], "/compat");
+  assertEquals(body, "GET 2.0");
+
+  client.close();
+  await once(client, "close");
+  server.close();
+  await once(server, "close");
+});
+
+Deno.test("[node/http2] session settings and goaway", async () => {
+  const server = http2.createServer();
+  const serverSession = deferred<http2.ServerHttp2Session>();
+  server.on("session", serverSession.resolve);
+  server.listen(0);
+  await once(server, "listening");
+  const { port } = server.address() as AddressInfo;
+
+  const client = http2.connect(`http://localhost:${port}`);
+  const settings = deferred<http2.Settings>();
+  client.settings({ initialWindowSize: 1024 }, (err, localSettings) => {
+    assertEquals(err, null);
+    settings.resolve(localSettings);
+  });
+  assertEquals((await settings).initialWindowSize, 1024);
+  assertEquals(client.localSettings.initialWindowSize, 1024);
+  assertThrows(
+    () => client.settings({ initialWindowSize: -1 }),
+    RangeError,
+    'Invalid value for setting "initialWindowSize": -1',
+  );
+  assertThrows(() => client.goaway(), Error, "Not implemented");
+
+  (await serverSession).goaway();
+  await once(client, "close");
+  server.close();
+  await once(server, "close");
+});
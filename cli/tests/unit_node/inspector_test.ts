@@ -0,0 +1,92 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+import inspector, { Session } from "node:inspector";
+import {
+  assertEquals,
+  assertThrows,
+} from "../../../test_util/std/testing/asserts.ts";
+import { deferred } from "../../../test_util/std/async/deferred.ts";
+
+function post(
+  session: Session,
+  method: string,
+  params?: Record<string, unknown>,
+  // deno-lint-ignore no-explicit-any
+): Promise<any> {
+  return new Promise((resolve, reject) => {
+    session.post(method, params, (err, result) => {
+      if (err) {
+        reject(err);
+      } else {
+        resolve(result);
+      }
+    });
+  });
+}
+
+Deno.test("[node/inspector] url() is undefined when inactive", () => {
+  assertEquals(inspector.url(), undefined);
+});
+
+Deno.test("[node/inspector] Session evaluates expressions", async () => {
+  const session = new Session();
+  session.connect();
+  try {
+    const { result } = await post(session, "Runtime.evaluate", {
+      expression: "1 + 2",
+    });
+    assertEquals(result, { type: "number", value: 3, description: "3" });
+  } finally {
+    session.disconnect();
+  }
+});
+
+Deno.test("[node/inspector] Session emits notifications", async () => {
+  const session = new Session();
+  session.connect();
+  try {
+    const notification = deferred<{ method: string }>();
+    session.on(
+      "Runtime.consoleAPICalled",
+      (message) => notification.resolve(message),
+    );
+    await post(session, "Runtime.enable");
+    await post(session, "Runtime.evaluate", {
+      expression: "console.debug('from the session')",
+    });
+    assertEquals((await notification).method, "Runtime.consoleAPICalled");
+  } finally {
+    session.disconnect();
+  }
+});
+
+Deno.test("[node/inspector] Session profiles the CPU", async () => {
+  const session = new Session();
+  session.connect();
+  try {
+    await post(session, "Profiler.enable");
+    await post(session, "Profiler.start");
+    const { profile } = await post(session, "Profiler.stop");
+    assertEquals(Array.isArray(profile.nodes), true);
+  } finally {
+    session.disconnect();
+  }
+});
+
+Deno.test("[node/inspector] Session reports errors of commands", async () => {
+  const session = new Session();
+  session.connect();
+  try {
+    const err = await post(session, "Foo.bar").catch((err) => err);
+    assertEquals(err.code, "ERR_INSPECTOR_COMMAND");
+  } finally {
+    session.disconnect();
+  }
+});
+
+Deno.test("[node/inspector] Session throws if it isn't connected", () => {
+  const session = new Session();
+  assertThrows(() => session.post("Runtime.enable"), Error, "not connected");
+  session.connect();
+  assertThrows(() => session.connect(), Error, "already connected");
+  session.disconnect();
+});
@@ -0,0 +1,5 @@
+import process from "node:process";
+
+process.on("message", (message) => {
+  process.send({ echo: message }, () => process.disconnect());
+});
@@ -0,0 +1,17 @@
+import cluster from "node:cluster";
+import http from "node:http";
+import process from "node:process";
+
+const server = http.createServer((_req, res) => {
+  res.end(`worker ${cluster.worker.id}`);
+});
+server.listen(Number(process.env.PORT));
+
+cluster.worker.on("message", (message) => {
+  if (message === "shutdown") {
+    server.close();
+    cluster.worker.disconnect();
+  } else {
+    cluster.worker.send({ echo: message });
+  }
+});
@@ -0,0 +1,11 @@
+import { createContext, runInContext } from "node:vm";
+
+self.onmessage = ({ data: flag }) => {
+  self.postMessage("running");
+  try {
+    runInContext("while (true) {}", createContext());
+  } finally {
+    // only reached if the termination of the worker was caught
+    Atomics.store(flag, 0, 1);
+  }
+};
@@ -0,0 +1,18 @@
+import {
+  getEnvironmentData,
+  isMainThread,
+  parentPort,
+  threadId,
+  workerData,
+} from "node:worker_threads";
+
+parentPort.postMessage({
+  environmentData: getEnvironmentData("test"),
+  isMainThread,
+  threadId,
+  workerData,
+});
+
+parentPort.on("message", (message) => {
+  parentPort.postMessage(message);
+});
@@ -0,0 +1,7 @@
+import process from "node:process";
+import { parentPort, workerData } from "node:worker_threads";
+
+parentPort.postMessage("done");
+if (workerData.exitCode !== undefined) {
+  process.exit(workerData.exitCode);
+}
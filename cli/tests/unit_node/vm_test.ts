@@ -0,0 +1,165 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+import {
+  compileFunction,
+  createContext,
+  isContext,
+  runInContext,
+  runInNewContext,
+  runInThisContext,
+  Script,
+} from "node:vm";
+import {
+  assert,
+  assertEquals,
+  assertThrows,
+} from "../../../test_util/std/testing/asserts.ts";
+import { deferred } from "../../../test_util/std/async/deferred.ts";
+import { delay } from "../../../test_util/std/async/delay.ts";
+
+Deno.test({
+  name: "vm runInContext shares globals with the context object",
+  fn() {
+    const context = createContext({ x: 2 });
+    assert(isContext(context));
+    assertEquals(runInContext("x * 21", context), 42);
+
+    runInContext("var y = 1; z = x + 1; function f() { return y; }", context);
+    assertEquals(context.y, 1);
+    assertEquals(context.z, 3);
+    assertEquals(typeof context.f, "function");
+
+    context.x = 10;
+    assertEquals(runInContext("x", context), 10);
+  },
+});
+
+Deno.test({
+  name: "vm contexts have their own builtins",
+  fn() {
+    const context = createContext({});
+    assertEquals(runInContext("typeof Deno", context), "undefined");
+    const array = runInContext("[1, 2, 3]", context);
+    assert(!(array instanceof Array));
+    assertEquals(runInContext("this.Array === Array", context), true);
+    assertEquals(runInContext("globalThis === this", context), true);
+  },
+});
+
+Deno.test({
+  name: "vm isContext",
+  fn() {
+    assertEquals(isContext({}), false);
+    assertEquals(isContext(createContext()), true);
+    assertThrows(
+      () => isContext(1 as unknown as object),
+      TypeError,
+      'The "contextifiedObject" argument must be of type object',
+    );
+  },
+});
+
+Deno.test({
+  name: "vm runInNewContext",
+  fn() {
+    const sandbox = { animal: "cat", count: 2 };
+    runInNewContext("count += 1; name = 'kitty'", sandbox);
+    assertEquals(sandbox, { animal: "cat", count: 3, name: "kitty" });
+  },
+});
+
+Deno.test({
+  name: "vm Script runs in several contexts",
+  fn() {
+    const script = new Script("count += 1", { filename: "counter.js" });
+    const contexts = [
+      createContext({ count: 0 }),
+      createContext({ count: 5 }),
+    ];
+    for (const context of contexts) {
+      script.runInContext(context);
+    }
+    assertEquals(contexts.map((context) => context.count), [1, 6]);
+  },
+});
+
+Deno.test({
+  name: "vm rethrows exceptions from the context",
+  fn() {
+    const context = createContext({});
+    const err = assertThrows(() =>
+      runInContext("throw new Error('boom')", context)
+    );
+    assertEquals((err as Error).message, "boom");
+    assertThrows(() => runInContext("syntax error ^^", context));
+    assertThrows(() => runInContext("1", {}), TypeError);
+  },
+});
+
+Deno.test({
+  name: "vm compileFunction",
+  fn() {
+    const add = compileFunction("return a + b", ["a", "b"]);
+    assertEquals(add(1, 2), 3);
+
+    const context = createContext({ value: 42 });
+    const getValue = compileFunction("return value", [], {
+      parsingContext: context,
+    });
+    assertEquals(getValue(), 42);
+  },
+});
+
+Deno.test({
+  name: "vm declarations reach the context object",
+  fn() {
+    const context = createContext({});
+    runInContext("var a; var b = 1; function c() { return b; }", context);
+    assert("a" in context);
+    assertEquals(context.a, undefined);
+    assertEquals(context.b, 1);
+    assertEquals(context.c(), 1);
+    assertEquals(Object.keys(context).sort(), ["a", "b", "c"]);
+  },
+});
+
+Deno.test({
+  name: "vm timeout",
+  fn() {
+    const context = createContext({ count: 0 });
+    assertThrows(
+      () => runInContext("while (true) { count++; }", context, { timeout: 10 }),
+      Error,
+      "Script execution timed out after 10ms",
+    );
+    assert(context.count > 0);
+    assertEquals(runInContext("1 + 1", context, { timeout: 1000 }), 2);
+    assertThrows(
+      () => runInThisContext("while (true) {}", { timeout: 10 }),
+      Error,
+      "Script execution timed out after 10ms",
+    );
+    assertThrows(
+      () => runInContext("1", context, { timeout: -1 }),
+      RangeError,
+      'The value of "options.timeout" is out of range',
+    );
+  },
+});
+
+Deno.test({
+  name: "vm doesn't catch the termination of a worker",
+  async fn() {
+    const flag = new Int32Array(new SharedArrayBuffer(4));
+    const worker = new Worker(
+      new URL("./testdata/vm_worker.mjs", import.meta.url),
+      { type: "module" },
+    );
+    const running = deferred();
+    worker.onmessage = () => running.resolve();
+    worker.postMessage(flag);
+    await running;
+    worker.terminate();
+    await delay(100);
+    assertEquals(Atomics.load(flag, 0), 0);
+  },
+});
@@ -0,0 +1,108 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+import {
+  assert,
+  assertEquals,
+  assertObjectMatch,
+} from "../../../test_util/std/testing/asserts.ts";
+import { deferred } from "../../../test_util/std/async/deferred.ts";
+import * as workerThreads from "node:worker_threads";
+import { once } from "node:events";
+
+Deno.test("[node/worker_threads] main thread", () => {
+  assert(workerThreads.isMainThread);
+  assertEquals(workerThreads.threadId, 0);
+  assertEquals(workerThreads.parentPort, null);
+  assertEquals(workerThreads.workerData, null);
+});
+
+Deno.test("[node/worker_threads] Worker with workerData", async () => {
+  workerThreads.setEnvironmentData("test", "example");
+  const worker = new workerThreads.Worker(
+    new URL("./testdata/worker_threads.mjs", import.meta.url),
+    {
+      workerData: { hello: "world" },
+      env: workerThreads.SHARE_ENV,
+    },
+  );
+  const [data] = await once(worker, "message");
+  assertEquals(data, {
+    environmentData: "example",
+    isMainThread: false,
+    threadId: worker.threadId,
+    workerData: { hello: "world" },
+  });
+
+  worker.postMessage("ping");
+  const [echo] = await once(worker, "message");
+  assertEquals(echo, "ping");
+
+  // an untransferable buffer is cloned instead of transferred
+  const buffer = new Uint8Array([1, 2, 3]).buffer;
+  workerThreads.markAsUntransferable(buffer);
+  worker.postMessage(buffer, [buffer]);
+  const [clone] = await once(worker, "message");
+  assertEquals(buffer.byteLength, 3);
+  assertEquals(new Uint8Array(clone), new Uint8Array([1, 2, 3]));
+
+  const exited = deferred<number>();
+  worker.on("exit", (code: number) => exited.resolve(code));
+  assertEquals(await worker.terminate(), 1);
+  assertEquals(await exited, 1);
+  workerThreads.setEnvironmentData("test", undefined);
+});
+
+Deno.test("[node/worker_threads] Worker exits on its own", async () => {
+  for (const exitCode of [undefined, 2]) {
+    const worker = new workerThreads.Worker(
+      new URL("./testdata/worker_threads_exit.mjs", import.meta.url),
+      { workerData: { exitCode } },
+    );
+    const messages: unknown[] = [];
+    worker.on("message", (message: unknown) => messages.push(message));
+    const [code] = await once(worker, "exit");
+    assertEquals(code, exitCode ?? 0);
+    assertEquals(messages, ["done"]);
+  }
+});
+
+Deno.test("[node/worker_threads] Worker with resourceLimits", async () => {
+  const worker = new workerThreads.Worker(
+    new URL("./testdata/worker_threads.mjs", import.meta.url),
+    { resourceLimits: { maxOldGenerationSizeMb: 64 } },
+  );
+  assertObjectMatch(worker.resourceLimits, { maxOldGenerationSizeMb: 64 });
+  await once(worker, "message");
+  await worker.terminate();
+});
+
+Deno.test("[node/worker_threads] receiveMessageOnPort", () => {
+  const { port1, port2 } = new workerThreads.MessageChannel();
+  assertEquals(workerThreads.receiveMessageOnPort(port2), undefined);
+  port1.postMessage({ hello: "world" });
+  port1.postMessage(42);
+  assertEquals(workerThreads.receiveMessageOnPort(port2), {
+    message: { hello: "world" },
+  });
+  assertEquals(workerThreads.receiveMessageOnPort(port2), { message: 42 });
+  assertEquals(workerThreads.receiveMessageOnPort(port2), undefined);
+  port1.close();
+  port2.close();
+});
+
+Deno.test(
+  "[node/worker_threads] receiveMessageOnPort with a listener",
+  async () => {
+    const { port1, port2 } = new workerThreads.MessageChannel();
+    const received = deferred<unknown>();
+    port2.onmessage = (event) => received.resolve(event.data);
+    // let the port start waiting for messages
+    await new Promise((resolve) => setTimeout(resolve, 0));
+    port1.postMessage(1);
+    assertEquals(workerThreads.receiveMessageOnPort(port2), { message: 1 });
+    port1.postMessage(2);
+    assertEquals(await received, 2);
+    port1.close();
+    port2.close();
+  },
+);
@@ -0,0 +1,83 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+import {
+  assertEquals,
+  assertThrows,
+} from "../../../test_util/std/testing/asserts.ts";
+import { deferred } from "../../../test_util/std/async/deferred.ts";
+import {
+  brotliCompress,
+  brotliCompressSync,
+  brotliDecompressSync,
+  constants,
+  createBrotliCompress,
+  createBrotliDecompress,
+} from "node:zlib";
+import { Buffer } from "node:buffer";
+import { once } from "node:events";
+
+Deno.test("[node/zlib] brotli compress and decompress sync", () => {
+  const input = Buffer.from("hello world ".repeat(100));
+  const compressed = brotliCompressSync(input);
+  assertEquals(compressed.byteLength < input.byteLength, true);
+  assertEquals(brotliDecompressSync(compressed), input);
+});
+
+Deno.test("[node/zlib] brotli params", () => {
+  const input = "hello world ".repeat(100);
+  const compressed = brotliCompressSync(input, {
+    params: {
+      [constants.BROTLI_PARAM_MODE]: constants.BROTLI_MODE_TEXT,
+      [constants.BROTLI_PARAM_QUALITY]: 4,
+      [constants.BROTLI_PARAM_SIZE_HINT]: input.length,
+    },
+  });
+  assertEquals(brotliDecompressSync(compressed).toString(), input);
+  assertThrows(() => brotliCompressSync(input, { params: { 1234: 1 } }));
+});
+
+Deno.test("[node/zlib] brotli decompress rejects invalid data", () => {
+  assertThrows(() => brotliDecompressSync(Buffer.from("not brotli")));
+});
+
+Deno.test("[node/zlib] brotli async", async () => {
+  const promise = deferred<Buffer>();
+  brotliCompress("hello world", (err, result) => {
+    if (err) promise.reject(err);
+    else promise.resolve(result);
+  });
+  assertEquals(
+    brotliDecompressSync(await promise).toString(),
+    "hello world",
+  );
+});
+
+Deno.test("[node/zlib] brotli streams", async () => {
+  const compress = createBrotliCompress();
+  const decompress = createBrotliDecompress();
+  compress.pipe(decompress);
+  const chunks: Buffer[] = [];
+  decompress.on("data", (chunk: Buffer) => chunks.push(chunk));
+  compress.write("hello ");
+  compress.end("world");
+  await once(decompress, "end");
+  assertEquals(Buffer.concat(chunks).toString(), "hello world");
+});
+
+Deno.test("[node/zlib] brotli interoperates with CompressionStream", async () => {
+  const input = new TextEncoder().encode("hello world ".repeat(100));
+  const compressed = await new Response(
+    new Blob([input]).stream().pipeThrough(new CompressionStream("br")),
+  ).arrayBuffer();
+  assertEquals(
+    new Uint8Array(brotliDecompressSync(Buffer.from(compressed))),
+    input,
+  );
+
+  const decompressed = await new Response(
+    new Blob([brotliCompressSync(input)]).stream().pipeThrough(
+      new DecompressionStream("br"),
+    ),
+  ).arrayBuffer();
+  assertEquals(new Uint8Array(decompressed), input);
+});
@@ -413,7 +413,9 @@ async fn check_specifiers(
     specifiers,
     false,
     lib,
-    PermissionsContainer::allow_all(),
+    PermissionsContainer::new(Permissions::allow_all_but_import(
+      &ps.options.permissions_options(),
+    )?),
     PermissionsContainer::new(permissions),
   )
   .await?;
@@ -378,6 +378,15 @@ async fn resolve_shim_data(
     executable_args.push("--no-npm".to_string());
   }
 
+  if !flags.conditions.is_empty() {
+    executable_args
+      .push(format!("--conditions={}", flags.conditions.join(",")));
+  }
+
+  for specifier in &flags.import {
+    executable_args.push(format!("--import={specifier}"));
+  }
+
   if flags.lock_write {
     executable_args.push("--lock-write".to_string());
   }
@@ -399,6 +408,14 @@ async fn resolve_shim_data(
     executable_args.push(seed.to_string());
   }
 
+  if let Some(max_heap_size) = flags.max_heap_size {
+    executable_args.push(format!("--max-heap-size={max_heap_size}"));
+  }
+
+  if let Some(max_cpu_time) = flags.max_cpu_time {
+    executable_args.push(format!("--max-cpu-time={max_cpu_time}"));
+  }
+
   if let Some(inspect) = flags.inspect {
     executable_args.push(format!("--inspect={inspect}"));
   }
@@ -0,0 +1,71 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::cache::dirs;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// The directory Jupyter looks for the Deno kernelspec in.
+/// <https://docs.jupyter.org/en/latest/use/jupyter-directories.html#data-files>
+fn kernel_dir() -> Result<PathBuf, AnyError> {
+  let data_dir = if let Some(data_dir) = env::var_os("JUPYTER_DATA_DIR") {
+    Some(PathBuf::from(data_dir))
+  } else if cfg!(target_os = "macos") {
+    dirs::home_dir().map(|home| home.join("Library/Jupyter"))
+  } else {
+    dirs::data_dir().map(|data| data.join("jupyter"))
+  };
+  data_dir
+    .map(|data_dir| data_dir.join("kernels").join("deno"))
+    .ok_or_else(|| generic_error("Could not find the Jupyter data directory"))
+}
+
+pub fn status() -> Result<(), AnyError> {
+  let kernel_json = kernel_dir()?.join("kernel.json");
+  if kernel_json.exists() {
+    log::info!(
+      "✅ Deno kernel already installed at {}",
+      kernel_json.display()
+    );
+  } else {
+    log::warn!(
+      "Deno kernel is not yet installed, run `deno jupyter --install` to set it up."
+    );
+  }
+  Ok(())
+}
+
+pub fn install() -> Result<(), AnyError> {
+  let kernel_dir = kernel_dir()?;
+  fs::create_dir_all(&kernel_dir)?;
+
+  let exec_path = env::current_exe()?;
+  let kernel_json = json!({
+    "argv": [
+      exec_path.to_string_lossy(),
+      "jupyter",
+      "--kernel",
+      "--conn",
+      "{connection_file}",
+    ],
+    "display_name": "Deno",
+    "language": "typescript",
+    // Jupyter interrupts kernels with SIGINT otherwise, which would exit.
+    "interrupt_mode": "message",
+  });
+  let kernel_json_path = kernel_dir.join("kernel.json");
+  fs::write(
+    &kernel_json_path,
+    serde_json::to_string_pretty(&kernel_json)?,
+  )?;
+
+  log::info!(
+    "✅ Deno kernelspec installed successfully at {}",
+    kernel_json_path.display()
+  );
+  Ok(())
+}
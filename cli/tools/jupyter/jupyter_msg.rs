@@ -0,0 +1,192 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use bytes::Bytes;
+use chrono::Utc;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use ring::hmac;
+use uuid::Uuid;
+use zeromq::ZmqMessage;
+
+const DELIMITER: &[u8] = b"<IDS|MSG>";
+
+/// A message of the Jupyter messaging protocol.
+/// <https://jupyter-client.readthedocs.io/en/latest/messaging.html>
+#[derive(Debug, Clone)]
+pub struct JupyterMessage {
+  zmq_identities: Vec<Bytes>,
+  header: Value,
+  parent_header: Value,
+  metadata: Value,
+  pub content: Value,
+}
+
+impl JupyterMessage {
+  /// Parses the frames of a message, verifying its signature when the
+  /// connection file specified a key.
+  pub fn from_zmq_message(
+    message: ZmqMessage,
+    key: Option<&hmac::Key>,
+  ) -> Result<Self, AnyError> {
+    let frames = message.into_vec();
+    let delimiter = frames
+      .iter()
+      .position(|frame| frame.as_ref() == DELIMITER)
+      .ok_or_else(|| generic_error("Missing delimiter in Jupyter message"))?;
+    if frames.len() < delimiter + 6 {
+      return Err(generic_error("Incomplete Jupyter message"));
+    }
+    let parts = &frames[delimiter + 2..delimiter + 6];
+    if let Some(key) = key {
+      let signature = sign(key, parts);
+      ring::constant_time::verify_slices_are_equal(
+        signature.as_bytes(),
+        &frames[delimiter + 1],
+      )
+      .map_err(|_| generic_error("Invalid Jupyter message signature"))?;
+    }
+
+    Ok(Self {
+      zmq_identities: frames[..delimiter].to_vec(),
+      header: serde_json::from_slice(&parts[0])?,
+      parent_header: serde_json::from_slice(&parts[1])?,
+      metadata: serde_json::from_slice(&parts[2])?,
+      content: serde_json::from_slice(&parts[3])?,
+    })
+  }
+
+  pub fn message_type(&self) -> &str {
+    self.header["msg_type"].as_str().unwrap_or("")
+  }
+
+  /// Creates a message with this message as its parent, to be published on
+  /// the IOPub socket.
+  pub fn new_message(&self, msg_type: &str) -> Self {
+    Self {
+      zmq_identities: vec![],
+      header: json!({
+        "msg_id": Uuid::new_v4().to_string(),
+        "session": self.header["session"],
+        "username": self.header["username"],
+        "date": Utc::now().to_rfc3339(),
+        "msg_type": msg_type,
+        "version": "5.3",
+      }),
+      parent_header: self.header.clone(),
+      metadata: json!({}),
+      content: json!({}),
+    }
+  }
+
+  /// Creates the reply to this request, to be sent back to the same peer.
+  pub fn new_reply(&self) -> Self {
+    let msg_type = self.message_type().replace("_request", "_reply");
+    let mut reply = self.new_message(&msg_type);
+    reply.zmq_identities = self.zmq_identities.clone();
+    reply
+  }
+
+  pub fn with_content(mut self, content: Value) -> Self {
+    self.content = content;
+    self
+  }
+
+  pub fn into_zmq_message(
+    self,
+    key: Option<&hmac::Key>,
+  ) -> Result<ZmqMessage, AnyError> {
+    let parts = [
+      Bytes::from(serde_json::to_vec(&self.header)?),
+      Bytes::from(serde_json::to_vec(&self.parent_header)?),
+      Bytes::from(serde_json::to_vec(&self.metadata)?),
+      Bytes::from(serde_json::to_vec(&self.content)?),
+    ];
+    let signature = match key {
+      Some(key) => sign(key, &parts),
+      None => String::new(),
+    };
+
+    let mut frames = self.zmq_identities;
+    frames.push(Bytes::from_static(DELIMITER));
+    frames.push(Bytes::from(signature));
+    frames.extend(parts);
+    ZmqMessage::try_from(frames).map_err(generic_error)
+  }
+}
+
+/// The hex encoded HMAC of the header, parent header, metadata and content.
+fn sign(key: &hmac::Key, parts: &[Bytes]) -> String {
+  let mut ctx = hmac::Context::with_key(key);
+  for part in parts {
+    ctx.update(part);
+  }
+  ctx
+    .sign()
+    .as_ref()
+    .iter()
+    .map(|byte| format!("{byte:02x}"))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn request(key: Option<&hmac::Key>) -> ZmqMessage {
+    let parts = [
+      Bytes::from(
+        r#"{"msg_id":"1","session":"s","username":"u","msg_type":"kernel_info_request"}"#,
+      ),
+      Bytes::from("{}"),
+      Bytes::from("{}"),
+      Bytes::from("{}"),
+    ];
+    let signature = key.map(|key| sign(key, &parts)).unwrap_or_default();
+    let mut frames = vec![
+      Bytes::from("peer"),
+      Bytes::from_static(DELIMITER),
+      Bytes::from(signature),
+    ];
+    frames.extend(parts);
+    ZmqMessage::try_from(frames).unwrap()
+  }
+
+  #[test]
+  fn test_reply_roundtrip() {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, b"secret");
+    let message =
+      JupyterMessage::from_zmq_message(request(Some(&key)), Some(&key))
+        .unwrap();
+    assert_eq!(message.message_type(), "kernel_info_request");
+
+    let reply = message.new_reply().with_content(json!({ "status": "ok" }));
+    assert_eq!(reply.message_type(), "kernel_info_reply");
+    assert_eq!(reply.parent_header["msg_id"], "1");
+    let reply = JupyterMessage::from_zmq_message(
+      reply.into_zmq_message(Some(&key)).unwrap(),
+      Some(&key),
+    )
+    .unwrap();
+    assert_eq!(reply.zmq_identities, vec![Bytes::from("peer")]);
+    assert_eq!(reply.header["session"], "s");
+    assert_eq!(reply.content, json!({ "status": "ok" }));
+  }
+
+  #[test]
+  fn test_invalid_signature() {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, b"secret");
+    let other_key = hmac::Key::new(hmac::HMAC_SHA256, b"other");
+    assert!(JupyterMessage::from_zmq_message(
+      request(Some(&other_key)),
+      Some(&key)
+    )
+    .is_err());
+    assert!(
+      JupyterMessage::from_zmq_message(request(None), Some(&key)).is_err()
+    );
+    assert!(JupyterMessage::from_zmq_message(request(None), None).is_ok());
+  }
+}
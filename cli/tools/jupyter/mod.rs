@@ -0,0 +1,72 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::args::Flags;
+use crate::args::JupyterFlags;
+use crate::ops;
+use crate::proc_state::ProcState;
+use crate::tools::repl::ReplSession;
+use crate::worker::create_main_worker_with_extensions;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::futures::channel::mpsc;
+use deno_core::located_script_name;
+use deno_core::resolve_url_or_path;
+use deno_core::serde_json;
+use deno_runtime::permissions::Permissions;
+use deno_runtime::permissions::PermissionsContainer;
+
+mod install;
+mod jupyter_msg;
+mod server;
+
+use server::ConnectionSpec;
+use server::JupyterServer;
+
+pub async fn kernel(
+  flags: Flags,
+  jupyter_flags: JupyterFlags,
+) -> Result<(), AnyError> {
+  if !jupyter_flags.kernel {
+    if jupyter_flags.install {
+      install::install()?;
+    } else {
+      install::status()?;
+    }
+    return Ok(());
+  }
+
+  let conn_file_path = jupyter_flags.conn_file.unwrap();
+  let conn_file =
+    std::fs::read_to_string(&conn_file_path).with_context(|| {
+      format!("Error reading connection file {}", conn_file_path.display())
+    })?;
+  let spec: ConnectionSpec =
+    serde_json::from_str(&conn_file).with_context(|| {
+      format!("Invalid connection file {}", conn_file_path.display())
+    })?;
+
+  let main_module = resolve_url_or_path("./$deno$jupyter.ts").unwrap();
+  let ps = ProcState::build(flags).await?;
+  let (broadcast_tx, broadcast_rx) = mpsc::unbounded();
+  // Cells can't prompt for permissions, they are granted with the flags in
+  // the argv of the kernelspec.
+  let permissions = PermissionsContainer::new(Permissions::from_options(
+    &ps.options.permissions_options(),
+  )?);
+  let mut worker = create_main_worker_with_extensions(
+    &ps,
+    main_module,
+    permissions,
+    vec![ops::jupyter::init(broadcast_tx)],
+  )
+  .await?;
+  worker.setup_repl().await?;
+  let mut worker = worker.into_main_worker();
+  worker.execute_script(
+    &located_script_name!(),
+    "Deno[Deno.internal].enableJupyter();",
+  )?;
+  let repl_session = ReplSession::initialize(ps, worker).await?;
+
+  JupyterServer::start(spec, broadcast_rx, repl_session).await
+}
@@ -0,0 +1,659 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::jupyter_msg::JupyterMessage;
+use crate::ops::jupyter::JupyterBroadcast;
+use crate::tools::repl::cdp;
+use crate::tools::repl::ReplSession;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::futures::channel::mpsc;
+use deno_core::futures::channel::mpsc::UnboundedReceiver;
+use deno_core::futures::channel::mpsc::UnboundedSender;
+use deno_core::futures::StreamExt;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::v8;
+use deno_runtime::tokio_util::create_basic_runtime;
+use ring::hmac;
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+use zeromq::PubSocket;
+use zeromq::RepSocket;
+use zeromq::RouterSocket;
+use zeromq::Socket;
+use zeromq::SocketRecv;
+use zeromq::SocketSend;
+use zeromq::ZmqMessage;
+
+/// The contents of the connection file Jupyter starts the kernel with.
+/// <https://jupyter-client.readthedocs.io/en/latest/kernels.html#connection-files>
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConnectionSpec {
+  ip: String,
+  transport: String,
+  control_port: u32,
+  shell_port: u32,
+  stdin_port: u32,
+  hb_port: u32,
+  iopub_port: u32,
+  signature_scheme: String,
+  key: String,
+}
+
+/// What a cell evaluated to.
+enum CellOutput {
+  /// A MIME bundle, or `None` if the cell evaluated to `undefined`.
+  Value(Option<Value>),
+  Error {
+    ename: String,
+    evalue: String,
+    traceback: Vec<String>,
+  },
+}
+
+/// Interrupts the running cell on behalf of the control thread.
+struct Interrupter {
+  isolate_handle: v8::IsolateHandle,
+  state: Mutex<InterruptState>,
+  /// Wakes up a cell that awaits a promise rather than running code.
+  interrupt_tx: UnboundedSender<()>,
+}
+
+#[derive(Default)]
+struct InterruptState {
+  executing: bool,
+  interrupted: bool,
+}
+
+impl Interrupter {
+  fn interrupt(&self) {
+    let mut state = self.state.lock();
+    if state.executing && !state.interrupted {
+      state.interrupted = true;
+      self.isolate_handle.terminate_execution();
+      let _ = self.interrupt_tx.unbounded_send(());
+    }
+  }
+
+  fn start(&self, interrupt_rx: &mut UnboundedReceiver<()>) {
+    let mut state = self.state.lock();
+    state.executing = true;
+    // Interrupts of the previous cell that it didn't wait for.
+    while let Ok(Some(())) = interrupt_rx.try_next() {}
+  }
+
+  /// Returns whether the cell was interrupted, in which case the termination
+  /// of the isolate is canceled so that the next cell can run.
+  fn finish(&self, isolate: &mut v8::Isolate) -> bool {
+    let mut state = self.state.lock();
+    state.executing = false;
+    if state.interrupted {
+      state.interrupted = false;
+      isolate.cancel_terminate_execution();
+      true
+    } else {
+      false
+    }
+  }
+}
+
+pub struct JupyterServer {
+  execution_count: usize,
+  key: Option<hmac::Key>,
+  iopub_socket: PubSocket,
+  repl_session: ReplSession,
+  broadcast_rx: UnboundedReceiver<JupyterBroadcast>,
+  interrupter: Arc<Interrupter>,
+  interrupt_rx: UnboundedReceiver<()>,
+  /// The parent of the messages the code of the notebook broadcasts.
+  last_execute_request: Option<JupyterMessage>,
+}
+
+impl JupyterServer {
+  pub async fn start(
+    spec: ConnectionSpec,
+    broadcast_rx: UnboundedReceiver<JupyterBroadcast>,
+    repl_session: ReplSession,
+  ) -> Result<(), AnyError> {
+    let key = if spec.key.is_empty() {
+      None
+    } else if spec.signature_scheme == "hmac-sha256" {
+      Some(hmac::Key::new(hmac::HMAC_SHA256, spec.key.as_bytes()))
+    } else {
+      return Err(generic_error(format!(
+        "Unsupported signature scheme \"{}\"",
+        spec.signature_scheme
+      )));
+    };
+
+    let mut shell_socket =
+      bind_socket::<RouterSocket>(&spec, spec.shell_port).await?;
+    // Input requests are not supported, but Jupyter expects the socket.
+    let _stdin_socket =
+      bind_socket::<RouterSocket>(&spec, spec.stdin_port).await?;
+    let iopub_socket = bind_socket::<PubSocket>(&spec, spec.iopub_port).await?;
+
+    let (interrupt_tx, interrupt_rx) = mpsc::unbounded();
+    let interrupter = Arc::new(Interrupter {
+      isolate_handle: repl_session
+        .worker
+        .js_runtime
+        .v8_isolate()
+        .thread_safe_handle(),
+      state: Mutex::new(InterruptState::default()),
+      interrupt_tx,
+    });
+    let (shutdown_tx, mut shutdown_rx) = mpsc::unbounded();
+    spawn_control_thread(spec, key.clone(), interrupter.clone(), shutdown_tx)
+      .await??;
+
+    let mut server = Self {
+      execution_count: 0,
+      key,
+      iopub_socket,
+      repl_session,
+      broadcast_rx,
+      interrupter,
+      interrupt_rx,
+      last_execute_request: None,
+    };
+
+    let mut poll_worker = true;
+    loop {
+      tokio::select! {
+        message = shell_socket.recv() => {
+          server.handle_request(message?, &mut shell_socket).await?;
+          poll_worker = true;
+        }
+        _ = shutdown_rx.next() => {
+          return Ok(());
+        }
+        Some(broadcast) = server.broadcast_rx.next() => {
+          server.forward_broadcast(broadcast).await?;
+        }
+        result = server.repl_session.run_event_loop(), if poll_worker => {
+          if let Err(err) = result {
+            let broadcast = JupyterBroadcast {
+              msg_type: "stream".to_string(),
+              content: json!({
+                "name": "stderr",
+                "text": format!("{err}\n"),
+              }),
+            };
+            server.forward_broadcast(broadcast).await?;
+          }
+          poll_worker = false;
+        }
+      }
+    }
+  }
+
+  /// Handles a request received on the shell socket.
+  async fn handle_request(
+    &mut self,
+    message: ZmqMessage,
+    socket: &mut RouterSocket,
+  ) -> Result<(), AnyError> {
+    let message =
+      match JupyterMessage::from_zmq_message(message, self.key.as_ref()) {
+        Ok(message) => message,
+        Err(err) => {
+          log::warn!("Ignoring Jupyter message: {}", err);
+          return Ok(());
+        }
+      };
+
+    let status = message.new_message("status");
+    self
+      .publish(status, json!({ "execution_state": "busy" }))
+      .await?;
+
+    let reply = match message.message_type() {
+      "kernel_info_request" => Some(kernel_info()),
+      "execute_request" => Some(self.execute(&message).await?),
+      "complete_request" => Some(self.complete(&message).await),
+      "comm_info_request" => Some(json!({ "status": "ok", "comms": {} })),
+      "history_request" => Some(json!({ "status": "ok", "history": [] })),
+      "inspect_request" => Some(json!({
+        "status": "ok",
+        "found": false,
+        "data": {},
+        "metadata": {},
+      })),
+      "is_complete_request" => Some(json!({ "status": "unknown" })),
+      msg_type => {
+        log::debug!("Unhandled Jupyter message: {}", msg_type);
+        None
+      }
+    };
+    if let Some(reply) = reply {
+      let reply = message.new_reply().with_content(reply);
+      socket
+        .send(reply.into_zmq_message(self.key.as_ref())?)
+        .await?;
+    }
+
+    let status = message.new_message("status");
+    self
+      .publish(status, json!({ "execution_state": "idle" }))
+      .await
+  }
+
+  async fn execute(
+    &mut self,
+    message: &JupyterMessage,
+  ) -> Result<Value, AnyError> {
+    let code = message.content["code"].as_str().unwrap_or_default();
+    let silent = message.content["silent"].as_bool().unwrap_or(false);
+    let store_history =
+      message.content["store_history"].as_bool().unwrap_or(true);
+    if !silent && store_history {
+      self.execution_count += 1;
+    }
+    self.last_execute_request = Some(message.clone());
+
+    if !silent {
+      self
+        .publish(
+          message.new_message("execute_input"),
+          json!({
+            "execution_count": self.execution_count,
+            "code": code,
+          }),
+        )
+        .await?;
+    }
+
+    self.interrupter.start(&mut self.interrupt_rx);
+    let output = {
+      let Self {
+        iopub_socket,
+        key,
+        repl_session,
+        broadcast_rx,
+        interrupt_rx,
+        ..
+      } = self;
+      let evaluation = evaluate_cell(repl_session, code);
+      tokio::pin!(evaluation);
+      let output = loop {
+        tokio::select! {
+          output = &mut evaluation => break Some(output),
+          // The cell awaits a promise, it is abandoned.
+          _ = interrupt_rx.next() => break None,
+          Some(broadcast) = broadcast_rx.next() => {
+            let broadcast = to_message(message, broadcast);
+            iopub_socket
+              .send(broadcast.into_zmq_message(key.as_ref())?)
+              .await?;
+          }
+        }
+      };
+      // Forward what the cell broadcast right before it completed.
+      while let Ok(Some(broadcast)) = broadcast_rx.try_next() {
+        let broadcast = to_message(message, broadcast);
+        iopub_socket
+          .send(broadcast.into_zmq_message(key.as_ref())?)
+          .await?;
+      }
+      output
+    };
+    let interrupted = self
+      .interrupter
+      .finish(self.repl_session.worker.js_runtime.v8_isolate());
+    let output = match output {
+      Some(Ok(output)) if !interrupted => output,
+      // The kernel keeps running for the next cell.
+      Some(Err(err)) if !interrupted => CellOutput::Error {
+        ename: "Error".to_string(),
+        evalue: err.to_string(),
+        traceback: vec![err.to_string()],
+      },
+      _ => CellOutput::Error {
+        ename: "Interrupted".to_string(),
+        evalue: "Execution was interrupted".to_string(),
+        traceback: vec!["Interrupted: Execution was interrupted".to_string()],
+      },
+    };
+
+    match output {
+      CellOutput::Value(bundle) => {
+        if let Some(bundle) = bundle.filter(|_| !silent) {
+          self
+            .publish(
+              message.new_message("execute_result"),
+              json!({
+                "execution_count": self.execution_count,
+                "data": bundle,
+                "metadata": {},
+              }),
+            )
+            .await?;
+        }
+        Ok(json!({
+          "status": "ok",
+          "execution_count": self.execution_count,
+          "user_expressions": {},
+          "payload": [],
+        }))
+      }
+      CellOutput::Error {
+        ename,
+        evalue,
+        traceback,
+      } => {
+        self
+          .publish(
+            message.new_message("error"),
+            json!({
+              "ename": ename,
+              "evalue": evalue,
+              "traceback": traceback,
+            }),
+          )
+          .await?;
+        Ok(json!({
+          "status": "error",
+          "execution_count": self.execution_count,
+          "ename": ename,
+          "evalue": evalue,
+          "traceback": traceback,
+        }))
+      }
+    }
+  }
+
+  async fn complete(&mut self, message: &JupyterMessage) -> Value {
+    let code = message.content["code"].as_str().unwrap_or_default();
+    // Jupyter counts the cursor position in code points.
+    let cursor_pos = message.content["cursor_pos"].as_u64().unwrap_or(0);
+    let position = code
+      .char_indices()
+      .nth(cursor_pos as usize)
+      .map(|(index, _)| index)
+      .unwrap_or(code.len());
+    let completions = self
+      .repl_session
+      .language_server
+      .completions(code, position)
+      .await;
+    let cursor_pos = cursor_pos as usize;
+    let code_points = |index: usize| {
+      code
+        .get(..index)
+        .map(|code| code.chars().count())
+        .unwrap_or(cursor_pos)
+    };
+    // assumes all lsp completions have the same range
+    let (cursor_start, cursor_end) = match completions.first() {
+      Some(completion) => (
+        code_points(completion.range.start),
+        code_points(completion.range.end),
+      ),
+      None => (cursor_pos, cursor_pos),
+    };
+    json!({
+      "status": "ok",
+      "matches": completions
+        .into_iter()
+        .map(|completion| completion.new_text)
+        .collect::<Vec<_>>(),
+      "cursor_start": cursor_start,
+      "cursor_end": cursor_end,
+      "metadata": {},
+    })
+  }
+
+  async fn forward_broadcast(
+    &mut self,
+    broadcast: JupyterBroadcast,
+  ) -> Result<(), AnyError> {
+    // Output from before the first cell has no parent to be shown with.
+    if let Some(parent) = &self.last_execute_request {
+      let message = to_message(parent, broadcast);
+      self
+        .iopub_socket
+        .send(message.into_zmq_message(self.key.as_ref())?)
+        .await?;
+    }
+    Ok(())
+  }
+
+  async fn publish(
+    &mut self,
+    message: JupyterMessage,
+    content: Value,
+  ) -> Result<(), AnyError> {
+    let message = message.with_content(content);
+    self
+      .iopub_socket
+      .send(message.into_zmq_message(self.key.as_ref())?)
+      .await?;
+    Ok(())
+  }
+}
+
+/// Serves the heartbeat and control sockets on a thread of their own, so that
+/// they are answered and a cell can be interrupted while it blocks the thread
+/// of the worker. Resolves once the sockets are bound.
+fn spawn_control_thread(
+  spec: ConnectionSpec,
+  key: Option<hmac::Key>,
+  interrupter: Arc<Interrupter>,
+  shutdown_tx: UnboundedSender<()>,
+) -> oneshot::Receiver<Result<(), AnyError>> {
+  let (bound_tx, bound_rx) = oneshot::channel();
+  std::thread::spawn(move || {
+    create_basic_runtime().block_on(async move {
+      let sockets = async {
+        let heartbeat_socket =
+          bind_socket::<RepSocket>(&spec, spec.hb_port).await?;
+        let control_socket =
+          bind_socket::<RouterSocket>(&spec, spec.control_port).await?;
+        Ok::<_, AnyError>((heartbeat_socket, control_socket))
+      };
+      let (mut heartbeat_socket, control_socket) = match sockets.await {
+        Ok(sockets) => {
+          let _ = bound_tx.send(Ok(()));
+          sockets
+        }
+        Err(err) => {
+          let _ = bound_tx.send(Err(err));
+          return;
+        }
+      };
+
+      tokio::spawn(async move {
+        while let Ok(message) = heartbeat_socket.recv().await {
+          if heartbeat_socket.send(message).await.is_err() {
+            break;
+          }
+        }
+      });
+
+      let result = serve_control(control_socket, key, &interrupter).await;
+      if let Err(err) = result {
+        log::error!("Error serving the Jupyter control socket: {}", err);
+      }
+      let _ = shutdown_tx.unbounded_send(());
+    });
+  });
+  bound_rx
+}
+
+/// Handles the requests received on the control socket until the kernel is
+/// shut down.
+async fn serve_control(
+  mut socket: RouterSocket,
+  key: Option<hmac::Key>,
+  interrupter: &Interrupter,
+) -> Result<(), AnyError> {
+  loop {
+    let message = socket.recv().await?;
+    let message = match JupyterMessage::from_zmq_message(message, key.as_ref())
+    {
+      Ok(message) => message,
+      Err(err) => {
+        log::warn!("Ignoring Jupyter message: {}", err);
+        continue;
+      }
+    };
+
+    let mut shutdown = false;
+    let reply = match message.message_type() {
+      "kernel_info_request" => kernel_info(),
+      "interrupt_request" => {
+        interrupter.interrupt();
+        json!({ "status": "ok" })
+      }
+      "shutdown_request" => {
+        // Stops the running cell, the kernel exits once it returned.
+        interrupter.interrupt();
+        shutdown = true;
+        json!({
+          "status": "ok",
+          "restart": message.content["restart"],
+        })
+      }
+      msg_type => {
+        log::debug!("Unhandled Jupyter message: {}", msg_type);
+        continue;
+      }
+    };
+    let reply = message.new_reply().with_content(reply);
+    socket.send(reply.into_zmq_message(key.as_ref())?).await?;
+    if shutdown {
+      return Ok(());
+    }
+  }
+}
+
+async fn bind_socket<S: Socket>(
+  spec: &ConnectionSpec,
+  port: u32,
+) -> Result<S, AnyError> {
+  let endpoint = format!("{}://{}:{}", spec.transport, spec.ip, port);
+  let mut socket = S::new();
+  socket.bind(&endpoint).await?;
+  Ok(socket)
+}
+
+fn to_message(
+  parent: &JupyterMessage,
+  broadcast: JupyterBroadcast,
+) -> JupyterMessage {
+  parent
+    .new_message(&broadcast.msg_type)
+    .with_content(broadcast.content)
+}
+
+fn kernel_info() -> Value {
+  json!({
+    "status": "ok",
+    "protocol_version": "5.3",
+    "implementation": "Deno kernel",
+    "implementation_version": crate::version::deno(),
+    "language_info": {
+      "name": "typescript",
+      "version": crate::version::TYPESCRIPT,
+      "mimetype": "text/x.typescript",
+      "file_extension": ".ts",
+      "pygments_lexer": "typescript",
+      "codemirror_mode": "typescript",
+      "nbconvert_exporter": "script",
+    },
+    "banner": "Welcome to Deno kernel",
+    "help_links": [{
+      "text": "Visit Deno manual",
+      "url": "https://deno.land/manual",
+    }],
+  })
+}
+
+/// Runs the code of a cell, and formats its result like the REPL does,
+/// except that values are turned into MIME bundles by
+/// `Deno[Deno.internal].jupyter.format()`.
+async fn evaluate_cell(
+  repl_session: &mut ReplSession,
+  code: &str,
+) -> Result<CellOutput, AnyError> {
+  let evaluate_response =
+    match repl_session.evaluate_line_with_object_wrapping(code).await {
+      Ok(evaluate_response) => evaluate_response,
+      // Parsing diagnostics, and errors while resolving npm packages.
+      Err(err) => {
+        return Ok(CellOutput::Error {
+          ename: "Error".to_string(),
+          evalue: err.to_string(),
+          traceback: vec![err.to_string()],
+        })
+      }
+    };
+  let cdp::EvaluateResponse {
+    result,
+    exception_details,
+  } = evaluate_response.value;
+
+  if let Some(exception_details) = exception_details {
+    repl_session.set_last_thrown_error(&result).await?;
+    return Ok(exception_to_output(exception_details));
+  }
+
+  repl_session.commit_input(&evaluate_response.ts_code).await;
+  repl_session.set_last_eval_result(&result).await?;
+  if result.kind == "undefined" {
+    return Ok(CellOutput::Value(None));
+  }
+
+  let response = repl_session
+    .post_message_with_event_loop(
+      "Runtime.callFunctionOn",
+      Some(cdp::CallFunctionOnArgs {
+        function_declaration:
+          "function (object) { return Deno[Deno.internal].jupyter.format(object); }"
+            .to_string(),
+        object_id: None,
+        arguments: Some(vec![(&result).into()]),
+        silent: None,
+        return_by_value: Some(true),
+        generate_preview: None,
+        user_gesture: None,
+        await_promise: Some(true),
+        execution_context_id: Some(repl_session.context_id),
+        object_group: None,
+        throw_on_side_effect: None,
+      }),
+    )
+    .await?;
+  let response: cdp::CallFunctionOnResponse = serde_json::from_value(response)?;
+  Ok(match response.exception_details {
+    Some(exception_details) => exception_to_output(exception_details),
+    None => CellOutput::Value(response.result.value),
+  })
+}
+
+fn exception_to_output(exception_details: cdp::ExceptionDetails) -> CellOutput {
+  let (ename, description) = match exception_details.exception {
+    Some(exception) => (
+      exception.class_name.unwrap_or_else(|| "Error".to_string()),
+      exception
+        .description
+        .unwrap_or_else(|| "Unknown exception".to_string()),
+    ),
+    None => ("Error".to_string(), exception_details.text),
+  };
+  // The description of errors is their stack, starting with "Name: message".
+  let first_line = description.lines().next().unwrap_or_default();
+  let evalue = first_line
+    .strip_prefix(&format!("{ename}: "))
+    .unwrap_or(first_line)
+    .to_string();
+  CellOutput::Error {
+    ename,
+    evalue,
+    traceback: description.lines().map(String::from).collect(),
+  }
+}
@@ -9,7 +9,10 @@ pub mod fmt;
 pub mod info;
 pub mod init;
 pub mod installer;
+pub mod jupyter;
 pub mod lint;
+pub mod permissions;
+pub mod profiler;
 pub mod repl;
 pub mod run;
 pub mod standalone;
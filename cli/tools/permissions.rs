@@ -0,0 +1,60 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::args::CliOptions;
+use crate::args::Flags;
+use crate::args::PermissionsFlags;
+use crate::colors;
+use crate::util::fs::resolve_from_cwd;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_runtime::permissions::Grants;
+use log::info;
+use std::path::Path;
+
+pub fn permissions(
+  flags: Flags,
+  permissions_flags: PermissionsFlags,
+) -> Result<(), AnyError> {
+  let cli_options = CliOptions::from_flags(flags)?;
+  let grants_file = cli_options.grants_file_path().ok_or_else(|| {
+    generic_error(
+      "Permissions are only remembered for projects with a configuration file, but none was found.",
+    )
+  })?;
+  let mut grants = Grants::load(&grants_file)?;
+
+  match permissions_flags {
+    PermissionsFlags::List => {
+      if grants.is_empty() {
+        info!("No permissions are remembered for this project.");
+      }
+      for (name, values) in grants.entries() {
+        for value in values {
+          println!("{} {}", colors::green(format!("{name:<6}")), value);
+        }
+      }
+    }
+    PermissionsFlags::Revoke { name, values } => {
+      // Paths are remembered as absolute paths.
+      let values = match name.as_deref() {
+        Some("ffi" | "read" | "write") => values
+          .iter()
+          .map(|value| {
+            resolve_from_cwd(Path::new(value))
+              .map(|path| path.to_string_lossy().to_string())
+          })
+          .collect::<Result<Vec<_>, _>>()?,
+        _ => values,
+      };
+      let count = grants.revoke(name.as_deref(), &values)?;
+      grants.save(&grants_file)?;
+      info!(
+        "{} {} remembered permission{}",
+        colors::green("Revoked"),
+        count,
+        if count == 1 { "" } else { "s" }
+      );
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,139 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::futures::channel::mpsc::UnboundedReceiver;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::LocalInspectorSession;
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Interval at which the CPU profiler samples the call stack, in
+/// microseconds.
+const CPU_PROFILE_SAMPLING_INTERVAL: u64 = 1000;
+
+static PROFILE_SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns a path in `dir` for a profile, named like the ones Node.js writes,
+/// e.g. `CPU.20230101.120000.1234.0.001.cpuprofile`.
+pub fn profile_file_path(dir: &Path, prefix: &str, extension: &str) -> PathBuf {
+  let now = chrono::Local::now();
+  let sequence = PROFILE_SEQUENCE.fetch_add(1, Ordering::SeqCst);
+  dir.join(format!(
+    "{prefix}.{}.{}.0.{:03}.{extension}",
+    now.format("%Y%m%d.%H%M%S"),
+    std::process::id(),
+    sequence
+  ))
+}
+
+/// Records a CPU profile and/or takes a heap snapshot when the program
+/// exits, through an inspector session in the same way as the coverage
+/// collector.
+pub struct Profiler {
+  cpu_prof_dir: Option<PathBuf>,
+  heap_snapshot_dir: Option<PathBuf>,
+  session: LocalInspectorSession,
+  notification_rx: UnboundedReceiver<Value>,
+}
+
+impl Profiler {
+  pub fn new(
+    cpu_prof_dir: Option<PathBuf>,
+    heap_snapshot_dir: Option<PathBuf>,
+    mut session: LocalInspectorSession,
+  ) -> Self {
+    let notification_rx = session.take_notification_rx();
+    Self {
+      cpu_prof_dir,
+      heap_snapshot_dir,
+      session,
+      notification_rx,
+    }
+  }
+
+  pub async fn start(&mut self) -> Result<(), AnyError> {
+    if self.cpu_prof_dir.is_some() {
+      self
+        .session
+        .post_message::<()>("Profiler.enable", None)
+        .await?;
+      self
+        .session
+        .post_message(
+          "Profiler.setSamplingInterval",
+          Some(json!({ "interval": CPU_PROFILE_SAMPLING_INTERVAL })),
+        )
+        .await?;
+      self
+        .session
+        .post_message::<()>("Profiler.start", None)
+        .await?;
+    }
+    if self.heap_snapshot_dir.is_some() {
+      self
+        .session
+        .post_message::<()>("HeapProfiler.enable", None)
+        .await?;
+    }
+    Ok(())
+  }
+
+  pub async fn stop(&mut self) -> Result<(), AnyError> {
+    if let Some(dir) = self.cpu_prof_dir.clone() {
+      let result = self
+        .session
+        .post_message::<()>("Profiler.stop", None)
+        .await?;
+      let profile = result
+        .get("profile")
+        .ok_or_else(|| generic_error("The CPU profile is missing"))?;
+      fs::create_dir_all(&dir)?;
+      let path = profile_file_path(&dir, "CPU", "cpuprofile");
+      let mut out = BufWriter::new(File::create(path)?);
+      serde_json::to_writer(&mut out, profile)?;
+      out.flush()?;
+      self
+        .session
+        .post_message::<()>("Profiler.disable", None)
+        .await?;
+    }
+
+    if let Some(dir) = self.heap_snapshot_dir.clone() {
+      fs::create_dir_all(&dir)?;
+      let path = profile_file_path(&dir, "Heap", "heapsnapshot");
+      let mut out = BufWriter::new(File::create(path)?);
+      self
+        .session
+        .post_message(
+          "HeapProfiler.takeHeapSnapshot",
+          Some(json!({ "reportProgress": false })),
+        )
+        .await?;
+      // All chunks are sent before the response to the request.
+      while let Ok(Some(notification)) = self.notification_rx.try_next() {
+        if notification["method"] != "HeapProfiler.addHeapSnapshotChunk" {
+          continue;
+        }
+        if let Some(chunk) = notification["params"]["chunk"].as_str() {
+          out.write_all(chunk.as_bytes())?;
+        }
+      }
+      out.flush()?;
+      self
+        .session
+        .post_message::<()>("HeapProfiler.disable", None)
+        .await?;
+    }
+
+    Ok(())
+  }
+}
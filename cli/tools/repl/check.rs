@@ -0,0 +1,152 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::args::TsConfigType;
+use crate::cache::FetchCacher;
+use crate::cache::TypeCheckCache;
+use crate::graph_util::graph_valid_with_cli_options;
+use crate::npm::resolve_graph_npm_info;
+use crate::proc_state::ProcState;
+use crate::tools::check;
+use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::anyhow;
+use deno_core::error::AnyError;
+use deno_core::futures;
+use deno_graph::source::CacheInfo;
+use deno_graph::source::LoadFuture;
+use deno_graph::source::LoadResponse;
+use deno_graph::source::Loader;
+use std::sync::Arc;
+
+/// Diagnostics which are expected in the virtual module, because the REPL
+/// allows redeclaring bindings from earlier inputs.
+const IGNORED_DIAGNOSTIC_CODES: &[u64] = &[
+  2300, // Duplicate identifier '{0}'.
+  2393, // Duplicate function implementation.
+  2451, // Cannot redeclare block-scoped variable '{0}'.
+];
+
+/// Loads the virtual module of the session from memory and everything it
+/// imports from the cache.
+struct VirtualModuleLoader {
+  specifier: ModuleSpecifier,
+  source: Arc<str>,
+  inner: FetchCacher,
+}
+
+impl Loader for VirtualModuleLoader {
+  fn get_cache_info(&self, specifier: &ModuleSpecifier) -> Option<CacheInfo> {
+    self.inner.get_cache_info(specifier)
+  }
+
+  fn load(
+    &mut self,
+    specifier: &ModuleSpecifier,
+    is_dynamic: bool,
+  ) -> LoadFuture {
+    if specifier != &self.specifier {
+      return self.inner.load(specifier, is_dynamic);
+    }
+    let response = LoadResponse::Module {
+      specifier: specifier.clone(),
+      content: self.source.clone(),
+      maybe_headers: None,
+    };
+    Box::pin(futures::future::ready(Ok(Some(response))))
+  }
+}
+
+/// Type checks an input in the context of the inputs committed before it.
+/// Those are kept in a virtual module at `specifier`, followed by the input,
+/// and only the diagnostics reported for the input are returned as an error.
+pub async fn check_input(
+  ps: &ProcState,
+  specifier: &ModuleSpecifier,
+  committed_inputs: &[String],
+  input: &str,
+) -> Result<(), AnyError> {
+  let mut source = String::new();
+  for committed_input in committed_inputs {
+    source.push_str(committed_input);
+    source.push('\n');
+  }
+  let input_line = source.matches('\n').count() as u64;
+  source.push_str(input);
+  // Makes sure the virtual module is checked as a module, which allows top
+  // level await.
+  source.push_str("\nexport {};\n");
+
+  let mut loader = VirtualModuleLoader {
+    specifier: specifier.clone(),
+    source: source.into(),
+    inner: ps.create_graph_loader(),
+  };
+  let roots = vec![specifier.clone()];
+  let graph = ps
+    .create_graph_with_loader(roots.clone(), &mut loader)
+    .await;
+  // The virtual module changes with every input, so don't keep it around.
+  ps.parsed_source_cache.free(specifier);
+  let graph = graph?;
+  graph_valid_with_cli_options(&graph, &roots, &ps.options)?;
+
+  let npm_graph_info = resolve_graph_npm_info(&graph);
+  ps.npm_resolver
+    .add_package_reqs(npm_graph_info.package_reqs)
+    .await?;
+  if npm_graph_info.has_node_builtin_specifier {
+    ps.npm_resolver
+      .inject_synthetic_types_node_package()
+      .await?;
+  }
+
+  let ts_config = ps
+    .options
+    .resolve_ts_config_for_emit(TsConfigType::Check {
+      lib: ps.options.ts_type_lib_window(),
+    })?
+    .ts_config;
+  let check_cache =
+    TypeCheckCache::new(&ps.dir.type_checking_cache_db_file_path());
+  let check_result = check::check(
+    Arc::new(graph),
+    &check_cache,
+    &ps.npm_resolver,
+    check::CheckOptions {
+      type_check_mode: ps.options.type_check_mode(),
+      debug: ps.options.log_level() == Some(log::Level::Debug),
+      maybe_config_specifier: ps.options.maybe_config_file_specifier(),
+      ts_config,
+      log_checks: false,
+      reload: false,
+      has_node_builtin_specifier: npm_graph_info.has_node_builtin_specifier,
+    },
+  )?;
+
+  let diagnostics = check_result.diagnostics.filter(|diagnostic| {
+    if diagnostic.file_name.as_deref() != Some(specifier.as_str()) {
+      return Some(diagnostic.clone());
+    }
+    if IGNORED_DIAGNOSTIC_CODES.contains(&diagnostic.code) {
+      return None;
+    }
+    // Earlier inputs were already reported when they were entered.
+    match &diagnostic.start {
+      Some(start) if start.line < input_line => return None,
+      _ => {}
+    }
+    // Report positions relative to the input.
+    let mut diagnostic = diagnostic.clone();
+    for position in [&mut diagnostic.start, &mut diagnostic.end]
+      .into_iter()
+      .flatten()
+    {
+      position.line = position.line.saturating_sub(input_line);
+    }
+    Some(diagnostic)
+  });
+  if diagnostics.is_empty() {
+    Ok(())
+  } else {
+    Err(anyhow!(diagnostics))
+  }
+}
@@ -447,8 +447,8 @@ impl ReplEditor {
     })
   }
 
-  pub fn readline(&self) -> Result<String, ReadlineError> {
-    self.inner.lock().readline("> ")
+  pub fn readline(&self, prompt: &str) -> Result<String, ReadlineError> {
+    self.inner.lock().readline(prompt)
   }
 
   pub fn update_history(&self, entry: String) {
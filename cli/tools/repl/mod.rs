@@ -11,8 +11,9 @@ use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsContainer;
 use rustyline::error::ReadlineError;
 
-mod cdp;
+pub mod cdp;
 mod channel;
+mod check;
 mod editor;
 mod session;
 
@@ -23,14 +24,16 @@ use channel::RustylineSyncResponse;
 use editor::EditorHelper;
 use editor::ReplEditor;
 use session::EvaluationOutput;
-use session::ReplSession;
+pub use session::ReplSession;
 
 async fn read_line_and_poll(
   repl_session: &mut ReplSession,
   message_handler: &mut RustylineSyncMessageHandler,
   editor: ReplEditor,
+  prompt: &'static str,
 ) -> Result<String, ReadlineError> {
-  let mut line_fut = tokio::task::spawn_blocking(move || editor.readline());
+  let mut line_fut =
+    tokio::task::spawn_blocking(move || editor.readline(prompt));
   let mut poll_worker = true;
 
   loop {
@@ -65,6 +68,103 @@ async fn read_line_and_poll(
   }
 }
 
+/// A command entered at the prompt instead of code, like `.save file.ts`.
+#[derive(Debug, PartialEq, Eq)]
+enum ReplCommand<'a> {
+  /// Writes the inputs that evaluated without throwing to a file.
+  Save(&'a str),
+  /// Evaluates a file or URL into the session.
+  Load(&'a str),
+  /// Reads a multi-line buffer until ctrl+d, then evaluates it.
+  Editor,
+  /// Prints the type the type checker infers for an expression.
+  Type(&'a str),
+}
+
+impl<'a> ReplCommand<'a> {
+  fn parse(line: &'a str) -> Option<Self> {
+    let line = line.trim();
+    let (name, arg) = match line.split_once(char::is_whitespace) {
+      Some((name, arg)) => (name, arg.trim()),
+      None => (line, ""),
+    };
+    match name {
+      ".save" => Some(Self::Save(arg)),
+      ".load" => Some(Self::Load(arg)),
+      ".editor" if arg.is_empty() => Some(Self::Editor),
+      ".type" => Some(Self::Type(arg)),
+      _ => None,
+    }
+  }
+}
+
+/// Runs a command, and returns what to print.
+async fn run_command(
+  command: ReplCommand<'_>,
+  ps: &ProcState,
+  repl_session: &mut ReplSession,
+  message_handler: &mut RustylineSyncMessageHandler,
+  editor: &ReplEditor,
+) -> Option<String> {
+  match command {
+    ReplCommand::Save("") => Some("Usage: .save <file>".to_string()),
+    ReplCommand::Save(path) => {
+      let inputs = repl_session.committed_inputs();
+      let mut text = inputs.join("\n");
+      text.push('\n');
+      Some(match std::fs::write(path, text) {
+        Ok(()) => format!("Saved {} inputs to \"{path}\"", inputs.len()),
+        Err(err) => format!("Error saving to \"{path}\": {err}"),
+      })
+    }
+    ReplCommand::Load("") => Some("Usage: .load <file>".to_string()),
+    ReplCommand::Load(path) => match read_eval_file(ps, path).await {
+      Ok(source) => Some(
+        repl_session
+          .evaluate_line_and_get_output(&source)
+          .await
+          .to_string(),
+      ),
+      Err(err) => Some(format!("Error loading \"{path}\": {err}")),
+    },
+    ReplCommand::Editor => {
+      println!("// Entering editor mode (ctrl+d to finish, ctrl+c to cancel)");
+      let mut buffer = String::new();
+      loop {
+        let line =
+          read_line_and_poll(repl_session, message_handler, editor.clone(), "")
+            .await;
+        match line {
+          Ok(line) => {
+            buffer.push_str(&line);
+            buffer.push('\n');
+          }
+          Err(ReadlineError::Eof) => break,
+          Err(ReadlineError::Interrupted) => return None,
+          Err(err) => return Some(format!("Error: {err:?}")),
+        }
+      }
+      editor.update_history(buffer.clone());
+      Some(
+        repl_session
+          .evaluate_line_and_get_output(&buffer)
+          .await
+          .to_string(),
+      )
+    }
+    ReplCommand::Type("") => Some("Usage: .type <expression>".to_string()),
+    ReplCommand::Type(expression) => Some(
+      repl_session
+        .language_server
+        .type_of(expression)
+        .await
+        .unwrap_or_else(|| {
+          format!("Unable to get the type of \"{expression}\"")
+        }),
+    ),
+  }
+}
+
 async fn read_eval_file(
   ps: &ProcState,
   eval_file: &str,
@@ -149,13 +249,31 @@ pub async fn run(flags: Flags, repl_flags: ReplFlags) -> Result<i32, AnyError> {
       &mut repl_session,
       &mut rustyline_channel.1,
       editor.clone(),
+      "> ",
     )
     .await;
     match line {
       Ok(line) => {
         editor.set_should_exit_on_interrupt(false);
         editor.update_history(line.clone());
-        let output = repl_session.evaluate_line_and_get_output(&line).await;
+        let output = match ReplCommand::parse(&line) {
+          Some(command) => {
+            run_command(
+              command,
+              &ps,
+              &mut repl_session,
+              &mut rustyline_channel.1,
+              &editor,
+            )
+            .await
+          }
+          None => Some(
+            repl_session
+              .evaluate_line_and_get_output(&line)
+              .await
+              .to_string(),
+          ),
+        };
 
         // We check for close and break here instead of making it a loop condition to get
         // consistent behavior in when the user evaluates a call to close().
@@ -163,7 +281,9 @@ pub async fn run(flags: Flags, repl_flags: ReplFlags) -> Result<i32, AnyError> {
           break;
         }
 
-        println!("{output}");
+        if let Some(output) = output {
+          println!("{output}");
+        }
       }
       Err(ReadlineError::Interrupted) => {
         if editor.should_exit_on_interrupt() {
@@ -185,3 +305,30 @@ pub async fn run(flags: Flags, repl_flags: ReplFlags) -> Result<i32, AnyError> {
 
   Ok(repl_session.worker.exit_code())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_command() {
+    assert_eq!(
+      ReplCommand::parse(".save session.ts"),
+      Some(ReplCommand::Save("session.ts"))
+    );
+    assert_eq!(
+      ReplCommand::parse("  .load   ./mod.ts "),
+      Some(ReplCommand::Load("./mod.ts"))
+    );
+    assert_eq!(ReplCommand::parse(".save"), Some(ReplCommand::Save("")));
+    assert_eq!(ReplCommand::parse(".editor"), Some(ReplCommand::Editor));
+    assert_eq!(
+      ReplCommand::parse(".type [1, \"a\"]"),
+      Some(ReplCommand::Type("[1, \"a\"]"))
+    );
+    // code that happens to start with a dot
+    assert_eq!(ReplCommand::parse(".5 + 1"), None);
+    assert_eq!(ReplCommand::parse(".editor()"), None);
+    assert_eq!(ReplCommand::parse(".saved"), None);
+  }
+}
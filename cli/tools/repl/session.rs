@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::TypeCheckMode;
 use crate::colors;
 use crate::lsp::ReplLanguageServer;
 use crate::ProcState;
@@ -24,6 +25,7 @@ use deno_runtime::deno_node;
 use deno_runtime::worker::MainWorker;
 
 use super::cdp;
+use super::check;
 
 static PRELUDE: &str = r#"
 Object.defineProperty(globalThis, "_", {
@@ -83,9 +85,9 @@ pub fn result_to_evaluation_output(
   }
 }
 
-struct TsEvaluateResponse {
-  ts_code: String,
-  value: cdp::EvaluateResponse,
+pub struct TsEvaluateResponse {
+  pub ts_code: String,
+  pub value: cdp::EvaluateResponse,
 }
 
 pub struct ReplSession {
@@ -96,6 +98,8 @@ pub struct ReplSession {
   pub language_server: ReplLanguageServer,
   has_initialized_node_runtime: bool,
   referrer: ModuleSpecifier,
+  /// The inputs that evaluated without throwing, for `.save`.
+  committed_inputs: Vec<String>,
   // FIXME(bartlomieju): this field should be used to listen
   // for "exceptionThrown" notifications
   #[allow(dead_code)]
@@ -153,6 +157,7 @@ impl ReplSession {
       language_server,
       has_initialized_node_runtime: false,
       referrer,
+      committed_inputs: Vec::new(),
       notification_rx,
     };
 
@@ -190,6 +195,17 @@ impl ReplSession {
     self.worker.run_event_loop(true).await
   }
 
+  /// Records an input that evaluated without throwing, so later completions
+  /// and types know about its declarations.
+  pub async fn commit_input(&mut self, ts_code: &str) {
+    self.language_server.commit_text(ts_code).await;
+    self.committed_inputs.push(ts_code.to_string());
+  }
+
+  pub fn committed_inputs(&self) -> &[String] {
+    &self.committed_inputs
+  }
+
   pub async fn evaluate_line_and_get_output(
     &mut self,
     line: &str,
@@ -229,10 +245,7 @@ impl ReplSession {
               exception_details.text, description
             ))
           } else {
-            session
-              .language_server
-              .commit_text(&evaluate_response.ts_code)
-              .await;
+            session.commit_input(&evaluate_response.ts_code).await;
 
             session.set_last_eval_result(&result).await?;
             let value = session.get_eval_value(&result).await?;
@@ -265,7 +278,7 @@ impl ReplSession {
     result_to_evaluation_output(result)
   }
 
-  async fn evaluate_line_with_object_wrapping(
+  pub async fn evaluate_line_with_object_wrapping(
     &mut self,
     line: &str,
   ) -> Result<TsEvaluateResponse, AnyError> {
@@ -299,7 +312,7 @@ impl ReplSession {
     }
   }
 
-  async fn set_last_thrown_error(
+  pub async fn set_last_thrown_error(
     &mut self,
     error: &cdp::RemoteObject,
   ) -> Result<(), AnyError> {
@@ -322,7 +335,7 @@ impl ReplSession {
     Ok(())
   }
 
-  async fn set_last_eval_result(
+  pub async fn set_last_eval_result(
     &mut self,
     evaluate_result: &cdp::RemoteObject,
   ) -> Result<(), AnyError> {
@@ -404,6 +417,16 @@ impl ReplSession {
       .check_for_npm_or_node_imports(&parsed_module.program())
       .await?;
 
+    if self.proc_state.options.type_check_mode() != TypeCheckMode::None {
+      check::check_input(
+        &self.proc_state,
+        &self.referrer,
+        &self.committed_inputs,
+        expression,
+      )
+      .await?;
+    }
+
     let transpiled_src = parsed_module
       .transpile(&deno_ast::EmitOptions {
         emit_metadata: false,
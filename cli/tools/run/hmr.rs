@@ -0,0 +1,266 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::colors;
+use crate::emit::emit_parsed_source;
+use crate::proc_state::ProcState;
+use crate::util::file_watcher::HotReloadChannel;
+
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::futures::channel::mpsc::UnboundedReceiver;
+use deno_core::futures::StreamExt;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::LocalInspectorSession;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::select;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScriptParsedParameters {
+  script_id: String,
+  url: String,
+}
+
+#[derive(Debug, Deserialize)]
+enum SetScriptSourceStatus {
+  Ok,
+  CompileError,
+  BlockedByActiveGenerator,
+  BlockedByActiveFunction,
+  BlockedByTopLevelEsModuleChange,
+}
+
+impl SetScriptSourceStatus {
+  fn explain(&self) -> &'static str {
+    match self {
+      Self::Ok => "it was replaced",
+      Self::CompileError => "the new source failed to compile",
+      Self::BlockedByActiveGenerator => "a changed generator is running",
+      Self::BlockedByActiveFunction => "a changed function is running",
+      Self::BlockedByTopLevelEsModuleChange => "top-level module code changed",
+    }
+  }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetScriptSourceReturnObject {
+  /// Only reported by newer versions of V8, older ones fail with an
+  /// `exceptionDetails` instead.
+  status: Option<SetScriptSourceStatus>,
+  exception_details: Option<Value>,
+}
+
+/// Hot replaces changed modules of a running worker for
+/// `deno run --watch-hmr`, through an inspector session in the same way as
+/// the coverage collector.
+///
+/// Changed functions are patched with `Debugger.setScriptSource`, after which
+/// an "hmr" event is dispatched on `globalThis`. Changes which V8 can't apply
+/// in place restart the worker instead.
+pub struct HmrRunner {
+  ps: ProcState,
+  session: LocalInspectorSession,
+  notification_rx: UnboundedReceiver<Value>,
+  hot_reload: HotReloadChannel,
+  changed_paths_rx: broadcast::Receiver<Vec<PathBuf>>,
+  /// Inspector script ids of the modules evaluated in the worker, by URL.
+  script_ids: HashMap<String, String>,
+}
+
+impl HmrRunner {
+  pub fn new(
+    ps: ProcState,
+    mut session: LocalInspectorSession,
+    hot_reload: HotReloadChannel,
+  ) -> Self {
+    let notification_rx = session.take_notification_rx();
+    // subscribe right away, so that no change is missed between updates
+    let changed_paths_rx = hot_reload.subscribe();
+    Self {
+      ps,
+      session,
+      notification_rx,
+      hot_reload,
+      changed_paths_rx,
+      script_ids: HashMap::new(),
+    }
+  }
+
+  pub async fn start(&mut self) -> Result<(), AnyError> {
+    self
+      .session
+      .post_message::<()>("Debugger.enable", None)
+      .await?;
+    self
+      .session
+      .post_message::<()>("Runtime.enable", None)
+      .await?;
+    Ok(())
+  }
+
+  /// Waits for the next file change reported by the watcher, while keeping
+  /// track of the scripts parsed in the worker. This is cancel safe, so it
+  /// can be polled alongside the worker's event loop.
+  pub async fn next_change(&mut self) -> Vec<PathBuf> {
+    loop {
+      select! {
+        biased;
+        Some(notification) = self.notification_rx.next() => {
+          self.handle_notification(notification);
+        }
+        changed_paths = self.changed_paths_rx.recv() => {
+          match changed_paths {
+            Ok(paths) => {
+              // Collect the scripts parsed up to this change.
+              while let Ok(Some(notification)) =
+                self.notification_rx.try_next()
+              {
+                self.handle_notification(notification);
+              }
+              return paths;
+            }
+            Err(RecvError::Lagged(_)) => self.hot_reload.request_restart(),
+            // the sender is kept alive by `self.hot_reload`
+            Err(RecvError::Closed) => unreachable!(),
+          }
+        }
+        _ = self.session.receive_from_v8_session() => {}
+      }
+    }
+  }
+
+  /// Hot replaces the changed modules, or restarts the worker if they can't
+  /// be replaced. This is not cancel safe and needs the worker's event loop
+  /// to be polled until it completes, see `MainWorker::with_event_loop`.
+  pub async fn apply(&mut self, paths: Vec<PathBuf>) {
+    if let Err(err) = self.apply_changes(&paths).await {
+      log::debug!("Failed to hot replace modules: {:#}", err);
+      self.hot_reload.request_restart();
+    }
+  }
+
+  fn handle_notification(&mut self, notification: Value) {
+    if notification["method"] != "Debugger.scriptParsed" {
+      return;
+    }
+    if let Ok(params) = serde_json::from_value::<ScriptParsedParameters>(
+      notification["params"].clone(),
+    ) {
+      if params.url.starts_with("file://") {
+        self.script_ids.insert(params.url, params.script_id);
+      }
+    }
+  }
+
+  async fn apply_changes(&mut self, paths: &[PathBuf]) -> Result<(), AnyError> {
+    for path in paths {
+      let specifier = ModuleSpecifier::from_file_path(path).map_err(|_| {
+        generic_error(format!("Invalid file path: {}", path.display()))
+      })?;
+      let script_id = match self.script_ids.get(specifier.as_str()) {
+        Some(script_id) => script_id.clone(),
+        // not a module of the program, e.g. a configuration file
+        None => {
+          return Err(generic_error(format!(
+            "Changed file is not a loaded module: {specifier}"
+          )))
+        }
+      };
+
+      let source = self.load_and_emit(&specifier, path)?;
+      let return_value = self
+        .session
+        .post_message(
+          "Debugger.setScriptSource",
+          Some(json!({
+            "scriptId": script_id,
+            "scriptSource": source,
+            "allowTopFrameEditing": true,
+          })),
+        )
+        .await?;
+      let return_object: SetScriptSourceReturnObject =
+        serde_json::from_value(return_value)?;
+      let status = match return_object.status {
+        Some(status) => status,
+        None if return_object.exception_details.is_some() => {
+          SetScriptSourceStatus::CompileError
+        }
+        None => SetScriptSourceStatus::Ok,
+      };
+      if !matches!(status, SetScriptSourceStatus::Ok) {
+        log::info!(
+          "{} Can't replace {} in place because {}.",
+          colors::intense_blue("HMR"),
+          specifier,
+          status.explain(),
+        );
+        self.hot_reload.request_restart();
+        return Ok(());
+      }
+
+      self.dispatch_hmr_event(path).await?;
+      log::info!("{} Replaced {}", colors::intense_blue("HMR"), specifier);
+    }
+    Ok(())
+  }
+
+  /// Reads the changed module from disk and transpiles it the same way as
+  /// the module loader does.
+  fn load_and_emit(
+    &self,
+    specifier: &ModuleSpecifier,
+    path: &Path,
+  ) -> Result<String, AnyError> {
+    let source: Arc<str> = std::fs::read_to_string(path)?.into();
+    let media_type = MediaType::from(specifier);
+    match media_type {
+      MediaType::JavaScript | MediaType::Mjs => Ok(source.to_string()),
+      MediaType::TypeScript
+      | MediaType::Mts
+      | MediaType::Jsx
+      | MediaType::Tsx => {
+        // the parsed source cache still holds the previous source, if any
+        self.ps.parsed_source_cache.free(specifier);
+        emit_parsed_source(
+          &self.ps.emit_cache,
+          &self.ps.parsed_source_cache,
+          specifier,
+          media_type,
+          &source,
+          &self.ps.emit_options,
+          self.ps.emit_options_hash,
+        )
+      }
+      _ => Err(generic_error(format!(
+        "Modules of type {media_type} can't be hot replaced: {specifier}"
+      ))),
+    }
+  }
+
+  async fn dispatch_hmr_event(&mut self, path: &Path) -> Result<(), AnyError> {
+    let expression = format!(
+      "dispatchEvent(new CustomEvent(\"hmr\", {{ detail: {{ path: {} }} }}));",
+      serde_json::to_string(&path.to_string_lossy())?
+    );
+    self
+      .session
+      .post_message(
+        "Runtime.evaluate",
+        Some(json!({ "expression": expression })),
+      )
+      .await?;
+    Ok(())
+  }
+}
@@ -17,8 +17,11 @@ use crate::args::RunFlags;
 use crate::file_fetcher::File;
 use crate::proc_state::ProcState;
 use crate::util;
+use crate::util::file_watcher::HotReloadChannel;
 use crate::worker::create_main_worker;
 
+pub mod hmr;
+
 pub async fn run_script(
   flags: Flags,
   run_flags: RunFlags,
@@ -104,16 +107,18 @@ async fn run_with_watch(flags: Flags, script: String) -> Result<i32, AnyError> {
   let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
   let mut ps =
     ProcState::build_for_file_watcher((*flags).clone(), sender.clone()).await?;
+  let maybe_hot_reload = ps.options.has_hmr().then(HotReloadChannel::default);
 
   let operation = |main_module: ModuleSpecifier| {
     ps.reset_for_file_watcher();
     let ps = ps.clone();
+    let maybe_hot_reload = maybe_hot_reload.clone();
     Ok(async move {
       let permissions = PermissionsContainer::new(Permissions::from_options(
         &ps.options.permissions_options(),
       )?);
       let worker = create_main_worker(&ps, main_module, permissions).await?;
-      worker.run_for_watcher().await?;
+      worker.run_for_watcher(maybe_hot_reload).await?;
 
       Ok(())
     })
@@ -127,6 +132,7 @@ async fn run_with_watch(flags: Flags, script: String) -> Result<i32, AnyError> {
       job_name: "Process".to_string(),
       clear_screen: !flags.no_clear_screen,
     },
+    maybe_hot_reload.clone(),
   )
   .await?;
 
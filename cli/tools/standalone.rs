@@ -21,6 +21,7 @@ use deno_core::resolve_url_or_path;
 use deno_core::serde_json;
 use deno_graph::ModuleSpecifier;
 use deno_runtime::colors;
+use deno_runtime::permissions::PermissionsOptions;
 use std::env;
 use std::fs;
 use std::fs::File;
@@ -178,7 +179,12 @@ async fn create_standalone_binary(
     unstable: ps.options.unstable(),
     seed: ps.options.seed(),
     location: ps.options.location_flag().clone(),
-    permissions: ps.options.permissions_options(),
+    permissions: PermissionsOptions {
+      // Grants remembered for the project don't carry over to the binary.
+      grants_file: None,
+      ..ps.options.permissions_options()
+    },
+    resource_limits: ps.options.resource_limits(),
     v8_flags: ps.options.v8_flags().clone(),
     unsafely_ignore_certificate_errors: ps
       .options
@@ -956,7 +956,9 @@ pub async fn check_specifiers(
       specifiers,
       false,
       lib,
-      PermissionsContainer::new(Permissions::allow_all()),
+      PermissionsContainer::new(Permissions::allow_all_but_import(
+        &ps.options.permissions_options(),
+      )?),
       PermissionsContainer::new(permissions.clone()),
     )
     .await?;
@@ -977,7 +979,9 @@ pub async fn check_specifiers(
     module_specifiers,
     false,
     lib,
-    PermissionsContainer::allow_all(),
+    PermissionsContainer::new(Permissions::allow_all_but_import(
+      &ps.options.permissions_options(),
+    )?),
     PermissionsContainer::new(permissions),
   )
   .await?;
@@ -4291,6 +4291,7 @@ declare namespace Deno {
     | "env"
     | "sys"
     | "ffi"
+    | "import"
     | "hrtime";
 
   /** The current status of the permission:
@@ -4413,6 +4414,21 @@ declare namespace Deno {
     path?: string | URL;
   }
 
+  /** The permission descriptor for the `allow-import` permissions, which
+   * controls the hosts remote modules can be imported from. The option `host`
+   * allows scoping the permission to a specific host and port.
+   *
+   * @category Permissions */
+  export interface ImportPermissionDescriptor {
+    name: "import";
+    /** Optional host string of the form `"<hostname>[:<port>]"`. Examples:
+     *
+     *      "deno.land"
+     *      "localhost:4545"
+     */
+    host?: string;
+  }
+
   /** The permission descriptor for the `allow-hrtime` permission, which
    * controls if the runtime code has access to high resolution time. High
    * resolution time is consider sensitive information, because it can be used
@@ -4440,6 +4456,7 @@ declare namespace Deno {
     | EnvPermissionDescriptor
     | SysPermissionDescriptor
     | FfiPermissionDescriptor
+    | ImportPermissionDescriptor
     | HrtimePermissionDescriptor;
 
   /** The interface which defines what event types are supported by
@@ -1406,6 +1406,80 @@ declare namespace Deno {
    * @category HTTP Server
    */
   export function upgradeHttpRaw(request: Request): [Deno.Conn, Uint8Array];
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A namespace for displaying rich output in Jupyter notebooks. It is only
+   * available in cells run by the `deno jupyter` kernel.
+   *
+   * @category Jupyter
+   */
+  export namespace jupyter {
+    /** A MIME bundle, mapping MIME types to the data shown for them. */
+    export type MediaBundle = Record<string, unknown>;
+
+    /** The symbol of the method that formats an object as a MIME bundle. */
+    export const $display: unique symbol;
+
+    /** An object that formats itself when displayed in a notebook.
+     *
+     * ```ts
+     * class Point {
+     *   constructor(public x: number, public y: number) {}
+     *   [Deno.jupyter.$display]() {
+     *     return { "application/json": { x: this.x, y: this.y } };
+     *   }
+     * }
+     * ```
+     */
+    export interface Displayable {
+      [$display](): MediaBundle | Promise<MediaBundle>;
+    }
+
+    export interface DisplayOptions {
+      /** Send `obj` as the MIME bundle itself, instead of formatting it. */
+      raw?: boolean;
+      /** Replace the output previously displayed with the same `display_id`. */
+      update?: boolean;
+      display_id?: string;
+    }
+
+    /** Displays an object in the output of the current cell.
+     *
+     * ```ts
+     * await Deno.jupyter.display({
+     *   "text/plain": "Hi",
+     *   "text/html": "<b>Hi</b>",
+     * }, { raw: true });
+     * ```
+     */
+    export function display(
+      obj: unknown,
+      options?: DisplayOptions,
+    ): Promise<void>;
+
+    /** Creates a displayable HTML object from a template literal. */
+    export function html(
+      strings: TemplateStringsArray,
+      ...values: unknown[]
+    ): Displayable;
+
+    /** Creates a displayable Markdown object from a template literal. */
+    export function md(
+      strings: TemplateStringsArray,
+      ...values: unknown[]
+    ): Displayable;
+
+    /** Creates a displayable SVG object from a template literal. */
+    export function svg(
+      strings: TemplateStringsArray,
+      ...values: unknown[]
+    ): Displayable;
+
+    /** Creates a displayable image from the bytes of a PNG or JPEG image, or
+     * from the path of such an image file. */
+    export function image(obj: Uint8Array | string): Displayable;
+  }
 }
 
 /** **UNSTABLE**: New API, yet to be vetted.
@@ -1459,6 +1533,31 @@ declare interface WorkerOptions {
   deno?: {
     /** Set to `"none"` to disable all the permissions in the worker. */
     permissions?: Deno.PermissionOptions;
+    /** Limits on the resources the worker may use. A worker exceeding one of
+     * them is terminated and an `"error"` event is dispatched on it.
+     *
+     * Limits that are not set are inherited from the thread the worker is
+     * created in, and can't be raised above that thread's limits.
+     *
+     * ```ts
+     * const worker = new Worker(
+     *   new URL("deno_worker.ts", import.meta.url).href, {
+     *     type: "module",
+     *     deno: { limits: { maxHeapSize: 64, maxCpuTime: 1000 } },
+     *   }
+     * );
+     * worker.onerror = (e) => {
+     *   e.preventDefault();
+     *   console.log(e.message);
+     * };
+     * ```
+     */
+    limits?: {
+      /** Maximum size of the worker's heap in megabytes. */
+      maxHeapSize?: number;
+      /** Maximum CPU time the worker may use in milliseconds. */
+      maxCpuTime?: number;
+    };
   };
 }
 
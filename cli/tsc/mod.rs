@@ -271,7 +271,9 @@ fn maybe_remap_specifier(
   } else {
     PathBuf::from(specifier.path())
   };
-  if path.extension().is_none() {
+  // Wasm modules are checked through their generated declarations, which
+  // tsc won't read from a file with a `.wasm` extension.
+  if path.extension().is_none() || path.extension() == Some("wasm".as_ref()) {
     Some(format!("{}{}", specifier, media_type.as_ts_extension()))
   } else {
     None
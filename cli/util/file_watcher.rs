@@ -19,8 +19,10 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::select;
+use tokio::sync::broadcast;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::Notify;
 use tokio::time::sleep;
 
 const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
@@ -127,6 +129,38 @@ pub struct PrintConfig {
   pub clear_screen: bool,
 }
 
+/// Lets the operation run by [`watch_func2`] apply file changes itself, e.g.
+/// by hot replacing modules, instead of being restarted on every change.
+#[derive(Clone)]
+pub struct HotReloadChannel {
+  changed_paths: broadcast::Sender<Vec<PathBuf>>,
+  restart: Arc<Notify>,
+}
+
+impl Default for HotReloadChannel {
+  fn default() -> Self {
+    let (changed_paths, _) = broadcast::channel(16);
+    Self {
+      changed_paths,
+      restart: Arc::new(Notify::new()),
+    }
+  }
+}
+
+impl HotReloadChannel {
+  /// Receives the paths changed while the operation is running. Changes are
+  /// only forwarded while there is a subscriber, otherwise the operation is
+  /// restarted.
+  pub fn subscribe(&self) -> broadcast::Receiver<Vec<PathBuf>> {
+    self.changed_paths.subscribe()
+  }
+
+  /// Restarts the operation, for changes which can't be applied in place.
+  pub fn request_restart(&self) {
+    self.restart.notify_one();
+  }
+}
+
 fn create_print_after_restart_fn(clear_screen: bool) -> impl Fn() {
   move || {
     if clear_screen && atty::is(atty::Stream::Stderr) {
@@ -261,11 +295,15 @@ where
 /// - `operation` is the actual operation we want to run every time the watcher detects file
 /// changes. For example, in the case where we would like to bundle, then `operation` would
 /// have the logic for it like bundling the code.
+///
+/// - `maybe_hot_reload` hands file changes to the running operation instead of
+/// restarting it, as long as the operation is subscribed to them.
 pub async fn watch_func2<T: Clone, O, F>(
   mut paths_to_watch_receiver: UnboundedReceiver<Vec<PathBuf>>,
   mut operation: O,
   operation_args: T,
   print_config: PrintConfig,
+  maybe_hot_reload: Option<HotReloadChannel>,
 ) -> Result<(), AnyError>
 where
   O: FnMut(T) -> Result<F, AnyError>,
@@ -303,34 +341,55 @@ where
     }
   }
 
-  loop {
+  'restart: loop {
     let mut watcher = new_watcher(watcher_sender.clone())?;
     consume_paths_to_watch(&mut watcher, &mut paths_to_watch_receiver);
 
-    let receiver_future = async {
-      loop {
-        let maybe_paths = paths_to_watch_receiver.recv().await;
-        add_paths_to_watcher(&mut watcher, &maybe_paths.unwrap());
-      }
-    };
     let operation_future = error_handler(operation(operation_args.clone())?);
+    tokio::pin!(operation_future);
 
-    select! {
-      _ = receiver_future => {},
-      _ = watcher_receiver.recv() => {
-        print_after_restart();
-        continue;
-      },
-      _ = operation_future => {
-        consume_paths_to_watch(&mut watcher, &mut paths_to_watch_receiver);
-        // TODO(bartlomieju): print exit code here?
-        info!(
-          "{} {} finished. Restarting on file change...",
-          colors::intense_blue("Watcher"),
-          job_name,
-        );
-      },
-    };
+    loop {
+      let receiver_future = async {
+        loop {
+          let maybe_paths = paths_to_watch_receiver.recv().await;
+          add_paths_to_watcher(&mut watcher, &maybe_paths.unwrap());
+        }
+      };
+      let restart_future = async {
+        match &maybe_hot_reload {
+          Some(hot_reload) => hot_reload.restart.notified().await,
+          None => std::future::pending().await,
+        }
+      };
+
+      select! {
+        _ = receiver_future => {},
+        maybe_paths = watcher_receiver.recv() => {
+          if let (Some(hot_reload), Some(paths)) = (&maybe_hot_reload, maybe_paths) {
+            // fails when the operation isn't listening for changes
+            if hot_reload.changed_paths.send(paths).is_ok() {
+              continue;
+            }
+          }
+          print_after_restart();
+          continue 'restart;
+        },
+        _ = restart_future => {
+          print_after_restart();
+          continue 'restart;
+        },
+        _ = &mut operation_future => {
+          consume_paths_to_watch(&mut watcher, &mut paths_to_watch_receiver);
+          // TODO(bartlomieju): print exit code here?
+          info!(
+            "{} {} finished. Restarting on file change...",
+            colors::intense_blue("Watcher"),
+            job_name,
+          );
+          break;
+        },
+      };
+    }
 
     let receiver_future = async {
       loop {
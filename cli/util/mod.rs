@@ -14,4 +14,5 @@ pub mod progress_bar;
 pub mod text_encoding;
 pub mod unix;
 pub mod v8;
+pub mod wasm;
 pub mod windows;
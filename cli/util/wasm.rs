@@ -0,0 +1,123 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::wasm::parse_wasm_module;
+use deno_core::wasm::WasmExportKind;
+use deno_core::wasm::WasmFunctionType;
+use deno_core::wasm::WasmValueType;
+use std::fmt::Write;
+
+/// Generates the TypeScript declarations of a Wasm module, which is how the
+/// module graph and the type checker see it. The modules it imports from are
+/// imported for their side effects, so they are part of the graph as well.
+pub fn wasm_module_to_dts(bytes: &[u8]) -> Result<String, AnyError> {
+  let info = parse_wasm_module(bytes)?;
+  let mut dts = String::new();
+  for module in info.import_modules() {
+    let module = serde_json::to_string(module).unwrap();
+    writeln!(dts, "import {module};").unwrap();
+  }
+  for (i, export) in info.exports.iter().enumerate() {
+    // TypeScript only allows identifiers as export names.
+    if !is_identifier_name(&export.name) {
+      continue;
+    }
+    let declaration = match &export.kind {
+      WasmExportKind::Function(function_type) => {
+        format!("function export{i}{}", render_signature(function_type))
+      }
+      WasmExportKind::Table => format!("const export{i}: WebAssembly.Table"),
+      WasmExportKind::Memory => format!("const export{i}: WebAssembly.Memory"),
+      WasmExportKind::Global => format!("const export{i}: WebAssembly.Global"),
+      WasmExportKind::Tag => format!("const export{i}: unknown"),
+    };
+    writeln!(dts, "declare {declaration};").unwrap();
+    writeln!(dts, "export {{ export{i} as {} }};", export.name).unwrap();
+  }
+  dts.push_str("export {};\n");
+  Ok(dts)
+}
+
+fn render_signature(function_type: &WasmFunctionType) -> String {
+  let params = function_type
+    .params
+    .iter()
+    .enumerate()
+    .map(|(i, value_type)| format!("arg{i}: {}", render_type(value_type)))
+    .collect::<Vec<_>>()
+    .join(", ");
+  let result = match function_type.results.as_slice() {
+    [] => "void".to_string(),
+    [value_type] => render_type(value_type).to_string(),
+    value_types => format!(
+      "[{}]",
+      value_types
+        .iter()
+        .map(render_type)
+        .collect::<Vec<_>>()
+        .join(", ")
+    ),
+  };
+  format!("({params}): {result}")
+}
+
+fn render_type(value_type: &WasmValueType) -> &'static str {
+  match value_type {
+    WasmValueType::I32 | WasmValueType::F32 | WasmValueType::F64 => "number",
+    WasmValueType::I64 => "bigint",
+    WasmValueType::FuncRef => "Function | null",
+    WasmValueType::V128 | WasmValueType::ExternRef => "unknown",
+  }
+}
+
+fn is_identifier_name(name: &str) -> bool {
+  let is_identifier_char =
+    |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+  match name.chars().next() {
+    Some(c) if !c.is_ascii_digit() => name.chars().all(is_identifier_char),
+    _ => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // (module
+  //   (import "./math.js" "offset" (func (result i32)))
+  //   (func (export "add") (param i32 i64) (result i32) i32.const 0)
+  //   (global (export "answer") i32 (i32.const 42)))
+  const MODULE: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0b, 0x02, 0x60,
+    0x00, 0x01, 0x7f, 0x60, 0x02, 0x7f, 0x7e, 0x01, 0x7f, 0x02, 0x14, 0x01,
+    0x09, 0x2e, 0x2f, 0x6d, 0x61, 0x74, 0x68, 0x2e, 0x6a, 0x73, 0x06, 0x6f,
+    0x66, 0x66, 0x73, 0x65, 0x74, 0x00, 0x00, 0x03, 0x02, 0x01, 0x01, 0x06,
+    0x06, 0x01, 0x7f, 0x00, 0x41, 0x2a, 0x0b, 0x07, 0x10, 0x02, 0x03, 0x61,
+    0x64, 0x64, 0x00, 0x01, 0x06, 0x61, 0x6e, 0x73, 0x77, 0x65, 0x72, 0x03,
+    0x00, 0x0a, 0x06, 0x01, 0x04, 0x00, 0x41, 0x00, 0x0b,
+  ];
+
+  #[test]
+  fn test_wasm_module_to_dts() {
+    assert_eq!(
+      wasm_module_to_dts(MODULE).unwrap(),
+      r#"import "./math.js";
+declare function export0(arg0: number, arg1: bigint): number;
+export { export0 as add };
+declare const export1: WebAssembly.Global;
+export { export1 as answer };
+export {};
+"#
+    );
+  }
+
+  #[test]
+  fn test_is_identifier_name() {
+    assert!(is_identifier_name("add"));
+    assert!(is_identifier_name("$_memory1"));
+    assert!(!is_identifier_name("1add"));
+    assert!(!is_identifier_name("add-one"));
+    assert!(!is_identifier_name(""));
+  }
+}
@@ -1,5 +1,9 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -20,12 +24,14 @@ use deno_runtime::deno_node;
 use deno_runtime::fmt_errors::format_js_error;
 use deno_runtime::ops::worker_host::CreateWebWorkerCb;
 use deno_runtime::ops::worker_host::WorkerEventCb;
+use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsContainer;
 use deno_runtime::web_worker::WebWorker;
 use deno_runtime::web_worker::WebWorkerOptions;
 use deno_runtime::worker::MainWorker;
 use deno_runtime::worker::WorkerOptions;
 use deno_runtime::BootstrapOptions;
+use tokio::select;
 
 use crate::args::DenoSubcommand;
 use crate::errors;
@@ -35,8 +41,12 @@ use crate::ops;
 use crate::proc_state::ProcState;
 use crate::tools;
 use crate::tools::coverage::CoverageCollector;
+use crate::tools::profiler;
+use crate::tools::profiler::Profiler;
+use crate::tools::run::hmr::HmrRunner;
 use crate::tools::test::TestMode;
 use crate::util::checksum;
+use crate::util::file_watcher::HotReloadChannel;
 use crate::version;
 
 pub struct CliMainWorker {
@@ -44,6 +54,7 @@ pub struct CliMainWorker {
   is_main_cjs: bool,
   worker: MainWorker,
   ps: ProcState,
+  is_node_initialized: bool,
 
   js_run_tests_callback: Option<v8::Global<v8::Function>>,
   js_run_benchmarks_callback: Option<v8::Global<v8::Function>>,
@@ -64,9 +75,11 @@ impl CliMainWorker {
   pub async fn run(&mut self) -> Result<i32, AnyError> {
     let mut maybe_coverage_collector =
       self.maybe_setup_coverage_collector().await?;
+    let mut maybe_profiler = self.maybe_setup_profiler().await?;
     log::debug!("main_module {}", self.main_module);
 
     if self.is_main_cjs {
+      self.execute_preload_modules().await?;
       self.initialize_main_module_for_node().await?;
       deno_node::load_cjs_module(
         &mut self.worker.js_runtime,
@@ -83,7 +96,9 @@ impl CliMainWorker {
     loop {
       self
         .worker
-        .run_event_loop(maybe_coverage_collector.is_none())
+        .run_event_loop(
+          maybe_coverage_collector.is_none() && maybe_profiler.is_none(),
+        )
         .await?;
       if !self
         .worker
@@ -101,11 +116,23 @@ impl CliMainWorker {
         .with_event_loop(coverage_collector.stop_collecting().boxed_local())
         .await?;
     }
+    if let Some(profiler) = maybe_profiler.as_mut() {
+      self
+        .worker
+        .with_event_loop(profiler.stop().boxed_local())
+        .await?;
+    }
+    if let Some(path) = self.ps.options.trace_ops_timeline() {
+      self.write_ops_timeline(&path)?;
+    }
 
     Ok(self.worker.exit_code())
   }
 
-  pub async fn run_for_watcher(self) -> Result<(), AnyError> {
+  pub async fn run_for_watcher(
+    self,
+    maybe_hot_reload: Option<HotReloadChannel>,
+  ) -> Result<(), AnyError> {
     /// The FileWatcherModuleExecutor provides module execution with safe dispatching of life-cycle events by tracking the
     /// state of any pending events and emitting accordingly on drop in the case of a future
     /// cancellation.
@@ -124,7 +151,25 @@ impl CliMainWorker {
 
       /// Execute the given main module emitting load and unload events before and after execution
       /// respectively.
-      pub async fn execute(&mut self) -> Result<(), AnyError> {
+      pub async fn execute(
+        &mut self,
+        maybe_hot_reload: Option<HotReloadChannel>,
+      ) -> Result<(), AnyError> {
+        let mut maybe_hmr_runner = match maybe_hot_reload {
+          Some(hot_reload) => {
+            let session = self.inner.worker.create_inspector_session().await;
+            let mut hmr_runner =
+              HmrRunner::new(self.inner.ps.clone(), session, hot_reload);
+            self
+              .inner
+              .worker
+              .with_event_loop(hmr_runner.start().boxed_local())
+              .await?;
+            Some(hmr_runner)
+          }
+          None => None,
+        };
+
         self.inner.execute_main_module_possibly_with_npm().await?;
         self
           .inner
@@ -133,7 +178,25 @@ impl CliMainWorker {
         self.pending_unload = true;
 
         let result = loop {
-          match self.inner.worker.run_event_loop(false).await {
+          let event_loop_result = match maybe_hmr_runner.as_mut() {
+            // Changed modules are replaced while the event loop runs. Only
+            // waiting for a change is cancelled when the event loop
+            // finishes, an update that is being applied always completes.
+            Some(hmr_runner) => loop {
+              select! {
+                biased;
+                changed_paths = hmr_runner.next_change() => {
+                  let apply = hmr_runner.apply(changed_paths).boxed_local();
+                  self.inner.worker.with_event_loop(apply).await;
+                }
+                result = self.inner.worker.run_event_loop(false) => {
+                  break result;
+                }
+              }
+            },
+            None => self.inner.worker.run_event_loop(false).await,
+          };
+          match event_loop_result {
             Ok(()) => {}
             Err(error) => break Err(error),
           }
@@ -172,7 +235,7 @@ impl CliMainWorker {
     }
 
     let mut executor = FileWatcherModuleExecutor::new(self);
-    executor.execute().await
+    executor.execute(maybe_hot_reload).await
   }
 
   pub async fn run_test_specifier(
@@ -192,6 +255,7 @@ impl CliMainWorker {
 
     let mut maybe_coverage_collector =
       self.maybe_setup_coverage_collector().await?;
+    let mut maybe_profiler = self.maybe_setup_profiler().await?;
 
     // We only execute the specifier as a module if it is tagged with TestMode::Module or
     // TestMode::Both.
@@ -220,6 +284,12 @@ impl CliMainWorker {
         .with_event_loop(coverage_collector.stop_collecting().boxed_local())
         .await?;
     }
+    if let Some(profiler) = maybe_profiler.as_mut() {
+      self
+        .worker
+        .with_event_loop(profiler.stop().boxed_local())
+        .await?;
+    }
     Ok(())
   }
 
@@ -275,9 +345,33 @@ impl CliMainWorker {
     Ok(())
   }
 
+  /// Serves the module loaders of the process with the hooks registered
+  /// through `module.register()`, in the worker of the hooks.
+  pub async fn run_module_hooks(&mut self) -> Result<(), AnyError> {
+    self.initialize_main_module_for_node().await?;
+    let promise = self.worker.js_runtime.execute_script(
+      &located_script_name!(),
+      "Deno[Deno.internal].moduleHooks.run()",
+    )?;
+    self.worker.js_runtime.resolve_value(promise).await?;
+    Ok(())
+  }
+
+  /// Evaluates the modules of `--import` before the main module, e.g. for
+  /// them to register hooks.
+  async fn execute_preload_modules(&mut self) -> Result<(), AnyError> {
+    for specifier in self.ps.options.preload_modules()? {
+      let id = self.worker.preload_side_module(&specifier).await?;
+      self.evaluate_module_possibly_with_npm(id).await?;
+      self.ps.module_hooks.wait_for_registrations().await?;
+    }
+    Ok(())
+  }
+
   async fn execute_main_module_possibly_with_npm(
     &mut self,
   ) -> Result<(), AnyError> {
+    self.execute_preload_modules().await?;
     let id = self.worker.preload_main_module(&self.main_module).await?;
     self.evaluate_module_possibly_with_npm(id).await
   }
@@ -285,6 +379,7 @@ impl CliMainWorker {
   async fn execute_side_module_possibly_with_npm(
     &mut self,
   ) -> Result<(), AnyError> {
+    self.execute_preload_modules().await?;
     let id = self.worker.preload_side_module(&self.main_module).await?;
     self.evaluate_module_possibly_with_npm(id).await
   }
@@ -302,6 +397,11 @@ impl CliMainWorker {
   }
 
   async fn initialize_main_module_for_node(&mut self) -> Result<(), AnyError> {
+    // The preloaded modules may have initialized it already.
+    if self.is_node_initialized {
+      return Ok(());
+    }
+    self.is_node_initialized = true;
     deno_node::initialize_runtime(
       &mut self.worker.js_runtime,
       self.ps.options.node_modules_dir(),
@@ -344,6 +444,31 @@ impl CliMainWorker {
     }
   }
 
+  async fn maybe_setup_profiler(
+    &mut self,
+  ) -> Result<Option<Profiler>, AnyError> {
+    let cpu_prof_dir = self.ps.options.cpu_prof_dir();
+    let heap_snapshot_dir = self.ps.options.heap_snapshot_on_exit_dir();
+    if cpu_prof_dir.is_none() && heap_snapshot_dir.is_none() {
+      return Ok(None);
+    }
+    let session = self.worker.create_inspector_session().await;
+    let mut profiler = Profiler::new(cpu_prof_dir, heap_snapshot_dir, session);
+    self
+      .worker
+      .with_event_loop(profiler.start().boxed_local())
+      .await?;
+    Ok(Some(profiler))
+  }
+
+  fn write_ops_timeline(&mut self, path: &Path) -> Result<(), AnyError> {
+    let op_state = self.worker.js_runtime.op_state();
+    let mut out = BufWriter::new(File::create(path)?);
+    op_state.borrow().tracker.write_timeline(&mut out)?;
+    out.flush()?;
+    Ok(())
+  }
+
   /// Run tests declared with `Deno.test()`. Test events will be dispatched
   /// by calling ops which are currently only implemented in the CLI crate.
   pub async fn run_tests(
@@ -416,6 +541,23 @@ pub async fn create_main_worker(
   .await
 }
 
+pub async fn create_main_worker_with_extensions(
+  ps: &ProcState,
+  main_module: ModuleSpecifier,
+  permissions: PermissionsContainer,
+  custom_extensions: Vec<Extension>,
+) -> Result<CliMainWorker, AnyError> {
+  create_main_worker_internal(
+    ps,
+    main_module,
+    permissions,
+    custom_extensions,
+    Default::default(),
+    false,
+  )
+  .await
+}
+
 pub async fn create_main_worker_for_test_or_bench(
   ps: &ProcState,
   main_module: ModuleSpecifier,
@@ -469,7 +611,9 @@ async fn create_main_worker_internal(
 
   let module_loader = CliModuleLoader::new(
     ps.clone(),
-    PermissionsContainer::allow_all(),
+    PermissionsContainer::new(Permissions::allow_all_but_import(
+      &ps.options.permissions_options(),
+    )?),
     permissions.clone(),
   );
 
@@ -547,6 +691,12 @@ async fn create_main_worker_internal(
     shared_array_buffer_store: Some(ps.shared_array_buffer_store.clone()),
     compiled_wasm_module_store: Some(ps.compiled_wasm_module_store.clone()),
     stdio,
+    resource_limits: ps.options.resource_limits(),
+    heap_snapshot_near_limit: ps
+      .options
+      .heap_snapshot_near_limit_dir()
+      .map(|dir| profiler::profile_file_path(&dir, "Heap", "heapsnapshot")),
+    ops_timeline: ps.options.trace_ops_timeline().is_some(),
   };
 
   let mut worker = MainWorker::bootstrap_from_options(
@@ -598,6 +748,7 @@ async fn create_main_worker_internal(
     is_main_cjs,
     worker,
     ps: ps.clone(),
+    is_node_initialized: false,
     js_run_tests_callback,
     js_run_benchmarks_callback,
     js_enable_test_callback,
@@ -623,7 +774,7 @@ fn create_web_worker_pre_execute_module_callback(
     let ps = ps.clone();
     let fut = async move {
       // this will be up to date after pre-load
-      if ps.npm_resolver.has_packages() {
+      if ps.npm_resolver.has_packages() || ps.has_node_builtin_specifier() {
         deno_node::initialize_runtime(
           &mut worker.js_runtime,
           ps.options.node_modules_dir(),
@@ -705,6 +856,8 @@ fn create_web_worker_callback(
       npm_resolver: Some(Rc::new(ps.npm_resolver.clone())),
       worker_type: args.worker_type,
       maybe_inspector_server,
+      should_break_on_first_statement: ps.options.inspect_brk().is_some(),
+      should_wait_for_inspector_session: ps.options.inspect_wait().is_some(),
       get_error_class_fn: Some(&errors::get_error_class_name),
       blob_store: ps.blob_store.clone(),
       broadcast_channel: ps.broadcast_channel.clone(),
@@ -712,6 +865,7 @@ fn create_web_worker_callback(
       compiled_wasm_module_store: Some(ps.compiled_wasm_module_store.clone()),
       stdio: stdio.clone(),
       cache_storage_dir,
+      resource_limits: args.resource_limits,
     };
 
     WebWorker::bootstrap_from_options(
@@ -777,6 +931,9 @@ mod tests {
       shared_array_buffer_store: None,
       compiled_wasm_module_store: None,
       stdio: Default::default(),
+      resource_limits: Default::default(),
+      heap_snapshot_near_limit: None,
+      ops_timeline: false,
     };
 
     MainWorker::bootstrap_from_options(main_module, permissions, options)
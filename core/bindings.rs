@@ -37,6 +37,9 @@ pub fn external_references(
     v8::ExternalReference {
       function: empty_fn.map_fn_to(),
     },
+    v8::ExternalReference {
+      function: traced_op.map_fn_to(),
+    },
   ];
 
   for ctx in ops {
@@ -160,9 +163,20 @@ fn initialize_ops(
   op_ctxs: &[OpCtx],
   snapshot_options: SnapshotOptions,
 ) {
+  let has_timeline = op_ctxs
+    .first()
+    .map(|ctx| ctx.state.borrow().tracker.has_timeline())
+    .unwrap_or(false);
   for ctx in op_ctxs {
     let ctx_ptr = ctx as *const OpCtx as *const c_void;
 
+    // Ops are called through `traced_op()` to record them in the timeline,
+    // which also means they can't use fast calls.
+    if has_timeline {
+      set_traced_op(scope, ops_obj, ctx);
+      continue;
+    }
+
     // If this is a fast op, we don't want it to be in the snapshot.
     // Only initialize once snapshot is loaded.
     if ctx.decl.fast_fn.is_some() && snapshot_options.loaded() {
@@ -235,6 +249,75 @@ pub fn set_func_raw(
   obj.set(scope, key.into(), val.into());
 }
 
+/// Binds an op to a function that calls it through another function, and
+/// records the call in the ops timeline.
+fn set_traced_op(
+  scope: &mut v8::HandleScope<'_>,
+  obj: v8::Local<v8::Object>,
+  ctx: &OpCtx,
+) {
+  let key = v8::String::new(scope, ctx.decl.name).unwrap();
+  let external = v8::External::new(scope, ctx as *const OpCtx as *mut c_void);
+  let op_fn = v8::FunctionTemplate::builder_raw(ctx.decl.v8_fn_ptr)
+    .data(external.into())
+    .build(scope)
+    .get_function(scope)
+    .unwrap();
+  let data =
+    v8::Array::new_with_elements(scope, &[external.into(), op_fn.into()]);
+  let val = v8::FunctionTemplate::builder(traced_op)
+    .data(data.into())
+    .build(scope)
+    .get_function(scope)
+    .unwrap();
+  val.set_name(key);
+  obj.set(scope, key.into(), val.into());
+}
+
+fn traced_op(
+  scope: &mut v8::HandleScope,
+  args: v8::FunctionCallbackArguments,
+  mut rv: v8::ReturnValue,
+) {
+  let data = v8::Local::<v8::Array>::try_from(args.data()).unwrap();
+  let external = data.get_index(scope, 0).unwrap();
+  // SAFETY: `set_traced_op()` stores a pointer to the OpCtx, which lives as
+  // long as the isolate.
+  let ctx = unsafe {
+    &*(v8::Local::<v8::External>::cast(external).value() as *const OpCtx)
+  };
+  let op_fn = data.get_index(scope, 1).unwrap();
+  let op_fn = v8::Local::<v8::Function>::try_from(op_fn).unwrap();
+  let name = ctx.decl.name;
+
+  let start = std::time::Instant::now();
+  let (outer_recording, async_index) = {
+    let state = ctx.state.borrow();
+    let outer_recording = state.resource_table.start_recording_rids();
+    let async_index = if ctx.decl.is_async {
+      args.get(0).int32_value(scope).and_then(|promise_id| {
+        state
+          .tracker
+          .timeline_async_started(name, promise_id, start)
+      })
+    } else {
+      None
+    };
+    (outer_recording, async_index)
+  };
+
+  let op_args = (0..args.length()).map(|i| args.get(i)).collect::<Vec<_>>();
+  if let Some(result) = op_fn.call(scope, args.this().into(), &op_args) {
+    rv.set(result);
+  }
+
+  let state = ctx.state.borrow();
+  let rids = state.resource_table.take_recorded_rids(outer_recording);
+  state
+    .tracker
+    .timeline_called(name, start, rids, async_index);
+}
+
 pub extern "C" fn wasm_async_resolve_promise_callback(
   _isolate: *mut v8::Isolate,
   context: v8::Local<v8::Context>,
@@ -359,6 +442,16 @@ pub extern "C" fn host_initialize_import_meta_object_callback(
   let val = v8::FunctionBuilder::<v8::Function>::build(builder, scope).unwrap();
   let resolve_key = v8::String::new(scope, "resolve").unwrap();
   meta.set(scope, resolve_key.into(), val.into());
+
+  if let Some(wasm_module) = module_map.wasm_module_store.get(&module_global) {
+    let wasm_module = v8::Local::new(scope, wasm_module);
+    let wasm_module_key = v8::String::new(scope, "wasmModule").unwrap();
+    meta.create_data_property(
+      scope,
+      wasm_module_key.into(),
+      wasm_module.into(),
+    );
+  }
 }
 
 fn import_meta_resolve(
@@ -18,6 +18,7 @@ use crate::futures::stream::StreamExt;
 use crate::futures::task;
 use crate::futures::task::Context;
 use crate::futures::task::Poll;
+use crate::inspector_network::NetworkInspector;
 use crate::serde_json;
 use crate::serde_json::json;
 use crate::serde_json::Value;
@@ -82,6 +83,7 @@ pub struct JsRuntimeInspector {
   flags: RefCell<InspectorFlags>,
   waker: Arc<InspectorWaker>,
   deregister_tx: Option<oneshot::Sender<()>>,
+  network: NetworkInspector,
 }
 
 impl Drop for JsRuntimeInspector {
@@ -158,13 +160,14 @@ impl JsRuntimeInspector {
   /// and thus it's id is provided as an associated contant.
   const CONTEXT_GROUP_ID: i32 = 1;
 
+  /// Creates the inspector of `context`. Network activity reported to
+  /// `network` is forwarded to its sessions.
   pub fn new(
-    isolate: &mut v8::OwnedIsolate,
+    scope: &mut v8::HandleScope<()>,
     context: v8::Global<v8::Context>,
     is_main: bool,
+    network: NetworkInspector,
   ) -> Rc<RefCell<Self>> {
-    let scope = &mut v8::HandleScope::new(isolate);
-
     let (new_session_tx, new_session_rx) =
       mpsc::unbounded::<InspectorSessionProxy>();
 
@@ -182,6 +185,7 @@ impl JsRuntimeInspector {
       flags: Default::default(),
       waker,
       deregister_tx: None,
+      network: network.clone(),
     }));
     let mut self_ = self__.borrow_mut();
     self_.v8_inspector = Rc::new(RefCell::new(
@@ -190,6 +194,7 @@ impl JsRuntimeInspector {
     self_.sessions = RefCell::new(SessionContainer::new(
       self_.v8_inspector.clone(),
       new_session_rx,
+      network,
     ));
 
     // Tell the inspector about the global context.
@@ -242,6 +247,16 @@ impl JsRuntimeInspector {
     self.sessions.borrow().has_active_sessions()
   }
 
+  /// Whether a debugger is connected, as opposed to local sessions only.
+  pub fn has_remote_sessions(&self) -> bool {
+    self.sessions.borrow().has_remote_sessions()
+  }
+
+  /// The `Network` domain of the sessions, to report network activity to.
+  pub fn network(&self) -> NetworkInspector {
+    self.network.clone()
+  }
+
   pub fn has_blocking_sessions(&self) -> bool {
     self.sessions.borrow().has_blocking_sessions()
   }
@@ -295,6 +310,7 @@ impl JsRuntimeInspector {
             sessions.v8_inspector.clone(),
             session_proxy,
             false,
+            sessions.network.clone(),
           );
           let prev = sessions.handshake.replace(session);
           assert!(prev.is_none());
@@ -374,6 +390,16 @@ impl JsRuntimeInspector {
     }
   }
 
+  /// This function blocks the thread until an inspector client has
+  /// established a session and sent "Runtime.runIfWaitingForDebugger", which
+  /// gives the client a chance to set up breakpoints before any code runs.
+  pub fn wait_for_debugger(&mut self) {
+    self.flags.get_mut().waiting_for_session = true;
+    while self.flags.get_mut().waiting_for_session {
+      let _ = self.poll_sessions(None).unwrap();
+    }
+  }
+
   /// This function blocks the thread until at least one inspector client has
   /// established a websocket connection.
   ///
@@ -399,12 +425,13 @@ impl JsRuntimeInspector {
 
   /// Create a channel that notifies the frontend when inspector is dropped.
   ///
-  /// NOTE: Only a single handler is currently available.
+  /// NOTE: Only a single handler is currently available. It can be replaced
+  /// once the receiver of the previous one was dropped.
   pub fn add_deregister_handler(&mut self) -> oneshot::Receiver<()> {
     let (tx, rx) = oneshot::channel::<()>();
     let prev = self.deregister_tx.replace(tx);
     assert!(
-      prev.is_none(),
+      prev.map_or(true, |tx| tx.is_canceled()),
       "Only a single deregister handler is allowed"
     );
     rx
@@ -426,8 +453,12 @@ impl JsRuntimeInspector {
 
     // InspectorSessions for a local session is added directly to the "established"
     // sessions, so it doesn't need to go through the session sender.
-    let inspector_session =
-      InspectorSession::new(self.v8_inspector.clone(), proxy, true);
+    let inspector_session = InspectorSession::new(
+      self.v8_inspector.clone(),
+      proxy,
+      true,
+      self.network.clone(),
+    );
     self
       .sessions
       .borrow_mut()
@@ -452,18 +483,21 @@ struct SessionContainer {
   session_rx: UnboundedReceiver<InspectorSessionProxy>,
   handshake: Option<Box<InspectorSession>>,
   established: SelectAll<Box<InspectorSession>>,
+  network: NetworkInspector,
 }
 
 impl SessionContainer {
   fn new(
     v8_inspector: Rc<RefCell<v8::UniquePtr<v8::inspector::V8Inspector>>>,
     new_session_rx: UnboundedReceiver<InspectorSessionProxy>,
+    network: NetworkInspector,
   ) -> Self {
     Self {
       v8_inspector,
       session_rx: new_session_rx,
       handshake: None,
       established: SelectAll::new(),
+      network,
     }
   }
 
@@ -481,6 +515,10 @@ impl SessionContainer {
     !self.established.is_empty() || self.handshake.is_some()
   }
 
+  fn has_remote_sessions(&self) -> bool {
+    self.established.iter().any(|s| !s.blocking) || self.handshake.is_some()
+  }
+
   fn has_blocking_sessions(&self) -> bool {
     self.established.iter().any(|s| s.blocking)
   }
@@ -496,6 +534,7 @@ impl SessionContainer {
       session_rx: rx,
       handshake: None,
       established: SelectAll::new(),
+      network: Default::default(),
     }
   }
 }
@@ -584,6 +623,7 @@ struct InspectorSession {
   // Describes if session should keep event loop alive, eg. a local REPL
   // session should keep event loop alive, but a Websocket session shouldn't.
   blocking: bool,
+  network: NetworkInspector,
 }
 
 impl InspectorSession {
@@ -593,6 +633,7 @@ impl InspectorSession {
     v8_inspector_rc: Rc<RefCell<v8::UniquePtr<v8::inspector::V8Inspector>>>,
     session_proxy: InspectorSessionProxy,
     blocking: bool,
+    network: NetworkInspector,
   ) -> Box<Self> {
     new_box_with(move |self_ptr| {
       let v8_channel = v8::inspector::ChannelBase::new::<Self>();
@@ -614,6 +655,7 @@ impl InspectorSession {
         v8_session,
         proxy: session_proxy,
         blocking,
+        network,
       }
     })
   }
@@ -695,11 +737,17 @@ impl Stream for InspectorSession {
     cx: &mut Context,
   ) -> Poll<Option<Self::Item>> {
     let inner = self.get_mut();
-    if let Poll::Ready(maybe_msg) = inner.proxy.rx.poll_next_unpin(cx) {
-      if let Some(msg) = maybe_msg {
-        return Poll::Ready(Some((&mut *inner.v8_session, msg)));
-      } else {
-        return Poll::Ready(None);
+    while let Poll::Ready(maybe_msg) = inner.proxy.rx.poll_next_unpin(cx) {
+      let msg = match maybe_msg {
+        Some(msg) => msg,
+        None => return Poll::Ready(None),
+      };
+      // V8 doesn't implement the `Network` domain.
+      match inner.network.handle_message(&inner.proxy.tx, &msg) {
+        Some(response) => {
+          let _ = inner.proxy.tx.unbounded_send(response);
+        }
+        None => return Poll::Ready(Some((&mut *inner.v8_session, msg))),
       }
     }
 
@@ -780,7 +828,11 @@ impl LocalInspectorSession {
     }
   }
 
-  async fn receive_from_v8_session(&mut self) {
+  /// Receives a single message from the V8 session, routing responses to
+  /// pending `post_message` calls and notifications to the notification
+  /// channel. Polling this lets a session receive notifications while no
+  /// message is in flight.
+  pub async fn receive_from_v8_session(&mut self) {
     let inspector_msg = self.v8_session_rx.next().await.unwrap();
     if let InspectorMsgKind::Message(msg_id) = inspector_msg.kind {
       let message: serde_json::Value =
@@ -807,12 +859,9 @@ impl LocalInspectorSession {
           },
         };
 
-      self
-        .response_tx_map
-        .remove(&msg_id)
-        .unwrap()
-        .send(message)
-        .unwrap();
+      // The caller may have stopped waiting for the response, e.g. because
+      // the evaluation was interrupted.
+      let _ = self.response_tx_map.remove(&msg_id).unwrap().send(message);
     } else {
       let message = serde_json::from_str(&inspector_msg.content).unwrap();
       // Ignore if the receiver has been dropped.
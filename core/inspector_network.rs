@@ -0,0 +1,441 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! The `Network` domain of the inspector protocol, which V8 doesn't
+//! implement. Extensions that perform network activity report it through a
+//! [`NetworkInspector`], which forwards it to all inspector sessions that
+//! enabled the domain.
+
+use crate::inspector::InspectorMsg;
+use crate::inspector::InspectorMsgKind;
+use crate::inspector::SessionProxySender;
+use crate::serde_json::json;
+use crate::serde_json::Map;
+use crate::serde_json::Value;
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+static MONOTONIC_START: Lazy<Instant> = Lazy::new(Instant::now);
+
+#[derive(Default)]
+struct NetworkState {
+  /// Senders of the sessions that enabled the `Network` domain.
+  sessions: Vec<SessionProxySender>,
+  next_request_id: u64,
+  response_bodies: VecDeque<(String, Vec<u8>)>,
+  response_bodies_size: usize,
+}
+
+/// Reports network activity to the inspector sessions of a `JsRuntime`. An
+/// embedder that exposes the inspector puts it into the `OpState`, where
+/// extensions can find it.
+#[derive(Clone, Default)]
+pub struct NetworkInspector(Rc<RefCell<NetworkState>>);
+
+impl NetworkInspector {
+  /// Maximum total size of the response bodies kept for
+  /// `Network.getResponseBody`. The oldest bodies are dropped first, and
+  /// larger bodies aren't kept at all.
+  pub const MAX_RESPONSE_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+  /// Whether any session enabled the `Network` domain. Network activity only
+  /// needs to be reported if it did.
+  pub fn is_enabled(&self) -> bool {
+    !self.0.borrow().sessions.is_empty()
+  }
+
+  pub fn next_request_id(&self) -> String {
+    let mut state = self.0.borrow_mut();
+    state.next_request_id += 1;
+    format!("deno-{}", state.next_request_id)
+  }
+
+  pub fn request_will_be_sent(
+    &self,
+    request_id: &str,
+    url: &str,
+    method: &str,
+    headers: Value,
+    post_data: Option<&[u8]>,
+  ) {
+    let mut request = json!({
+      "url": url,
+      "method": method,
+      "headers": headers,
+      "hasPostData": post_data.is_some(),
+    });
+    if let Some(post_data) = post_data {
+      request["postData"] = String::from_utf8_lossy(post_data).into();
+    }
+    self.emit(
+      "Network.requestWillBeSent",
+      json!({
+        "requestId": request_id,
+        "loaderId": "",
+        "documentURL": url,
+        "request": request,
+        "timestamp": timestamp(),
+        "wallTime": wall_time(),
+        "initiator": { "type": "script" },
+        "type": "Fetch",
+      }),
+    );
+  }
+
+  pub fn response_received(
+    &self,
+    request_id: &str,
+    url: &str,
+    status: u16,
+    status_text: &str,
+    headers: Value,
+  ) {
+    let mime_type = headers
+      .as_object()
+      .and_then(|headers| {
+        headers
+          .iter()
+          .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+      })
+      .and_then(|(_, value)| value.as_str())
+      .and_then(|value| value.split(';').next())
+      .unwrap_or_default()
+      .trim()
+      .to_string();
+    self.emit(
+      "Network.responseReceived",
+      json!({
+        "requestId": request_id,
+        "loaderId": "",
+        "timestamp": timestamp(),
+        "type": "Fetch",
+        "response": {
+          "url": url,
+          "status": status,
+          "statusText": status_text,
+          "headers": headers,
+          "mimeType": mime_type,
+          "connectionReused": false,
+          "connectionId": 0,
+          "encodedDataLength": 0,
+          "securityState": "unknown",
+        },
+      }),
+    );
+  }
+
+  /// Reports that the response `request_id` was received completely, and
+  /// keeps its body for `Network.getResponseBody`, unless it was too large
+  /// to be kept.
+  pub fn loading_finished(
+    &self,
+    request_id: &str,
+    length: usize,
+    body: Option<Vec<u8>>,
+  ) {
+    if let Some(body) = body {
+      self.store_response_body(request_id, body);
+    }
+    self.emit(
+      "Network.loadingFinished",
+      json!({
+        "requestId": request_id,
+        "timestamp": timestamp(),
+        "encodedDataLength": length,
+      }),
+    );
+  }
+
+  pub fn loading_failed(&self, request_id: &str, error_text: &str) {
+    self.emit(
+      "Network.loadingFailed",
+      json!({
+        "requestId": request_id,
+        "timestamp": timestamp(),
+        "type": "Fetch",
+        "errorText": error_text,
+        "canceled": false,
+      }),
+    );
+  }
+
+  pub fn web_socket_created(&self, request_id: &str, url: &str) {
+    self.emit(
+      "Network.webSocketCreated",
+      json!({
+        "requestId": request_id,
+        "url": url,
+        "initiator": { "type": "script" },
+      }),
+    );
+  }
+
+  pub fn web_socket_handshake(
+    &self,
+    request_id: &str,
+    request_headers: Value,
+    status: u16,
+    status_text: &str,
+    response_headers: Value,
+  ) {
+    self.emit(
+      "Network.webSocketWillSendHandshakeRequest",
+      json!({
+        "requestId": request_id,
+        "timestamp": timestamp(),
+        "wallTime": wall_time(),
+        "request": { "headers": request_headers },
+      }),
+    );
+    self.emit(
+      "Network.webSocketHandshakeResponseReceived",
+      json!({
+        "requestId": request_id,
+        "timestamp": timestamp(),
+        "response": {
+          "status": status,
+          "statusText": status_text,
+          "headers": response_headers,
+        },
+      }),
+    );
+  }
+
+  /// Reports a frame sent or received over the WebSocket `request_id`.
+  /// `opcode` is 1 for text and 2 for binary frames.
+  pub fn web_socket_frame(
+    &self,
+    request_id: &str,
+    sent: bool,
+    opcode: u8,
+    payload: &[u8],
+  ) {
+    let payload_data = if opcode == 1 {
+      String::from_utf8_lossy(payload).to_string()
+    } else {
+      base64::encode(payload)
+    };
+    let method = if sent {
+      "Network.webSocketFrameSent"
+    } else {
+      "Network.webSocketFrameReceived"
+    };
+    self.emit(
+      method,
+      json!({
+        "requestId": request_id,
+        "timestamp": timestamp(),
+        "response": {
+          "opcode": opcode,
+          "mask": sent,
+          "payloadData": payload_data,
+        },
+      }),
+    );
+  }
+
+  pub fn web_socket_closed(&self, request_id: &str) {
+    self.emit(
+      "Network.webSocketClosed",
+      json!({
+        "requestId": request_id,
+        "timestamp": timestamp(),
+      }),
+    );
+  }
+
+  fn emit(&self, method: &str, params: Value) {
+    let message = json!({ "method": method, "params": params }).to_string();
+    // Sessions that are gone are dropped.
+    self.0.borrow_mut().sessions.retain(|tx| {
+      tx.unbounded_send(InspectorMsg {
+        kind: InspectorMsgKind::Notification,
+        content: message.clone(),
+      })
+      .is_ok()
+    });
+  }
+
+  fn store_response_body(&self, request_id: &str, body: Vec<u8>) {
+    if body.len() > Self::MAX_RESPONSE_BODY_SIZE {
+      return;
+    }
+    let mut state = self.0.borrow_mut();
+    state.response_bodies_size += body.len();
+    state
+      .response_bodies
+      .push_back((request_id.to_string(), body));
+    while state.response_bodies_size > Self::MAX_RESPONSE_BODY_SIZE {
+      let (_, body) = state.response_bodies.pop_front().unwrap();
+      state.response_bodies_size -= body.len();
+    }
+  }
+
+  /// Handles a message of the `Network` domain sent by the session `tx`
+  /// belongs to. Returns `None` if the message isn't one.
+  pub(crate) fn handle_message(
+    &self,
+    tx: &SessionProxySender,
+    message: &str,
+  ) -> Option<InspectorMsg> {
+    // Avoid parsing every message.
+    if !message.contains("\"Network.") {
+      return None;
+    }
+    let message: Value = crate::serde_json::from_str(message).ok()?;
+    let method = message.get("method")?.as_str()?;
+    if !method.starts_with("Network.") {
+      return None;
+    }
+    let id = message.get("id")?.as_i64()? as i32;
+
+    let result = match method {
+      "Network.enable" => {
+        let mut state = self.0.borrow_mut();
+        if !state.sessions.iter().any(|s| s.same_receiver(tx)) {
+          state.sessions.push(tx.clone());
+        }
+        Ok(json!({}))
+      }
+      "Network.disable" => {
+        self
+          .0
+          .borrow_mut()
+          .sessions
+          .retain(|s| !s.same_receiver(tx));
+        Ok(json!({}))
+      }
+      "Network.getResponseBody" => {
+        let request_id = message["params"]["requestId"].as_str();
+        let state = self.0.borrow();
+        let body = state
+          .response_bodies
+          .iter()
+          .find(|(stored_id, _)| Some(stored_id.as_str()) == request_id)
+          .map(|(_, body)| body);
+        match body {
+          Some(body) => Ok(match std::str::from_utf8(body) {
+            Ok(body) => json!({ "body": body, "base64Encoded": false }),
+            Err(_) => {
+              json!({ "body": base64::encode(body), "base64Encoded": true })
+            }
+          }),
+          None => Err((
+            -32000,
+            "No resource with given identifier found".to_string(),
+          )),
+        }
+      }
+      _ => Err((-32601, format!("'{method}' wasn't found"))),
+    };
+
+    let content = match result {
+      Ok(result) => json!({ "id": id, "result": result }),
+      Err((code, message)) => {
+        json!({ "id": id, "error": { "code": code, "message": message } })
+      }
+    };
+    Some(InspectorMsg {
+      kind: InspectorMsgKind::Message(id),
+      content: content.to_string(),
+    })
+  }
+}
+
+/// Converts header name and value pairs into a `Network.Headers` object.
+/// Values of repeated headers are joined by newlines, like Chrome does.
+pub fn network_headers<'a>(
+  headers: impl IntoIterator<Item = (&'a [u8], &'a [u8])>,
+) -> Value {
+  let mut map = Map::new();
+  for (name, value) in headers {
+    let name = String::from_utf8_lossy(name).to_string();
+    let value = String::from_utf8_lossy(value);
+    match map.get_mut(&name) {
+      Some(Value::String(existing)) => {
+        existing.push('\n');
+        existing.push_str(&value);
+      }
+      _ => {
+        map.insert(name, value.into());
+      }
+    }
+  }
+  Value::Object(map)
+}
+
+/// Seconds since an arbitrary point in the past, as `Network.MonotonicTime`.
+fn timestamp() -> f64 {
+  MONOTONIC_START.elapsed().as_secs_f64()
+}
+
+/// Seconds since the epoch, as `Network.TimeSinceEpoch`.
+fn wall_time() -> f64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs_f64())
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::futures::channel::mpsc;
+  use crate::futures::StreamExt;
+
+  #[test]
+  fn network_domain() {
+    let network = NetworkInspector::default();
+    let (tx, mut rx) = mpsc::unbounded();
+    assert!(network
+      .handle_message(&tx, r#"{"id":1,"method":"Runtime.enable"}"#)
+      .is_none());
+    assert!(!network.is_enabled());
+
+    let response = network
+      .handle_message(&tx, r#"{"id":2,"method":"Network.enable"}"#)
+      .unwrap();
+    assert_eq!(response.content, r#"{"id":2,"result":{}}"#);
+    assert!(network.is_enabled());
+
+    let request_id = network.next_request_id();
+    network.loading_finished(&request_id, 5, Some(b"hello".to_vec()));
+    let event = rx.try_next().unwrap().unwrap();
+    assert!(event
+      .content
+      .starts_with(r#"{"method":"Network.loadingFinished""#));
+
+    let message = json!({
+      "id": 3,
+      "method": "Network.getResponseBody",
+      "params": { "requestId": request_id },
+    });
+    let response = network.handle_message(&tx, &message.to_string()).unwrap();
+    assert_eq!(
+      response.content,
+      r#"{"id":3,"result":{"body":"hello","base64Encoded":false}}"#
+    );
+
+    network.handle_message(&tx, r#"{"id":4,"method":"Network.disable"}"#);
+    assert!(!network.is_enabled());
+    drop(tx);
+    assert!(futures::executor::block_on(rx.next()).is_none());
+  }
+
+  #[test]
+  fn headers() {
+    let headers = network_headers([
+      (&b"content-type"[..], &b"text/plain"[..]),
+      (&b"set-cookie"[..], &b"a=1"[..]),
+      (&b"set-cookie"[..], &b"b=2"[..]),
+    ]);
+    assert_eq!(
+      headers,
+      json!({ "content-type": "text/plain", "set-cookie": "a=1\nb=2" })
+    );
+  }
+}
@@ -8,6 +8,7 @@ mod extensions;
 mod flags;
 mod gotham_state;
 mod inspector;
+mod inspector_network;
 mod io;
 mod module_specifier;
 mod modules;
@@ -20,6 +21,7 @@ mod resources;
 mod runtime;
 pub mod snapshot_util;
 mod source_map;
+pub mod wasm;
 
 // Re-exports
 pub use anyhow;
@@ -64,6 +66,8 @@ pub use crate::inspector::InspectorMsgKind;
 pub use crate::inspector::InspectorSessionProxy;
 pub use crate::inspector::JsRuntimeInspector;
 pub use crate::inspector::LocalInspectorSession;
+pub use crate::inspector_network::network_headers;
+pub use crate::inspector_network::NetworkInspector;
 pub use crate::io::BufMutView;
 pub use crate::io::BufView;
 pub use crate::io::WriteOutcome;
@@ -2,10 +2,12 @@
 
 use crate::bindings;
 use crate::error::generic_error;
+use crate::error::type_error;
 use crate::extensions::ExtensionFileSource;
 use crate::module_specifier::ModuleSpecifier;
 use crate::resolve_import;
 use crate::resolve_url;
+use crate::wasm;
 use crate::JsRuntime;
 use crate::OpState;
 use anyhow::Error;
@@ -41,7 +43,7 @@ fn strip_bom(source_code: &[u8]) -> &[u8] {
   }
 }
 
-const SUPPORTED_TYPE_ASSERTIONS: &[&str] = &["json"];
+const SUPPORTED_TYPE_ASSERTIONS: &[&str] = &["json", "text", "bytes"];
 
 /// Throws V8 exception if assertions are invalid
 pub(crate) fn validate_import_assertions(
@@ -109,12 +111,11 @@ pub(crate) fn get_asserted_module_type_from_assertions(
 ) -> AssertedModuleType {
   assertions
     .get("type")
-    .map(|ty| {
-      if ty == "json" {
-        AssertedModuleType::Json
-      } else {
-        AssertedModuleType::JavaScriptOrWasm
-      }
+    .map(|ty| match ty.as_str() {
+      "json" => AssertedModuleType::Json,
+      "text" => AssertedModuleType::Text,
+      "bytes" => AssertedModuleType::Bytes,
+      _ => AssertedModuleType::JavaScriptOrWasm,
     })
     .unwrap_or(AssertedModuleType::JavaScriptOrWasm)
 }
@@ -122,7 +123,7 @@ pub(crate) fn get_asserted_module_type_from_assertions(
 // Clippy thinks the return value doesn't need to be an Option, it's unaware
 // of the mapping that MapFnFrom<F> does for ResolveModuleCallback.
 #[allow(clippy::unnecessary_wraps)]
-fn json_module_evaluation_steps<'a>(
+fn synthetic_module_evaluation_steps<'a>(
   context: v8::Local<'a, v8::Context>,
   module: v8::Local<v8::Module>,
 ) -> Option<v8::Local<'a, v8::Value>> {
@@ -134,7 +135,7 @@ fn json_module_evaluation_steps<'a>(
   let handle = v8::Global::<v8::Module>::new(tc_scope, module);
   let value_handle = module_map
     .borrow_mut()
-    .json_value_store
+    .synthetic_module_value_store
     .remove(&handle)
     .unwrap();
   let value_local = v8::Local::new(tc_scope, value_handle);
@@ -154,6 +155,33 @@ fn json_module_evaluation_steps<'a>(
   Some(resolver.get_promise(tc_scope).into())
 }
 
+/// Renders the JavaScript module standing in for a Wasm module. It imports the
+/// modules the Wasm module imports from, instantiates the compiled module with
+/// them and exports its exports.
+fn render_wasm_module_source(info: &wasm::WasmModuleInfo) -> String {
+  let mut source = String::new();
+  let import_modules = info.import_modules();
+  for (i, module) in import_modules.iter().enumerate() {
+    let module = serde_json::to_string(module).unwrap();
+    source.push_str(&format!("import * as import{i} from {module};\n"));
+  }
+  source.push_str(
+    "const { exports } = new WebAssembly.Instance(import.meta.wasmModule, {\n",
+  );
+  for (i, module) in import_modules.iter().enumerate() {
+    let module = serde_json::to_string(module).unwrap();
+    source.push_str(&format!("  {module}: import{i},\n"));
+  }
+  source.push_str("});\n");
+  for (i, export) in info.exports.iter().enumerate() {
+    let name = serde_json::to_string(&export.name).unwrap();
+    source.push_str(&format!(
+      "const export{i} = exports[{name}];\nexport {{ export{i} as {name} }};\n"
+    ));
+  }
+  source
+}
+
 /// A type of module to be executed.
 ///
 /// For non-`JavaScript` modules, this value doesn't tell
@@ -165,6 +193,9 @@ fn json_module_evaluation_steps<'a>(
 pub enum ModuleType {
   JavaScript,
   Json,
+  Wasm,
+  Text,
+  Bytes,
 }
 
 impl std::fmt::Display for ModuleType {
@@ -172,6 +203,9 @@ impl std::fmt::Display for ModuleType {
     match self {
       Self::JavaScript => write!(f, "JavaScript"),
       Self::Json => write!(f, "JSON"),
+      Self::Wasm => write!(f, "Wasm"),
+      Self::Text => write!(f, "text"),
+      Self::Bytes => write!(f, "bytes"),
     }
   }
 }
@@ -250,6 +284,21 @@ pub trait ModuleLoader {
     is_dyn_import: bool,
   ) -> Pin<Box<ModuleSourceFuture>>;
 
+  /// Given ModuleSpecifier, load its contents as they are, for a module
+  /// imported with a `"text"` or `"bytes"` type assertion. The contents are
+  /// exposed to JavaScript verbatim, so they must not be transpiled.
+  ///
+  /// The `module_type` of the returned source is ignored. By default this
+  /// is the same as `load`.
+  fn load_raw(
+    &self,
+    module_specifier: &ModuleSpecifier,
+    maybe_referrer: Option<ModuleSpecifier>,
+    is_dyn_import: bool,
+  ) -> Pin<Box<ModuleSourceFuture>> {
+    self.load(module_specifier, maybe_referrer, is_dyn_import)
+  }
+
   /// This hook can be used by implementors to do some preparation
   /// work before starting loading of modules.
   ///
@@ -484,6 +533,8 @@ impl ModuleLoader for FsModuleLoader {
         let ext = extension.to_string_lossy().to_lowercase();
         if ext == "json" {
           ModuleType::Json
+        } else if ext == "wasm" {
+          ModuleType::Wasm
         } else {
           ModuleType::JavaScript
         }
@@ -759,6 +810,23 @@ impl RecursiveModuleLoad {
           &module_source.module_url_found,
           &module_source.code,
         )?,
+        ModuleType::Wasm => self.module_map_rc.borrow_mut().new_wasm_module(
+          scope,
+          self.is_currently_loading_main_module(),
+          &module_source.module_url_found,
+          &module_source.code,
+          self.is_dynamic_import(),
+        )?,
+        ModuleType::Text => self.module_map_rc.borrow_mut().new_text_module(
+          scope,
+          &module_source.module_url_found,
+          &module_source.code,
+        )?,
+        ModuleType::Bytes => self.module_map_rc.borrow_mut().new_bytes_module(
+          scope,
+          &module_source.module_url_found,
+          &module_source.code,
+        )?,
       },
     };
 
@@ -790,19 +858,16 @@ impl RecursiveModuleLoad {
           ) {
             already_registered.push_back((module_id, module_request.clone()));
           } else {
-            let request = module_request.clone();
             let specifier =
               ModuleSpecifier::parse(&module_request.specifier).unwrap();
-            let referrer = referrer.clone();
-            let loader = self.loader.clone();
-            let is_dynamic_import = self.is_dynamic_import();
-            let fut = async move {
-              let load_result = loader
-                .load(&specifier, Some(referrer.clone()), is_dynamic_import)
-                .await;
-              load_result.map(|s| (request, s))
-            };
-            self.pending.push(fut.boxed_local());
+            let fut = load_module_request(
+              self.loader.clone(),
+              module_request.clone(),
+              specifier,
+              Some(referrer.clone()),
+              self.is_dynamic_import(),
+            );
+            self.pending.push(fut);
           }
           self.visited.insert(module_request);
         }
@@ -877,15 +942,13 @@ impl Stream for RecursiveModuleLoad {
             specifier: module_specifier.to_string(),
             asserted_module_type,
           };
-          let loader = inner.loader.clone();
-          let is_dynamic_import = inner.is_dynamic_import();
-          async move {
-            let result = loader
-              .load(&module_specifier, maybe_referrer, is_dynamic_import)
-              .await;
-            result.map(|s| (module_request, s))
-          }
-          .boxed_local()
+          load_module_request(
+            inner.loader.clone(),
+            module_request,
+            module_specifier,
+            maybe_referrer,
+            inner.is_dynamic_import(),
+          )
         };
         inner.pending.push(load_fut);
         inner.state = LoadState::LoadingRoot;
@@ -903,18 +966,60 @@ impl Stream for RecursiveModuleLoad {
   }
 }
 
+/// Loads the source of a module request. Modules imported as text or bytes
+/// are loaded verbatim, and their type is the one they were imported as.
+fn load_module_request(
+  loader: Rc<dyn ModuleLoader>,
+  module_request: ModuleRequest,
+  specifier: ModuleSpecifier,
+  maybe_referrer: Option<ModuleSpecifier>,
+  is_dynamic_import: bool,
+) -> Pin<Box<ModuleLoadFuture>> {
+  async move {
+    let raw_module_type = match module_request.asserted_module_type {
+      AssertedModuleType::Text => Some(ModuleType::Text),
+      AssertedModuleType::Bytes => Some(ModuleType::Bytes),
+      _ => None,
+    };
+    let module_source = match raw_module_type {
+      Some(module_type) => {
+        let module_source = loader
+          .load_raw(&specifier, maybe_referrer, is_dynamic_import)
+          .await?;
+        ModuleSource {
+          module_type,
+          ..module_source
+        }
+      }
+      None => {
+        loader
+          .load(&specifier, maybe_referrer, is_dynamic_import)
+          .await?
+      }
+    };
+    Ok((module_request, module_source))
+  }
+  .boxed_local()
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 #[repr(u32)]
 pub(crate) enum AssertedModuleType {
   JavaScriptOrWasm,
   Json,
+  Text,
+  Bytes,
 }
 
 impl From<ModuleType> for AssertedModuleType {
   fn from(module_type: ModuleType) -> AssertedModuleType {
     match module_type {
-      ModuleType::JavaScript => AssertedModuleType::JavaScriptOrWasm,
+      ModuleType::JavaScript | ModuleType::Wasm => {
+        AssertedModuleType::JavaScriptOrWasm
+      }
       ModuleType::Json => AssertedModuleType::Json,
+      ModuleType::Text => AssertedModuleType::Text,
+      ModuleType::Bytes => AssertedModuleType::Bytes,
     }
   }
 }
@@ -924,14 +1029,16 @@ impl std::fmt::Display for AssertedModuleType {
     match self {
       Self::JavaScriptOrWasm => write!(f, "JavaScriptOrWasm"),
       Self::Json => write!(f, "JSON"),
+      Self::Text => write!(f, "text"),
+      Self::Bytes => write!(f, "bytes"),
     }
   }
 }
 
 /// Describes a request for a module as parsed from the source code.
 /// Usually executable (`JavaScriptOrWasm`) is used, except when an
-/// import assertions explicitly constrains an import to JSON, in
-/// which case this will have a `AssertedModuleType::Json`.
+/// import assertions explicitly constrains an import to JSON, text or
+/// bytes, in which case this will have the matching `AssertedModuleType`.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub(crate) struct ModuleRequest {
   pub specifier: String,
@@ -984,9 +1091,16 @@ pub(crate) struct ModuleMap {
   pub(crate) pending_dynamic_imports:
     FuturesUnordered<StreamFuture<RecursiveModuleLoad>>,
 
-  // This store is used temporarly, to forward parsed JSON
-  // value from `new_json_module` to `json_module_evaluation_steps`
-  json_value_store: HashMap<v8::Global<v8::Module>, v8::Global<v8::Value>>,
+  // This store is used temporarly, to forward the default export of
+  // synthetic modules (the parsed JSON value, text or bytes) from
+  // `new_synthetic_module` to `synthetic_module_evaluation_steps`
+  synthetic_module_value_store:
+    HashMap<v8::Global<v8::Module>, v8::Global<v8::Value>>,
+
+  // The compiled modules of Wasm modules, which their JavaScript module reads
+  // from `import.meta.wasmModule` to instantiate them.
+  pub(crate) wasm_module_store:
+    HashMap<v8::Global<v8::Module>, v8::Global<v8::WasmModuleObject>>,
 
   pub(crate) snapshot_loaded_and_not_snapshotting: bool,
 }
@@ -1141,6 +1255,8 @@ impl ModuleMap {
           let asserted_module_type = match asserted_module_type_no {
             0 => AssertedModuleType::JavaScriptOrWasm,
             1 => AssertedModuleType::Json,
+            2 => AssertedModuleType::Text,
+            3 => AssertedModuleType::Bytes,
             _ => unreachable!(),
           };
           requests.push(ModuleRequest {
@@ -1158,6 +1274,9 @@ impl ModuleMap {
         let module_type = match module_type_no {
           0 => ModuleType::JavaScript,
           1 => ModuleType::Json,
+          2 => ModuleType::Wasm,
+          3 => ModuleType::Text,
+          4 => ModuleType::Bytes,
           _ => unreachable!(),
         };
 
@@ -1198,6 +1317,8 @@ impl ModuleMap {
         {
           0 => AssertedModuleType::JavaScriptOrWasm,
           1 => AssertedModuleType::Json,
+          2 => AssertedModuleType::Text,
+          3 => AssertedModuleType::Bytes,
           _ => unreachable!(),
         };
         let key = (specifier, asserted_module_type);
@@ -1240,7 +1361,8 @@ impl ModuleMap {
       dynamic_import_map: HashMap::new(),
       preparing_dynamic_imports: FuturesUnordered::new(),
       pending_dynamic_imports: FuturesUnordered::new(),
-      json_value_store: HashMap::new(),
+      synthetic_module_value_store: HashMap::new(),
+      wasm_module_store: HashMap::new(),
       snapshot_loaded_and_not_snapshotting,
     }
   }
@@ -1272,7 +1394,6 @@ impl ModuleMap {
     name: &str,
     source: &[u8],
   ) -> Result<ModuleId, ModuleError> {
-    let name_str = v8::String::new(scope, name).unwrap();
     let source_str = v8::String::new_from_utf8(
       scope,
       strip_bom(source),
@@ -1292,22 +1413,62 @@ impl ModuleMap {
       }
     };
 
-    let export_names = [v8::String::new(tc_scope, "default").unwrap()];
+    Ok(self.new_synthetic_module(tc_scope, ModuleType::Json, name, parsed_json))
+  }
+
+  fn new_text_module(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    name: &str,
+    source: &[u8],
+  ) -> Result<ModuleId, ModuleError> {
+    let text = std::str::from_utf8(strip_bom(source)).map_err(|_| {
+      ModuleError::Other(type_error(format!(
+        "The text module \"{name}\" is not valid UTF-8."
+      )))
+    })?;
+    let value = v8::String::new(scope, text).unwrap();
+    Ok(self.new_synthetic_module(scope, ModuleType::Text, name, value.into()))
+  }
+
+  fn new_bytes_module(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    name: &str,
+    source: &[u8],
+  ) -> Result<ModuleId, ModuleError> {
+    let backing_store =
+      v8::ArrayBuffer::new_backing_store_from_vec(source.to_vec());
+    let buffer =
+      v8::ArrayBuffer::with_backing_store(scope, &backing_store.make_shared());
+    let value = v8::Uint8Array::new(scope, buffer, 0, source.len()).unwrap();
+    Ok(self.new_synthetic_module(scope, ModuleType::Bytes, name, value.into()))
+  }
+
+  // Create a module with `value` as its default export.
+  fn new_synthetic_module(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    module_type: ModuleType,
+    name: &str,
+    value: v8::Local<v8::Value>,
+  ) -> ModuleId {
+    let name_str = v8::String::new(scope, name).unwrap();
+    let export_names = [v8::String::new(scope, "default").unwrap()];
     let module = v8::Module::create_synthetic_module(
-      tc_scope,
+      scope,
       name_str,
       &export_names,
-      json_module_evaluation_steps,
+      synthetic_module_evaluation_steps,
     );
 
-    let handle = v8::Global::<v8::Module>::new(tc_scope, module);
-    let value_handle = v8::Global::<v8::Value>::new(tc_scope, parsed_json);
-    self.json_value_store.insert(handle.clone(), value_handle);
-
-    let id =
-      self.create_module_info(name, ModuleType::Json, handle, false, vec![]);
+    let handle = v8::Global::<v8::Module>::new(scope, module);
+    let value_handle = v8::Global::<v8::Value>::new(scope, value);
+    self
+      .synthetic_module_value_store
+      .insert(handle.clone(), value_handle);
 
-    Ok(id)
+    self.create_module_info(name, module_type, handle, false, vec![])
   }
 
   // Create and compile an ES module.
@@ -1318,6 +1479,66 @@ impl ModuleMap {
     name: &str,
     source: &[u8],
     is_dynamic_import: bool,
+  ) -> Result<ModuleId, ModuleError> {
+    self.new_module_from_js_source(
+      scope,
+      main,
+      ModuleType::JavaScript,
+      name,
+      source,
+      is_dynamic_import,
+    )
+  }
+
+  // Compile a Wasm module and create the ES module which instantiates it.
+  // The imports of the Wasm module are the imports of the ES module, so they
+  // are resolved and loaded like the imports of any other module.
+  fn new_wasm_module(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    main: bool,
+    name: &str,
+    source: &[u8],
+    is_dynamic_import: bool,
+  ) -> Result<ModuleId, ModuleError> {
+    let wasm_module_info =
+      wasm::parse_wasm_module(source).map_err(ModuleError::Other)?;
+
+    let tc_scope = &mut v8::TryCatch::new(scope);
+    let wasm_module = match v8::WasmModuleObject::compile(tc_scope, source) {
+      Some(wasm_module) => wasm_module,
+      None => {
+        assert!(tc_scope.has_caught());
+        let exception = tc_scope.exception().unwrap();
+        let exception = v8::Global::new(tc_scope, exception);
+        return Err(ModuleError::Exception(exception));
+      }
+    };
+    let wasm_module = v8::Global::new(tc_scope, wasm_module);
+
+    let source = render_wasm_module_source(&wasm_module_info);
+    let id = self.new_module_from_js_source(
+      tc_scope,
+      main,
+      ModuleType::Wasm,
+      name,
+      source.as_bytes(),
+      is_dynamic_import,
+    )?;
+    let handle = self.get_handle(id).unwrap();
+    self.wasm_module_store.insert(handle, wasm_module);
+
+    Ok(id)
+  }
+
+  fn new_module_from_js_source(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    main: bool,
+    module_type: ModuleType,
+    name: &str,
+    source: &[u8],
+    is_dynamic_import: bool,
   ) -> Result<ModuleId, ModuleError> {
     let name_str = v8::String::new(scope, name).unwrap();
     let source_str =
@@ -1403,13 +1624,7 @@ impl ModuleMap {
     }
 
     let handle = v8::Global::<v8::Module>::new(tc_scope, module);
-    let id = self.create_module_info(
-      name,
-      ModuleType::JavaScript,
-      handle,
-      main,
-      requests,
-    );
+    let id = self.create_module_info(name, module_type, handle, main, requests);
 
     Ok(id)
   }
@@ -2789,6 +3004,146 @@ if (import.meta.url != 'file:///main_with_code.js') throw Error();
     futures::executor::block_on(runtime.run_event_loop(false)).unwrap();
   }
 
+  #[test]
+  fn wasm_module() {
+    // (module
+    //   (import "./math.js" "offset" (func $offset (result i32)))
+    //   (func (export "add") (param i32 i32) (result i32)
+    //     local.get 0
+    //     local.get 1
+    //     i32.add
+    //     call $offset
+    //     i32.add))
+    const ADD_WASM: &[u8] = &[
+      0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0b, 0x02, 0x60,
+      0x00, 0x01, 0x7f, 0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7f, 0x02, 0x14, 0x01,
+      0x09, 0x2e, 0x2f, 0x6d, 0x61, 0x74, 0x68, 0x2e, 0x6a, 0x73, 0x06, 0x6f,
+      0x66, 0x66, 0x73, 0x65, 0x74, 0x00, 0x00, 0x03, 0x02, 0x01, 0x01, 0x07,
+      0x07, 0x01, 0x03, 0x61, 0x64, 0x64, 0x00, 0x01, 0x0a, 0x0c, 0x01, 0x0a,
+      0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x10, 0x00, 0x6a, 0x0b,
+    ];
+
+    struct ModsLoader;
+
+    impl ModuleLoader for ModsLoader {
+      fn resolve(
+        &self,
+        specifier: &str,
+        referrer: &str,
+        _kind: ResolutionKind,
+      ) -> Result<ModuleSpecifier, Error> {
+        Ok(resolve_import(specifier, referrer).unwrap())
+      }
+
+      fn load(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        _maybe_referrer: Option<ModuleSpecifier>,
+        _is_dyn_import: bool,
+      ) -> Pin<Box<ModuleSourceFuture>> {
+        let (code, module_type): (&[u8], _) = match module_specifier.as_str() {
+          "file:///main.js" => (
+            b"import { add } from './add.wasm';
+              if (add(1, 2) !== 13) throw Error();",
+            ModuleType::JavaScript,
+          ),
+          "file:///add.wasm" => (ADD_WASM, ModuleType::Wasm),
+          "file:///math.js" => (
+            b"export function offset() { return 10; }",
+            ModuleType::JavaScript,
+          ),
+          _ => unreachable!(),
+        };
+        let module_source = ModuleSource {
+          code: code.to_vec().into_boxed_slice(),
+          module_type,
+          module_url_specified: module_specifier.to_string(),
+          module_url_found: module_specifier.to_string(),
+        };
+        async move { Ok(module_source) }.boxed()
+      }
+    }
+
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      module_loader: Some(Rc::new(ModsLoader)),
+      ..Default::default()
+    });
+
+    let main_specifier = resolve_url("file:///main.js").unwrap();
+    let main_id_fut = runtime
+      .load_main_module(&main_specifier, None)
+      .boxed_local();
+    let main_id = futures::executor::block_on(main_id_fut).unwrap();
+
+    let receiver = runtime.mod_evaluate(main_id);
+    futures::executor::block_on(runtime.run_event_loop(false)).unwrap();
+    futures::executor::block_on(receiver).unwrap().unwrap();
+  }
+
+  #[test]
+  fn text_and_bytes_modules() {
+    struct ModsLoader;
+
+    impl ModuleLoader for ModsLoader {
+      fn resolve(
+        &self,
+        specifier: &str,
+        referrer: &str,
+        _kind: ResolutionKind,
+      ) -> Result<ModuleSpecifier, Error> {
+        Ok(resolve_import(specifier, referrer).unwrap())
+      }
+
+      fn load(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        _maybe_referrer: Option<ModuleSpecifier>,
+        _is_dyn_import: bool,
+      ) -> Pin<Box<ModuleSourceFuture>> {
+        let code: &[u8] = match module_specifier.as_str() {
+          "file:///main.js" => {
+            b"import query from './query.sql' assert { type: 'text' };
+              import data from './data.bin' assert { type: 'bytes' };
+              if (query !== 'SELECT 1;\\n') throw Error(query);
+              if (!(data instanceof Uint8Array)) throw Error();
+              if (data.join() !== '0,159,255') throw Error(data.join());
+              const again = await import('./data.bin', {
+                assert: { type: 'bytes' },
+              });
+              if (again.default !== data) throw Error();"
+          }
+          "file:///query.sql" => b"\xef\xbb\xbfSELECT 1;\n",
+          "file:///data.bin" => &[0x00, 0x9f, 0xff],
+          _ => unreachable!(),
+        };
+        // The type of modules imported as text or bytes is the one they are
+        // imported as.
+        let module_source = ModuleSource {
+          code: code.to_vec().into_boxed_slice(),
+          module_type: ModuleType::JavaScript,
+          module_url_specified: module_specifier.to_string(),
+          module_url_found: module_specifier.to_string(),
+        };
+        async move { Ok(module_source) }.boxed()
+      }
+    }
+
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      module_loader: Some(Rc::new(ModsLoader)),
+      ..Default::default()
+    });
+
+    let main_specifier = resolve_url("file:///main.js").unwrap();
+    let main_id_fut = runtime
+      .load_main_module(&main_specifier, None)
+      .boxed_local();
+    let main_id = futures::executor::block_on(main_id_fut).unwrap();
+
+    let receiver = runtime.mod_evaluate(main_id);
+    futures::executor::block_on(runtime.run_event_loop(false)).unwrap();
+    futures::executor::block_on(receiver).unwrap().unwrap();
+  }
+
   #[test]
   fn dynamic_imports_snapshot() {
     //TODO: Once the issue with the ModuleNamespaceEntryGetter is fixed, we can maintain a reference to the module
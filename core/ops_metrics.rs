@@ -2,8 +2,13 @@
 
 use crate::serde::Serialize;
 use crate::OpId;
+use crate::PromiseId;
+use crate::ResourceId;
 use std::cell::RefCell;
 use std::cell::RefMut;
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Instant;
 
 // TODO(@AaronO): split into AggregateMetrics & PerOpMetrics
 #[derive(Clone, Default, Debug, Serialize)]
@@ -28,12 +33,78 @@ pub struct OpMetrics {
 #[derive(Default, Debug)]
 pub struct OpsTracker {
   ops: RefCell<Vec<OpMetrics>>,
+  timeline: Option<RefCell<OpsTimeline>>,
 }
 
 impl OpsTracker {
   pub fn new(ops_count: usize) -> Self {
     Self {
       ops: RefCell::new(vec![Default::default(); ops_count]),
+      timeline: None,
+    }
+  }
+
+  /// Starts recording every op call in a timeline, which can be written with
+  /// `write_timeline()`. This has to happen before the ops are bound in JS.
+  pub fn enable_timeline(&mut self) {
+    self.timeline = Some(RefCell::new(OpsTimeline::new()));
+  }
+
+  pub fn has_timeline(&self) -> bool {
+    self.timeline.is_some()
+  }
+
+  /// Writes the timeline in the Chrome trace event format, which can be
+  /// opened in Perfetto or `chrome://tracing`.
+  pub fn write_timeline<W: Write>(
+    &self,
+    writer: W,
+  ) -> Result<(), serde_json::Error> {
+    let events = match &self.timeline {
+      Some(timeline) => std::mem::take(&mut timeline.borrow_mut().events),
+      None => vec![],
+    };
+    serde_json::to_writer(
+      writer,
+      &Trace {
+        trace_events: events,
+        display_time_unit: "ms",
+      },
+    )
+  }
+
+  /// Records the start of an async op, whose completion is recorded by
+  /// `track_async_completed()`. Returns the index of the event, to add the
+  /// resources that the op used once the call returned.
+  pub(crate) fn timeline_async_started(
+    &self,
+    name: &'static str,
+    promise_id: PromiseId,
+    start: Instant,
+  ) -> Option<usize> {
+    let mut timeline = self.timeline.as_ref()?.borrow_mut();
+    let index = timeline.push(name, "async", "b", start, Some(promise_id));
+    timeline.pending_async.insert(promise_id, name);
+    Some(index)
+  }
+
+  /// Records the synchronous part of an op call, from JS to Rust and back.
+  pub(crate) fn timeline_called(
+    &self,
+    name: &'static str,
+    start: Instant,
+    rids: Vec<ResourceId>,
+    async_index: Option<usize>,
+  ) {
+    if let Some(timeline) = &self.timeline {
+      let mut timeline = timeline.borrow_mut();
+      let index = timeline.push(name, "sync", "X", start, None);
+      let event = &mut timeline.events[index];
+      event.dur = Some(micros_since(start, Instant::now()));
+      event.args.rids = rids.clone();
+      if let Some(index) = async_index {
+        timeline.events[index].args.rids = rids;
+      }
     }
   }
 
@@ -83,9 +154,89 @@ impl OpsTracker {
   }
 
   #[inline]
-  pub fn track_async_completed(&self, id: OpId) {
+  pub fn track_async_completed(&self, id: OpId, promise_id: PromiseId) {
     let mut metrics = self.metrics_mut(id);
     metrics.ops_completed += 1;
     metrics.ops_completed_async += 1;
+    if let Some(timeline) = &self.timeline {
+      let mut timeline = timeline.borrow_mut();
+      if let Some(name) = timeline.pending_async.remove(&promise_id) {
+        timeline.push(name, "async", "e", Instant::now(), Some(promise_id));
+      }
+    }
+  }
+}
+
+/// Op calls recorded as Chrome trace events. Sync calls, and the synchronous
+/// part of async calls, are complete events on the JS thread; async ops are
+/// nestable async events that span until the op completes.
+#[derive(Debug)]
+struct OpsTimeline {
+  start: Instant,
+  events: Vec<TraceEvent>,
+  pending_async: HashMap<PromiseId, &'static str>,
+}
+
+impl OpsTimeline {
+  fn new() -> Self {
+    Self {
+      start: Instant::now(),
+      events: vec![],
+      pending_async: HashMap::new(),
+    }
   }
+
+  fn push(
+    &mut self,
+    name: &'static str,
+    cat: &'static str,
+    ph: &'static str,
+    time: Instant,
+    id: Option<PromiseId>,
+  ) -> usize {
+    self.events.push(TraceEvent {
+      name,
+      cat,
+      ph,
+      ts: micros_since(self.start, time),
+      dur: None,
+      id,
+      pid: std::process::id(),
+      tid: 1,
+      args: TraceEventArgs { rids: vec![] },
+    });
+    self.events.len() - 1
+  }
+}
+
+fn micros_since(earlier: Instant, later: Instant) -> f64 {
+  later.saturating_duration_since(earlier).as_nanos() as f64 / 1000.0
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Trace {
+  trace_events: Vec<TraceEvent>,
+  display_time_unit: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct TraceEvent {
+  name: &'static str,
+  cat: &'static str,
+  ph: &'static str,
+  ts: f64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  dur: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  id: Option<PromiseId>,
+  pid: u32,
+  tid: u32,
+  args: TraceEventArgs,
+}
+
+#[derive(Debug, Serialize)]
+struct TraceEventArgs {
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  rids: Vec<ResourceId>,
 }
@@ -17,7 +17,10 @@ use std::any::type_name;
 use std::any::Any;
 use std::any::TypeId;
 use std::borrow::Cow;
+use std::cell::Cell;
+use std::cell::RefCell;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::iter::Iterator;
 use std::pin::Pin;
 use std::rc::Rc;
@@ -220,6 +223,17 @@ pub type ResourceId = u32;
 pub struct ResourceTable {
   index: BTreeMap<ResourceId, Rc<dyn Resource>>,
   next_rid: ResourceId,
+  /// Whether an op call is being recorded in the ops timeline.
+  recording_rids: Cell<bool>,
+  /// The ids of the resources used during the recorded op call.
+  recorded_rids: RefCell<BTreeSet<ResourceId>>,
+}
+
+/// The recording state of an op call that is suspended while a nested op call
+/// is recorded.
+pub(crate) struct RidRecording {
+  recording: bool,
+  rids: BTreeSet<ResourceId>,
 }
 
 impl ResourceTable {
@@ -249,6 +263,7 @@ impl ResourceTable {
     let removed_resource = self.index.insert(rid, resource);
     assert!(removed_resource.is_none());
     self.next_rid += 1;
+    self.record_rid(rid);
     rid
   }
 
@@ -261,6 +276,7 @@ impl ResourceTable {
   /// given `rid`. If `rid` is not present or has a type different than `T`,
   /// this function returns `None`.
   pub fn get<T: Resource>(&self, rid: ResourceId) -> Result<Rc<T>, Error> {
+    self.record_rid(rid);
     self
       .index
       .get(&rid)
@@ -270,6 +286,7 @@ impl ResourceTable {
   }
 
   pub fn get_any(&self, rid: ResourceId) -> Result<Rc<dyn Resource>, Error> {
+    self.record_rid(rid);
     self
       .index
       .get(&rid)
@@ -315,6 +332,7 @@ impl ResourceTable {
     &mut self,
     rid: ResourceId,
   ) -> Result<Rc<dyn Resource>, Error> {
+    self.record_rid(rid);
     self.index.remove(&rid).ok_or_else(bad_resource_id)
   }
 
@@ -325,6 +343,7 @@ impl ResourceTable {
   /// may implement the `close()` method to perform clean-ups such as canceling
   /// ops.
   pub fn close(&mut self, rid: ResourceId) -> Result<(), Error> {
+    self.record_rid(rid);
     self
       .index
       .remove(&rid)
@@ -350,6 +369,32 @@ impl ResourceTable {
       .iter()
       .map(|(&id, resource)| (id, resource.name()))
   }
+
+  /// Starts recording the ids of the resources that are used, until
+  /// `take_recorded_rids()` is called. Returns the recording of the op call
+  /// this one is nested in, which must be passed to `take_recorded_rids()`.
+  pub(crate) fn start_recording_rids(&self) -> RidRecording {
+    RidRecording {
+      recording: self.recording_rids.replace(true),
+      rids: self.recorded_rids.take(),
+    }
+  }
+
+  /// Stops recording and resumes the `outer` recording.
+  pub(crate) fn take_recorded_rids(
+    &self,
+    outer: RidRecording,
+  ) -> Vec<ResourceId> {
+    self.recording_rids.set(outer.recording);
+    self.recorded_rids.replace(outer.rids).into_iter().collect()
+  }
+
+  #[inline]
+  fn record_rid(&self, rid: ResourceId) {
+    if self.recording_rids.get() {
+      self.recorded_rids.borrow_mut().insert(rid);
+    }
+  }
 }
 
 #[macro_export]
@@ -7,7 +7,9 @@ use crate::error::JsError;
 use crate::extensions::OpDecl;
 use crate::extensions::OpEventLoopFn;
 use crate::inspector::JsRuntimeInspector;
+use crate::inspector_network::NetworkInspector;
 use crate::module_specifier::ModuleSpecifier;
+use crate::modules::AssertedModuleType;
 use crate::modules::InternalModuleLoaderCb;
 use crate::modules::ModuleError;
 use crate::modules::ModuleId;
@@ -91,8 +93,6 @@ pub struct JsRuntime {
   extensions: Vec<Extension>,
   extensions_with_js: Vec<Extension>,
   event_loop_middlewares: Vec<Box<OpEventLoopFn>>,
-  // Marks if this is considered the top-level runtime. Used only be inspector.
-  is_main: bool,
 }
 
 pub(crate) struct DynImportModEvaluate {
@@ -193,6 +193,11 @@ pub struct JsRuntimeState {
   // flimsy. Try to poll it similarly to `pending_promise_rejections`.
   pub(crate) dispatched_exceptions: VecDeque<v8::Global<v8::Value>>,
   pub(crate) inspector: Option<Rc<RefCell<JsRuntimeInspector>>>,
+  /// Put into the op state for extensions to report network activity to,
+  /// which is forwarded to the sessions of the inspector once it exists.
+  network_inspector: NetworkInspector,
+  // Marks if this is considered the top-level runtime. Used only be inspector.
+  is_main: bool,
   waker: AtomicWaker,
 }
 
@@ -305,6 +310,10 @@ pub struct RuntimeOptions {
   /// Describe if this is the main runtime instance, used by debuggers in some
   /// situation - like disconnecting when program finishes running.
   pub is_main: bool,
+
+  /// Record every op call in a timeline, which can be written with
+  /// `OpsTracker::write_timeline()`. Ops don't use fast calls in that case.
+  pub ops_timeline: bool,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -379,7 +388,11 @@ impl JsRuntime {
     if let Some(get_error_class_fn) = options.get_error_class_fn {
       op_state.get_error_class_fn = get_error_class_fn;
     }
+    if options.ops_timeline {
+      op_state.tracker.enable_timeline();
+    }
     let op_state = Rc::new(RefCell::new(op_state));
+    let network_inspector = NetworkInspector::default();
 
     let align = std::mem::align_of::<usize>();
     let layout = std::alloc::Layout::from_size_align(
@@ -410,6 +423,8 @@ impl JsRuntime {
       dispatched_exceptions: Default::default(),
       // Some fields are initialized later after isolate is created
       inspector: None,
+      network_inspector: network_inspector.clone(),
+      is_main: options.is_main,
       global_realm: None,
       known_realms: Vec::with_capacity(1),
     }));
@@ -599,11 +614,13 @@ impl JsRuntime {
     );
 
     op_state.borrow_mut().put(isolate_ptr);
+    op_state.borrow_mut().put(network_inspector.clone());
     let inspector = if options.inspector {
       Some(JsRuntimeInspector::new(
-        &mut isolate,
+        &mut v8::HandleScope::new(&mut isolate),
         global_context.clone(),
         options.is_main,
+        network_inspector,
       ))
     } else {
       None
@@ -669,7 +686,6 @@ impl JsRuntime {
       extensions_with_js: options.extensions_with_js,
       state: state_rc,
       module_map: Some(module_map_rc),
-      is_main: options.is_main,
     };
 
     // Init resources and ops before extensions to make sure they are
@@ -812,6 +828,16 @@ impl JsRuntime {
     state
   }
 
+  /// Whether `name` is the URL of a JavaScript or Wasm module loaded into
+  /// the runtime of `isolate`. Unlike the names that scripts are given with
+  /// a `//# sourceURL=` comment, these are only registered by the loader.
+  pub fn is_module_loaded(isolate: &v8::Isolate, name: &str) -> bool {
+    Self::module_map(isolate)
+      .borrow()
+      .by_name
+      .contains_key(&(name.to_string(), AssertedModuleType::JavaScriptOrWasm))
+  }
+
   pub(crate) fn module_map(isolate: &v8::Isolate) -> Rc<RefCell<ModuleMap>> {
     let module_map_ptr = isolate.get_data(Self::MODULE_MAP_DATA_OFFSET);
     let module_map_rc =
@@ -1204,16 +1230,30 @@ impl JsRuntime {
   }
 
   pub fn maybe_init_inspector(&mut self) {
-    if self.state.borrow().inspector.is_some() {
-      return;
-    }
+    Self::inspector_from_scope(&mut self.handle_scope());
+  }
 
-    let mut state = self.state.borrow_mut();
-    state.inspector = Some(JsRuntimeInspector::new(
-      self.v8_isolate.as_mut().unwrap(),
-      state.global_realm.clone().unwrap().0,
-      self.is_main,
-    ));
+  /// Returns the inspector of the runtime `scope` belongs to, creating it on
+  /// first use. Lets ops connect to the inspector on demand.
+  pub fn inspector_from_scope(
+    scope: &mut v8::HandleScope,
+  ) -> Rc<RefCell<JsRuntimeInspector>> {
+    let state_rc = Self::state(scope);
+    let (context, is_main, network_inspector) = {
+      let state = state_rc.borrow();
+      if let Some(inspector) = &state.inspector {
+        return inspector.clone();
+      }
+      (
+        state.global_realm.clone().unwrap().0,
+        state.is_main,
+        state.network_inspector.clone(),
+      )
+    };
+    let inspector =
+      JsRuntimeInspector::new(scope, context, is_main, network_inspector);
+    state_rc.borrow_mut().inspector = Some(inspector.clone());
+    inspector
   }
 
   pub fn poll_value(
@@ -1352,10 +1392,12 @@ impl JsRuntime {
     if !pending_state.is_pending() && !maybe_scheduling {
       if has_inspector {
         let inspector = self.inspector();
-        let has_active_sessions = inspector.borrow().has_active_sessions();
+        // Local sessions, e.g. of `node:inspector`, don't keep the event loop
+        // alive on their own.
+        let has_remote_sessions = inspector.borrow().has_remote_sessions();
         let has_blocking_sessions = inspector.borrow().has_blocking_sessions();
 
-        if wait_for_inspector && has_active_sessions {
+        if wait_for_inspector && has_remote_sessions {
           // If there are no blocking sessions (eg. REPL) we can now notify
           // debugger that the program has finished running and we're ready
           // to exit the process once debugger disconnects.
@@ -2330,7 +2372,11 @@ impl JsRuntime {
       while let Poll::Ready(Some(item)) = state.pending_ops.poll_next_unpin(cx)
       {
         let (realm_idx, promise_id, op_id, resp) = item;
-        state.op_state.borrow().tracker.track_async_completed(op_id);
+        state
+          .op_state
+          .borrow()
+          .tracker
+          .track_async_completed(op_id, promise_id);
         responses_per_realm[realm_idx].push((promise_id, resp));
       }
     }
@@ -2434,7 +2480,11 @@ impl JsRuntime {
           state.global_realm.as_ref().unwrap().context()
         );
         realm_state.unrefed_ops.remove(&promise_id);
-        state.op_state.borrow().tracker.track_async_completed(op_id);
+        state
+          .op_state
+          .borrow()
+          .tracker
+          .track_async_completed(op_id, promise_id);
         args.push(v8::Integer::new(scope, promise_id).into());
         args.push(match resp.to_v8(scope) {
           Ok(v) => v,
@@ -2592,6 +2642,18 @@ impl JsRealm {
       .clone()
   }
 
+  /// Lets `context`, a bare context created in the same isolate by an
+  /// extension (e.g. for `node:vm`), share the state of the current realm.
+  /// Callbacks from V8 such as the promise rejection tracker look up this
+  /// state from whichever context is current when they are called.
+  pub fn share_state_with(
+    scope: &mut v8::HandleScope,
+    context: v8::Local<v8::Context>,
+  ) {
+    let state = Self::state_from_scope(scope);
+    context.set_slot(scope, state);
+  }
+
   pub fn handle_scope<'s>(
     &self,
     isolate: &'s mut v8::Isolate,
@@ -2744,7 +2806,11 @@ pub fn queue_async_op(
         resp.to_v8(scope).unwrap(),
       ];
 
-      ctx.state.borrow_mut().tracker.track_async_completed(op_id);
+      ctx
+        .state
+        .borrow_mut()
+        .tracker
+        .track_async_completed(op_id, promise_id);
 
       let tc_scope = &mut v8::TryCatch::new(scope);
       let js_recv_cb =
@@ -5027,4 +5093,153 @@ Deno.core.ops.op_async_serialize_object_with_numbers_as_keys({
       "Cannot load internal module from external code"
     );
   }
+
+  #[tokio::test]
+  async fn test_ops_timeline() {
+    struct TestResource;
+
+    impl crate::Resource for TestResource {}
+
+    #[op]
+    fn op_add_resource(state: &mut OpState) -> crate::ResourceId {
+      state.resource_table.add(TestResource)
+    }
+
+    #[op]
+    async fn op_use_resource(
+      state: Rc<RefCell<OpState>>,
+      rid: crate::ResourceId,
+    ) -> Result<(), Error> {
+      state.borrow().resource_table.get::<TestResource>(rid)?;
+      tokio::task::yield_now().await;
+      Ok(())
+    }
+
+    let ext = Extension::builder("test_ext")
+      .ops(vec![op_add_resource::decl(), op_use_resource::decl()])
+      .build();
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      extensions: vec![ext],
+      ops_timeline: true,
+      ..Default::default()
+    });
+    runtime
+      .execute_script(
+        "test_ops_timeline.js",
+        r#"
+Deno.core.initializeAsyncOps();
+const rid = Deno.core.ops.op_add_resource();
+Deno.core.opAsync("op_use_resource", rid);
+"#,
+      )
+      .unwrap();
+    runtime.run_event_loop(false).await.unwrap();
+
+    let mut out = vec![];
+    let op_state = runtime.op_state();
+    op_state.borrow().tracker.write_timeline(&mut out).unwrap();
+    let trace: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let events = trace["traceEvents"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|event| {
+        (
+          event["name"].as_str().unwrap(),
+          event["ph"].as_str().unwrap(),
+          event["args"]["rids"].clone(),
+        )
+      })
+      .collect::<Vec<_>>();
+    let rids = serde_json::json!([0]);
+    assert_eq!(
+      events,
+      vec![
+        ("op_add_resource", "X", rids.clone()),
+        ("op_use_resource", "b", rids.clone()),
+        ("op_use_resource", "X", rids),
+        ("op_use_resource", "e", serde_json::Value::Null),
+      ]
+    );
+    let trace_events = trace["traceEvents"].as_array().unwrap();
+    assert_eq!(trace_events[1]["id"], trace_events[3]["id"]);
+  }
+
+  #[test]
+  fn test_ops_timeline_nested() {
+    struct TestResource;
+
+    impl crate::Resource for TestResource {}
+
+    #[op]
+    fn op_add_resource(state: &mut OpState) -> crate::ResourceId {
+      state.resource_table.add(TestResource)
+    }
+
+    #[op(v8)]
+    fn op_use_resource_with_callback(
+      scope: &mut v8::HandleScope,
+      rid: crate::ResourceId,
+      callback: serde_v8::Value,
+    ) {
+      let op_state = JsRuntime::state(scope).borrow().op_state.clone();
+      op_state
+        .borrow()
+        .resource_table
+        .get::<TestResource>(rid)
+        .unwrap();
+      let callback =
+        v8::Local::<v8::Function>::try_from(callback.v8_value).unwrap();
+      let recv = v8::undefined(scope).into();
+      callback.call(scope, recv, &[]).unwrap();
+    }
+
+    let ext = Extension::builder("test_ext")
+      .ops(vec![
+        op_add_resource::decl(),
+        op_use_resource_with_callback::decl(),
+      ])
+      .build();
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      extensions: vec![ext],
+      ops_timeline: true,
+      ..Default::default()
+    });
+    runtime
+      .execute_script(
+        "test_ops_timeline_nested.js",
+        r#"
+const rid = Deno.core.ops.op_add_resource();
+Deno.core.ops.op_use_resource_with_callback(rid, () => {
+  Deno.core.ops.op_add_resource();
+});
+"#,
+      )
+      .unwrap();
+
+    let mut out = vec![];
+    let op_state = runtime.op_state();
+    op_state.borrow().tracker.write_timeline(&mut out).unwrap();
+    let trace: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let events = trace["traceEvents"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|event| {
+        (
+          event["name"].as_str().unwrap(),
+          event["args"]["rids"].clone(),
+        )
+      })
+      .collect::<Vec<_>>();
+    // The nested call doesn't reset the resources recorded for the outer one.
+    assert_eq!(
+      events,
+      vec![
+        ("op_add_resource", serde_json::json!([0])),
+        ("op_add_resource", serde_json::json!([1])),
+        ("op_use_resource_with_callback", serde_json::json!([0])),
+      ]
+    );
+  }
 }
@@ -0,0 +1,322 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Reading the imports and exports of a WebAssembly module from its binary,
+//! which is needed to link it as an ES module before it is instantiated.
+//! <https://webassembly.github.io/spec/core/binary/modules.html>
+
+use crate::error::generic_error;
+use anyhow::Error;
+
+const MAGIC_AND_VERSION: &[u8] = b"\0asm\x01\0\0\0";
+
+const TYPE_SECTION: u8 = 1;
+const IMPORT_SECTION: u8 = 2;
+const FUNCTION_SECTION: u8 = 3;
+const EXPORT_SECTION: u8 = 7;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WasmValueType {
+  I32,
+  I64,
+  F32,
+  F64,
+  V128,
+  FuncRef,
+  ExternRef,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WasmFunctionType {
+  pub params: Vec<WasmValueType>,
+  pub results: Vec<WasmValueType>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WasmExportKind {
+  Function(WasmFunctionType),
+  Table,
+  Memory,
+  Global,
+  Tag,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WasmImport {
+  pub module: String,
+  pub name: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WasmExport {
+  pub name: String,
+  pub kind: WasmExportKind,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WasmModuleInfo {
+  pub imports: Vec<WasmImport>,
+  pub exports: Vec<WasmExport>,
+}
+
+impl WasmModuleInfo {
+  /// The modules this module imports from, in order of their first import
+  /// and without duplicates.
+  pub fn import_modules(&self) -> Vec<&str> {
+    let mut modules: Vec<&str> = vec![];
+    for import in &self.imports {
+      if !modules.contains(&import.module.as_str()) {
+        modules.push(&import.module);
+      }
+    }
+    modules
+  }
+}
+
+/// Parses the imports and exports of a WebAssembly module. The rest of the
+/// module is left for V8 to validate when compiling it.
+pub fn parse_wasm_module(bytes: &[u8]) -> Result<WasmModuleInfo, Error> {
+  let mut reader = Reader { bytes, offset: 0 };
+  if reader.read_bytes(MAGIC_AND_VERSION.len())? != MAGIC_AND_VERSION {
+    return Err(generic_error("Invalid or unsupported Wasm module header"));
+  }
+
+  let mut types = vec![];
+  // The type of every function, imported functions first.
+  let mut functions = vec![];
+  let mut info = WasmModuleInfo::default();
+  while !reader.is_empty() {
+    let id = reader.read_u8()?;
+    let size = reader.read_u32()? as usize;
+    let mut section = Reader {
+      bytes: reader.read_bytes(size)?,
+      offset: 0,
+    };
+    match id {
+      TYPE_SECTION => {
+        for _ in 0..section.read_u32()? {
+          types.push(section.read_function_type()?);
+        }
+      }
+      IMPORT_SECTION => {
+        for _ in 0..section.read_u32()? {
+          let module = section.read_name()?;
+          let name = section.read_name()?;
+          match section.read_u8()? {
+            // Function.
+            0x00 => functions.push(section.read_u32()?),
+            // Table.
+            0x01 => {
+              section.read_u8()?;
+              section.read_limits()?;
+            }
+            // Memory.
+            0x02 => section.read_limits()?,
+            // Global.
+            0x03 => {
+              section.read_value_type()?;
+              section.read_u8()?;
+            }
+            // Tag.
+            0x04 => {
+              section.read_u8()?;
+              section.read_u32()?;
+            }
+            kind => return Err(unsupported("import kind", kind)),
+          }
+          info.imports.push(WasmImport { module, name });
+        }
+      }
+      FUNCTION_SECTION => {
+        for _ in 0..section.read_u32()? {
+          functions.push(section.read_u32()?);
+        }
+      }
+      EXPORT_SECTION => {
+        for _ in 0..section.read_u32()? {
+          let name = section.read_name()?;
+          let kind = section.read_u8()?;
+          let index = section.read_u32()? as usize;
+          let kind = match kind {
+            0x00 => {
+              let function_type = functions
+                .get(index)
+                .and_then(|type_index| types.get(*type_index as usize))
+                .ok_or_else(|| {
+                  generic_error(format!(
+                    "Invalid function index {index} for Wasm export \"{name}\""
+                  ))
+                })?;
+              WasmExportKind::Function(function_type.clone())
+            }
+            0x01 => WasmExportKind::Table,
+            0x02 => WasmExportKind::Memory,
+            0x03 => WasmExportKind::Global,
+            0x04 => WasmExportKind::Tag,
+            kind => return Err(unsupported("export kind", kind)),
+          };
+          info.exports.push(WasmExport { name, kind });
+        }
+      }
+      _ => {}
+    }
+  }
+  Ok(info)
+}
+
+fn unsupported(what: &str, byte: u8) -> Error {
+  generic_error(format!("Unsupported Wasm {what} 0x{byte:02x}"))
+}
+
+struct Reader<'a> {
+  bytes: &'a [u8],
+  offset: usize,
+}
+
+impl<'a> Reader<'a> {
+  fn is_empty(&self) -> bool {
+    self.offset >= self.bytes.len()
+  }
+
+  fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+    let bytes = self
+      .bytes
+      .get(self.offset..self.offset + len)
+      .ok_or_else(|| generic_error("Unexpected end of Wasm module"))?;
+    self.offset += len;
+    Ok(bytes)
+  }
+
+  fn read_u8(&mut self) -> Result<u8, Error> {
+    Ok(self.read_bytes(1)?[0])
+  }
+
+  /// Reads an unsigned LEB128 integer.
+  fn read_u64(&mut self) -> Result<u64, Error> {
+    let mut result = 0;
+    let mut shift = 0;
+    loop {
+      let byte = self.read_u8()?;
+      if shift >= 64 {
+        return Err(generic_error("Invalid integer in Wasm module"));
+      }
+      result |= u64::from(byte & 0x7f) << shift;
+      if byte & 0x80 == 0 {
+        return Ok(result);
+      }
+      shift += 7;
+    }
+  }
+
+  fn read_u32(&mut self) -> Result<u32, Error> {
+    u32::try_from(self.read_u64()?)
+      .map_err(|_| generic_error("Invalid integer in Wasm module"))
+  }
+
+  fn read_name(&mut self) -> Result<String, Error> {
+    let len = self.read_u32()? as usize;
+    let bytes = self.read_bytes(len)?;
+    String::from_utf8(bytes.to_vec())
+      .map_err(|_| generic_error("Invalid name in Wasm module"))
+  }
+
+  fn read_limits(&mut self) -> Result<(), Error> {
+    let flags = self.read_u8()?;
+    self.read_u64()?;
+    if flags & 0x01 != 0 {
+      self.read_u64()?;
+    }
+    Ok(())
+  }
+
+  fn read_value_type(&mut self) -> Result<WasmValueType, Error> {
+    match self.read_u8()? {
+      0x7f => Ok(WasmValueType::I32),
+      0x7e => Ok(WasmValueType::I64),
+      0x7d => Ok(WasmValueType::F32),
+      0x7c => Ok(WasmValueType::F64),
+      0x7b => Ok(WasmValueType::V128),
+      0x70 => Ok(WasmValueType::FuncRef),
+      0x6f => Ok(WasmValueType::ExternRef),
+      byte => Err(unsupported("value type", byte)),
+    }
+  }
+
+  fn read_value_types(&mut self) -> Result<Vec<WasmValueType>, Error> {
+    (0..self.read_u32()?)
+      .map(|_| self.read_value_type())
+      .collect()
+  }
+
+  fn read_function_type(&mut self) -> Result<WasmFunctionType, Error> {
+    match self.read_u8()? {
+      0x60 => Ok(WasmFunctionType {
+        params: self.read_value_types()?,
+        results: self.read_value_types()?,
+      }),
+      byte => Err(unsupported("type", byte)),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // (module
+  //   (import "./math.js" "offset" (func $offset (result i32)))
+  //   (memory (export "memory") 1)
+  //   (func (export "add") (param i32 i64) (result i32)
+  //     local.get 0
+  //     call $offset
+  //     i32.add))
+  const MODULE: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+    0x01, 0x0b, 0x02, 0x60, 0x00, 0x01, 0x7f, 0x60, 0x02, 0x7f, 0x7e, 0x01,
+    0x7f, // type section
+    0x02, 0x14, 0x01, 0x09, 0x2e, 0x2f, 0x6d, 0x61, 0x74, 0x68, 0x2e, 0x6a,
+    0x73, 0x06, 0x6f, 0x66, 0x66, 0x73, 0x65, 0x74, 0x00,
+    0x00, // import section
+    0x03, 0x02, 0x01, 0x01, // function section
+    0x05, 0x03, 0x01, 0x00, 0x01, // memory section
+    0x07, 0x10, 0x02, 0x06, 0x6d, 0x65, 0x6d, 0x6f, 0x72, 0x79, 0x02, 0x00,
+    0x03, 0x61, 0x64, 0x64, 0x00, 0x01, // export section
+    0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x10, 0x00, 0x6a,
+    0x0b, // code section
+  ];
+
+  #[test]
+  fn test_parse_wasm_module() {
+    let info = parse_wasm_module(MODULE).unwrap();
+    assert_eq!(
+      info.imports,
+      vec![WasmImport {
+        module: "./math.js".to_string(),
+        name: "offset".to_string(),
+      }]
+    );
+    assert_eq!(info.import_modules(), vec!["./math.js"]);
+    assert_eq!(
+      info.exports,
+      vec![
+        WasmExport {
+          name: "memory".to_string(),
+          kind: WasmExportKind::Memory,
+        },
+        WasmExport {
+          name: "add".to_string(),
+          kind: WasmExportKind::Function(WasmFunctionType {
+            params: vec![WasmValueType::I32, WasmValueType::I64],
+            results: vec![WasmValueType::I32],
+          }),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn test_parse_invalid_wasm_module() {
+    assert!(parse_wasm_module(b"\0asm").is_err());
+    assert!(parse_wasm_module(b"export {};").is_err());
+    assert!(parse_wasm_module(&MODULE[..MODULE.len() - 1]).is_err());
+  }
+}
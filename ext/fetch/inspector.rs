@@ -0,0 +1,127 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::BytesStream;
+use deno_core::futures::Stream;
+use deno_core::futures::StreamExt;
+use deno_core::network_headers;
+use deno_core::ByteString;
+use deno_core::NetworkInspector;
+use deno_core::OpState;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+/// A request made with `fetch()` that is reported to the `Network` domain of
+/// the inspector.
+pub struct InspectedRequest {
+  network: NetworkInspector,
+  request_id: String,
+}
+
+impl InspectedRequest {
+  /// Reports the request if an inspector session enabled the `Network`
+  /// domain.
+  pub fn new(
+    state: &OpState,
+    url: &str,
+    method: &str,
+    headers: &[(ByteString, ByteString)],
+    body: Option<&[u8]>,
+  ) -> Option<Self> {
+    let network = state.try_borrow::<NetworkInspector>()?;
+    if !network.is_enabled() {
+      return None;
+    }
+    let request_id = network.next_request_id();
+    let headers =
+      network_headers(headers.iter().map(|(k, v)| (&k[..], &v[..])));
+    network.request_will_be_sent(&request_id, url, method, headers, body);
+    Some(Self {
+      network: network.clone(),
+      request_id,
+    })
+  }
+
+  pub fn response_received(
+    &self,
+    url: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+  ) {
+    let headers = network_headers(
+      headers
+        .iter()
+        .map(|(name, value)| (name.as_str().as_bytes(), value.as_bytes())),
+    );
+    self.network.response_received(
+      &self.request_id,
+      url,
+      status.as_u16(),
+      status.canonical_reason().unwrap_or(""),
+      headers,
+    );
+  }
+
+  pub fn failed(&self, error_text: &str) {
+    self.network.loading_failed(&self.request_id, error_text);
+  }
+
+  /// Wraps the stream of the response body, to report when it was received
+  /// completely.
+  pub fn body_stream(self, stream: BytesStream) -> BytesStream {
+    Box::pin(InspectedBodyStream {
+      stream,
+      request: Some(self),
+      length: 0,
+      body: Some(Vec::new()),
+    })
+  }
+}
+
+struct InspectedBodyStream {
+  stream: BytesStream,
+  request: Option<InspectedRequest>,
+  length: usize,
+  /// `None` once the body got too large to be kept.
+  body: Option<Vec<u8>>,
+}
+
+impl Stream for InspectedBodyStream {
+  type Item = Result<bytes::Bytes, std::io::Error>;
+
+  fn poll_next(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Option<Self::Item>> {
+    let this = &mut *self;
+    let poll = this.stream.poll_next_unpin(cx);
+    match &poll {
+      Poll::Ready(Some(Ok(chunk))) => {
+        this.length += chunk.len();
+        if this.length > NetworkInspector::MAX_RESPONSE_BODY_SIZE {
+          this.body = None;
+        } else if let Some(body) = &mut this.body {
+          body.extend_from_slice(chunk);
+        }
+      }
+      Poll::Ready(Some(Err(err))) => {
+        if let Some(request) = this.request.take() {
+          request.failed(&err.to_string());
+        }
+      }
+      Poll::Ready(None) => {
+        if let Some(request) = this.request.take() {
+          request.network.loading_finished(
+            &request.request_id,
+            this.length,
+            this.body.take(),
+          );
+        }
+      }
+      Poll::Pending => {}
+    }
+    poll
+  }
+}
@@ -2,6 +2,7 @@
 
 mod byte_stream;
 mod fs_fetch_handler;
+mod inspector;
 
 use data_url::DataUrl;
 use deno_core::error::type_error;
@@ -65,6 +66,7 @@ pub use reqwest;
 pub use fs_fetch_handler::FsFetchHandler;
 
 use crate::byte_stream::MpscByteStream;
+use crate::inspector::InspectedRequest;
 
 #[derive(Clone)]
 pub struct Options {
@@ -239,7 +241,10 @@ where
       let file_fetch_handler = file_fetch_handler.clone();
       let (request, maybe_request_body, maybe_cancel_handle) =
         file_fetch_handler.fetch_file(state, url);
-      let request_rid = state.resource_table.add(FetchRequestResource(request));
+      let request_rid = state.resource_table.add(FetchRequestResource {
+        future: request,
+        inspected: None,
+      });
       let maybe_request_body_rid =
         maybe_request_body.map(|r| state.resource_table.add(r));
       let maybe_cancel_handle_rid = maybe_cancel_handle
@@ -257,6 +262,14 @@ where
         return Err(type_error("Invalid URL"));
       }
 
+      let inspected = InspectedRequest::new(
+        state,
+        url.as_str(),
+        method.as_str(),
+        &headers,
+        data.as_deref(),
+      );
+
       let mut request = client.request(method.clone(), url);
 
       let request_body_rid = if has_body {
@@ -333,9 +346,10 @@ where
           .map(|res| res.map_err(|err| type_error(err.to_string())))
       };
 
-      let request_rid = state
-        .resource_table
-        .add(FetchRequestResource(Box::pin(fut)));
+      let request_rid = state.resource_table.add(FetchRequestResource {
+        future: Box::pin(fut),
+        inspected,
+      });
 
       let cancel_handle_rid =
         state.resource_table.add(FetchCancelHandle(cancel_handle));
@@ -357,9 +371,10 @@ where
 
       let fut = async move { Ok(Ok(Response::from(response))) };
 
-      let request_rid = state
-        .resource_table
-        .add(FetchRequestResource(Box::pin(fut)));
+      let request_rid = state.resource_table.add(FetchRequestResource {
+        future: Box::pin(fut),
+        inspected: None,
+      });
 
       (request_rid, None, None)
     }
@@ -399,14 +414,24 @@ pub async fn op_fetch_send(
     .resource_table
     .take::<FetchRequestResource>(rid)?;
 
-  let request = Rc::try_unwrap(request)
+  let FetchRequestResource { future, inspected } = Rc::try_unwrap(request)
     .ok()
     .expect("multiple op_fetch_send ongoing");
 
-  let res = match request.0.await {
+  let res = match future.await {
     Ok(Ok(res)) => res,
-    Ok(Err(err)) => return Err(type_error(err.to_string())),
-    Err(_) => return Err(type_error("request was cancelled")),
+    Ok(Err(err)) => {
+      if let Some(inspected) = &inspected {
+        inspected.failed(&err.to_string());
+      }
+      return Err(type_error(err.to_string()));
+    }
+    Err(_) => {
+      if let Some(inspected) = &inspected {
+        inspected.failed("request was cancelled");
+      }
+      return Err(type_error("request was cancelled"));
+    }
   };
 
   //debug!("Fetch response {}", url);
@@ -418,10 +443,16 @@ pub async fn op_fetch_send(
   }
 
   let content_length = res.content_length();
+  if let Some(inspected) = &inspected {
+    inspected.response_received(&url, status, res.headers());
+  }
 
-  let stream: BytesStream = Box::pin(res.bytes_stream().map(|r| {
+  let mut stream: BytesStream = Box::pin(res.bytes_stream().map(|r| {
     r.map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))
   }));
+  if let Some(inspected) = inspected {
+    stream = inspected.body_stream(stream);
+  }
   let rid = state
     .borrow_mut()
     .resource_table
@@ -443,9 +474,10 @@ pub async fn op_fetch_send(
 
 type CancelableResponseResult = Result<Result<Response, AnyError>, Canceled>;
 
-struct FetchRequestResource(
-  Pin<Box<dyn Future<Output = CancelableResponseResult>>>,
-);
+struct FetchRequestResource {
+  future: Pin<Box<dyn Future<Output = CancelableResponseResult>>>,
+  inspected: Option<InspectedRequest>,
+}
 
 impl Resource for FetchRequestResource {
   fn name(&self) -> Cow<str> {
@@ -0,0 +1,91 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::network_headers;
+use deno_core::serde_json::Value;
+use deno_core::NetworkInspector;
+use hyper::HeaderMap;
+use hyper::StatusCode;
+use std::cell::Cell;
+use std::cell::RefCell;
+
+/// An incoming request whose response is reported to the `Network` domain of
+/// the inspector.
+pub struct InspectedStream {
+  network: NetworkInspector,
+  request_id: String,
+  url: String,
+  length: Cell<usize>,
+  /// `None` once the body got too large to be kept, or was reported.
+  body: RefCell<Option<Vec<u8>>>,
+}
+
+impl InspectedStream {
+  /// Reports the request if an inspector session enabled the `Network`
+  /// domain.
+  pub fn new(
+    network: &NetworkInspector,
+    url: &str,
+    method: &str,
+    headers: &HeaderMap,
+  ) -> Option<Self> {
+    if !network.is_enabled() {
+      return None;
+    }
+    let request_id = network.next_request_id();
+    network.request_will_be_sent(
+      &request_id,
+      url,
+      method,
+      to_network_headers(headers),
+      None,
+    );
+    Some(Self {
+      network: network.clone(),
+      request_id,
+      url: url.to_string(),
+      length: Cell::new(0),
+      body: RefCell::new(Some(Vec::new())),
+    })
+  }
+
+  pub fn response_received(&self, status: u16, headers: &HeaderMap) {
+    let status_text = StatusCode::from_u16(status)
+      .ok()
+      .and_then(|status| status.canonical_reason())
+      .unwrap_or("");
+    self.network.response_received(
+      &self.request_id,
+      &self.url,
+      status,
+      status_text,
+      to_network_headers(headers),
+    );
+  }
+
+  /// Records a chunk of the response body, which is sent uncompressed.
+  pub fn write(&self, chunk: &[u8]) {
+    let length = self.length.get() + chunk.len();
+    self.length.set(length);
+    let mut body = self.body.borrow_mut();
+    if length > NetworkInspector::MAX_RESPONSE_BODY_SIZE {
+      *body = None;
+    } else if let Some(body) = &mut *body {
+      body.extend_from_slice(chunk);
+    }
+  }
+
+  pub fn finished(&self) {
+    let body = self.body.borrow_mut().take();
+    self
+      .network
+      .loading_finished(&self.request_id, self.length.get(), body);
+  }
+}
+
+fn to_network_headers(headers: &HeaderMap) -> Value {
+  network_headers(
+    headers
+      .iter()
+      .map(|(name, value)| (name.as_str().as_bytes(), value.as_bytes())),
+  )
+}
@@ -30,6 +30,7 @@ use deno_core::CancelFuture;
 use deno_core::CancelHandle;
 use deno_core::CancelTryFuture;
 use deno_core::Extension;
+use deno_core::NetworkInspector;
 use deno_core::OpState;
 use deno_core::RcRef;
 use deno_core::Resource;
@@ -71,10 +72,12 @@ use tokio::io::AsyncWrite;
 use tokio::io::AsyncWriteExt;
 use tokio::task::spawn_local;
 
+use crate::inspector::InspectedStream;
 use crate::reader_stream::ExternallyAbortableReaderStream;
 use crate::reader_stream::ShutdownHandle;
 
 pub mod compressible;
+mod inspector;
 mod reader_stream;
 
 pub fn init() -> Extension {
@@ -169,6 +172,7 @@ impl HttpConnResource {
   // Accepts a new incoming HTTP request.
   async fn accept(
     self: &Rc<Self>,
+    network: Option<NetworkInspector>,
   ) -> Result<Option<(HttpStreamResource, String, String)>, AnyError> {
     let fut = async {
       let (request_tx, request_rx) = oneshot::channel();
@@ -193,8 +197,16 @@ impl HttpConnResource {
 
       let method = request.method().to_string();
       let url = req_url(&request, self.scheme, &self.addr);
-      let stream =
-        HttpStreamResource::new(self, request, response_tx, accept_encoding);
+      let inspected = network.and_then(|network| {
+        InspectedStream::new(&network, &url, &method, request.headers())
+      });
+      let stream = HttpStreamResource::new(
+        self,
+        request,
+        response_tx,
+        accept_encoding,
+        inspected,
+      );
       Some((stream, method, url))
     };
 
@@ -315,6 +327,7 @@ pub struct HttpStreamResource {
   accept_encoding: Encoding,
   cancel_handle: CancelHandle,
   size: SizeHint,
+  inspected: Option<InspectedStream>,
 }
 
 impl HttpStreamResource {
@@ -323,6 +336,7 @@ impl HttpStreamResource {
     request: Request<Body>,
     response_tx: oneshot::Sender<Response<Body>>,
     accept_encoding: Encoding,
+    inspected: Option<InspectedStream>,
   ) -> Self {
     let size = request.body().size_hint();
     Self {
@@ -332,6 +346,7 @@ impl HttpStreamResource {
       accept_encoding,
       size,
       cancel_handle: CancelHandle::new(),
+      inspected,
     }
   }
 }
@@ -479,8 +494,9 @@ async fn op_http_accept(
   rid: ResourceId,
 ) -> Result<Option<NextRequestResponse>, AnyError> {
   let conn = state.borrow().resource_table.get::<HttpConnResource>(rid)?;
+  let network = state.borrow().try_borrow::<NetworkInspector>().cloned();
 
-  match conn.accept().await {
+  match conn.accept(network).await {
     Ok(Some((stream, method, url))) => {
       let stream_rid =
         state.borrow_mut().resource_table.add_rc(Rc::new(stream));
@@ -623,6 +639,14 @@ async fn op_http_write_headers(
     );
   }
 
+  if let Some(inspected) = &stream.inspected {
+    inspected.response_received(status, hmap);
+    if let Some(data) = &data {
+      inspected.write(data);
+      inspected.finished();
+    }
+  }
+
   let (new_wr, body) = http_response(data, compressing, encoding)?;
   let body = builder.status(status).body(body)?;
 
@@ -808,6 +832,9 @@ async fn op_http_write_resource(
     if view.is_empty() {
       break;
     }
+    if let Some(inspected) = &http_stream.inspected {
+      inspected.write(&view);
+    }
 
     match &mut *wr {
       HttpResponseWriter::Body { writer, .. } => {
@@ -856,6 +883,9 @@ async fn op_http_write(
     HttpResponseWriter::Headers(_) => Err(http_error("no response headers")),
     HttpResponseWriter::Closed => Err(http_error("response already completed")),
     HttpResponseWriter::Body { writer, .. } => {
+      if let Some(inspected) = &stream.inspected {
+        inspected.write(&buf);
+      }
       let mut result = writer.write_all(&buf).await;
       if result.is_ok() {
         result = writer.flush().await;
@@ -874,6 +904,9 @@ async fn op_http_write(
       }
     }
     HttpResponseWriter::BodyUncompressed(body) => {
+      if let Some(inspected) = &stream.inspected {
+        inspected.write(&buf);
+      }
       let bytes = Bytes::from(buf);
       match body.sender().send_data(bytes).await {
         Ok(_) => Ok(()),
@@ -919,9 +952,15 @@ async fn op_http_shutdown(
           stream.conn.closed().await?;
         }
       }
+      if let Some(inspected) = &stream.inspected {
+        inspected.finished();
+      }
     }
     HttpResponseWriter::BodyUncompressed(body) => {
       body.shutdown();
+      if let Some(inspected) = &stream.inspected {
+        inspected.finished();
+      }
     }
     _ => {}
   };
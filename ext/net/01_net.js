@@ -351,9 +351,19 @@ class Datagram {
 }
 
 function listen(args) {
+  return listenWith(ops.op_net_listen_tcp, args);
+}
+
+function createListen(tcpOpFn) {
+  return function listen(args) {
+    return listenWith(tcpOpFn, args);
+  };
+}
+
+function listenWith(tcpOpFn, args) {
   switch (args.transport ?? "tcp") {
     case "tcp": {
-      const { 0: rid, 1: addr } = ops.op_net_listen_tcp({
+      const { 0: rid, 1: addr } = tcpOpFn({
         hostname: args.hostname ?? "0.0.0.0",
         port: args.port,
       }, args.reusePort);
@@ -434,6 +444,7 @@ async function connect(args) {
 export {
   Conn,
   connect,
+  createListen,
   createListenDatagram,
   Datagram,
   listen,
@@ -53,6 +53,7 @@ pub fn init<P: NetPermissions + 'static>() -> Vec<OpDecl> {
     #[cfg(unix)]
     crate::ops_unix::op_net_connect_unix::decl::<P>(),
     op_net_listen_tcp::decl::<P>(),
+    op_node_unstable_net_listen_tcp::decl::<P>(),
     op_net_listen_udp::decl::<P>(),
     op_node_unstable_net_listen_udp::decl::<P>(),
     #[cfg(unix)]
@@ -258,6 +259,66 @@ where
   if reuse_port {
     super::check_unstable(state, "Deno.listen({ reusePort: true })");
   }
+  // `Deno.listen()` only shares ports on Linux, where the kernel balances the
+  // connections.
+  let reuse_port = reuse_port && cfg!(target_os = "linux");
+  net_listen_tcp::<NP>(state, addr, reuse_port)
+}
+
+/// Like `op_net_listen_tcp`, but allows `reuse_port` without `--unstable`
+/// for the servers of `node:cluster` workers, and shares the port on the
+/// other Unix systems as well.
+#[op]
+fn op_node_unstable_net_listen_tcp<NP>(
+  state: &mut OpState,
+  addr: IpAddr,
+  reuse_port: bool,
+) -> Result<(ResourceId, IpAddr), AnyError>
+where
+  NP: NetPermissions + 'static,
+{
+  net_listen_tcp::<NP>(state, addr, reuse_port)
+}
+
+/// Lets other sockets bind to the same address and port. Linux and FreeBSD
+/// distribute the incoming connections among the sockets, the other BSDs and
+/// macOS only share the port. There is no equivalent on Windows, so it's
+/// ignored there.
+fn set_reuse_port(socket: &Socket) -> Result<(), AnyError> {
+  #[cfg(target_os = "freebsd")]
+  {
+    use std::os::unix::io::AsRawFd;
+    // SO_REUSEPORT doesn't balance the connections on FreeBSD.
+    let value: libc::c_int = 1;
+    // SAFETY: libc call, the option value outlives the call
+    let result = unsafe {
+      libc::setsockopt(
+        socket.as_raw_fd(),
+        libc::SOL_SOCKET,
+        libc::SO_REUSEPORT_LB,
+        &value as *const libc::c_int as *const libc::c_void,
+        std::mem::size_of_val(&value) as libc::socklen_t,
+      )
+    };
+    if result != 0 {
+      return Err(std::io::Error::last_os_error().into());
+    }
+  }
+  #[cfg(all(unix, not(target_os = "freebsd")))]
+  socket.set_reuse_port(true)?;
+  #[cfg(windows)]
+  let _ = socket;
+  Ok(())
+}
+
+fn net_listen_tcp<NP>(
+  state: &mut OpState,
+  addr: IpAddr,
+  reuse_port: bool,
+) -> Result<(ResourceId, IpAddr), AnyError>
+where
+  NP: NetPermissions + 'static,
+{
   state
     .borrow_mut::<NP>()
     .check_net(&(&addr.hostname, Some(addr.port)), "Deno.listen()")?;
@@ -273,8 +334,7 @@ where
   #[cfg(not(windows))]
   socket.set_reuse_address(true)?;
   if reuse_port {
-    #[cfg(target_os = "linux")]
-    socket.set_reuse_port(true)?;
+    set_reuse_port(&socket)?;
   }
   let socket_addr = socket2::SockAddr::from(addr);
   socket.bind(&socket_addr)?;
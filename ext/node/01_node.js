@@ -111,6 +111,8 @@ function initialize(nodeModules, nodeGlobalThisName) {
   });
   // FIXME(bartlomieju): not nice to depend on `Deno` namespace here
   internals.__bootstrapNodeProcess(Deno.args, Deno.version);
+  internals.__initWorkerThreads();
+  internals.__initCluster();
 }
 
 internals.node = {
@@ -544,6 +544,15 @@ Module._resolveFilename = function (
   isMain,
   options,
 ) {
+  // The hooks of `module.register()` resolve built-in modules as well.
+  const hooked = ops.op_require_hooks_resolve(
+    request,
+    parent?.filename || null,
+  );
+  if (hooked !== null) {
+    return hooked;
+  }
+
   if (
     StringPrototypeStartsWith(request, "node:") ||
     nativeModuleCanBeRequiredByUsers(request)
@@ -758,8 +767,15 @@ Module.prototype._compile = function (content, filename) {
   return result;
 };
 
+// Reads the source of a module, unless the hooks of `module.register()` load
+// it.
+function readSource(filename, format) {
+  return ops.op_require_hooks_load(filename, format) ??
+    ops.op_require_read_file(filename);
+}
+
 Module._extensions[".js"] = function (module, filename) {
-  const content = ops.op_require_read_file(filename);
+  const content = readSource(filename, "commonjs");
 
   if (StringPrototypeEndsWith(filename, ".js")) {
     const pkg = ops.op_require_read_closest_package_json(filename);
@@ -788,7 +804,7 @@ function stripBOM(content) {
 
 // Native extension for .json
 Module._extensions[".json"] = function (module, filename) {
-  const content = ops.op_require_read_file(filename);
+  const content = readSource(filename, "json");
 
   try {
     module.exports = JSONParse(stripBOM(content));
@@ -876,6 +892,24 @@ function createRequire(filenameOrUrl) {
 
 Module.createRequire = createRequire;
 
+// https://nodejs.org/api/module.html#moduleregisterspecifier-parenturl-options
+function register(specifier, parentURL = undefined, options) {
+  if (
+    parentURL !== null && typeof parentURL === "object" &&
+    !(parentURL instanceof URL)
+  ) {
+    options = parentURL;
+    parentURL = options.parentURL;
+  }
+  ops.op_node_module_register(
+    String(specifier),
+    parentURL === undefined ? "data:" : String(parentURL),
+    options?.data,
+  );
+}
+
+Module.register = register;
+
 Module._initPaths = function () {
   const paths = ops.op_require_init_paths();
   modulePaths = paths;
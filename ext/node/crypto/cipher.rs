@@ -0,0 +1,303 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::typenum::U12;
+use aes::cipher::typenum::U16;
+use aes::cipher::BlockDecryptMut;
+use aes::cipher::BlockEncryptMut;
+use aes::cipher::KeyIvInit;
+use aes::cipher::StreamCipher;
+use aes_gcm::aead::AeadCore;
+use aes_gcm::aead::AeadInPlace;
+use aes_gcm::aead::KeyInit;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::Resource;
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+const BLOCK_SIZE: usize = 16;
+
+type Aes192Gcm = aes_gcm::AesGcm<aes::Aes192, U12>;
+
+/// A block cipher mode which processes one 16 byte block at a time.
+trait BlockMode {
+  fn process(&mut self, block: &mut [u8]);
+}
+
+struct Encrypt<C>(C);
+
+impl<C: BlockEncryptMut<BlockSize = U16>> BlockMode for Encrypt<C> {
+  fn process(&mut self, block: &mut [u8]) {
+    self
+      .0
+      .encrypt_block_mut(GenericArray::from_mut_slice(block));
+  }
+}
+
+struct Decrypt<C>(C);
+
+impl<C: BlockDecryptMut<BlockSize = U16>> BlockMode for Decrypt<C> {
+  fn process(&mut self, block: &mut [u8]) {
+    self
+      .0
+      .decrypt_block_mut(GenericArray::from_mut_slice(block));
+  }
+}
+
+trait Keystream {
+  fn apply(&mut self, data: &mut [u8]);
+}
+
+impl<C: StreamCipher> Keystream for C {
+  fn apply(&mut self, data: &mut [u8]) {
+    self.apply_keystream(data);
+  }
+}
+
+/// An AEAD cipher with 96 bit nonces and 128 bit tags.
+trait AeadCipher {
+  fn seal(&self, nonce: &[u8], aad: &[u8], data: &mut Vec<u8>) -> Vec<u8>;
+  fn open(
+    &self,
+    nonce: &[u8],
+    aad: &[u8],
+    data: &mut Vec<u8>,
+    tag: &[u8],
+  ) -> Result<(), AnyError>;
+}
+
+impl<C> AeadCipher for C
+where
+  C: AeadInPlace + AeadCore<NonceSize = U12, TagSize = U16>,
+{
+  fn seal(&self, nonce: &[u8], aad: &[u8], data: &mut Vec<u8>) -> Vec<u8> {
+    let tag = self
+      .encrypt_in_place_detached(GenericArray::from_slice(nonce), aad, data)
+      .expect("the plaintext is within the limits of the cipher");
+    tag.to_vec()
+  }
+
+  fn open(
+    &self,
+    nonce: &[u8],
+    aad: &[u8],
+    data: &mut Vec<u8>,
+    tag: &[u8],
+  ) -> Result<(), AnyError> {
+    if tag.len() != BLOCK_SIZE {
+      return Err(type_error("Invalid authentication tag length"));
+    }
+    self
+      .decrypt_in_place_detached(
+        GenericArray::from_slice(nonce),
+        aad,
+        data,
+        GenericArray::from_slice(tag),
+      )
+      .map_err(|_| {
+        type_error("Unsupported state or unable to authenticate data")
+      })
+  }
+}
+
+enum Mode {
+  Cbc(Box<dyn BlockMode>),
+  Ctr(Box<dyn Keystream>),
+  /// AEAD ciphers can only authenticate the whole message, so the data is
+  /// processed once the cipher is finalized.
+  Aead {
+    cipher: Box<dyn AeadCipher>,
+    nonce: Vec<u8>,
+    aad: Vec<u8>,
+  },
+}
+
+/// The state of a `Cipheriv` or `Decipheriv`.
+pub struct CipherContext {
+  mode: RefCell<Mode>,
+  decrypt: bool,
+  buffer: RefCell<Vec<u8>>,
+}
+
+impl Resource for CipherContext {
+  fn name(&self) -> Cow<str> {
+    "cryptoCipher".into()
+  }
+}
+
+pub struct CipherFinal {
+  pub output: Vec<u8>,
+  pub auth_tag: Option<Vec<u8>>,
+}
+
+fn invalid_key_length() -> AnyError {
+  type_error("Invalid key length")
+}
+
+fn invalid_iv() -> AnyError {
+  type_error("Invalid initialization vector")
+}
+
+macro_rules! block_mode {
+  ($decrypt:expr, $cipher:ty, $key:expr, $iv:expr) => {{
+    if $iv.len() != BLOCK_SIZE {
+      return Err(invalid_iv());
+    }
+    if $decrypt {
+      let cipher = cbc::Decryptor::<$cipher>::new_from_slices($key, $iv)
+        .map_err(|_| invalid_key_length())?;
+      Mode::Cbc(Box::new(Decrypt(cipher)))
+    } else {
+      let cipher = cbc::Encryptor::<$cipher>::new_from_slices($key, $iv)
+        .map_err(|_| invalid_key_length())?;
+      Mode::Cbc(Box::new(Encrypt(cipher)))
+    }
+  }};
+}
+
+macro_rules! ctr_mode {
+  ($cipher:ty, $key:expr, $iv:expr) => {{
+    if $iv.len() != BLOCK_SIZE {
+      return Err(invalid_iv());
+    }
+    let cipher = ctr::Ctr128BE::<$cipher>::new_from_slices($key, $iv)
+      .map_err(|_| invalid_key_length())?;
+    Mode::Ctr(Box::new(cipher))
+  }};
+}
+
+macro_rules! aead_mode {
+  ($cipher:ty, $key:expr, $iv:expr) => {{
+    if $iv.len() != 12 {
+      return Err(invalid_iv());
+    }
+    let cipher =
+      <$cipher>::new_from_slice($key).map_err(|_| invalid_key_length())?;
+    Mode::Aead {
+      cipher: Box::new(cipher),
+      nonce: $iv.to_vec(),
+      aad: Vec::new(),
+    }
+  }};
+}
+
+impl CipherContext {
+  pub fn new(
+    algorithm: &str,
+    key: &[u8],
+    iv: &[u8],
+    decrypt: bool,
+  ) -> Result<Self, AnyError> {
+    let mode = match algorithm {
+      "aes-128-cbc" | "aes128" => block_mode!(decrypt, aes::Aes128, key, iv),
+      "aes-192-cbc" | "aes192" => block_mode!(decrypt, aes::Aes192, key, iv),
+      "aes-256-cbc" | "aes256" => block_mode!(decrypt, aes::Aes256, key, iv),
+      "aes-128-ctr" => ctr_mode!(aes::Aes128, key, iv),
+      "aes-192-ctr" => ctr_mode!(aes::Aes192, key, iv),
+      "aes-256-ctr" => ctr_mode!(aes::Aes256, key, iv),
+      "aes-128-gcm" => aead_mode!(aes_gcm::Aes128Gcm, key, iv),
+      "aes-192-gcm" => aead_mode!(Aes192Gcm, key, iv),
+      "aes-256-gcm" => aead_mode!(aes_gcm::Aes256Gcm, key, iv),
+      "chacha20-poly1305" => {
+        aead_mode!(chacha20poly1305::ChaCha20Poly1305, key, iv)
+      }
+      _ => return Err(type_error(format!("Unknown cipher: {algorithm}"))),
+    };
+    Ok(Self {
+      mode: RefCell::new(mode),
+      decrypt,
+      buffer: RefCell::new(Vec::new()),
+    })
+  }
+
+  pub fn set_aad(&self, data: &[u8]) -> Result<(), AnyError> {
+    match &mut *self.mode.borrow_mut() {
+      Mode::Aead { aad, .. } => {
+        aad.extend_from_slice(data);
+        Ok(())
+      }
+      _ => Err(type_error("Cipher does not support additional data")),
+    }
+  }
+
+  pub fn update(&self, input: &[u8]) -> Vec<u8> {
+    let mut buffer = self.buffer.borrow_mut();
+    match &mut *self.mode.borrow_mut() {
+      Mode::Cbc(mode) => {
+        buffer.extend_from_slice(input);
+        let mut len = buffer.len() - buffer.len() % BLOCK_SIZE;
+        // The last block is kept until the end, as it may contain padding.
+        if self.decrypt && len == buffer.len() {
+          len = len.saturating_sub(BLOCK_SIZE);
+        }
+        let mut output: Vec<u8> = buffer.drain(..len).collect();
+        for block in output.chunks_mut(BLOCK_SIZE) {
+          mode.process(block);
+        }
+        output
+      }
+      Mode::Ctr(mode) => {
+        let mut output = input.to_vec();
+        mode.apply(&mut output);
+        output
+      }
+      Mode::Aead { .. } => {
+        buffer.extend_from_slice(input);
+        Vec::new()
+      }
+    }
+  }
+
+  pub fn finalize(
+    self,
+    auto_padding: bool,
+    auth_tag: Option<&[u8]>,
+  ) -> Result<CipherFinal, AnyError> {
+    let mut buffer = self.buffer.into_inner();
+    let mut auth_tag_out = None;
+    match self.mode.into_inner() {
+      Mode::Cbc(mut mode) => {
+        if !self.decrypt && auto_padding {
+          // PKCS#7 padding
+          let padding = BLOCK_SIZE - buffer.len() % BLOCK_SIZE;
+          buffer.resize(buffer.len() + padding, padding as u8);
+        }
+        if buffer.len() % BLOCK_SIZE != 0 {
+          return Err(type_error("wrong final block length"));
+        }
+        for block in buffer.chunks_mut(BLOCK_SIZE) {
+          mode.process(block);
+        }
+        if self.decrypt && auto_padding {
+          let padding = buffer.last().copied().unwrap_or(0) as usize;
+          if padding == 0
+            || padding > BLOCK_SIZE
+            || padding > buffer.len()
+            || !buffer[buffer.len() - padding..]
+              .iter()
+              .all(|byte| *byte as usize == padding)
+          {
+            return Err(type_error("bad decrypt"));
+          }
+          buffer.truncate(buffer.len() - padding);
+        }
+      }
+      Mode::Ctr(_) => {}
+      Mode::Aead { cipher, nonce, aad } => {
+        if self.decrypt {
+          let tag = auth_tag.ok_or_else(|| {
+            type_error("Unsupported state or unable to authenticate data")
+          })?;
+          cipher.open(&nonce, &aad, &mut buffer, tag)?;
+        } else {
+          auth_tag_out = Some(cipher.seal(&nonce, &aad, &mut buffer));
+        }
+      }
+    }
+    Ok(CipherFinal {
+      output: buffer,
+      auth_tag: auth_tag_out,
+    })
+  }
+}
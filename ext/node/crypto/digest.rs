@@ -115,3 +115,50 @@ impl Clone for Hash {
     }
   }
 }
+
+/// Evaluates `$body` with `$D` as the digest type named by `$algorithm`.
+macro_rules! with_digest {
+  ($algorithm:expr, $D:ident => $body:expr) => {
+    match $algorithm {
+      "md4" => {
+        type $D = md4::Md4;
+        $body
+      }
+      "md5" => {
+        type $D = md5::Md5;
+        $body
+      }
+      "ripemd160" | "rmd160" => {
+        type $D = ripemd::Ripemd160;
+        $body
+      }
+      "sha1" => {
+        type $D = sha1::Sha1;
+        $body
+      }
+      "sha224" => {
+        type $D = sha2::Sha224;
+        $body
+      }
+      "sha256" => {
+        type $D = sha2::Sha256;
+        $body
+      }
+      "sha384" => {
+        type $D = sha2::Sha384;
+        $body
+      }
+      "sha512" => {
+        type $D = sha2::Sha512;
+        $body
+      }
+      algorithm => {
+        return Err(deno_core::error::type_error(format!(
+          "Invalid digest: {algorithm}"
+        )))
+      }
+    }
+  };
+}
+
+pub(crate) use with_digest;
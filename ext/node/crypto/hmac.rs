@@ -0,0 +1,55 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::Resource;
+use hmac::Mac;
+use hmac::SimpleHmac;
+use std::borrow::Cow;
+use std::cell::RefCell;
+
+use super::digest::with_digest;
+
+trait DynMac {
+  fn update(&mut self, data: &[u8]);
+  fn finalize(self: Box<Self>) -> Vec<u8>;
+}
+
+impl<M: Mac> DynMac for M {
+  fn update(&mut self, data: &[u8]) {
+    Mac::update(self, data);
+  }
+
+  fn finalize(self: Box<Self>) -> Vec<u8> {
+    Mac::finalize(*self).into_bytes().to_vec()
+  }
+}
+
+pub struct Context {
+  mac: RefCell<Box<dyn DynMac>>,
+}
+
+impl Context {
+  pub fn new(algorithm: &str, key: &[u8]) -> Result<Self, AnyError> {
+    let mac: Box<dyn DynMac> = with_digest!(algorithm, D => Box::new(
+      <SimpleHmac<D> as Mac>::new_from_slice(key)
+        .map_err(|_| type_error("Invalid key length"))?,
+    ));
+    Ok(Self {
+      mac: RefCell::new(mac),
+    })
+  }
+
+  pub fn update(&self, data: &[u8]) {
+    self.mac.borrow_mut().update(data);
+  }
+
+  pub fn digest(self) -> Vec<u8> {
+    self.mac.into_inner().finalize()
+  }
+}
+
+impl Resource for Context {
+  fn name(&self) -> Cow<str> {
+    "cryptoHmac".into()
+  }
+}
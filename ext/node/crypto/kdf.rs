@@ -0,0 +1,76 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+use deno_core::error::range_error;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use hkdf::SimpleHkdf;
+use hmac::SimpleHmac;
+use serde::Deserialize;
+
+use super::digest::with_digest;
+
+pub fn pbkdf2(
+  algorithm: &str,
+  password: &[u8],
+  salt: &[u8],
+  iterations: u32,
+  key_length: usize,
+) -> Result<Vec<u8>, AnyError> {
+  let mut output = vec![0; key_length];
+  with_digest!(algorithm, D => pbkdf2::pbkdf2::<SimpleHmac<D>>(
+    password,
+    salt,
+    iterations,
+    &mut output,
+  ));
+  Ok(output)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScryptParams {
+  cost: u64,
+  block_size: u32,
+  parallelization: u32,
+  max_memory: u64,
+}
+
+pub fn scrypt(
+  password: &[u8],
+  salt: &[u8],
+  key_length: usize,
+  params: ScryptParams,
+) -> Result<Vec<u8>, AnyError> {
+  // the cost must be a power of two, greater than one
+  if params.cost < 2 || !params.cost.is_power_of_two() {
+    return Err(type_error("Invalid scrypt parameter"));
+  }
+  // the memory used by the work buffers, computed like OpenSSL does
+  let block_len = 128 * params.block_size as u128;
+  let memory =
+    block_len * (params.cost as u128 + 2 + params.parallelization as u128);
+  if memory > params.max_memory as u128 {
+    return Err(range_error("Invalid scrypt parameter"));
+  }
+  let log_n = params.cost.trailing_zeros() as u8;
+  let params =
+    scrypt::Params::new(log_n, params.block_size, params.parallelization)
+      .map_err(|_| type_error("Invalid scrypt parameter"))?;
+  let mut output = vec![0; key_length];
+  scrypt::scrypt(password, salt, &params, &mut output)
+    .map_err(|_| type_error("Invalid key length"))?;
+  Ok(output)
+}
+
+pub fn hkdf(
+  algorithm: &str,
+  key: &[u8],
+  salt: &[u8],
+  info: &[u8],
+  key_length: usize,
+) -> Result<Vec<u8>, AnyError> {
+  let mut output = vec![0; key_length];
+  with_digest!(algorithm, D => SimpleHkdf::<D>::new(Some(salt), key)
+    .expand(info, &mut output)
+    .map_err(|_| type_error("Invalid key length"))?);
+  Ok(output)
+}
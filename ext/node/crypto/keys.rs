@@ -0,0 +1,747 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::ZeroCopyBuf;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use rand::rngs::OsRng;
+use ring::signature::EcdsaKeyPair;
+use ring::signature::EcdsaSigningAlgorithm;
+use ring::signature::EcdsaVerificationAlgorithm;
+use rsa::pkcs1::der::Decode;
+use rsa::pkcs1::der::Encode;
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs1::EncodeRsaPrivateKey;
+use rsa::pkcs1::EncodeRsaPublicKey;
+use rsa::pkcs1::UIntRef;
+use rsa::pkcs8::der::pem;
+use rsa::pkcs8::der::pem::LineEnding;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::pkcs8::EncodePrivateKey;
+use rsa::pkcs8::EncodePublicKey;
+use rsa::pkcs8::PrivateKeyInfo;
+use rsa::BigUint;
+use rsa::PublicKeyParts;
+use rsa::RsaPrivateKey;
+use rsa::RsaPublicKey;
+use serde::Deserialize;
+use serde::Serialize;
+use signature::RandomizedSigner;
+use signature::Signer;
+use signature::Verifier;
+
+const URL_SAFE_FORGIVING: base64::Config =
+  base64::URL_SAFE_NO_PAD.decode_allow_trailing_bits(true);
+
+/// Padding of RSA signatures, as in `crypto.constants`.
+const RSA_PKCS1_PSS_PADDING: u32 = 6;
+const RSA_PSS_SALTLEN_DIGEST: i32 = -1;
+
+/// Key material handed over by `node:crypto`. Private keys are kept as
+/// PKCS#8 and public keys as SPKI documents by `KeyObject`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyInput {
+  Pem(ZeroCopyBuf),
+  Pkcs1(ZeroCopyBuf),
+  Pkcs8(ZeroCopyBuf),
+  Sec1(ZeroCopyBuf),
+  Spki(ZeroCopyBuf),
+  Jwk(Jwk),
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct Jwk {
+  kty: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  crv: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  n: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  e: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  d: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  p: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  q: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  dp: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  dq: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  qi: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  x: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  y: Option<String>,
+}
+
+pub enum AsymmetricKey {
+  RsaPrivate(RsaPrivateKey),
+  RsaPublic(RsaPublicKey),
+  P256Private(p256::SecretKey),
+  P256Public(p256::PublicKey),
+  P384Private(p384::SecretKey),
+  P384Public(p384::PublicKey),
+}
+
+/// What a `KeyObject` knows about an asymmetric key.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyInfo {
+  #[serde(rename = "type")]
+  kind: &'static str,
+  asymmetric_key_type: &'static str,
+  /// PKCS#8 for private keys, SPKI for public keys.
+  der: ZeroCopyBuf,
+  modulus_length: Option<usize>,
+  public_exponent: Option<u64>,
+  named_curve: Option<&'static str>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+  format: String,
+  #[serde(rename = "type")]
+  kind: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum ExportedKey {
+  Pem(String),
+  Der(ZeroCopyBuf),
+  Jwk(Jwk),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateOptions {
+  modulus_length: Option<usize>,
+  public_exponent: Option<u64>,
+  named_curve: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SignOptions {
+  padding: Option<u32>,
+  salt_length: Option<i32>,
+  dsa_encoding: Option<String>,
+}
+
+fn invalid_key() -> AnyError {
+  type_error("Invalid key data")
+}
+
+fn unsupported_key_type() -> AnyError {
+  type_error("Unsupported key type")
+}
+
+fn b64_decode(value: &Option<String>) -> Result<Vec<u8>, AnyError> {
+  let value = value.as_ref().ok_or_else(invalid_key)?;
+  base64::decode_config(value, URL_SAFE_FORGIVING).map_err(|_| invalid_key())
+}
+
+fn b64_encode(bytes: &[u8]) -> String {
+  base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
+
+fn uint_to_b64(value: UIntRef) -> String {
+  b64_encode(value.as_bytes())
+}
+
+macro_rules! ec_from_jwk {
+  ($curve:ident, $size:expr, $jwk:expr) => {{
+    let x = b64_decode(&$jwk.x)?;
+    let y = b64_decode(&$jwk.y)?;
+    if x.len() != $size || y.len() != $size {
+      return Err(invalid_key());
+    }
+    match &$jwk.d {
+      Some(_) => {
+        let d = b64_decode(&$jwk.d)?;
+        let key =
+          $curve::SecretKey::from_be_bytes(&d).map_err(|_| invalid_key())?;
+        let point = key.public_key().to_encoded_point(false);
+        if point.x().map(|v| v.as_slice()) != Some(x.as_slice())
+          || point.y().map(|v| v.as_slice()) != Some(y.as_slice())
+        {
+          return Err(invalid_key());
+        }
+        key.into()
+      }
+      None => {
+        let point = $curve::EncodedPoint::from_affine_coordinates(
+          $curve::FieldBytes::from_slice(&x),
+          $curve::FieldBytes::from_slice(&y),
+          false,
+        );
+        $curve::PublicKey::from_sec1_bytes(point.as_bytes())
+          .map_err(|_| invalid_key())?
+          .into()
+      }
+    }
+  }};
+}
+
+impl From<p256::SecretKey> for AsymmetricKey {
+  fn from(key: p256::SecretKey) -> Self {
+    Self::P256Private(key)
+  }
+}
+
+impl From<p256::PublicKey> for AsymmetricKey {
+  fn from(key: p256::PublicKey) -> Self {
+    Self::P256Public(key)
+  }
+}
+
+impl From<p384::SecretKey> for AsymmetricKey {
+  fn from(key: p384::SecretKey) -> Self {
+    Self::P384Private(key)
+  }
+}
+
+impl From<p384::PublicKey> for AsymmetricKey {
+  fn from(key: p384::PublicKey) -> Self {
+    Self::P384Public(key)
+  }
+}
+
+impl AsymmetricKey {
+  pub fn import(input: KeyInput) -> Result<Self, AnyError> {
+    match input {
+      KeyInput::Pem(data) => {
+        let (label, der) =
+          pem::decode_vec(&data).map_err(|_| type_error("Invalid PEM"))?;
+        match label {
+          "PRIVATE KEY" => Self::from_pkcs8(&der),
+          "RSA PRIVATE KEY" => Self::from_pkcs1(&der),
+          "EC PRIVATE KEY" => Self::from_sec1(&der),
+          "PUBLIC KEY" => Self::from_spki(&der),
+          "RSA PUBLIC KEY" => Self::from_pkcs1(&der),
+          "ENCRYPTED PRIVATE KEY" => {
+            Err(type_error("Encrypted private keys are not supported"))
+          }
+          _ => Err(type_error(format!("Unsupported PEM label: {label}"))),
+        }
+      }
+      KeyInput::Pkcs1(der) => Self::from_pkcs1(&der),
+      KeyInput::Pkcs8(der) => Self::from_pkcs8(&der),
+      KeyInput::Sec1(der) => Self::from_sec1(&der),
+      KeyInput::Spki(der) => Self::from_spki(&der),
+      KeyInput::Jwk(jwk) => Self::from_jwk(&jwk),
+    }
+  }
+
+  fn from_pkcs1(der: &[u8]) -> Result<Self, AnyError> {
+    if let Ok(key) = RsaPrivateKey::from_pkcs1_der(der) {
+      return Ok(Self::RsaPrivate(key));
+    }
+    let key = RsaPublicKey::from_pkcs1_der(der).map_err(|_| invalid_key())?;
+    Ok(Self::RsaPublic(key))
+  }
+
+  fn from_pkcs8(der: &[u8]) -> Result<Self, AnyError> {
+    if let Ok(key) = RsaPrivateKey::from_pkcs8_der(der) {
+      Ok(Self::RsaPrivate(key))
+    } else if let Ok(key) = p256::SecretKey::from_pkcs8_der(der) {
+      Ok(key.into())
+    } else if let Ok(key) = p384::SecretKey::from_pkcs8_der(der) {
+      Ok(key.into())
+    } else {
+      Err(unsupported_key_type())
+    }
+  }
+
+  fn from_sec1(der: &[u8]) -> Result<Self, AnyError> {
+    if let Ok(key) = p256::SecretKey::from_sec1_der(der) {
+      Ok(key.into())
+    } else if let Ok(key) = p384::SecretKey::from_sec1_der(der) {
+      Ok(key.into())
+    } else {
+      Err(unsupported_key_type())
+    }
+  }
+
+  fn from_spki(der: &[u8]) -> Result<Self, AnyError> {
+    if let Ok(key) = RsaPublicKey::from_public_key_der(der) {
+      Ok(Self::RsaPublic(key))
+    } else if let Ok(key) = p256::PublicKey::from_public_key_der(der) {
+      Ok(key.into())
+    } else if let Ok(key) = p384::PublicKey::from_public_key_der(der) {
+      Ok(key.into())
+    } else {
+      Err(unsupported_key_type())
+    }
+  }
+
+  fn from_jwk(jwk: &Jwk) -> Result<Self, AnyError> {
+    match (jwk.kty.as_str(), jwk.crv.as_deref()) {
+      ("RSA", _) => {
+        let n = b64_decode(&jwk.n)?;
+        let e = b64_decode(&jwk.e)?;
+        if jwk.d.is_none() {
+          let public_key = rsa::pkcs1::RsaPublicKey {
+            modulus: UIntRef::new(&n)?,
+            public_exponent: UIntRef::new(&e)?,
+          };
+          return Self::from_pkcs1(&public_key.to_vec()?);
+        }
+        let d = b64_decode(&jwk.d)?;
+        let p = b64_decode(&jwk.p)?;
+        let q = b64_decode(&jwk.q)?;
+        let dp = b64_decode(&jwk.dp)?;
+        let dq = b64_decode(&jwk.dq)?;
+        let qi = b64_decode(&jwk.qi)?;
+        let private_key = rsa::pkcs1::RsaPrivateKey {
+          modulus: UIntRef::new(&n)?,
+          public_exponent: UIntRef::new(&e)?,
+          private_exponent: UIntRef::new(&d)?,
+          prime1: UIntRef::new(&p)?,
+          prime2: UIntRef::new(&q)?,
+          exponent1: UIntRef::new(&dp)?,
+          exponent2: UIntRef::new(&dq)?,
+          coefficient: UIntRef::new(&qi)?,
+          other_prime_infos: None,
+        };
+        Self::from_pkcs1(&private_key.to_vec()?)
+      }
+      ("EC", Some("P-256")) => Ok(ec_from_jwk!(p256, 32, jwk)),
+      ("EC", Some("P-384")) => Ok(ec_from_jwk!(p384, 48, jwk)),
+      _ => Err(unsupported_key_type()),
+    }
+  }
+
+  pub fn is_private(&self) -> bool {
+    matches!(
+      self,
+      Self::RsaPrivate(_) | Self::P256Private(_) | Self::P384Private(_)
+    )
+  }
+
+  pub fn to_public(&self) -> Self {
+    match self {
+      Self::RsaPrivate(key) => Self::RsaPublic(key.to_public_key()),
+      Self::P256Private(key) => Self::P256Public(key.public_key()),
+      Self::P384Private(key) => Self::P384Public(key.public_key()),
+      Self::RsaPublic(key) => Self::RsaPublic(key.clone()),
+      Self::P256Public(key) => Self::P256Public(*key),
+      Self::P384Public(key) => Self::P384Public(*key),
+    }
+  }
+
+  fn to_pkcs8_der(&self) -> Result<Vec<u8>, AnyError> {
+    let document = match self {
+      Self::RsaPrivate(key) => key.to_pkcs8_der()?,
+      Self::P256Private(key) => key.to_pkcs8_der()?,
+      Self::P384Private(key) => key.to_pkcs8_der()?,
+      _ => return Err(type_error("Not a private key")),
+    };
+    Ok(document.as_bytes().to_vec())
+  }
+
+  fn to_spki_der(&self) -> Result<Vec<u8>, AnyError> {
+    let document = match self.to_public() {
+      Self::RsaPublic(key) => key.to_public_key_der()?,
+      Self::P256Public(key) => key.to_public_key_der()?,
+      Self::P384Public(key) => key.to_public_key_der()?,
+      _ => unreachable!(),
+    };
+    Ok(document.as_bytes().to_vec())
+  }
+
+  pub fn info(&self) -> Result<KeyInfo, AnyError> {
+    let (kind, der) = if self.is_private() {
+      ("private", self.to_pkcs8_der()?)
+    } else {
+      ("public", self.to_spki_der()?)
+    };
+    let mut info = KeyInfo {
+      kind,
+      asymmetric_key_type: "ec",
+      der: der.into(),
+      modulus_length: None,
+      public_exponent: None,
+      named_curve: None,
+    };
+    match self.to_public() {
+      Self::RsaPublic(key) => {
+        info.asymmetric_key_type = "rsa";
+        info.modulus_length = Some(key.n().bits());
+        info.public_exponent =
+          key.e().to_bytes_be().iter().try_fold(0u64, |acc, byte| {
+            acc.checked_mul(256).map(|acc| acc + *byte as u64)
+          });
+      }
+      Self::P256Public(_) => info.named_curve = Some("prime256v1"),
+      Self::P384Public(_) => info.named_curve = Some("secp384r1"),
+      _ => unreachable!(),
+    }
+    Ok(info)
+  }
+
+  pub fn export(
+    &self,
+    options: ExportOptions,
+  ) -> Result<ExportedKey, AnyError> {
+    if options.format == "jwk" {
+      return self.to_jwk().map(ExportedKey::Jwk);
+    }
+    let kind = options.kind.as_deref().unwrap_or(if self.is_private() {
+      "pkcs8"
+    } else {
+      "spki"
+    });
+    let (label, der) = match (kind, self) {
+      ("pkcs8", _) => ("PRIVATE KEY", self.to_pkcs8_der()?),
+      ("spki", _) => ("PUBLIC KEY", self.to_spki_der()?),
+      ("pkcs1", Self::RsaPrivate(key)) => {
+        ("RSA PRIVATE KEY", key.to_pkcs1_der()?.as_bytes().to_vec())
+      }
+      ("pkcs1", Self::RsaPublic(key)) => {
+        ("RSA PUBLIC KEY", key.to_pkcs1_der()?.as_bytes().to_vec())
+      }
+      ("sec1", Self::P256Private(_) | Self::P384Private(_)) => {
+        let pkcs8 = self.to_pkcs8_der()?;
+        let info = PrivateKeyInfo::from_der(&pkcs8)?;
+        ("EC PRIVATE KEY", info.private_key.to_vec())
+      }
+      _ => {
+        return Err(type_error(format!(
+          "The selected key encoding {kind} can't be used for this key"
+        )))
+      }
+    };
+    match options.format.as_str() {
+      "pem" => Ok(ExportedKey::Pem(pem::encode_string(
+        label,
+        LineEnding::LF,
+        &der,
+      )?)),
+      "der" => Ok(ExportedKey::Der(der.into())),
+      format => Err(type_error(format!("Invalid key format: {format}"))),
+    }
+  }
+
+  fn to_jwk(&self) -> Result<Jwk, AnyError> {
+    let jwk = match self {
+      Self::RsaPrivate(key) => {
+        let der = key.to_pkcs1_der()?;
+        let key = rsa::pkcs1::RsaPrivateKey::from_der(der.as_bytes())?;
+        Jwk {
+          kty: "RSA".to_string(),
+          n: Some(uint_to_b64(key.modulus)),
+          e: Some(uint_to_b64(key.public_exponent)),
+          d: Some(uint_to_b64(key.private_exponent)),
+          p: Some(uint_to_b64(key.prime1)),
+          q: Some(uint_to_b64(key.prime2)),
+          dp: Some(uint_to_b64(key.exponent1)),
+          dq: Some(uint_to_b64(key.exponent2)),
+          qi: Some(uint_to_b64(key.coefficient)),
+          ..Default::default()
+        }
+      }
+      Self::RsaPublic(key) => Jwk {
+        kty: "RSA".to_string(),
+        n: Some(b64_encode(&key.n().to_bytes_be())),
+        e: Some(b64_encode(&key.e().to_bytes_be())),
+        ..Default::default()
+      },
+      Self::P256Private(key) => Jwk {
+        d: Some(b64_encode(&key.to_be_bytes())),
+        ..ec_public_jwk(
+          "P-256",
+          key.public_key().to_encoded_point(false).as_bytes(),
+        )?
+      },
+      Self::P256Public(key) => {
+        ec_public_jwk("P-256", key.to_encoded_point(false).as_bytes())?
+      }
+      Self::P384Private(key) => Jwk {
+        d: Some(b64_encode(&key.to_be_bytes())),
+        ..ec_public_jwk(
+          "P-384",
+          key.public_key().to_encoded_point(false).as_bytes(),
+        )?
+      },
+      Self::P384Public(key) => {
+        ec_public_jwk("P-384", key.to_encoded_point(false).as_bytes())?
+      }
+    };
+    Ok(jwk)
+  }
+
+  pub fn generate(
+    kind: &str,
+    options: GenerateOptions,
+  ) -> Result<Self, AnyError> {
+    match kind {
+      "rsa" => {
+        let bits = options
+          .modulus_length
+          .ok_or_else(|| type_error("Missing modulusLength"))?;
+        let exponent = BigUint::from(options.public_exponent.unwrap_or(65537));
+        let key = RsaPrivateKey::new_with_exp(&mut OsRng, bits, &exponent)?;
+        Ok(Self::RsaPrivate(key))
+      }
+      "ec" => match options.named_curve.as_deref() {
+        Some("prime256v1" | "P-256") => {
+          Ok(p256::SecretKey::random(&mut OsRng).into())
+        }
+        Some("secp384r1" | "P-384") => {
+          Ok(p384::SecretKey::random(&mut OsRng).into())
+        }
+        _ => Err(type_error("Invalid EC curve name")),
+      },
+      _ => Err(type_error(format!("Unsupported key type: {kind}"))),
+    }
+  }
+
+  /// The digest used by signatures when none is given.
+  pub fn default_digest(&self) -> &'static str {
+    match self {
+      Self::P384Private(_) | Self::P384Public(_) => "sha384",
+      _ => "sha256",
+    }
+  }
+
+  pub fn sign(
+    &self,
+    digest: &str,
+    data: &[u8],
+    options: SignOptions,
+  ) -> Result<Vec<u8>, AnyError> {
+    match self {
+      Self::RsaPrivate(key) => {
+        let key = key.clone();
+        if options.padding == Some(RSA_PKCS1_PSS_PADDING) {
+          use rsa::pss::SigningKey;
+          let digest_length = digest_length(digest)?;
+          // by default, the salt is as large as possible
+          let salt_length = match options.salt_length {
+            Some(RSA_PSS_SALTLEN_DIGEST) => digest_length,
+            Some(length) if length >= 0 => length as usize,
+            _ => key.size() - digest_length - 2,
+          };
+          Ok(match digest {
+            "sha256" => {
+              SigningKey::<sha2::Sha256>::new_with_salt_len(key, salt_length)
+                .sign_with_rng(OsRng, data)
+                .to_vec()
+            }
+            "sha384" => {
+              SigningKey::<sha2::Sha384>::new_with_salt_len(key, salt_length)
+                .sign_with_rng(OsRng, data)
+                .to_vec()
+            }
+            "sha512" => {
+              SigningKey::<sha2::Sha512>::new_with_salt_len(key, salt_length)
+                .sign_with_rng(OsRng, data)
+                .to_vec()
+            }
+            _ => return Err(invalid_digest(digest)),
+          })
+        } else {
+          use rsa::pkcs1v15::SigningKey;
+          Ok(match digest {
+            "sha224" => SigningKey::<sha2::Sha224>::new_with_prefix(key)
+              .sign(data)
+              .to_vec(),
+            "sha256" => SigningKey::<sha2::Sha256>::new_with_prefix(key)
+              .sign(data)
+              .to_vec(),
+            "sha384" => SigningKey::<sha2::Sha384>::new_with_prefix(key)
+              .sign(data)
+              .to_vec(),
+            "sha512" => SigningKey::<sha2::Sha512>::new_with_prefix(key)
+              .sign(data)
+              .to_vec(),
+            _ => return Err(invalid_digest(digest)),
+          })
+        }
+      }
+      Self::P256Private(key) => {
+        let algorithm = ecdsa_signing_algorithm(
+          digest == "sha256",
+          options.dsa_encoding.as_deref(),
+          &ring::signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+          &ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+        )
+        .ok_or_else(|| invalid_digest(digest))?;
+        let point = key.public_key().to_encoded_point(false);
+        ecdsa_sign(algorithm, &key.to_be_bytes(), point.as_bytes(), data)
+      }
+      Self::P384Private(key) => {
+        let algorithm = ecdsa_signing_algorithm(
+          digest == "sha384",
+          options.dsa_encoding.as_deref(),
+          &ring::signature::ECDSA_P384_SHA384_ASN1_SIGNING,
+          &ring::signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+        )
+        .ok_or_else(|| invalid_digest(digest))?;
+        let point = key.public_key().to_encoded_point(false);
+        ecdsa_sign(algorithm, &key.to_be_bytes(), point.as_bytes(), data)
+      }
+      _ => Err(type_error("Signing requires a private key")),
+    }
+  }
+
+  pub fn verify(
+    &self,
+    digest: &str,
+    data: &[u8],
+    signature: &[u8],
+    options: SignOptions,
+  ) -> Result<bool, AnyError> {
+    match self.to_public() {
+      Self::RsaPublic(key) => {
+        if options.padding == Some(RSA_PKCS1_PSS_PADDING) {
+          use rsa::pss::Signature;
+          use rsa::pss::VerifyingKey;
+          let signature: Signature = signature.to_vec().into();
+          Ok(match digest {
+            "sha256" => VerifyingKey::<sha2::Sha256>::from(key)
+              .verify(data, &signature)
+              .is_ok(),
+            "sha384" => VerifyingKey::<sha2::Sha384>::from(key)
+              .verify(data, &signature)
+              .is_ok(),
+            "sha512" => VerifyingKey::<sha2::Sha512>::from(key)
+              .verify(data, &signature)
+              .is_ok(),
+            _ => return Err(invalid_digest(digest)),
+          })
+        } else {
+          use rsa::pkcs1v15::Signature;
+          use rsa::pkcs1v15::VerifyingKey;
+          let signature: Signature = signature.to_vec().into();
+          Ok(match digest {
+            "sha224" => VerifyingKey::<sha2::Sha224>::new_with_prefix(key)
+              .verify(data, &signature)
+              .is_ok(),
+            "sha256" => VerifyingKey::<sha2::Sha256>::new_with_prefix(key)
+              .verify(data, &signature)
+              .is_ok(),
+            "sha384" => VerifyingKey::<sha2::Sha384>::new_with_prefix(key)
+              .verify(data, &signature)
+              .is_ok(),
+            "sha512" => VerifyingKey::<sha2::Sha512>::new_with_prefix(key)
+              .verify(data, &signature)
+              .is_ok(),
+            _ => return Err(invalid_digest(digest)),
+          })
+        }
+      }
+      Self::P256Public(key) => {
+        let algorithm = ecdsa_verification_algorithm(
+          digest == "sha256",
+          options.dsa_encoding.as_deref(),
+          &ring::signature::ECDSA_P256_SHA256_ASN1,
+          &ring::signature::ECDSA_P256_SHA256_FIXED,
+        )
+        .ok_or_else(|| invalid_digest(digest))?;
+        let point = key.to_encoded_point(false);
+        Ok(
+          ring::signature::UnparsedPublicKey::new(algorithm, point.as_bytes())
+            .verify(data, signature)
+            .is_ok(),
+        )
+      }
+      Self::P384Public(key) => {
+        let algorithm = ecdsa_verification_algorithm(
+          digest == "sha384",
+          options.dsa_encoding.as_deref(),
+          &ring::signature::ECDSA_P384_SHA384_ASN1,
+          &ring::signature::ECDSA_P384_SHA384_FIXED,
+        )
+        .ok_or_else(|| invalid_digest(digest))?;
+        let point = key.to_encoded_point(false);
+        Ok(
+          ring::signature::UnparsedPublicKey::new(algorithm, point.as_bytes())
+            .verify(data, signature)
+            .is_ok(),
+        )
+      }
+      _ => unreachable!(),
+    }
+  }
+}
+
+fn ec_public_jwk(crv: &str, point: &[u8]) -> Result<Jwk, AnyError> {
+  // uncompressed points are 0x04 || x || y
+  let size = (point.len() - 1) / 2;
+  if point.first() != Some(&4) || point.len() != 1 + 2 * size {
+    return Err(invalid_key());
+  }
+  Ok(Jwk {
+    kty: "EC".to_string(),
+    crv: Some(crv.to_string()),
+    x: Some(b64_encode(&point[1..1 + size])),
+    y: Some(b64_encode(&point[1 + size..])),
+    ..Default::default()
+  })
+}
+
+fn invalid_digest(digest: &str) -> AnyError {
+  type_error(format!("Invalid digest for this key: {digest}"))
+}
+
+fn digest_length(digest: &str) -> Result<usize, AnyError> {
+  match digest {
+    "sha256" => Ok(32),
+    "sha384" => Ok(48),
+    "sha512" => Ok(64),
+    _ => Err(invalid_digest(digest)),
+  }
+}
+
+/// ECDSA is only supported with the digest matching the curve.
+fn ecdsa_signing_algorithm(
+  supported_digest: bool,
+  dsa_encoding: Option<&str>,
+  der: &'static EcdsaSigningAlgorithm,
+  ieee_p1363: &'static EcdsaSigningAlgorithm,
+) -> Option<&'static EcdsaSigningAlgorithm> {
+  match (supported_digest, dsa_encoding) {
+    (true, None | Some("der")) => Some(der),
+    (true, Some("ieee-p1363")) => Some(ieee_p1363),
+    _ => None,
+  }
+}
+
+fn ecdsa_verification_algorithm(
+  supported_digest: bool,
+  dsa_encoding: Option<&str>,
+  der: &'static EcdsaVerificationAlgorithm,
+  ieee_p1363: &'static EcdsaVerificationAlgorithm,
+) -> Option<&'static EcdsaVerificationAlgorithm> {
+  match (supported_digest, dsa_encoding) {
+    (true, None | Some("der")) => Some(der),
+    (true, Some("ieee-p1363")) => Some(ieee_p1363),
+    _ => None,
+  }
+}
+
+fn ecdsa_sign(
+  algorithm: &'static EcdsaSigningAlgorithm,
+  private_key: &[u8],
+  public_key: &[u8],
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  let key_pair = EcdsaKeyPair::from_private_key_and_public_key(
+    algorithm,
+    private_key,
+    public_key,
+  )
+  .map_err(|_| invalid_key())?;
+  let rng = ring::rand::SystemRandom::new();
+  let signature = key_pair
+    .sign(&rng, data)
+    .map_err(|_| type_error("Failed to sign"))?;
+  Ok(signature.as_ref().to_vec())
+}
@@ -6,6 +6,7 @@ use deno_core::OpState;
 use deno_core::ResourceId;
 use deno_core::StringOrBuffer;
 use deno_core::ZeroCopyBuf;
+use serde::Serialize;
 use std::rc::Rc;
 
 use rsa::padding::PaddingScheme;
@@ -15,7 +16,11 @@ use rsa::PublicKey;
 use rsa::RsaPrivateKey;
 use rsa::RsaPublicKey;
 
+mod cipher;
 mod digest;
+mod hmac;
+mod kdf;
+mod keys;
 
 #[op]
 pub fn op_node_create_hash(
@@ -126,3 +131,249 @@ pub fn op_node_public_encrypt(
     _ => Err(type_error("Unknown padding")),
   }
 }
+
+#[op]
+pub fn op_node_create_cipheriv(
+  state: &mut OpState,
+  algorithm: String,
+  key: &[u8],
+  iv: &[u8],
+) -> Result<ResourceId, AnyError> {
+  let context = cipher::CipherContext::new(&algorithm, key, iv, false)?;
+  Ok(state.resource_table.add(context))
+}
+
+#[op]
+pub fn op_node_create_decipheriv(
+  state: &mut OpState,
+  algorithm: String,
+  key: &[u8],
+  iv: &[u8],
+) -> Result<ResourceId, AnyError> {
+  let context = cipher::CipherContext::new(&algorithm, key, iv, true)?;
+  Ok(state.resource_table.add(context))
+}
+
+#[op]
+pub fn op_node_cipher_set_aad(
+  state: &mut OpState,
+  rid: ResourceId,
+  data: &[u8],
+) -> Result<(), AnyError> {
+  let context = state.resource_table.get::<cipher::CipherContext>(rid)?;
+  context.set_aad(data)
+}
+
+#[op]
+pub fn op_node_cipher_update(
+  state: &mut OpState,
+  rid: ResourceId,
+  data: &[u8],
+) -> Result<ZeroCopyBuf, AnyError> {
+  let context = state.resource_table.get::<cipher::CipherContext>(rid)?;
+  Ok(context.update(data).into())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CipherFinal {
+  output: ZeroCopyBuf,
+  auth_tag: Option<ZeroCopyBuf>,
+}
+
+#[op]
+pub fn op_node_cipher_final(
+  state: &mut OpState,
+  rid: ResourceId,
+  auto_padding: bool,
+  auth_tag: Option<ZeroCopyBuf>,
+) -> Result<CipherFinal, AnyError> {
+  let context = state.resource_table.take::<cipher::CipherContext>(rid)?;
+  let context = Rc::try_unwrap(context)
+    .map_err(|_| type_error("Cipher context is already in use"))?;
+  let result = context.finalize(auto_padding, auth_tag.as_deref())?;
+  Ok(CipherFinal {
+    output: result.output.into(),
+    auth_tag: result.auth_tag.map(Into::into),
+  })
+}
+
+#[op]
+pub fn op_node_create_hmac(
+  state: &mut OpState,
+  algorithm: String,
+  key: &[u8],
+) -> Result<ResourceId, AnyError> {
+  Ok(
+    state
+      .resource_table
+      .add(hmac::Context::new(&algorithm, key)?),
+  )
+}
+
+#[op]
+pub fn op_node_hmac_update(
+  state: &mut OpState,
+  rid: ResourceId,
+  data: &[u8],
+) -> Result<(), AnyError> {
+  let context = state.resource_table.get::<hmac::Context>(rid)?;
+  context.update(data);
+  Ok(())
+}
+
+#[op]
+pub fn op_node_hmac_digest(
+  state: &mut OpState,
+  rid: ResourceId,
+) -> Result<ZeroCopyBuf, AnyError> {
+  let context = state.resource_table.take::<hmac::Context>(rid)?;
+  let context = Rc::try_unwrap(context)
+    .map_err(|_| type_error("Hmac context is already in use"))?;
+  Ok(context.digest().into())
+}
+
+#[op]
+pub fn op_node_pbkdf2(
+  algorithm: String,
+  password: &[u8],
+  salt: &[u8],
+  iterations: u32,
+  key_length: usize,
+) -> Result<ZeroCopyBuf, AnyError> {
+  Ok(kdf::pbkdf2(&algorithm, password, salt, iterations, key_length)?.into())
+}
+
+#[op]
+pub async fn op_node_pbkdf2_async(
+  algorithm: String,
+  password: ZeroCopyBuf,
+  salt: ZeroCopyBuf,
+  iterations: u32,
+  key_length: usize,
+) -> Result<ZeroCopyBuf, AnyError> {
+  let fun =
+    move || kdf::pbkdf2(&algorithm, &password, &salt, iterations, key_length);
+  Ok(tokio::task::spawn_blocking(fun).await.unwrap()?.into())
+}
+
+#[op]
+pub fn op_node_scrypt(
+  password: &[u8],
+  salt: &[u8],
+  key_length: usize,
+  params: kdf::ScryptParams,
+) -> Result<ZeroCopyBuf, AnyError> {
+  Ok(kdf::scrypt(password, salt, key_length, params)?.into())
+}
+
+#[op]
+pub async fn op_node_scrypt_async(
+  password: ZeroCopyBuf,
+  salt: ZeroCopyBuf,
+  key_length: usize,
+  params: kdf::ScryptParams,
+) -> Result<ZeroCopyBuf, AnyError> {
+  let fun = move || kdf::scrypt(&password, &salt, key_length, params);
+  Ok(tokio::task::spawn_blocking(fun).await.unwrap()?.into())
+}
+
+#[op]
+pub fn op_node_hkdf(
+  algorithm: String,
+  key: &[u8],
+  salt: &[u8],
+  info: &[u8],
+  key_length: usize,
+) -> Result<ZeroCopyBuf, AnyError> {
+  Ok(kdf::hkdf(&algorithm, key, salt, info, key_length)?.into())
+}
+
+#[op]
+pub async fn op_node_hkdf_async(
+  algorithm: String,
+  key: ZeroCopyBuf,
+  salt: ZeroCopyBuf,
+  info: ZeroCopyBuf,
+  key_length: usize,
+) -> Result<ZeroCopyBuf, AnyError> {
+  let fun = move || kdf::hkdf(&algorithm, &key, &salt, &info, key_length);
+  Ok(tokio::task::spawn_blocking(fun).await.unwrap()?.into())
+}
+
+#[op]
+pub fn op_node_create_private_key(
+  key: keys::KeyInput,
+) -> Result<keys::KeyInfo, AnyError> {
+  let key = keys::AsymmetricKey::import(key)?;
+  if !key.is_private() {
+    return Err(type_error("Invalid private key"));
+  }
+  key.info()
+}
+
+/// Public keys can also be derived from private keys.
+#[op]
+pub fn op_node_create_public_key(
+  key: keys::KeyInput,
+) -> Result<keys::KeyInfo, AnyError> {
+  keys::AsymmetricKey::import(key)?.to_public().info()
+}
+
+#[op]
+pub fn op_node_export_key(
+  key: keys::KeyInput,
+  options: keys::ExportOptions,
+) -> Result<keys::ExportedKey, AnyError> {
+  keys::AsymmetricKey::import(key)?.export(options)
+}
+
+fn generate_key_pair(
+  kind: &str,
+  options: keys::GenerateOptions,
+) -> Result<(keys::KeyInfo, keys::KeyInfo), AnyError> {
+  let private_key = keys::AsymmetricKey::generate(kind, options)?;
+  Ok((private_key.info()?, private_key.to_public().info()?))
+}
+
+#[op]
+pub fn op_node_generate_key_pair(
+  kind: String,
+  options: keys::GenerateOptions,
+) -> Result<(keys::KeyInfo, keys::KeyInfo), AnyError> {
+  generate_key_pair(&kind, options)
+}
+
+#[op]
+pub async fn op_node_generate_key_pair_async(
+  kind: String,
+  options: keys::GenerateOptions,
+) -> Result<(keys::KeyInfo, keys::KeyInfo), AnyError> {
+  let fun = move || generate_key_pair(&kind, options);
+  tokio::task::spawn_blocking(fun).await.unwrap()
+}
+
+#[op]
+pub fn op_node_sign(
+  key: keys::KeyInput,
+  digest: Option<String>,
+  data: &[u8],
+  options: Option<keys::SignOptions>,
+) -> Result<ZeroCopyBuf, AnyError> {
+  let key = keys::AsymmetricKey::import(key)?;
+  let digest = digest.as_deref().unwrap_or_else(|| key.default_digest());
+  Ok(key.sign(digest, data, options.unwrap_or_default())?.into())
+}
+
+#[op]
+pub fn op_node_verify(
+  key: keys::KeyInput,
+  digest: Option<String>,
+  data: &[u8],
+  signature: &[u8],
+  options: Option<keys::SignOptions>,
+) -> Result<bool, AnyError> {
+  let key = keys::AsymmetricKey::import(key)?;
+  let digest = digest.as_deref().unwrap_or_else(|| key.default_digest());
+  key.verify(digest, data, signature, options.unwrap_or_default())
+}
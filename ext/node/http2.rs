@@ -0,0 +1,805 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::io;
+use std::io::BufReader;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+
+use bytes::Bytes;
+use deno_core::error::bad_resource;
+use deno_core::error::bad_resource_id;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::futures::future::poll_fn;
+use deno_core::op;
+use deno_core::AsyncRefCell;
+use deno_core::ByteString;
+use deno_core::CancelFuture;
+use deno_core::CancelHandle;
+use deno_core::OpState;
+use deno_core::RcRef;
+use deno_core::Resource;
+use deno_core::ResourceId;
+use deno_core::ZeroCopyBuf;
+use deno_net::io::TcpStreamResource;
+use deno_net::ops_tls::TlsStream;
+use deno_net::ops_tls::TlsStreamResource;
+use deno_net::DefaultTlsOptions;
+use deno_net::UnsafelyIgnoreCertificateErrors;
+use deno_tls::create_client_config;
+use deno_tls::load_certs;
+use deno_tls::load_private_keys;
+use deno_tls::rustls::ServerConfig;
+use deno_tls::rustls::ServerName;
+use h2::client::ResponseFuture;
+use h2::client::SendRequest;
+use h2::server::SendResponse;
+use h2::Ping;
+use h2::PingPong;
+use h2::Reason;
+use h2::RecvStream;
+use h2::SendStream;
+use http::header::HeaderName;
+use http::header::HeaderValue;
+use http::HeaderMap;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+use http::Uri;
+use http::Version;
+use serde::Deserialize;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+
+type Headers = Vec<(ByteString, ByteString)>;
+
+/// The connection a session runs on, taken over from a `Deno.Conn` or
+/// `Deno.TlsConn`.
+pub enum NetworkStream {
+  Tcp(TcpStream),
+  Tls(TlsStream),
+}
+
+impl NetworkStream {
+  fn take_from(
+    state: &mut OpState,
+    rid: ResourceId,
+  ) -> Result<NetworkStream, AnyError> {
+    if let Ok(resource_rc) = state.resource_table.take::<TcpStreamResource>(rid)
+    {
+      let resource = Rc::try_unwrap(resource_rc)
+        .map_err(|_| bad_resource("TCP stream is currently in use"))?;
+      let (read_half, write_half) = resource.into_inner();
+      return Ok(NetworkStream::Tcp(read_half.reunite(write_half)?));
+    }
+
+    if let Ok(resource_rc) = state.resource_table.take::<TlsStreamResource>(rid)
+    {
+      let resource = Rc::try_unwrap(resource_rc)
+        .map_err(|_| bad_resource("TLS stream is currently in use"))?;
+      let (read_half, write_half) = resource.into_inner();
+      return Ok(NetworkStream::Tls(read_half.reunite(write_half)));
+    }
+
+    Err(bad_resource_id())
+  }
+
+  /// Takes over a plain TCP connection, to encrypt it with TLS.
+  fn take_tcp_from(
+    state: &mut OpState,
+    rid: ResourceId,
+  ) -> Result<TcpStream, AnyError> {
+    match NetworkStream::take_from(state, rid)? {
+      NetworkStream::Tcp(tcp_stream) => Ok(tcp_stream),
+      NetworkStream::Tls(_) => {
+        Err(type_error("The connection is already encrypted"))
+      }
+    }
+  }
+}
+
+impl AsyncRead for NetworkStream {
+  fn poll_read(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<io::Result<()>> {
+    match self.get_mut() {
+      NetworkStream::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+      NetworkStream::Tls(stream) => Pin::new(stream).poll_read(cx, buf),
+    }
+  }
+}
+
+impl AsyncWrite for NetworkStream {
+  fn poll_write(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<io::Result<usize>> {
+    match self.get_mut() {
+      NetworkStream::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+      NetworkStream::Tls(stream) => Pin::new(stream).poll_write(cx, buf),
+    }
+  }
+
+  fn poll_flush(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    match self.get_mut() {
+      NetworkStream::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+      NetworkStream::Tls(stream) => Pin::new(stream).poll_flush(cx),
+    }
+  }
+
+  fn poll_shutdown(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    match self.get_mut() {
+      NetworkStream::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+      NetworkStream::Tls(stream) => Pin::new(stream).poll_shutdown(cx),
+    }
+  }
+}
+
+/// The subset of the settings of `node:http2` which the `h2` crate allows to
+/// configure.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Http2Settings {
+  enable_push: Option<bool>,
+  initial_window_size: Option<u32>,
+  max_concurrent_streams: Option<u32>,
+  max_frame_size: Option<u32>,
+  max_header_list_size: Option<u32>,
+}
+
+/// TLS is set up by the ops themselves rather than by `Deno.connectTls()`
+/// and `Deno.listenTls()`, as those only negotiate "h2" with ALPN when
+/// unstable APIs are enabled.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientTlsOptions {
+  server_name: String,
+  ca_certs: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ServerTlsOptions {
+  cert: String,
+  key: String,
+}
+
+fn connect_tls(
+  state: &mut OpState,
+  rid: ResourceId,
+  options: ClientTlsOptions,
+) -> Result<TlsStream, AnyError> {
+  let root_cert_store =
+    state.borrow::<DefaultTlsOptions>().root_cert_store.clone();
+  let unsafely_ignore_certificate_errors = state
+    .try_borrow::<UnsafelyIgnoreCertificateErrors>()
+    .and_then(|it| it.0.clone());
+  let ca_certs = options
+    .ca_certs
+    .into_iter()
+    .map(|s| s.into_bytes())
+    .collect::<Vec<_>>();
+  let mut tls_config = create_client_config(
+    root_cert_store,
+    ca_certs,
+    unsafely_ignore_certificate_errors,
+    None,
+  )?;
+  tls_config.alpn_protocols = vec![b"h2".to_vec()];
+  let server_name =
+    ServerName::try_from(&*options.server_name).map_err(|_| {
+      type_error(format!("Invalid hostname: '{}'", options.server_name))
+    })?;
+
+  let tcp_stream = NetworkStream::take_tcp_from(state, rid)?;
+  Ok(TlsStream::new_client_side(
+    tcp_stream,
+    Arc::new(tls_config),
+    server_name,
+  ))
+}
+
+fn accept_tls(
+  state: &mut OpState,
+  rid: ResourceId,
+  options: ServerTlsOptions,
+) -> Result<TlsStream, AnyError> {
+  let cert_chain = load_certs(&mut BufReader::new(options.cert.as_bytes()))?;
+  let key_der = load_private_keys(options.key.as_bytes())?.remove(0);
+  let mut tls_config = ServerConfig::builder()
+    .with_safe_defaults()
+    .with_no_client_auth()
+    .with_single_cert(cert_chain, key_der)
+    .map_err(|e| type_error(format!("Invalid key or certificate: {e}")))?;
+  tls_config.alpn_protocols = vec![b"h2".to_vec()];
+
+  let tcp_stream = NetworkStream::take_tcp_from(state, rid)?;
+  Ok(TlsStream::new_server_side(tcp_stream, Arc::new(tls_config)))
+}
+
+/// Changes to a session, which are applied by the op that drives its
+/// connection, as that holds the connection for as long as it's open.
+enum SessionCommand {
+  InitialWindowSize(u32),
+  Goaway(Reason),
+}
+
+fn session_commands() -> (
+  mpsc::UnboundedSender<SessionCommand>,
+  AsyncRefCell<mpsc::UnboundedReceiver<SessionCommand>>,
+) {
+  let (tx, rx) = mpsc::unbounded_channel();
+  (tx, AsyncRefCell::new(rx))
+}
+
+pub struct Http2ClientSession {
+  client: AsyncRefCell<SendRequest<Bytes>>,
+  conn: AsyncRefCell<Option<h2::client::Connection<NetworkStream, Bytes>>>,
+  ping_pong: AsyncRefCell<Option<PingPong>>,
+  commands: mpsc::UnboundedSender<SessionCommand>,
+  pending_commands: AsyncRefCell<mpsc::UnboundedReceiver<SessionCommand>>,
+  cancel: CancelHandle,
+}
+
+impl Resource for Http2ClientSession {
+  fn name(&self) -> Cow<str> {
+    "http2ClientSession".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel.cancel();
+  }
+}
+
+pub struct Http2ServerSession {
+  conn: AsyncRefCell<h2::server::Connection<NetworkStream, Bytes>>,
+  ping_pong: AsyncRefCell<Option<PingPong>>,
+  commands: mpsc::UnboundedSender<SessionCommand>,
+  pending_commands: AsyncRefCell<mpsc::UnboundedReceiver<SessionCommand>>,
+  cancel: CancelHandle,
+}
+
+impl Resource for Http2ServerSession {
+  fn name(&self) -> Cow<str> {
+    "http2ServerSession".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel.cancel();
+  }
+}
+
+/// A stream of either side of a session. The response parts are only used
+/// by the side which they belong to.
+pub struct Http2Stream {
+  /// The response of a client stream, until it's received.
+  response: AsyncRefCell<Option<ResponseFuture>>,
+  /// The handle to respond to a server stream with, until it's used.
+  send_response: AsyncRefCell<Option<SendResponse<Bytes>>>,
+  send: AsyncRefCell<Option<SendStream<Bytes>>>,
+  recv: AsyncRefCell<Option<RecvStream>>,
+  cancel: CancelHandle,
+}
+
+impl Resource for Http2Stream {
+  fn name(&self) -> Cow<str> {
+    "http2Stream".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel.cancel();
+  }
+}
+
+fn to_header_map(headers: Headers) -> Result<HeaderMap, AnyError> {
+  let mut header_map = HeaderMap::with_capacity(headers.len());
+  for (name, value) in headers {
+    header_map.append(
+      HeaderName::from_bytes(&name)?,
+      HeaderValue::from_bytes(&value)?,
+    );
+  }
+  Ok(header_map)
+}
+
+fn from_header_map(header_map: &HeaderMap) -> Headers {
+  header_map
+    .iter()
+    .map(|(name, value)| (name.as_str().into(), value.as_bytes().into()))
+    .collect()
+}
+
+/// Builds a request from headers with pseudo-headers, as passed to
+/// `ClientHttp2Session.request()`.
+fn build_request(headers: Headers) -> Result<Request<()>, AnyError> {
+  let mut builder = Request::builder().version(Version::HTTP_2);
+  let mut uri = Uri::builder();
+  for (name, value) in headers {
+    match &name[..] {
+      b":method" => builder = builder.method(&value[..]),
+      b":scheme" => uri = uri.scheme(&value[..]),
+      b":authority" => uri = uri.authority(&value[..]),
+      b":path" => uri = uri.path_and_query(&value[..]),
+      name if name.starts_with(b":") => {
+        return Err(type_error(format!(
+          "Invalid pseudo-header: {}",
+          String::from_utf8_lossy(name)
+        )))
+      }
+      _ => builder = builder.header(&name[..], &value[..]),
+    }
+  }
+  Ok(builder.uri(uri.build()?).body(())?)
+}
+
+/// The pseudo-headers and headers of a request received by a server.
+fn request_headers(request: &Request<RecvStream>) -> Headers {
+  let uri = request.uri();
+  let mut headers: Headers =
+    vec![(":method".into(), request.method().as_str().into())];
+  if let Some(scheme) = uri.scheme_str() {
+    headers.push((":scheme".into(), scheme.into()));
+  }
+  if let Some(authority) = uri.authority() {
+    headers.push((":authority".into(), authority.as_str().into()));
+  }
+  if let Some(path) = uri.path_and_query() {
+    headers.push((":path".into(), path.as_str().into()));
+  }
+  headers.extend(from_header_map(request.headers()));
+  headers
+}
+
+#[op]
+pub async fn op_http2_connect(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  settings: Option<Http2Settings>,
+  tls: Option<ClientTlsOptions>,
+) -> Result<ResourceId, AnyError> {
+  let network_stream = {
+    let mut state = state.borrow_mut();
+    match tls {
+      Some(options) => {
+        NetworkStream::Tls(connect_tls(&mut state, rid, options)?)
+      }
+      None => NetworkStream::take_from(&mut state, rid)?,
+    }
+  };
+  let settings = settings.unwrap_or_default();
+
+  let mut builder = h2::client::Builder::new();
+  if let Some(enable_push) = settings.enable_push {
+    builder.enable_push(enable_push);
+  }
+  if let Some(size) = settings.initial_window_size {
+    builder.initial_window_size(size);
+  }
+  if let Some(max) = settings.max_concurrent_streams {
+    builder.max_concurrent_streams(max);
+  }
+  if let Some(size) = settings.max_frame_size {
+    builder.max_frame_size(size);
+  }
+  if let Some(size) = settings.max_header_list_size {
+    builder.max_header_list_size(size);
+  }
+  let (client, mut conn) =
+    builder.handshake::<_, Bytes>(network_stream).await?;
+  let ping_pong = conn.ping_pong();
+  let (commands, pending_commands) = session_commands();
+
+  let rid = state.borrow_mut().resource_table.add(Http2ClientSession {
+    client: AsyncRefCell::new(client),
+    conn: AsyncRefCell::new(Some(conn)),
+    ping_pong: AsyncRefCell::new(ping_pong),
+    commands,
+    pending_commands,
+    cancel: CancelHandle::new(),
+  });
+  Ok(rid)
+}
+
+/// Drives the connection of a client session, which resolves once the
+/// connection is closed.
+#[op]
+pub async fn op_http2_poll_client_connection(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<(), AnyError> {
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<Http2ClientSession>(rid)?;
+  let conn = RcRef::map(&resource, |r| &r.conn).borrow_mut().await.take();
+  if let Some(mut conn) = conn {
+    let mut commands = RcRef::map(&resource, |r| &r.pending_commands)
+      .borrow_mut()
+      .await;
+    let drive = async {
+      loop {
+        tokio::select! {
+          result = &mut conn => return result.map_err(AnyError::from),
+          Some(command) = commands.recv() => match command {
+            SessionCommand::InitialWindowSize(size) => {
+              conn.set_initial_window_size(size)?
+            }
+            SessionCommand::Goaway(_) => {
+              unreachable!("GOAWAY frames are only sent by server sessions")
+            }
+          },
+        }
+      }
+    };
+    let cancel = RcRef::map(&resource, |r| &r.cancel);
+    // the connection is dropped when the session is destroyed
+    if let Ok(result) = drive.or_cancel(cancel).await {
+      result?;
+    }
+  }
+  Ok(())
+}
+
+#[op]
+pub async fn op_http2_client_request(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  headers: Headers,
+  end_stream: bool,
+) -> Result<(ResourceId, u32), AnyError> {
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<Http2ClientSession>(rid)?;
+  let request = build_request(headers)?;
+
+  let client = RcRef::map(&resource, |r| &r.client).borrow().await;
+  let cancel = RcRef::map(&resource, |r| &r.cancel);
+  let mut client = client.clone().ready().or_cancel(cancel).await??;
+  let (response, send) = client.send_request(request, end_stream)?;
+  let stream_id = response.stream_id().as_u32();
+
+  let rid = state.borrow_mut().resource_table.add(Http2Stream {
+    response: AsyncRefCell::new(Some(response)),
+    send_response: AsyncRefCell::new(None),
+    send: AsyncRefCell::new(Some(send)),
+    recv: AsyncRefCell::new(None),
+    cancel: CancelHandle::new(),
+  });
+  Ok((rid, stream_id))
+}
+
+#[op]
+pub async fn op_http2_client_get_response(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<(u16, Headers), AnyError> {
+  let resource = state.borrow().resource_table.get::<Http2Stream>(rid)?;
+  let response = RcRef::map(&resource, |r| &r.response)
+    .borrow_mut()
+    .await
+    .take()
+    .ok_or_else(|| type_error("The response was already received"))?;
+  let cancel = RcRef::map(&resource, |r| &r.cancel);
+  let (parts, body) = response.or_cancel(cancel).await??.into_parts();
+  *RcRef::map(&resource, |r| &r.recv).borrow_mut().await = Some(body);
+  Ok((parts.status.as_u16(), from_header_map(&parts.headers)))
+}
+
+#[op]
+pub async fn op_http2_accept(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  settings: Option<Http2Settings>,
+  tls: Option<ServerTlsOptions>,
+) -> Result<ResourceId, AnyError> {
+  let network_stream = {
+    let mut state = state.borrow_mut();
+    match tls {
+      Some(options) => {
+        NetworkStream::Tls(accept_tls(&mut state, rid, options)?)
+      }
+      None => NetworkStream::take_from(&mut state, rid)?,
+    }
+  };
+  let settings = settings.unwrap_or_default();
+
+  let mut builder = h2::server::Builder::new();
+  if let Some(size) = settings.initial_window_size {
+    builder.initial_window_size(size);
+  }
+  if let Some(max) = settings.max_concurrent_streams {
+    builder.max_concurrent_streams(max);
+  }
+  if let Some(size) = settings.max_frame_size {
+    builder.max_frame_size(size);
+  }
+  if let Some(size) = settings.max_header_list_size {
+    builder.max_header_list_size(size);
+  }
+  let mut conn = builder.handshake::<_, Bytes>(network_stream).await?;
+  let ping_pong = conn.ping_pong();
+  let (commands, pending_commands) = session_commands();
+
+  let rid = state.borrow_mut().resource_table.add(Http2ServerSession {
+    conn: AsyncRefCell::new(conn),
+    ping_pong: AsyncRefCell::new(ping_pong),
+    commands,
+    pending_commands,
+    cancel: CancelHandle::new(),
+  });
+  Ok(rid)
+}
+
+/// Waits for the next stream opened by the client. This also drives the
+/// connection, so a call has to be pending for as long as the session is
+/// open. Resolves to `None` once the connection is closed.
+#[op]
+pub async fn op_http2_server_accept_stream(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<Option<(ResourceId, u32, Headers)>, AnyError> {
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<Http2ServerSession>(rid)?;
+  let mut conn = RcRef::map(&resource, |r| &r.conn).borrow_mut().await;
+  let mut commands = RcRef::map(&resource, |r| &r.pending_commands)
+    .borrow_mut()
+    .await;
+  let accept = async {
+    loop {
+      tokio::select! {
+        accepted = conn.accept() => return accepted,
+        Some(command) = commands.recv() => match command {
+          SessionCommand::InitialWindowSize(size) => {
+            if let Err(err) = conn.set_initial_window_size(size) {
+              return Some(Err(err));
+            }
+          }
+          SessionCommand::Goaway(Reason::NO_ERROR) => conn.graceful_shutdown(),
+          SessionCommand::Goaway(reason) => conn.abrupt_shutdown(reason),
+        },
+      }
+    }
+  };
+  let cancel = RcRef::map(&resource, |r| &r.cancel);
+  let (request, send_response) = match accept.or_cancel(cancel).await {
+    Ok(Some(accepted)) => accepted?,
+    Ok(None) | Err(_) => return Ok(None),
+  };
+  let stream_id = send_response.stream_id().as_u32();
+  let headers = request_headers(&request);
+
+  let rid = state.borrow_mut().resource_table.add(Http2Stream {
+    response: AsyncRefCell::new(None),
+    send_response: AsyncRefCell::new(Some(send_response)),
+    send: AsyncRefCell::new(None),
+    recv: AsyncRefCell::new(Some(request.into_body())),
+    cancel: CancelHandle::new(),
+  });
+  Ok(Some((rid, stream_id, headers)))
+}
+
+#[op]
+pub async fn op_http2_server_respond(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  status: u16,
+  headers: Headers,
+  end_stream: bool,
+) -> Result<(), AnyError> {
+  let resource = state.borrow().resource_table.get::<Http2Stream>(rid)?;
+  let mut send_response = RcRef::map(&resource, |r| &r.send_response)
+    .borrow_mut()
+    .await
+    .take()
+    .ok_or_else(|| type_error("The response was already sent"))?;
+
+  let mut response = Response::new(());
+  *response.status_mut() = StatusCode::from_u16(status)?;
+  *response.headers_mut() = to_header_map(headers)?;
+  let send = send_response.send_response(response, end_stream)?;
+  *RcRef::map(&resource, |r| &r.send).borrow_mut().await = Some(send);
+  Ok(())
+}
+
+/// Reads the next chunk of data of a stream, or `None` at its end.
+#[op]
+pub async fn op_http2_stream_read(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<Option<ZeroCopyBuf>, AnyError> {
+  let resource = state.borrow().resource_table.get::<Http2Stream>(rid)?;
+  let mut recv = RcRef::map(&resource, |r| &r.recv).borrow_mut().await;
+  let recv = recv
+    .as_mut()
+    .ok_or_else(|| type_error("The stream has no body yet"))?;
+  let cancel = RcRef::map(&resource, |r| &r.cancel);
+  match recv.data().or_cancel(cancel).await? {
+    Some(data) => {
+      let data = data?;
+      // let the peer send more data
+      recv.flow_control().release_capacity(data.len())?;
+      Ok(Some(data.to_vec().into()))
+    }
+    None => Ok(None),
+  }
+}
+
+/// Receives the trailers of a stream, after its data has been read.
+#[op]
+pub async fn op_http2_stream_get_trailers(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<Option<Headers>, AnyError> {
+  let resource = state.borrow().resource_table.get::<Http2Stream>(rid)?;
+  let mut recv = RcRef::map(&resource, |r| &r.recv).borrow_mut().await;
+  let recv = recv
+    .as_mut()
+    .ok_or_else(|| type_error("The stream has no body yet"))?;
+  let cancel = RcRef::map(&resource, |r| &r.cancel);
+  let trailers = recv.trailers().or_cancel(cancel).await??;
+  Ok(trailers.as_ref().map(from_header_map))
+}
+
+/// Writes data to a stream, waiting for the peer to have capacity for it.
+#[op]
+pub async fn op_http2_stream_write(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  data: ZeroCopyBuf,
+  end_stream: bool,
+) -> Result<(), AnyError> {
+  let resource = state.borrow().resource_table.get::<Http2Stream>(rid)?;
+  let mut send = RcRef::map(&resource, |r| &r.send).borrow_mut().await;
+  let send = send
+    .as_mut()
+    .ok_or_else(|| type_error("The stream can't be written to yet"))?;
+  let cancel = RcRef::map(&resource, |r| &r.cancel);
+
+  let mut data = Bytes::from(data.to_vec());
+  if data.is_empty() {
+    send.send_data(data, end_stream)?;
+    return Ok(());
+  }
+  while !data.is_empty() {
+    send.reserve_capacity(data.len());
+    let capacity = poll_fn(|cx| send.poll_capacity(cx))
+      .or_cancel(cancel.clone())
+      .await?
+      .ok_or_else(|| type_error("The stream was closed"))??;
+    if capacity == 0 {
+      continue;
+    }
+    let chunk = data.split_to(capacity.min(data.len()));
+    send.send_data(chunk, end_stream && data.is_empty())?;
+  }
+  Ok(())
+}
+
+#[op]
+pub async fn op_http2_stream_send_trailers(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  trailers: Headers,
+) -> Result<(), AnyError> {
+  let resource = state.borrow().resource_table.get::<Http2Stream>(rid)?;
+  let mut send = RcRef::map(&resource, |r| &r.send).borrow_mut().await;
+  let send = send
+    .as_mut()
+    .ok_or_else(|| type_error("The stream can't be written to yet"))?;
+  send.send_trailers(to_header_map(trailers)?)?;
+  Ok(())
+}
+
+/// Resets a stream with an HTTP/2 error code, like `Http2Stream.close()`.
+#[op]
+pub async fn op_http2_stream_reset(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  code: u32,
+) -> Result<(), AnyError> {
+  let resource = state.borrow().resource_table.get::<Http2Stream>(rid)?;
+  let reason = Reason::from(code);
+  if let Some(send) = RcRef::map(&resource, |r| &r.send)
+    .borrow_mut()
+    .await
+    .as_mut()
+  {
+    send.send_reset(reason);
+  } else if let Some(send_response) =
+    RcRef::map(&resource, |r| &r.send_response)
+      .borrow_mut()
+      .await
+      .as_mut()
+  {
+    send_response.send_reset(reason);
+  }
+  Ok(())
+}
+
+/// Sends a PING frame on a session and waits for the acknowledgement.
+#[op]
+pub async fn op_http2_ping(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<(), AnyError> {
+  let ping_pong = {
+    let state = state.borrow();
+    match state.resource_table.get::<Http2ClientSession>(rid) {
+      Ok(session) => RcRef::map(session, |r| &r.ping_pong),
+      Err(_) => {
+        RcRef::map(state.resource_table.get::<Http2ServerSession>(rid)?, |r| {
+          &r.ping_pong
+        })
+      }
+    }
+  };
+  let mut ping_pong = ping_pong.borrow_mut().await;
+  let ping_pong = ping_pong
+    .as_mut()
+    .ok_or_else(|| type_error("Pings are not supported by the session"))?;
+  ping_pong.ping(Ping::opaque()).await?;
+  Ok(())
+}
+
+fn session_commands_sender(
+  state: &OpState,
+  rid: ResourceId,
+) -> Result<mpsc::UnboundedSender<SessionCommand>, AnyError> {
+  match state.resource_table.get::<Http2ClientSession>(rid) {
+    Ok(session) => Ok(session.commands.clone()),
+    Err(_) => Ok(
+      state
+        .resource_table
+        .get::<Http2ServerSession>(rid)?
+        .commands
+        .clone(),
+    ),
+  }
+}
+
+/// Sends a SETTINGS frame on a session. Only the initial window size can be
+/// changed once the connection is established.
+#[op]
+pub fn op_http2_settings(
+  state: &mut OpState,
+  rid: ResourceId,
+  initial_window_size: u32,
+) -> Result<(), AnyError> {
+  session_commands_sender(state, rid)?
+    .send(SessionCommand::InitialWindowSize(initial_window_size))
+    .map_err(|_| bad_resource("The session is closed"))
+}
+
+/// Sends a GOAWAY frame on a server session. With `NO_ERROR` the open streams
+/// are finished first, otherwise the connection is closed right away.
+#[op]
+pub fn op_http2_goaway(
+  state: &mut OpState,
+  rid: ResourceId,
+  code: u32,
+) -> Result<(), AnyError> {
+  let session = state.resource_table.get::<Http2ServerSession>(rid)?;
+  session
+    .commands
+    .send(SessionCommand::Goaway(Reason::from(code)))
+    .map_err(|_| bad_resource("The session is closed"))
+}
@@ -0,0 +1,116 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Sessions of `node:inspector`, which talk to the V8 inspector of the
+//! current isolate through the Chrome DevTools Protocol.
+
+use deno_core::error::AnyError;
+use deno_core::futures::channel::mpsc::UnboundedReceiver;
+use deno_core::op;
+use deno_core::serde_json::Value;
+use deno_core::v8;
+use deno_core::AsyncRefCell;
+use deno_core::CancelFuture;
+use deno_core::CancelHandle;
+use deno_core::JsRuntime;
+use deno_core::LocalInspectorSession;
+use deno_core::OpState;
+use deno_core::RcRef;
+use deno_core::Resource;
+use deno_core::ResourceId;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct InspectorSessionResource {
+  session: AsyncRefCell<LocalInspectorSession>,
+  notification_rx: AsyncRefCell<UnboundedReceiver<Value>>,
+  /// Cancels the pending `op_node_inspector_receive`, which holds the session
+  /// while it waits for notifications.
+  receive_cancel: RefCell<Rc<CancelHandle>>,
+}
+
+impl Resource for InspectorSessionResource {
+  fn name(&self) -> Cow<str> {
+    "inspectorSession".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.receive_cancel.borrow().cancel();
+  }
+}
+
+/// Connects a new session to the inspector of the isolate.
+#[op(v8)]
+pub fn op_node_inspector_connect(
+  scope: &mut v8::HandleScope,
+  state: &mut OpState,
+) -> ResourceId {
+  let inspector = JsRuntime::inspector_from_scope(scope);
+  let mut session = inspector.borrow().create_local_session();
+  let notification_rx = session.take_notification_rx();
+  state.resource_table.add(InspectorSessionResource {
+    session: AsyncRefCell::new(session),
+    notification_rx: AsyncRefCell::new(notification_rx),
+    receive_cancel: Default::default(),
+  })
+}
+
+/// Posts a message to the inspector and resolves with its result. Errors of
+/// the protocol are thrown with the JSON of the error as message.
+#[op]
+pub async fn op_node_inspector_post(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  method: String,
+  params: Option<Value>,
+) -> Result<Value, AnyError> {
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<InspectorSessionResource>(rid)?;
+  // Notifications received in the meantime are queued and picked up by the
+  // next `op_node_inspector_receive`.
+  resource.receive_cancel.borrow().cancel();
+  let mut session = RcRef::map(&resource, |r| &r.session).borrow_mut().await;
+  session.post_message(&method, params).await
+}
+
+/// Waits for the notifications of the inspector. Resolves with an empty list
+/// when a message is posted in the meantime, or the session is closed.
+#[op]
+pub async fn op_node_inspector_receive(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<Vec<Value>, AnyError> {
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<InspectorSessionResource>(rid)?;
+  let cancel = Rc::new(CancelHandle::new());
+  *resource.receive_cancel.borrow_mut() = cancel.clone();
+
+  let mut session = RcRef::map(&resource, |r| &r.session).borrow_mut().await;
+  let mut notification_rx = RcRef::map(&resource, |r| &r.notification_rx)
+    .borrow_mut()
+    .await;
+
+  let mut notifications = drain(&mut notification_rx);
+  if notifications.is_empty()
+    && session
+      .receive_from_v8_session()
+      .or_cancel(cancel)
+      .await
+      .is_ok()
+  {
+    notifications = drain(&mut notification_rx);
+  }
+  Ok(notifications)
+}
+
+fn drain(notification_rx: &mut UnboundedReceiver<Value>) -> Vec<Value> {
+  let mut notifications = vec![];
+  while let Ok(Some(notification)) = notification_rx.try_next() {
+    notifications.push(notification);
+  }
+  notifications
+}
@@ -0,0 +1,173 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! The channel between a process and the children it `fork()`s. Messages are
+//! serialized as JSON, one per line, like the `"json"` serialization of
+//! Node.js.
+
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::OpState;
+use deno_core::ResourceId;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The environment variable with the file descriptor of the channel of a
+/// child process.
+pub const CHANNEL_FD_ENV: &str = "DENO_CHANNEL_FD";
+
+#[cfg(unix)]
+pub use unix::IpcJsonStreamResource;
+
+#[cfg(unix)]
+mod unix {
+  use deno_core::AsyncRefCell;
+  use deno_core::CancelHandle;
+  use deno_core::CancelTryFuture;
+  use deno_core::RcRef;
+  use deno_core::Resource;
+  use std::borrow::Cow;
+  use std::rc::Rc;
+  use tokio::io::AsyncBufReadExt;
+  use tokio::io::AsyncWriteExt;
+  use tokio::io::BufReader;
+  use tokio::net::unix::OwnedReadHalf;
+  use tokio::net::unix::OwnedWriteHalf;
+  use tokio::net::UnixStream;
+
+  pub struct IpcJsonStreamResource {
+    read_half: AsyncRefCell<BufReader<OwnedReadHalf>>,
+    write_half: AsyncRefCell<OwnedWriteHalf>,
+    cancel: CancelHandle,
+  }
+
+  impl Resource for IpcJsonStreamResource {
+    fn name(&self) -> Cow<str> {
+      "ipcJsonStream".into()
+    }
+
+    fn close(self: Rc<Self>) {
+      self.cancel.cancel();
+    }
+  }
+
+  impl From<UnixStream> for IpcJsonStreamResource {
+    fn from(stream: UnixStream) -> Self {
+      let (read_half, write_half) = stream.into_split();
+      Self {
+        read_half: AsyncRefCell::new(BufReader::new(read_half)),
+        write_half: AsyncRefCell::new(write_half),
+        cancel: Default::default(),
+      }
+    }
+  }
+
+  impl IpcJsonStreamResource {
+    /// Reads the next message, or `None` once the other side has
+    /// disconnected.
+    pub async fn read_message(
+      self: Rc<Self>,
+    ) -> Result<Option<String>, std::io::Error> {
+      let mut read_half =
+        RcRef::map(&self, |r| &r.read_half).borrow_mut().await;
+      let cancel = RcRef::map(&self, |r| &r.cancel);
+      let mut line = String::new();
+      let read = read_half.read_line(&mut line).try_or_cancel(cancel).await?;
+      if read == 0 {
+        return Ok(None);
+      }
+      Ok(Some(line))
+    }
+
+    pub async fn write_message(
+      self: Rc<Self>,
+      message: &str,
+    ) -> Result<(), std::io::Error> {
+      let mut write_half =
+        RcRef::map(&self, |r| &r.write_half).borrow_mut().await;
+      let cancel = RcRef::map(&self, |r| &r.cancel);
+      let mut data = Vec::with_capacity(message.len() + 1);
+      data.extend_from_slice(message.as_bytes());
+      data.push(b'\n');
+      write_half.write_all(&data).try_or_cancel(cancel).await
+    }
+  }
+}
+
+/// Opens the channel to the parent process, if this process was forked. The
+/// channel can only be opened once.
+#[op]
+pub fn op_node_ipc_pipe(
+  state: &mut OpState,
+) -> Result<Option<ResourceId>, AnyError> {
+  let fd = match std::env::var(CHANNEL_FD_ENV) {
+    Ok(fd) => fd,
+    Err(_) => return Ok(None),
+  };
+  // Grandchildren must not inherit the channel.
+  std::env::remove_var(CHANNEL_FD_ENV);
+
+  #[cfg(unix)]
+  {
+    use std::os::unix::io::FromRawFd;
+
+    let fd: std::os::unix::io::RawFd = fd.parse()?;
+    // SAFETY: the parent passed the descriptor of its socket to this process,
+    // and the variable is removed so that it's only taken once.
+    let stream = unsafe { std::os::unix::net::UnixStream::from_raw_fd(fd) };
+    stream.set_nonblocking(true)?;
+    let stream = tokio::net::UnixStream::from_std(stream)?;
+    Ok(Some(
+      state
+        .resource_table
+        .add(IpcJsonStreamResource::from(stream)),
+    ))
+  }
+  #[cfg(not(unix))]
+  {
+    let _ = (state, fd);
+    Ok(None)
+  }
+}
+
+#[op]
+pub async fn op_node_ipc_write(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  message: String,
+) -> Result<(), AnyError> {
+  #[cfg(unix)]
+  {
+    let resource = state
+      .borrow()
+      .resource_table
+      .get::<IpcJsonStreamResource>(rid)?;
+    resource.write_message(&message).await?;
+    Ok(())
+  }
+  #[cfg(not(unix))]
+  {
+    let _ = (state, rid, message);
+    Err(deno_core::error::not_supported())
+  }
+}
+
+/// Reads the next JSON encoded message.
+#[op]
+pub async fn op_node_ipc_read(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<Option<String>, AnyError> {
+  #[cfg(unix)]
+  {
+    let resource = state
+      .borrow()
+      .resource_table
+      .get::<IpcJsonStreamResource>(rid)?;
+    Ok(resource.read_message().await?)
+  }
+  #[cfg(not(unix))]
+  {
+    let _ = (state, rid);
+    Err(deno_core::error::not_supported())
+  }
+}
@@ -4,6 +4,7 @@ use deno_core::error::AnyError;
 use deno_core::include_js_files;
 use deno_core::located_script_name;
 use deno_core::op;
+use deno_core::serde_json::Value;
 use deno_core::Extension;
 use deno_core::JsRuntime;
 use once_cell::sync::Lazy;
@@ -14,15 +15,22 @@ use std::rc::Rc;
 
 mod crypto;
 pub mod errors;
+mod http2;
 mod idna;
+mod inspector;
+mod ipc;
 mod ops;
 mod package_json;
 mod path;
 mod polyfill;
 mod resolution;
 mod v8;
+mod vm;
 mod winerror;
 
+#[cfg(unix)]
+pub use ipc::IpcJsonStreamResource;
+pub use ipc::CHANNEL_FD_ENV;
 pub use package_json::PackageJson;
 pub use path::PathClean;
 pub use polyfill::find_builtin_node_module;
@@ -36,6 +44,7 @@ pub use resolution::package_exports_resolve;
 pub use resolution::package_imports_resolve;
 pub use resolution::package_resolve;
 pub use resolution::path_to_declaration_path;
+pub use resolution::with_custom_conditions;
 pub use resolution::NodeModuleKind;
 pub use resolution::NodeResolutionMode;
 pub use resolution::DEFAULT_CONDITIONS;
@@ -64,6 +73,33 @@ pub trait RequireNpmResolver {
     permissions: &mut dyn NodePermissions,
     path: &Path,
   ) -> Result<(), AnyError>;
+  /// The custom conditions, e.g. of `--conditions`, to resolve the "exports"
+  /// and "imports" of packages with in addition to the default ones.
+  fn conditions(&self) -> &[String];
+}
+
+/// The customization hooks of `node:module`, registered with
+/// `module.register()`. They run apart from the worker, which waits for them
+/// to resolve and load its modules.
+pub trait NodeModuleHooks {
+  /// Imports the module of the hooks `specifier`, resolved from `parent_url`,
+  /// and calls its `initialize` hook with `data`.
+  fn register(
+    &self,
+    specifier: &str,
+    parent_url: &str,
+    data: Option<Value>,
+  ) -> Result<(), AnyError>;
+  /// Whether hooks were registered for the modules of the worker.
+  fn is_active(&self) -> bool;
+  /// Resolves the request of a `require()` to the URL of a module.
+  fn resolve_require(
+    &self,
+    specifier: &str,
+    parent_url: Option<&str>,
+  ) -> Result<String, AnyError>;
+  /// Loads the source of a CommonJS module or JSON file that is required.
+  fn load_require(&self, url: &str, format: &str) -> Result<String, AnyError>;
 }
 
 pub static NODE_GLOBAL_THIS_NAME: Lazy<String> = Lazy::new(|| {
@@ -98,6 +134,7 @@ fn op_node_build_os() -> String {
 pub fn init_polyfill() -> Extension {
   let esm_files = include_js_files!(
     dir "polyfills",
+    "_brotli.ts",
     "_core.ts",
     "_events.mjs",
     "_fs/_fs_access.ts",
@@ -248,7 +285,12 @@ pub fn init_polyfill() -> Extension {
     "internal/blob.mjs",
     "internal/buffer.mjs",
     "internal/child_process.ts",
+    "internal/child_process/ipc.ts",
     "internal/cli_table.ts",
+    "internal/cluster/child.ts",
+    "internal/cluster/primary.ts",
+    "internal/cluster/utils.ts",
+    "internal/cluster/worker.ts",
     "internal/console/constructor.mjs",
     "internal/constants.ts",
     "internal/crypto/_hex.ts",
@@ -374,9 +416,54 @@ pub fn init_polyfill() -> Extension {
       crypto::op_node_private_encrypt::decl(),
       crypto::op_node_private_decrypt::decl(),
       crypto::op_node_public_encrypt::decl(),
+      crypto::op_node_create_cipheriv::decl(),
+      crypto::op_node_create_decipheriv::decl(),
+      crypto::op_node_cipher_set_aad::decl(),
+      crypto::op_node_cipher_update::decl(),
+      crypto::op_node_cipher_final::decl(),
+      crypto::op_node_create_hmac::decl(),
+      crypto::op_node_hmac_update::decl(),
+      crypto::op_node_hmac_digest::decl(),
+      crypto::op_node_pbkdf2::decl(),
+      crypto::op_node_pbkdf2_async::decl(),
+      crypto::op_node_scrypt::decl(),
+      crypto::op_node_scrypt_async::decl(),
+      crypto::op_node_hkdf::decl(),
+      crypto::op_node_hkdf_async::decl(),
+      crypto::op_node_create_private_key::decl(),
+      crypto::op_node_create_public_key::decl(),
+      crypto::op_node_export_key::decl(),
+      crypto::op_node_generate_key_pair::decl(),
+      crypto::op_node_generate_key_pair_async::decl(),
+      crypto::op_node_sign::decl(),
+      crypto::op_node_verify::decl(),
       winerror::op_node_sys_to_uv_error::decl(),
+      inspector::op_node_inspector_connect::decl(),
+      inspector::op_node_inspector_post::decl(),
+      inspector::op_node_inspector_receive::decl(),
+      ipc::op_node_ipc_pipe::decl(),
+      ipc::op_node_ipc_write::decl(),
+      ipc::op_node_ipc_read::decl(),
       v8::op_v8_cached_data_version_tag::decl(),
       v8::op_v8_get_heap_statistics::decl(),
+      vm::op_vm_create_context::decl(),
+      vm::op_vm_is_context::decl(),
+      vm::op_vm_run_in_context::decl(),
+      http2::op_http2_connect::decl(),
+      http2::op_http2_poll_client_connection::decl(),
+      http2::op_http2_client_request::decl(),
+      http2::op_http2_client_get_response::decl(),
+      http2::op_http2_accept::decl(),
+      http2::op_http2_server_accept_stream::decl(),
+      http2::op_http2_server_respond::decl(),
+      http2::op_http2_stream_read::decl(),
+      http2::op_http2_stream_get_trailers::decl(),
+      http2::op_http2_stream_write::decl(),
+      http2::op_http2_stream_send_trailers::decl(),
+      http2::op_http2_stream_reset::decl(),
+      http2::op_http2_ping::decl(),
+      http2::op_http2_settings::decl(),
+      http2::op_http2_goaway::decl(),
       idna::op_node_idna_domain_to_ascii::decl(),
       idna::op_node_idna_domain_to_unicode::decl(),
       idna::op_node_idna_punycode_decode::decl(),
@@ -418,6 +505,9 @@ pub fn init<P: NodePermissions + 'static>(
       ops::op_require_read_package_scope::decl::<P>(),
       ops::op_require_package_imports_resolve::decl::<P>(),
       ops::op_require_break_on_next_statement::decl(),
+      ops::op_require_hooks_resolve::decl(),
+      ops::op_require_hooks_load::decl::<P>(),
+      ops::op_node_module_register::decl(),
     ])
     .state(move |state| {
       if let Some(npm_resolver) = maybe_npm_resolver.clone() {
@@ -1,18 +1,21 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::error::custom_error;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::normalize_path;
 use deno_core::op;
+use deno_core::serde_json::Value;
 use deno_core::url::Url;
-use deno_core::JsRuntimeInspector;
+use deno_core::v8;
+use deno_core::JsRuntime;
 use deno_core::OpState;
-use std::cell::RefCell;
 use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
 
 use super::resolution;
+use super::NodeModuleHooks;
 use super::NodeModuleKind;
 use super::NodePermissions;
 use super::NodeResolutionMode;
@@ -407,7 +410,10 @@ where
       exports,
       &referrer,
       NodeModuleKind::Cjs,
-      resolution::REQUIRE_CONDITIONS,
+      &resolution::with_custom_conditions(
+        resolution::REQUIRE_CONDITIONS,
+        resolver.conditions(),
+      ),
       NodeResolutionMode::Execution,
       &*resolver,
       permissions,
@@ -479,7 +485,10 @@ where
       exports,
       &referrer,
       NodeModuleKind::Cjs,
-      resolution::REQUIRE_CONDITIONS,
+      &resolution::with_custom_conditions(
+        resolution::REQUIRE_CONDITIONS,
+        resolver.conditions(),
+      ),
       NodeResolutionMode::Execution,
       &*resolver,
       permissions,
@@ -551,7 +560,10 @@ where
       &request,
       &referrer,
       NodeModuleKind::Cjs,
-      resolution::REQUIRE_CONDITIONS,
+      &resolution::with_custom_conditions(
+        resolution::REQUIRE_CONDITIONS,
+        resolver.conditions(),
+      ),
       NodeResolutionMode::Execution,
       &*resolver,
       permissions,
@@ -564,10 +576,101 @@ where
   }
 }
 
-#[op]
-fn op_require_break_on_next_statement(state: &mut OpState) {
-  let inspector = state.borrow::<Rc<RefCell<JsRuntimeInspector>>>();
+#[op(v8)]
+fn op_require_break_on_next_statement(scope: &mut v8::HandleScope) {
+  let inspector = JsRuntime::inspector_from_scope(scope);
   inspector
     .borrow_mut()
     .wait_for_session_and_break_on_next_statement()
 }
+
+fn active_module_hooks(state: &OpState) -> Option<Rc<dyn NodeModuleHooks>> {
+  state
+    .try_borrow::<Rc<dyn NodeModuleHooks>>()
+    .filter(|hooks| hooks.is_active())
+    .cloned()
+}
+
+/// The URL the hooks know a module by. Modules they resolved to something
+/// else than a file keep their URL as filename.
+fn filename_to_url(filename: &str) -> Result<Url, AnyError> {
+  if Path::new(filename).is_absolute() {
+    Url::from_file_path(filename)
+      .map_err(|_| generic_error(format!("Invalid file path: {filename}")))
+  } else {
+    Ok(Url::parse(filename)?)
+  }
+}
+
+/// Resolves `request` with the `resolve` hooks of `module.register()`, or
+/// returns `None` if there are none.
+#[op]
+fn op_require_hooks_resolve(
+  state: &mut OpState,
+  request: String,
+  parent_filename: Option<String>,
+) -> Result<Option<String>, AnyError> {
+  let hooks = match active_module_hooks(state) {
+    Some(hooks) => hooks,
+    None => return Ok(None),
+  };
+  let parent_url = parent_filename
+    .as_deref()
+    .map(filename_to_url)
+    .transpose()?;
+  let url = Url::parse(
+    &hooks.resolve_require(&request, parent_url.as_ref().map(Url::as_str))?,
+  )?;
+  // Built-in modules keep their `node:` specifier.
+  let filename = match url.scheme() {
+    "file" => url
+      .to_file_path()
+      .map_err(|_| generic_error(format!("Invalid file URL: {url}")))?
+      .to_string_lossy()
+      .to_string(),
+    _ => url.to_string(),
+  };
+  Ok(Some(filename))
+}
+
+/// Loads the source of `filename` with the `load` hooks of
+/// `module.register()`, or returns `None` if there are none.
+#[op]
+fn op_require_hooks_load<P>(
+  state: &mut OpState,
+  filename: String,
+  format: String,
+) -> Result<Option<String>, AnyError>
+where
+  P: NodePermissions + 'static,
+{
+  let hooks = match active_module_hooks(state) {
+    Some(hooks) => hooks,
+    None => return Ok(None),
+  };
+  let path = Path::new(&filename);
+  if path.is_absolute() {
+    ensure_read_permission::<P>(state, path)?;
+  }
+  let url = filename_to_url(&filename)?;
+  Ok(Some(hooks.load_require(url.as_str(), &format)?))
+}
+
+#[op]
+fn op_node_module_register(
+  state: &mut OpState,
+  specifier: String,
+  parent_url: String,
+  data: Option<Value>,
+) -> Result<(), AnyError> {
+  let hooks = match state.try_borrow::<Rc<dyn NodeModuleHooks>>() {
+    Some(hooks) => hooks.clone(),
+    None => {
+      return Err(custom_error(
+        "NotSupported",
+        "module.register() is not supported",
+      ))
+    }
+  };
+  hooks.register(&specifier, &parent_url, data)
+}
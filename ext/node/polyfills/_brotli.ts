@@ -0,0 +1,270 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+// deno-lint-ignore-file no-explicit-any
+
+import { Buffer } from "internal:deno_node/polyfills/buffer.ts";
+import { Transform } from "internal:deno_node/polyfills/stream.ts";
+import { zlib as constants } from "internal:deno_node/polyfills/internal_binding/constants.ts";
+import {
+  ERR_BROTLI_INVALID_PARAM,
+  ERR_INVALID_ARG_TYPE,
+} from "internal:deno_node/polyfills/internal/errors.ts";
+
+const { core } = globalThis.__bootstrap;
+const { ops } = core;
+
+export interface BrotliOptions {
+  flush?: number;
+  finishFlush?: number;
+  chunkSize?: number;
+  params?: Record<number, number | boolean>;
+  maxOutputLength?: number;
+}
+
+/** Maps the `BROTLI_PARAM_*` options to the parameters of the native encoder. */
+function toEncoderParams(params: BrotliOptions["params"] = {}) {
+  const encoderParams: Record<string, number | boolean> = {
+    quality: constants.BROTLI_DEFAULT_QUALITY,
+  };
+  for (const key of Object.keys(params)) {
+    const value = params[key as unknown as number];
+    switch (Number(key)) {
+      case constants.BROTLI_PARAM_MODE:
+        encoderParams.mode = Number(value);
+        break;
+      case constants.BROTLI_PARAM_QUALITY:
+        encoderParams.quality = Number(value);
+        break;
+      case constants.BROTLI_PARAM_LGWIN:
+        encoderParams.lgwin = Number(value);
+        break;
+      case constants.BROTLI_PARAM_LGBLOCK:
+        encoderParams.lgblock = Number(value);
+        break;
+      case constants.BROTLI_PARAM_SIZE_HINT:
+        encoderParams.sizeHint = Number(value);
+        break;
+      case constants.BROTLI_PARAM_LARGE_WINDOW:
+        encoderParams.largeWindow = Boolean(value);
+        break;
+      // these only tune the encoder, and are accepted but ignored
+      case constants.BROTLI_PARAM_DISABLE_LITERAL_CONTEXT_MODELING:
+      case constants.BROTLI_PARAM_NPOSTFIX:
+      case constants.BROTLI_PARAM_NDIRECT:
+        break;
+      default:
+        throw new ERR_BROTLI_INVALID_PARAM(key);
+    }
+  }
+  return encoderParams;
+}
+
+function toBuffer(chunk: Uint8Array): Buffer {
+  return Buffer.from(chunk.buffer, chunk.byteOffset, chunk.byteLength);
+}
+
+function toInput(buffer: unknown): Uint8Array {
+  if (typeof buffer === "string") {
+    return Buffer.from(buffer);
+  }
+  if (ArrayBuffer.isView(buffer)) {
+    return new Uint8Array(buffer.buffer, buffer.byteOffset, buffer.byteLength);
+  }
+  if (buffer instanceof ArrayBuffer) {
+    return new Uint8Array(buffer);
+  }
+  throw new ERR_INVALID_ARG_TYPE(
+    "buffer",
+    ["string", "Buffer", "TypedArray", "DataView", "ArrayBuffer"],
+    buffer,
+  );
+}
+
+class Brotli extends Transform {
+  #rid: number | null;
+  bytesWritten = 0;
+
+  constructor(options: BrotliOptions = {}, isDecoder: boolean) {
+    super(options);
+    this.#rid = ops.op_compression_new(
+      "br",
+      isDecoder,
+      isDecoder ? undefined : toEncoderParams(options.params),
+    );
+  }
+
+  get _closed(): boolean {
+    return this.#rid === null;
+  }
+
+  override _transform(
+    chunk: any,
+    _encoding: string,
+    callback: (err?: Error | null) => void,
+  ) {
+    if (this.#rid === null) {
+      return callback(new Error("zlib binding closed"));
+    }
+    try {
+      const input = toInput(chunk);
+      const output = ops.op_compression_write(this.#rid, input);
+      this.bytesWritten += input.byteLength;
+      if (output.byteLength > 0) {
+        this.push(toBuffer(output));
+      }
+      callback();
+    } catch (err) {
+      callback(err as Error);
+    }
+  }
+
+  override _flush(callback: (err?: Error | null) => void) {
+    if (this.#rid === null) {
+      return callback(new Error("zlib binding closed"));
+    }
+    const rid = this.#rid;
+    this.#rid = null;
+    try {
+      const output = ops.op_compression_finish(rid);
+      if (output.byteLength > 0) {
+        this.push(toBuffer(output));
+      }
+      callback();
+    } catch (err) {
+      callback(err as Error);
+    }
+  }
+
+  override _destroy(
+    err: Error | null,
+    callback: (err: Error | null) => void,
+  ) {
+    this.close();
+    callback(err);
+  }
+
+  /** Every write is flushed, so this only waits for pending writes. */
+  flush(kind?: number | (() => void), callback?: () => void) {
+    if (typeof kind === "function") {
+      callback = kind;
+    }
+    this.write(Buffer.alloc(0), "", callback);
+  }
+
+  params() {
+    // Brotli streams can't be reconfigured once created, like in Node.js.
+    throw new ERR_BROTLI_INVALID_PARAM("params");
+  }
+
+  reset() {
+    // The native stream can't be reused.
+  }
+
+  close(callback?: () => void) {
+    if (this.#rid !== null) {
+      core.tryClose(this.#rid);
+      this.#rid = null;
+    }
+    if (callback) {
+      this.once("close", callback);
+    }
+    if (!this.destroyed) {
+      this.destroy();
+    }
+  }
+}
+
+export class BrotliCompress extends Brotli {
+  constructor(options?: BrotliOptions) {
+    super(options, false);
+  }
+}
+
+export class BrotliDecompress extends Brotli {
+  constructor(options?: BrotliOptions) {
+    super(options, true);
+  }
+}
+
+export function createBrotliCompress(options?: BrotliOptions) {
+  return new BrotliCompress(options);
+}
+
+export function createBrotliDecompress(options?: BrotliOptions) {
+  return new BrotliDecompress(options);
+}
+
+function processSync(
+  buffer: unknown,
+  isDecoder: boolean,
+  options: BrotliOptions = {},
+): Buffer {
+  const input = toInput(buffer);
+  const rid = ops.op_compression_new(
+    "br",
+    isDecoder,
+    isDecoder ? undefined : toEncoderParams(options.params),
+  );
+  let written;
+  try {
+    written = ops.op_compression_write(rid, input);
+  } catch (err) {
+    core.tryClose(rid);
+    throw err;
+  }
+  const finished = ops.op_compression_finish(rid);
+  return Buffer.concat([toBuffer(written), toBuffer(finished)]);
+}
+
+type Callback = (err: Error | null, result?: Buffer) => void;
+
+function processAsync(
+  buffer: unknown,
+  isDecoder: boolean,
+  options: BrotliOptions | Callback | undefined,
+  callback: Callback | undefined,
+) {
+  if (typeof options === "function") {
+    callback = options;
+    options = {};
+  }
+  if (typeof callback !== "function") {
+    throw new ERR_INVALID_ARG_TYPE("callback", "Function", callback);
+  }
+  const input = toInput(buffer);
+  const stream = isDecoder
+    ? new BrotliDecompress(options)
+    : new BrotliCompress(options);
+  const chunks: Buffer[] = [];
+  stream.on("data", (chunk: Buffer) => chunks.push(chunk));
+  stream.on("error", (err: Error) => callback!(err));
+  stream.on("end", () => callback!(null, Buffer.concat(chunks)));
+  stream.end(input);
+}
+
+export function brotliCompress(
+  buffer: unknown,
+  options: BrotliOptions | Callback | undefined,
+  callback?: Callback,
+) {
+  processAsync(buffer, false, options, callback);
+}
+
+export function brotliCompressSync(buffer: unknown, options?: BrotliOptions) {
+  return processSync(buffer, false, options);
+}
+
+export function brotliDecompress(
+  buffer: unknown,
+  options: BrotliOptions | Callback | undefined,
+  callback?: Callback,
+) {
+  processAsync(buffer, true, options, callback);
+}
+
+export function brotliDecompressSync(
+  buffer: unknown,
+  options?: BrotliOptions,
+) {
+  return processSync(buffer, true, options);
+}
@@ -1,69 +1,106 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 // Copyright Joyent and Node contributors. All rights reserved. MIT license.
 
-import { notImplemented } from "internal:deno_node/polyfills/_utils.ts";
+import { EventEmitter } from "internal:deno_node/polyfills/events.ts";
+import process from "internal:deno_node/polyfills/process.ts";
+import {
+  ClusterSettings,
+  initPrimary,
+  Primary,
+  SCHED_NONE,
+  SCHED_RR,
+} from "internal:deno_node/polyfills/internal/cluster/primary.ts";
+import { initChild } from "internal:deno_node/polyfills/internal/cluster/child.ts";
+import { Worker } from "internal:deno_node/polyfills/internal/cluster/worker.ts";
+
+const internals = globalThis.__bootstrap.internals;
+
+export { SCHED_NONE, SCHED_RR, Worker };
+
+// The module itself is an event emitter in Node.js.
+const cluster = new EventEmitter() as Primary;
 
-/** A Worker object contains all public information and method about a worker.
- * In the primary it can be obtained using cluster.workers. In a worker it can
- * be obtained using cluster.worker.
- */
-export class Worker {
-  constructor() {
-    notImplemented("cluster.Worker.prototype.constructor");
-  }
-}
-/** Calls .disconnect() on each worker in cluster.workers. */
-export function disconnected() {
-  notImplemented("cluster.disconnected");
-}
-/** Spawn a new worker process. */
-export function fork() {
-  notImplemented("cluster.fork");
-}
 /** True if the process is a primary. This is determined by
  * the process.env.NODE_UNIQUE_ID. If process.env.NODE_UNIQUE_ID is undefined,
  * then isPrimary is true. */
-export const isPrimary = undefined;
+export let isPrimary = true;
 /** True if the process is not a primary (it is the negation of
  * cluster.isPrimary). */
-export const isWorker = undefined;
+export let isWorker = false;
 /** Deprecated alias for cluster.isPrimary. details. */
-export const isMaster = isPrimary;
+export let isMaster = true;
 /** The scheduling policy, either cluster.SCHED_RR for round-robin or
- * cluster.SCHED_NONE to leave it to the operating system. This is a global
- * setting and effectively frozen once either the first worker is spawned, or
- * .setupPrimary() is called, whichever comes first. */
-export const schedulingPolicy = undefined;
+ * cluster.SCHED_NONE to leave it to the operating system. Only SCHED_NONE is
+ * supported: the servers of all workers listen with `SO_REUSEPORT`, and the
+ * kernel distributes the connections. */
+export const schedulingPolicy = SCHED_NONE;
 /** The settings object */
-export const settings = undefined;
-/** Deprecated alias for .setupPrimary(). */
-export function setupMaster() {
-  notImplemented("cluster.setupMaster");
-}
-/** setupPrimary is used to change the default 'fork' behavior. Once called,
- * the settings will be present in cluster.settings. */
-export function setupPrimary() {
-  notImplemented("cluster.setupPrimary");
-}
+export let settings: ClusterSettings | undefined = undefined;
 /** A reference to the current worker object. Not available in the primary
  * process. */
-export const worker = undefined;
+export let worker: Worker | undefined = undefined;
 /** A hash that stores the active worker objects, keyed by id field. Makes it
  * easy to loop through all the workers. It is only available in the primary
  * process. */
-export const workers = undefined;
+export let workers: Record<number, Worker> | undefined = undefined;
 
-export default {
-  Worker,
-  disconnected,
-  fork,
-  isPrimary,
-  isWorker,
-  isMaster,
-  schedulingPolicy,
-  settings,
-  setupMaster,
-  setupPrimary,
-  worker,
-  workers,
+/** Calls .disconnect() on each worker in cluster.workers. */
+export function disconnect(callback?: () => void) {
+  return cluster.disconnect(callback);
+}
+/** Spawn a new worker process. */
+export function fork(env?: Record<string, unknown>): Worker {
+  return cluster.fork(env);
+}
+/** Deprecated alias for .setupPrimary(). */
+export function setupMaster(options?: ClusterSettings) {
+  return setupPrimary(options);
+}
+/** setupPrimary is used to change the default 'fork' behavior. Once called,
+ * the settings will be present in cluster.settings. */
+export function setupPrimary(options?: ClusterSettings) {
+  return cluster.setupPrimary(options);
+}
+
+function workerId(): number | undefined {
+  // Only forked processes have an IPC channel, and they run with all
+  // permissions.
+  if (!("send" in process)) {
+    return undefined;
+  }
+  const id = Deno.env.get("NODE_UNIQUE_ID");
+  return id === undefined ? undefined : Number(id);
+}
+
+// Should be called only once, when the Node.js compatibility layer is
+// initialized, after the process is bootstrapped.
+internals.__initCluster = function () {
+  const id = workerId();
+  isPrimary = isMaster = id === undefined;
+  isWorker = !isPrimary;
+
+  if (isPrimary) {
+    initPrimary(cluster);
+    settings = cluster.settings;
+    workers = cluster.workers;
+  } else {
+    worker = initChild(id!);
+    // Node.js deletes the NODE_UNIQUE_ID of workers, so that their own
+    // children aren't workers as well.
+    Deno.env.delete("NODE_UNIQUE_ID");
+  }
+
+  Object.assign(cluster, {
+    isPrimary,
+    isWorker,
+    isMaster,
+    schedulingPolicy,
+    SCHED_NONE,
+    SCHED_RR,
+    Worker,
+    worker,
+  });
+  delete internals.__initCluster;
 };
+
+export default cluster;
@@ -1,66 +1,1349 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 // Copyright Joyent and Node contributors. All rights reserved. MIT license.
 
+// deno-lint-ignore-file no-explicit-any
+
 import { notImplemented } from "internal:deno_node/polyfills/_utils.ts";
+import { EventEmitter } from "internal:deno_node/polyfills/events.ts";
+import { Buffer } from "internal:deno_node/polyfills/buffer.ts";
+import {
+  Duplex,
+  Readable,
+  Stream,
+} from "internal:deno_node/polyfills/stream.ts";
+import { nextTick } from "internal:deno_node/polyfills/_next_tick.ts";
+import { _normalizeArgs } from "internal:deno_node/polyfills/net.ts";
+import { kStreamBaseField } from "internal:deno_node/polyfills/internal_binding/stream_wrap.ts";
+import {
+  validateFunction,
+  validateNumber,
+  validateObject,
+  validatePort,
+} from "internal:deno_node/polyfills/internal/validators.mjs";
+import {
+  ERR_HTTP2_HEADERS_SENT,
+  ERR_HTTP2_INVALID_PACKED_SETTINGS_LENGTH,
+  ERR_HTTP2_INVALID_SESSION,
+  ERR_HTTP2_INVALID_SETTING_VALUE,
+  ERR_HTTP2_INVALID_STREAM,
+  ERR_HTTP2_PING_LENGTH,
+  ERR_HTTP2_SOCKET_UNBOUND,
+  ERR_HTTP2_TRAILERS_ALREADY_SENT,
+  ERR_HTTP2_TRAILERS_NOT_READY,
+  ERR_HTTP2_UNSUPPORTED_PROTOCOL,
+  ERR_SERVER_NOT_RUNNING,
+} from "internal:deno_node/polyfills/internal/errors.ts";
+
+const { core } = globalThis.__bootstrap;
+const promiseIdSymbol = Symbol.for("Deno.core.internalPromiseId");
+
+export const constants = {
+  NGHTTP2_SESSION_SERVER: 0,
+  NGHTTP2_SESSION_CLIENT: 1,
+  NGHTTP2_NO_ERROR: 0,
+  NGHTTP2_PROTOCOL_ERROR: 1,
+  NGHTTP2_INTERNAL_ERROR: 2,
+  NGHTTP2_FLOW_CONTROL_ERROR: 3,
+  NGHTTP2_SETTINGS_TIMEOUT: 4,
+  NGHTTP2_STREAM_CLOSED: 5,
+  NGHTTP2_FRAME_SIZE_ERROR: 6,
+  NGHTTP2_REFUSED_STREAM: 7,
+  NGHTTP2_CANCEL: 8,
+  NGHTTP2_COMPRESSION_ERROR: 9,
+  NGHTTP2_CONNECT_ERROR: 10,
+  NGHTTP2_ENHANCE_YOUR_CALM: 11,
+  NGHTTP2_INADEQUATE_SECURITY: 12,
+  NGHTTP2_HTTP_1_1_REQUIRED: 13,
+  NGHTTP2_DEFAULT_WEIGHT: 16,
+  NGHTTP2_SETTINGS_HEADER_TABLE_SIZE: 1,
+  NGHTTP2_SETTINGS_ENABLE_PUSH: 2,
+  NGHTTP2_SETTINGS_MAX_CONCURRENT_STREAMS: 3,
+  NGHTTP2_SETTINGS_INITIAL_WINDOW_SIZE: 4,
+  NGHTTP2_SETTINGS_MAX_FRAME_SIZE: 5,
+  NGHTTP2_SETTINGS_MAX_HEADER_LIST_SIZE: 6,
+  NGHTTP2_SETTINGS_ENABLE_CONNECT_PROTOCOL: 8,
+  DEFAULT_SETTINGS_HEADER_TABLE_SIZE: 4096,
+  DEFAULT_SETTINGS_ENABLE_PUSH: 1,
+  DEFAULT_SETTINGS_MAX_CONCURRENT_STREAMS: 4294967295,
+  DEFAULT_SETTINGS_INITIAL_WINDOW_SIZE: 65535,
+  DEFAULT_SETTINGS_MAX_FRAME_SIZE: 16384,
+  DEFAULT_SETTINGS_MAX_HEADER_LIST_SIZE: 65535,
+  DEFAULT_SETTINGS_ENABLE_CONNECT_PROTOCOL: 0,
+  MAX_MAX_FRAME_SIZE: 16777215,
+  MIN_MAX_FRAME_SIZE: 16384,
+  MAX_INITIAL_WINDOW_SIZE: 2147483647,
+  HTTP2_HEADER_STATUS: ":status",
+  HTTP2_HEADER_METHOD: ":method",
+  HTTP2_HEADER_AUTHORITY: ":authority",
+  HTTP2_HEADER_SCHEME: ":scheme",
+  HTTP2_HEADER_PATH: ":path",
+  HTTP2_HEADER_PROTOCOL: ":protocol",
+  HTTP2_HEADER_ACCEPT: "accept",
+  HTTP2_HEADER_ACCEPT_ENCODING: "accept-encoding",
+  HTTP2_HEADER_ACCEPT_LANGUAGE: "accept-language",
+  HTTP2_HEADER_AUTHORIZATION: "authorization",
+  HTTP2_HEADER_CACHE_CONTROL: "cache-control",
+  HTTP2_HEADER_CONNECTION: "connection",
+  HTTP2_HEADER_CONTENT_ENCODING: "content-encoding",
+  HTTP2_HEADER_CONTENT_LENGTH: "content-length",
+  HTTP2_HEADER_CONTENT_TYPE: "content-type",
+  HTTP2_HEADER_COOKIE: "cookie",
+  HTTP2_HEADER_DATE: "date",
+  HTTP2_HEADER_ETAG: "etag",
+  HTTP2_HEADER_HOST: "host",
+  HTTP2_HEADER_KEEP_ALIVE: "keep-alive",
+  HTTP2_HEADER_LAST_MODIFIED: "last-modified",
+  HTTP2_HEADER_LOCATION: "location",
+  HTTP2_HEADER_PROXY_CONNECTION: "proxy-connection",
+  HTTP2_HEADER_SET_COOKIE: "set-cookie",
+  HTTP2_HEADER_TE: "te",
+  HTTP2_HEADER_TRAILER: "trailer",
+  HTTP2_HEADER_TRANSFER_ENCODING: "transfer-encoding",
+  HTTP2_HEADER_UPGRADE: "upgrade",
+  HTTP2_HEADER_USER_AGENT: "user-agent",
+  HTTP2_METHOD_CONNECT: "CONNECT",
+  HTTP2_METHOD_DELETE: "DELETE",
+  HTTP2_METHOD_GET: "GET",
+  HTTP2_METHOD_HEAD: "HEAD",
+  HTTP2_METHOD_OPTIONS: "OPTIONS",
+  HTTP2_METHOD_PATCH: "PATCH",
+  HTTP2_METHOD_POST: "POST",
+  HTTP2_METHOD_PUT: "PUT",
+  HTTP_STATUS_CONTINUE: 100,
+  HTTP_STATUS_OK: 200,
+  HTTP_STATUS_NO_CONTENT: 204,
+  HTTP_STATUS_MOVED_PERMANENTLY: 301,
+  HTTP_STATUS_FOUND: 302,
+  HTTP_STATUS_NOT_MODIFIED: 304,
+  HTTP_STATUS_BAD_REQUEST: 400,
+  HTTP_STATUS_UNAUTHORIZED: 401,
+  HTTP_STATUS_FORBIDDEN: 403,
+  HTTP_STATUS_NOT_FOUND: 404,
+  HTTP_STATUS_METHOD_NOT_ALLOWED: 405,
+  HTTP_STATUS_INTERNAL_SERVER_ERROR: 500,
+  HTTP_STATUS_NOT_IMPLEMENTED: 501,
+  HTTP_STATUS_BAD_GATEWAY: 502,
+  HTTP_STATUS_SERVICE_UNAVAILABLE: 503,
+  HTTP_STATUS_GATEWAY_TIMEOUT: 504,
+};
+
+const {
+  NGHTTP2_CANCEL,
+  NGHTTP2_INTERNAL_ERROR,
+  NGHTTP2_NO_ERROR,
+  NGHTTP2_SESSION_CLIENT,
+  NGHTTP2_SESSION_SERVER,
+} = constants;
+
+export const sensitiveHeaders = Symbol("nodejs.http2.sensitiveHeaders");
 
-export class Http2Session {
-  constructor() {
-    notImplemented("Http2Session.prototype.constructor");
+export interface Settings {
+  headerTableSize?: number;
+  enablePush?: boolean;
+  initialWindowSize?: number;
+  maxFrameSize?: number;
+  maxConcurrentStreams?: number;
+  maxHeaderListSize?: number;
+  maxHeaderSize?: number;
+  enableConnectProtocol?: boolean;
+}
+
+export function getDefaultSettings(): Settings {
+  return {
+    headerTableSize: constants.DEFAULT_SETTINGS_HEADER_TABLE_SIZE,
+    enablePush: true,
+    initialWindowSize: constants.DEFAULT_SETTINGS_INITIAL_WINDOW_SIZE,
+    maxFrameSize: constants.DEFAULT_SETTINGS_MAX_FRAME_SIZE,
+    maxConcurrentStreams: constants.DEFAULT_SETTINGS_MAX_CONCURRENT_STREAMS,
+    maxHeaderListSize: constants.DEFAULT_SETTINGS_MAX_HEADER_LIST_SIZE,
+    maxHeaderSize: constants.DEFAULT_SETTINGS_MAX_HEADER_LIST_SIZE,
+    enableConnectProtocol: false,
+  };
+}
+
+const settingIds: [keyof Settings, number][] = [
+  ["headerTableSize", constants.NGHTTP2_SETTINGS_HEADER_TABLE_SIZE],
+  ["enablePush", constants.NGHTTP2_SETTINGS_ENABLE_PUSH],
+  ["maxConcurrentStreams", constants.NGHTTP2_SETTINGS_MAX_CONCURRENT_STREAMS],
+  ["initialWindowSize", constants.NGHTTP2_SETTINGS_INITIAL_WINDOW_SIZE],
+  ["maxFrameSize", constants.NGHTTP2_SETTINGS_MAX_FRAME_SIZE],
+  ["maxHeaderListSize", constants.NGHTTP2_SETTINGS_MAX_HEADER_LIST_SIZE],
+  [
+    "enableConnectProtocol",
+    constants.NGHTTP2_SETTINGS_ENABLE_CONNECT_PROTOCOL,
+  ],
+];
+
+/** Serializes settings as the payload of a SETTINGS frame. */
+export function getPackedSettings(settings: Settings = {}): Buffer {
+  const entries: [number, number][] = [];
+  for (const [name, id] of settingIds) {
+    const value = name === "maxHeaderListSize"
+      ? settings.maxHeaderListSize ?? settings.maxHeaderSize
+      : settings[name];
+    if (value !== undefined) {
+      entries.push([id, Number(value)]);
+    }
   }
+  const buffer = Buffer.alloc(entries.length * 6);
+  entries.forEach(([id, value], i) => {
+    buffer.writeUInt16BE(id, i * 6);
+    buffer.writeUInt32BE(value, i * 6 + 2);
+  });
+  return buffer;
 }
-export class ServerHttp2Session {
-  constructor() {
-    notImplemented("ServerHttp2Session");
+
+export function getUnpackedSettings(buffer: Uint8Array): Settings {
+  if (buffer.byteLength % 6 !== 0) {
+    throw new ERR_HTTP2_INVALID_PACKED_SETTINGS_LENGTH();
+  }
+  const view = new DataView(
+    buffer.buffer,
+    buffer.byteOffset,
+    buffer.byteLength,
+  );
+  const settings: Settings = {};
+  for (let offset = 0; offset < buffer.byteLength; offset += 6) {
+    const id = view.getUint16(offset);
+    const value = view.getUint32(offset + 2);
+    const entry = settingIds.find(([, settingId]) => settingId === id);
+    if (entry === undefined) {
+      continue;
+    }
+    const [name] = entry;
+    if (name === "enablePush" || name === "enableConnectProtocol") {
+      settings[name] = value !== 0;
+    } else {
+      settings[name] = value;
+    }
+    if (name === "maxHeaderListSize") {
+      settings.maxHeaderSize = value;
+    }
   }
+  return settings;
 }
-export class ClientHttp2Session {
-  constructor() {
-    notImplemented("ClientHttp2Session");
+
+/** The settings which can be applied to a native session. */
+function toNativeSettings(settings: Settings = {}) {
+  return {
+    enablePush: settings.enablePush,
+    initialWindowSize: settings.initialWindowSize,
+    maxConcurrentStreams: settings.maxConcurrentStreams,
+    maxFrameSize: settings.maxFrameSize,
+    maxHeaderListSize: settings.maxHeaderListSize ?? settings.maxHeaderSize,
+  };
+}
+
+type Headers = Record<string, string | string[] | number | undefined>;
+type HeaderList = [string, string][];
+
+function toHeaderList(headers: Headers = {}): HeaderList {
+  const list: HeaderList = [];
+  // `Object.keys()` leaves out the `sensitiveHeaders` symbol
+  for (const name of Object.keys(headers)) {
+    const value = headers[name];
+    if (value === undefined) {
+      continue;
+    }
+    const lowerName = name.toLowerCase();
+    if (Array.isArray(value)) {
+      for (const item of value) {
+        list.push([lowerName, String(item)]);
+      }
+    } else {
+      list.push([lowerName, String(value)]);
+    }
   }
+  return list;
 }
-export class Http2Stream {
-  constructor() {
-    notImplemented("Http2Stream");
+
+function fromHeaderList(list: HeaderList): Headers {
+  const headers: Headers = Object.create(null);
+  for (const [name, value] of list) {
+    const existing = headers[name];
+    if (existing === undefined) {
+      headers[name] = name === "set-cookie" ? [value] : value;
+    } else if (Array.isArray(existing)) {
+      existing.push(value);
+    } else {
+      headers[name] = [String(existing), value];
+    }
   }
+  return headers;
 }
-export class ClientHttp2Stream {
-  constructor() {
-    notImplemented("ClientHttp2Stream");
+
+const kOpened = Symbol("kOpened");
+const kWritable = Symbol("kWritable");
+const kReadable = Symbol("kReadable");
+const kEndedLocally = Symbol("kEndedLocally");
+const kWaitForTrailers = Symbol("kWaitForTrailers");
+const kSession = Symbol("kSession");
+const kTrackStream = Symbol("kTrackStream");
+const kStreamClosed = Symbol("kStreamClosed");
+const kDrive = Symbol("kDrive");
+
+export class Http2Stream extends Duplex {
+  id?: number;
+  rstCode?: number;
+  sentHeaders: Headers = {};
+  sentTrailers?: Headers;
+  [kSession]: Http2Session;
+  /** Resolves to the resource id of the stream once it's opened. */
+  [kOpened]: Promise<number>;
+  /** Resolves to the resource id once data can be written. */
+  [kWritable]: Promise<number>;
+  /** Resolves to the resource id once data can be read. */
+  [kReadable]: Promise<number>;
+  /** Whether the stream was already ended with its headers. */
+  [kEndedLocally] = false;
+  [kWaitForTrailers] = false;
+  #rid: number | null = null;
+  #reading = false;
+  #resumeRead: (() => void) | null = null;
+  #finalCallback: ((err?: Error | null) => void) | null = null;
+
+  constructor(session: Http2Session, opened: Promise<number>) {
+    super({ allowHalfOpen: true });
+    this[kSession] = session;
+    this[kOpened] = opened.then((rid) => {
+      if (this.destroyed) {
+        core.tryClose(rid);
+        throw new ERR_HTTP2_INVALID_STREAM();
+      }
+      this.#rid = rid;
+      return rid;
+    });
+    this[kWritable] = this[kReadable] = this[kOpened];
+    this[kOpened].catch((err) => this.destroy(err));
+    session[kTrackStream](this);
+  }
+
+  get session(): Http2Session {
+    return this[kSession];
+  }
+
+  get pending(): boolean {
+    return this.#rid === null;
+  }
+
+  get state() {
+    return {
+      localClose: this.writableFinished ? 1 : 0,
+      remoteClose: this.readableEnded ? 1 : 0,
+    };
+  }
+
+  priority() {
+    // Stream priorities are deprecated by RFC 9113 and not supported.
+  }
+
+  setTimeout() {
+    notImplemented("Http2Stream.setTimeout");
+  }
+
+  sendTrailers(headers: Headers) {
+    if (this.destroyed) {
+      throw new ERR_HTTP2_INVALID_STREAM();
+    }
+    if (this.sentTrailers !== undefined) {
+      throw new ERR_HTTP2_TRAILERS_ALREADY_SENT();
+    }
+    const callback = this.#finalCallback;
+    if (callback === null) {
+      throw new ERR_HTTP2_TRAILERS_NOT_READY();
+    }
+    this.#finalCallback = null;
+    this.sentTrailers = headers;
+    const list = toHeaderList(headers);
+    const rid = this.#rid!;
+    // Without trailers the stream is ended with an empty DATA frame.
+    const sent = list.length === 0
+      ? core.opAsync("op_http2_stream_write", rid, new Uint8Array(), true)
+      : core.opAsync("op_http2_stream_send_trailers", rid, list);
+    sent.then(() => callback(), callback);
+  }
+
+  close(code = NGHTTP2_NO_ERROR, callback?: () => void) {
+    if (callback !== undefined) {
+      this.once("close", callback);
+    }
+    if (this.destroyed || this.rstCode !== undefined) {
+      return;
+    }
+    this.rstCode = code;
+    this[kOpened]
+      .then((rid) => core.opAsync("op_http2_stream_reset", rid, code))
+      .finally(() => this.destroy())
+      .catch(() => {});
+  }
+
+  _write(
+    chunk: Buffer,
+    _encoding: string,
+    callback: (err?: Error | null) => void,
+  ) {
+    this[kWritable]
+      .then((rid) => core.opAsync("op_http2_stream_write", rid, chunk, false))
+      .then(() => callback(), callback);
+  }
+
+  _final(callback: (err?: Error | null) => void) {
+    this[kWritable].then((rid) => {
+      if (this[kEndedLocally]) {
+        callback();
+      } else if (this[kWaitForTrailers]) {
+        this.#finalCallback = callback;
+        this.emit("wantTrailers");
+      } else {
+        return core.opAsync("op_http2_stream_write", rid, new Uint8Array(), true)
+          .then(() => callback());
+      }
+    }).catch(callback);
+  }
+
+  _read() {
+    if (this.#resumeRead !== null) {
+      const resume = this.#resumeRead;
+      this.#resumeRead = null;
+      resume();
+    } else if (!this.#reading) {
+      this.#reading = true;
+      this.#readLoop();
+    }
+  }
+
+  async #readLoop() {
+    try {
+      const rid = await this[kReadable];
+      while (true) {
+        const chunk: Uint8Array | null = await core.opAsync(
+          "op_http2_stream_read",
+          rid,
+        );
+        if (chunk === null) {
+          break;
+        }
+        const buffer = Buffer.from(
+          chunk.buffer,
+          chunk.byteOffset,
+          chunk.byteLength,
+        );
+        if (!this.push(buffer)) {
+          await new Promise<void>((resolve) => this.#resumeRead = resolve);
+        }
+      }
+      const trailers: HeaderList | null = await core.opAsync(
+        "op_http2_stream_get_trailers",
+        rid,
+      );
+      if (trailers !== null) {
+        this.emit("trailers", fromHeaderList(trailers), 0);
+      }
+      this.push(null);
+    } catch (err) {
+      this.destroy(err as Error);
+    }
+  }
+
+  _destroy(err: Error | null, callback: (err: Error | null) => void) {
+    const rid = this.#rid;
+    if (rid !== null) {
+      this.#rid = null;
+      if (
+        this.rstCode === undefined &&
+        !(this.writableFinished && this.readableEnded)
+      ) {
+        this.rstCode = err ? NGHTTP2_INTERNAL_ERROR : NGHTTP2_CANCEL;
+        core.opAsync("op_http2_stream_reset", rid, this.rstCode)
+          .catch(() => {})
+          .finally(() => core.tryClose(rid));
+      } else {
+        core.tryClose(rid);
+      }
+    }
+    this[kSession][kStreamClosed](this);
+    callback(err);
   }
 }
-export class ServerHttp2Stream {
-  constructor() {
-    notImplemented("ServerHttp2Stream");
+
+function isPayloadMeaningless(method: unknown) {
+  return method === "GET" || method === "HEAD" || method === "DELETE";
+}
+
+export class ClientHttp2Stream extends Http2Stream {
+  constructor(
+    session: ClientHttp2Session,
+    sessionRid: Promise<number>,
+    headers: HeaderList,
+    endStream: boolean,
+  ) {
+    const request: Promise<[number, number]> = sessionRid.then((rid) =>
+      core.opAsync("op_http2_client_request", rid, headers, endStream)
+    );
+    super(session, request.then(([rid]) => rid));
+    this[kEndedLocally] = endStream;
+    request.then(([, id]) => {
+      this.id = id;
+      this.emit("ready");
+    }, () => {});
+    this[kReadable] = this[kOpened].then(async (rid) => {
+      const [status, list]: [number, HeaderList] = await core.opAsync(
+        "op_http2_client_get_response",
+        rid,
+      );
+      const headers = fromHeaderList(list);
+      headers[":status"] = status;
+      this.emit("response", headers, 0);
+      return rid;
+    });
+    this[kReadable].catch((err) => this.destroy(err));
+    if (endStream) {
+      this.end();
+    }
   }
 }
-export class Http2Server {
-  constructor() {
-    notImplemented("Http2Server");
+
+export class ServerHttp2Stream extends Http2Stream {
+  headersSent = false;
+  #respondWith!: (responded: Promise<number>) => void;
+
+  constructor(session: ServerHttp2Session, rid: number, id: number) {
+    super(session, Promise.resolve(rid));
+    this.id = id;
+    this[kWritable] = new Promise((resolve) => this.#respondWith = resolve);
+  }
+
+  respond(
+    headers: Headers = {},
+    options: { endStream?: boolean; waitForTrailers?: boolean } = {},
+  ) {
+    if (this.destroyed) {
+      throw new ERR_HTTP2_INVALID_STREAM();
+    }
+    if (this.headersSent) {
+      throw new ERR_HTTP2_HEADERS_SENT();
+    }
+    this.headersSent = true;
+    this.sentHeaders = headers;
+    const { ":status": status = 200, ...responseHeaders } = headers;
+    const endStream = !!options.endStream;
+    this[kEndedLocally] = endStream;
+    this[kWaitForTrailers] = !!options.waitForTrailers;
+
+    const responded = this[kOpened].then(async (rid) => {
+      await core.opAsync(
+        "op_http2_server_respond",
+        rid,
+        Number(status),
+        toHeaderList(responseHeaders),
+        endStream,
+      );
+      return rid;
+    });
+    responded.catch((err) => this.destroy(err));
+    this.#respondWith(responded);
+    if (endStream) {
+      this.end();
+    }
+  }
+
+  // Data written before responding is sent with the default headers, like
+  // in Node.js.
+  _write(
+    chunk: Buffer,
+    encoding: string,
+    callback: (err?: Error | null) => void,
+  ) {
+    if (!this.headersSent) {
+      this.respond();
+    }
+    super._write(chunk, encoding, callback);
+  }
+
+  _final(callback: (err?: Error | null) => void) {
+    if (!this.headersSent) {
+      this.respond({}, { endStream: true });
+    }
+    super._final(callback);
+  }
+
+  pushStream() {
+    notImplemented("ServerHttp2Stream.pushStream");
+  }
+
+  additionalHeaders() {
+    notImplemented("ServerHttp2Stream.additionalHeaders");
+  }
+
+  respondWithFD() {
+    notImplemented("ServerHttp2Stream.respondWithFD");
+  }
+
+  respondWithFile() {
+    notImplemented("ServerHttp2Stream.respondWithFile");
   }
 }
-export class Http2SecureServer {
-  constructor() {
-    notImplemented("Http2SecureServer");
+
+export class Http2Session extends EventEmitter {
+  readonly type: number;
+  encrypted = false;
+  alpnProtocol?: string;
+  localSettings: Settings;
+  remoteSettings: Settings = getDefaultSettings();
+  /** Resolves to the resource id of the session once it's established. */
+  [kOpened]: Promise<number>;
+  #rid: number | null = null;
+  #streams = new Set<Http2Stream>();
+  #closed = false;
+  #destroyed = false;
+  #unref = false;
+  #promiseId: number | null = null;
+
+  constructor(type: number, settings: Settings = {}, opened: Promise<number>) {
+    super();
+    this.type = type;
+    this.localSettings = { ...getDefaultSettings(), ...settings };
+    this[kOpened] = opened.then((rid) => {
+      if (this.#destroyed) {
+        core.tryClose(rid);
+        throw new ERR_HTTP2_INVALID_SESSION();
+      }
+      this.#rid = rid;
+      return rid;
+    });
+  }
+
+  get closed(): boolean {
+    return this.#closed;
+  }
+
+  get destroyed(): boolean {
+    return this.#destroyed;
+  }
+
+  get connecting(): boolean {
+    return this.#rid === null && !this.#destroyed;
+  }
+
+  get pendingSettingsAck(): boolean {
+    return false;
+  }
+
+  [kTrackStream](stream: Http2Stream) {
+    this.#streams.add(stream);
+  }
+
+  [kStreamClosed](stream: Http2Stream) {
+    this.#streams.delete(stream);
+    if (this.#closed && this.#streams.size === 0) {
+      this.destroy();
+    }
+  }
+
+  /** Keeps track of the op which drives the connection, for `unref()`. */
+  [kDrive]<T>(promise: Promise<T>): Promise<T> {
+    this.#promiseId = (promise as any)[promiseIdSymbol];
+    if (this.#unref) {
+      core.unrefOp(this.#promiseId);
+    }
+    return promise;
+  }
+
+  ref() {
+    this.#unref = false;
+    if (this.#promiseId !== null) {
+      core.refOp(this.#promiseId);
+    }
+  }
+
+  unref() {
+    this.#unref = true;
+    if (this.#promiseId !== null) {
+      core.unrefOp(this.#promiseId);
+    }
+  }
+
+  /**
+   * Sends a PING frame. The payload is only echoed to the callback, as the
+   * native session uses opaque payloads.
+   */
+  ping(
+    payload?: Uint8Array | ((...args: any[]) => void),
+    callback?: (err: Error | null, duration: number, payload: Buffer) => void,
+  ): boolean {
+    if (typeof payload === "function") {
+      callback = payload;
+      payload = undefined;
+    }
+    if (payload !== undefined && payload.byteLength !== 8) {
+      throw new ERR_HTTP2_PING_LENGTH();
+    }
+    if (this.#destroyed) {
+      throw new ERR_HTTP2_INVALID_SESSION();
+    }
+    const echo = payload === undefined
+      ? Buffer.alloc(8)
+      : Buffer.from(payload.buffer, payload.byteOffset, payload.byteLength);
+    const start = performance.now();
+    this[kOpened]
+      .then((rid) => core.opAsync("op_http2_ping", rid))
+      .then(
+        () => callback?.(null, performance.now() - start, echo),
+        (err) => callback?.(err, 0, echo),
+      );
+    return true;
+  }
+
+  /**
+   * Sends a SETTINGS frame. Only `initialWindowSize` can be changed once the
+   * session is established, and the callback doesn't wait for the peer to
+   * acknowledge the settings.
+   */
+  settings(
+    settings: Settings,
+    callback?: (
+      err: Error | null,
+      settings: Settings,
+      duration: number,
+    ) => void,
+  ) {
+    if (this.#destroyed) {
+      throw new ERR_HTTP2_INVALID_SESSION();
+    }
+    validateObject(settings, "settings");
+    if (callback !== undefined) {
+      validateFunction(callback, "callback");
+    }
+    const { initialWindowSize, ...rest } = settings;
+    for (const [name, value] of Object.entries(rest)) {
+      if (value !== undefined) {
+        notImplemented(`Http2Session.settings with "${name}"`);
+      }
+    }
+    if (
+      initialWindowSize !== undefined &&
+      (!Number.isInteger(initialWindowSize) || initialWindowSize < 0 ||
+        initialWindowSize > constants.MAX_INITIAL_WINDOW_SIZE)
+    ) {
+      throw new ERR_HTTP2_INVALID_SETTING_VALUE(
+        "initialWindowSize",
+        initialWindowSize,
+        0,
+        constants.MAX_INITIAL_WINDOW_SIZE,
+      );
+    }
+    const start = performance.now();
+    this[kOpened]
+      .then((rid) => {
+        if (initialWindowSize !== undefined) {
+          core.ops.op_http2_settings(rid, initialWindowSize);
+          this.localSettings = { ...this.localSettings, initialWindowSize };
+        }
+      })
+      .then(
+        () => {
+          this.emit("localSettings", this.localSettings);
+          callback?.(null, this.localSettings, performance.now() - start);
+        },
+        (err) => callback?.(err, this.localSettings, 0),
+      );
+  }
+
+  /**
+   * Sends a GOAWAY frame, which only server sessions can do. The last stream
+   * id and the opaque data are chosen by the native session.
+   */
+  goaway(
+    code: number = NGHTTP2_NO_ERROR,
+    _lastStreamID?: number,
+    _opaqueData?: ArrayBufferView,
+  ) {
+    if (this.#destroyed) {
+      throw new ERR_HTTP2_INVALID_SESSION();
+    }
+    if (this.type !== NGHTTP2_SESSION_SERVER) {
+      notImplemented("Http2Session.goaway on client sessions");
+    }
+    validateNumber(code, "code");
+    this[kOpened]
+      .then((rid) => core.ops.op_http2_goaway(rid, code))
+      // the session was closed in the meantime
+      .catch(() => {});
+  }
+
+  setTimeout() {
+    notImplemented("Http2Session.setTimeout");
+  }
+
+  /** Closes the session once its open streams are done. */
+  close(callback?: () => void) {
+    if (this.#closed || this.#destroyed) {
+      return;
+    }
+    this.#closed = true;
+    if (callback !== undefined) {
+      this.once("close", callback);
+    }
+    if (this.#streams.size === 0) {
+      this.destroy();
+    }
+  }
+
+  destroy(error?: Error, _code?: number) {
+    if (this.#destroyed) {
+      return;
+    }
+    this.#destroyed = true;
+    this.#closed = true;
+    for (const stream of this.#streams) {
+      stream.destroy(error);
+    }
+    if (this.#rid !== null) {
+      core.tryClose(this.#rid);
+    }
+    nextTick(() => {
+      if (error) {
+        this.emit("error", error);
+      }
+      this.emit("close");
+    });
   }
 }
-export function createServer() {}
-export function createSecureServer() {}
-export function connect() {}
-export const constants = {};
-export function getDefaultSettings() {}
-export function getPackedSettings() {}
-export function getUnpackedSettings() {}
-export const sensitiveHeaders = Symbol("nodejs.http2.sensitiveHeaders");
-export class Http2ServerRequest {
-  constructor() {
-    notImplemented("Http2ServerRequest");
+
+interface ClientSessionOptions {
+  settings?: Settings;
+  ca?: string | Buffer | (string | Buffer)[];
+  servername?: string;
+  createConnection?: (url: URL, options: ClientSessionOptions) => any;
+}
+
+/** Takes over the connection of a socket from `options.createConnection`. */
+function takeSocketConnection(socket: any): Promise<number> {
+  // keep the socket from reading, so its connection is free to be taken
+  socket.pause();
+  return new Promise((resolve, reject) => {
+    const onConnect = () => {
+      socket.off("error", reject);
+      const conn = socket._handle?.[kStreamBaseField];
+      if (conn === undefined) {
+        reject(new ERR_HTTP2_SOCKET_UNBOUND());
+      } else {
+        resolve(conn.rid);
+      }
+    };
+    if (!socket.connecting) {
+      onConnect();
+      return;
+    }
+    socket.once(socket.encrypted ? "secureConnect" : "connect", onConnect);
+    socket.once("error", reject);
+  });
+}
+
+async function connectSession(
+  url: URL,
+  options: ClientSessionOptions,
+): Promise<number> {
+  const hostname = url.hostname.replace(/^\[|\]$/g, "");
+  const secure = url.protocol === "https:";
+  let rid;
+  if (options.createConnection !== undefined) {
+    rid = await takeSocketConnection(options.createConnection(url, options));
+  } else {
+    const port = url.port === "" ? (secure ? 443 : 80) : Number(url.port);
+    rid = (await Deno.connect({ hostname, port })).rid;
   }
+  // A socket from `createConnection` is expected to be encrypted already.
+  const tls = secure && options.createConnection === undefined
+    ? {
+      serverName: options.servername ?? hostname,
+      caCerts: [options.ca ?? []].flat().map(String),
+    }
+    : undefined;
+  return await core.opAsync(
+    "op_http2_connect",
+    rid,
+    toNativeSettings(options.settings),
+    tls,
+  );
 }
-export class Http2ServerResponse {
-  constructor() {
-    notImplemented("Http2ServerResponse");
+
+export class ClientHttp2Session extends Http2Session {
+  #url: URL;
+
+  constructor(
+    url: URL,
+    options: ClientSessionOptions = {},
+    listener?: (session: ClientHttp2Session) => void,
+  ) {
+    super(
+      NGHTTP2_SESSION_CLIENT,
+      options.settings,
+      connectSession(url, options),
+    );
+    this.#url = url;
+    this.encrypted = url.protocol === "https:";
+    this.alpnProtocol = this.encrypted ? "h2" : "h2c";
+    if (listener !== undefined) {
+      this.once("connect", listener);
+    }
+    this[kOpened].then((rid) => {
+      this.emit("connect", this, null);
+      return this[kDrive](
+        core.opAsync("op_http2_poll_client_connection", rid),
+      );
+    }).then(() => this.destroy(), (err) => this.destroy(err));
+  }
+
+  request(
+    headers: Headers = {},
+    options: { endStream?: boolean; waitForTrailers?: boolean } = {},
+  ): ClientHttp2Stream {
+    if (this.destroyed) {
+      throw new ERR_HTTP2_INVALID_SESSION();
+    }
+    const requestHeaders: Headers = {
+      ":method": "GET",
+      ":scheme": this.#url.protocol.slice(0, -1),
+      ":authority": this.#url.host,
+      ":path": "/",
+      ...headers,
+    };
+    const endStream = options.endStream ??
+      isPayloadMeaningless(requestHeaders[":method"]);
+    const stream = new ClientHttp2Stream(
+      this,
+      this[kOpened],
+      toHeaderList(requestHeaders),
+      endStream,
+    );
+    stream.sentHeaders = requestHeaders;
+    stream[kWaitForTrailers] = !!options.waitForTrailers;
+    return stream;
   }
 }
+
+interface ServerTlsOptions {
+  cert: string;
+  key: string;
+}
+
+export class ServerHttp2Session extends Http2Session {
+  constructor(
+    conn: Deno.Conn,
+    settings: Settings | undefined,
+    tls: ServerTlsOptions | undefined,
+  ) {
+    super(
+      NGHTTP2_SESSION_SERVER,
+      settings,
+      core.opAsync(
+        "op_http2_accept",
+        conn.rid,
+        toNativeSettings(settings),
+        tls,
+      ),
+    );
+    this.encrypted = tls !== undefined;
+    this.alpnProtocol = this.encrypted ? "h2" : "h2c";
+    this[kOpened].then((rid) => {
+      this.emit("connect", this, null);
+      return this.#acceptStreams(rid);
+    }).then(() => this.destroy(), (err) => this.destroy(err));
+  }
+
+  async #acceptStreams(rid: number) {
+    while (true) {
+      const accepted: [number, number, HeaderList] | null = await this
+        [kDrive](core.opAsync("op_http2_server_accept_stream", rid));
+      if (accepted === null) {
+        return;
+      }
+      const [streamRid, id, list] = accepted;
+      const stream = new ServerHttp2Stream(this, streamRid, id);
+      this.emit("stream", stream, fromHeaderList(list), 0);
+    }
+  }
+}
+
+interface ServerOptions {
+  settings?: Settings;
+  cert?: string | Buffer;
+  key?: string | Buffer;
+}
+
+type RequestListener = (
+  request: Http2ServerRequest,
+  response: Http2ServerResponse,
+) => void;
+
+class Http2ServerBase extends EventEmitter {
+  listening = false;
+  #options: ServerOptions;
+  #tls: ServerTlsOptions | undefined;
+  #listener?: Deno.Listener;
+  #sessions = new Set<ServerHttp2Session>();
+
+  constructor(
+    options: ServerOptions,
+    tls: ServerTlsOptions | undefined,
+    requestListener?: RequestListener,
+  ) {
+    super();
+    this.#options = options;
+    this.#tls = tls;
+    if (requestListener !== undefined) {
+      this.on("request", requestListener);
+    }
+  }
+
+  listen(...args: unknown[]): this {
+    const [options, cb] = _normalizeArgs(args) as [any, any];
+    if (cb !== null) {
+      this.once("listening", cb);
+    }
+    let port = 0;
+    if (typeof options.port === "number" || typeof options.port === "string") {
+      validatePort(options.port, "options.port");
+      port = options.port | 0;
+    }
+    this.#listener = Deno.listen({ port, hostname: options.host ?? "0.0.0.0" });
+    this.listening = true;
+    nextTick(() => {
+      this.emit("listening");
+      this.#acceptLoop(this.#listener!);
+    });
+    return this;
+  }
+
+  address() {
+    if (this.#listener === undefined) {
+      return null;
+    }
+    const addr = this.#listener.addr as Deno.NetAddr;
+    return {
+      port: addr.port,
+      address: addr.hostname,
+      family: addr.hostname.includes(":") ? "IPv6" : "IPv4",
+    };
+  }
+
+  async #acceptLoop(listener: Deno.Listener) {
+    try {
+      for await (const conn of listener) {
+        this.#handleConnection(conn);
+      }
+    } catch {
+      // the listener was closed
+    }
+  }
+
+  #handleConnection(conn: Deno.Conn) {
+    const session = new ServerHttp2Session(
+      conn,
+      this.#options.settings,
+      this.#tls,
+    );
+    this.#sessions.add(session);
+    session.on("connect", () => this.emit("session", session));
+    session.on("stream", (stream, headers, flags) => {
+      this.emit("stream", stream, headers, flags);
+      if (this.listenerCount("request") > 0) {
+        const request = new Http2ServerRequest(stream, headers);
+        const response = new Http2ServerResponse(stream);
+        this.emit("request", request, response);
+      }
+    });
+    session.on("error", (err) => this.emit("sessionError", err, session));
+    session.on("close", () => {
+      this.#sessions.delete(session);
+      this.#maybeEmitClose();
+    });
+  }
+
+  #maybeEmitClose() {
+    if (!this.listening && this.#sessions.size === 0) {
+      this.emit("close");
+    }
+  }
+
+  /** Stops accepting new sessions, open sessions are kept. */
+  close(cb?: (err?: Error) => void): this {
+    const listening = this.listening;
+    if (typeof cb === "function") {
+      if (listening) {
+        this.once("close", cb);
+      } else {
+        nextTick(() => cb(new ERR_SERVER_NOT_RUNNING()));
+      }
+    }
+    if (listening) {
+      this.listening = false;
+      this.#listener!.close();
+      nextTick(() => this.#maybeEmitClose());
+    }
+    return this;
+  }
+
+  /** Applies to the sessions created after the call. */
+  updateSettings(settings: Settings) {
+    this.#options = {
+      ...this.#options,
+      settings: { ...this.#options.settings, ...settings },
+    };
+  }
+
+  setTimeout() {
+    notImplemented("Http2Server.setTimeout");
+  }
+
+  ref() {
+    (this.#listener as any)?.ref?.();
+  }
+
+  unref() {
+    (this.#listener as any)?.unref?.();
+  }
+}
+
+export class Http2Server extends Http2ServerBase {
+  constructor(options: ServerOptions = {}, requestListener?: RequestListener) {
+    super(options, undefined, requestListener);
+  }
+}
+
+export class Http2SecureServer extends Http2ServerBase {
+  constructor(options: ServerOptions = {}, requestListener?: RequestListener) {
+    super(options, {
+      cert: String(options.cert),
+      key: String(options.key),
+    }, requestListener);
+  }
+}
+
+export function createServer(
+  options?: ServerOptions | RequestListener,
+  requestListener?: RequestListener,
+): Http2Server {
+  if (typeof options === "function") {
+    requestListener = options;
+    options = {};
+  }
+  return new Http2Server(options, requestListener);
+}
+
+export function createSecureServer(
+  options?: ServerOptions | RequestListener,
+  requestListener?: RequestListener,
+): Http2SecureServer {
+  if (typeof options === "function") {
+    requestListener = options;
+    options = {};
+  }
+  return new Http2SecureServer(options, requestListener);
+}
+
+export function connect(
+  authority: string | URL,
+  options?: ClientSessionOptions | ((session: ClientHttp2Session) => void),
+  listener?: (session: ClientHttp2Session) => void,
+): ClientHttp2Session {
+  if (typeof options === "function") {
+    listener = options;
+    options = {};
+  }
+  const url = authority instanceof URL ? authority : new URL(authority);
+  if (url.protocol !== "http:" && url.protocol !== "https:") {
+    throw new ERR_HTTP2_UNSUPPORTED_PROTOCOL(url.protocol);
+  }
+  return new ClientHttp2Session(url, options, listener);
+}
+
+export class Http2ServerRequest extends Readable {
+  readonly stream: ServerHttp2Stream;
+  readonly headers: Headers;
+  readonly rawHeaders: string[];
+  trailers: Headers = {};
+  rawTrailers: string[] = [];
+
+  constructor(stream: ServerHttp2Stream, headers: Headers) {
+    super({ autoDestroy: false });
+    this.stream = stream;
+    this.headers = headers;
+    this.rawHeaders = toHeaderList(headers).flat();
+    stream.on("data", (chunk) => {
+      if (!this.push(chunk)) {
+        stream.pause();
+      }
+    });
+    stream.on("trailers", (trailers) => {
+      this.trailers = trailers;
+      this.rawTrailers = toHeaderList(trailers).flat();
+    });
+    stream.on("end", () => this.push(null));
+    stream.on("error", (err) => this.destroy(err));
+    stream.on("close", () => this.emit("close"));
+  }
+
+  _read() {
+    this.stream.resume();
+  }
+
+  get method(): string {
+    return this.headers[":method"] as string;
+  }
+
+  get authority(): string {
+    return this.headers[":authority"] as string;
+  }
+
+  get scheme(): string {
+    return this.headers[":scheme"] as string;
+  }
+
+  get url(): string {
+    return this.headers[":path"] as string;
+  }
+
+  get httpVersion(): string {
+    return "2.0";
+  }
+
+  get httpVersionMajor(): number {
+    return 2;
+  }
+
+  get httpVersionMinor(): number {
+    return 0;
+  }
+
+  get complete(): boolean {
+    return this.readableEnded;
+  }
+
+  setTimeout() {
+    notImplemented("Http2ServerRequest.setTimeout");
+  }
+}
+
+export class Http2ServerResponse extends Stream {
+  readonly stream: ServerHttp2Stream;
+  statusCode = 200;
+  statusMessage = "";
+  sendDate = true;
+  #headers: Headers = {};
+  #trailers: Headers = {};
+
+  constructor(stream: ServerHttp2Stream) {
+    super();
+    this.stream = stream;
+    // Trailers may be added until the response ends.
+    stream.on("wantTrailers", () => stream.sendTrailers(this.#trailers));
+    stream.on("finish", () => this.emit("finish"));
+    stream.on("close", () => this.emit("close"));
+    stream.on("error", (err) => this.emit("error", err));
+  }
+
+  get headersSent(): boolean {
+    return this.stream.headersSent;
+  }
+
+  get writableEnded(): boolean {
+    return this.stream.writableEnded;
+  }
+
+  get finished(): boolean {
+    return this.stream.writableEnded;
+  }
+
+  setHeader(name: string, value: string | string[] | number): this {
+    if (this.headersSent) {
+      throw new ERR_HTTP2_HEADERS_SENT();
+    }
+    this.#headers[name.toLowerCase()] = value;
+    return this;
+  }
+
+  appendHeader(name: string, value: string | string[]): this {
+    const existing = this.#headers[name.toLowerCase()];
+    const values = existing === undefined ? [] : [existing].flat();
+    return this.setHeader(name, [...values.map(String), value].flat());
+  }
+
+  getHeader(name: string) {
+    return this.#headers[name.toLowerCase()];
+  }
+
+  getHeaders(): Headers {
+    return { ...this.#headers };
+  }
+
+  getHeaderNames(): string[] {
+    return Object.keys(this.#headers);
+  }
+
+  hasHeader(name: string): boolean {
+    return name.toLowerCase() in this.#headers;
+  }
+
+  removeHeader(name: string) {
+    if (this.headersSent) {
+      throw new ERR_HTTP2_HEADERS_SENT();
+    }
+    delete this.#headers[name.toLowerCase()];
+  }
+
+  addTrailers(headers: Headers) {
+    Object.assign(this.#trailers, headers);
+  }
+
+  writeHead(
+    statusCode: number,
+    statusMessage?: string | Headers,
+    headers?: Headers,
+  ): this {
+    if (typeof statusMessage === "object") {
+      headers = statusMessage;
+    }
+    this.statusCode = statusCode;
+    for (const [name, value] of Object.entries(headers ?? {})) {
+      this.setHeader(name, value!);
+    }
+    this.#respond();
+    return this;
+  }
+
+  #respond() {
+    if (this.headersSent) {
+      return;
+    }
+    const headers: Headers = { ...this.#headers, ":status": this.statusCode };
+    if (this.sendDate && headers["date"] === undefined) {
+      headers["date"] = new Date().toUTCString();
+    }
+    this.stream.respond(headers, { waitForTrailers: true });
+  }
+
+  write(chunk: any, encoding?: any, callback?: any): boolean {
+    this.#respond();
+    return this.stream.write(chunk, encoding, callback);
+  }
+
+  end(chunk?: any, encoding?: any, callback?: any): this {
+    this.#respond();
+    this.stream.end(chunk, encoding, callback);
+    return this;
+  }
+
+  createPushResponse() {
+    notImplemented("Http2ServerResponse.createPushResponse");
+  }
+
+  setTimeout() {
+    notImplemented("Http2ServerResponse.setTimeout");
+  }
+}
+
 export default {
   Http2Session,
   ServerHttp2Session,
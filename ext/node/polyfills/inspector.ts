@@ -2,26 +2,61 @@
 // Copyright Joyent and Node contributors. All rights reserved. MIT license.
 
 import { EventEmitter } from "internal:deno_node/polyfills/events.ts";
+import { nextTick } from "internal:deno_node/polyfills/_next_tick.ts";
 import { notImplemented } from "internal:deno_node/polyfills/_utils.ts";
+import {
+  ERR_INSPECTOR_ALREADY_ACTIVATED,
+  ERR_INSPECTOR_ALREADY_CONNECTED,
+  ERR_INSPECTOR_CLOSED,
+  ERR_INSPECTOR_COMMAND,
+  ERR_INSPECTOR_NOT_ACTIVE,
+  ERR_INSPECTOR_NOT_CONNECTED,
+} from "internal:deno_node/polyfills/internal/errors.ts";
+import {
+  validateFunction,
+  validateInt32,
+  validateObject,
+  validateString,
+} from "internal:deno_node/polyfills/internal/validators.mjs";
+
+const { core } = globalThis.__bootstrap;
+
+const promiseIdSymbol = Symbol.for("Deno.core.internalPromiseId");
+
+type PostCallback = (err: Error | null, result?: unknown) => void;
+
+interface Notification {
+  method: string;
+  params?: unknown;
+}
 
-const connectionSymbol = Symbol("connectionProperty");
-const messageCallbacksSymbol = Symbol("messageCallbacks");
-const nextIdSymbol = Symbol("nextId");
-const onMessageSymbol = Symbol("onMessage");
+function toCommandError(err: Error): Error {
+  // The op throws errors of the protocol with their JSON as message.
+  try {
+    const { code, message } = JSON.parse(err.message);
+    return new ERR_INSPECTOR_COMMAND(code, message);
+  } catch {
+    return err;
+  }
+}
 
 class Session extends EventEmitter {
-  [connectionSymbol]: null;
-  [nextIdSymbol]: number;
-  [messageCallbacksSymbol]: Map<string, (e: Error) => void>;
+  #rid: number | null = null;
+  // The callbacks of the messages posted, and not answered yet.
+  #pending = new Map<number, PostCallback>();
+  #nextId = 1;
 
   constructor() {
     super();
-    notImplemented("inspector.Session.prototype.constructor");
   }
 
   /** Connects the session to the inspector back-end. */
   connect() {
-    notImplemented("inspector.Session.prototype.connect");
+    if (this.#rid !== null) {
+      throw new ERR_INSPECTOR_ALREADY_CONNECTED("The inspector session");
+    }
+    this.#rid = core.ops.op_node_inspector_connect();
+    this.#receive(this.#rid!);
   }
 
   /** Connects the session to the main thread
@@ -30,17 +65,69 @@ class Session extends EventEmitter {
     notImplemented("inspector.Session.prototype.connectToMainThread");
   }
 
-  [onMessageSymbol](_message: string) {
-    notImplemented("inspector.Session.prototype[Symbol('onMessage')]");
+  // Emits the notifications of the session until it's disconnected. The
+  // pending receive doesn't keep the event loop alive.
+  async #receive(rid: number) {
+    while (this.#rid === rid) {
+      const promise = core.opAsync("op_node_inspector_receive", rid);
+      core.unrefOp(promise[promiseIdSymbol]);
+
+      let notifications: Notification[];
+      try {
+        notifications = await promise;
+      } catch {
+        // The resource is closed by `disconnect()`.
+        return;
+      }
+
+      for (const notification of notifications) {
+        if (this.#rid !== rid) {
+          return;
+        }
+        this.emit("inspectorNotification", notification);
+        this.emit(notification.method, notification);
+      }
+    }
   }
 
   /** Posts a message to the inspector back-end. */
   post(
-    _method: string,
-    _params?: Record<string, unknown>,
-    _callback?: (...args: unknown[]) => void,
+    method: string,
+    params?: Record<string, unknown> | PostCallback,
+    callback?: PostCallback,
   ) {
-    notImplemented("inspector.Session.prototype.post");
+    validateString(method, "method");
+    if (!callback && typeof params === "function") {
+      callback = params;
+      params = undefined;
+    }
+    if (params) {
+      validateObject(params, "params");
+    }
+    if (callback) {
+      validateFunction(callback, "callback");
+    }
+
+    if (this.#rid === null) {
+      throw new ERR_INSPECTOR_NOT_CONNECTED();
+    }
+
+    const id = this.#nextId++;
+    const pending = callback ?? (() => {});
+    this.#pending.set(id, pending);
+    core.opAsync("op_node_inspector_post", this.#rid, method, params ?? null)
+      .then(
+        (result: unknown) => {
+          if (this.#pending.delete(id)) {
+            pending(null, result);
+          }
+        },
+        (err: Error) => {
+          if (this.#pending.delete(id)) {
+            pending(toCommandError(err));
+          }
+        },
+      );
   }
 
   /** Immediately closes the session, all pending
@@ -48,33 +135,58 @@ class Session extends EventEmitter {
    * error.
    */
   disconnect() {
-    notImplemented("inspector.Session.prototype.disconnect");
+    if (this.#rid === null) {
+      return;
+    }
+    core.tryClose(this.#rid);
+    this.#rid = null;
+
+    const pending = this.#pending;
+    this.#pending = new Map();
+    for (const callback of pending.values()) {
+      nextTick(callback, new ERR_INSPECTOR_CLOSED());
+    }
   }
 }
 
 /** Activates inspector on host and port.
  * See https://nodejs.org/api/inspector.html#inspectoropenport-host-wait */
-function open(_port?: number, _host?: string, _wait?: boolean) {
-  notImplemented("inspector.Session.prototype.open");
+function open(port?: number, host?: string, wait?: boolean) {
+  if (core.ops.op_inspector_url() !== null) {
+    throw new ERR_INSPECTOR_ALREADY_ACTIVATED();
+  }
+  if (port === undefined) {
+    port = 9229;
+  } else {
+    validateInt32(port, "port", 0, 65535);
+  }
+  if (host === undefined) {
+    host = "127.0.0.1";
+  } else {
+    validateString(host, "host");
+  }
+  core.ops.op_inspector_open(host, port, !!wait);
 }
 
 /** Deactivate the inspector. Blocks until there are no active connections.
  * See https://nodejs.org/api/inspector.html#inspectorclose */
 function close() {
-  notImplemented("inspector.Session.prototype.close");
+  core.ops.op_inspector_close();
 }
 
 /** Return the URL of the active inspector, or undefined if there is none.
  * See https://nodejs.org/api/inspector.html#inspectorurl */
-function url() {
-  // TODO(kt3k): returns undefined for now, which means the inspector is not activated.
-  return undefined;
+function url(): string | undefined {
+  return core.ops.op_inspector_url() ?? undefined;
 }
 
 /** Blocks until a client (existing or connected later) has sent Runtime.runIfWaitingForDebugger command.
  * See https://nodejs.org/api/inspector.html#inspectorwaitfordebugger */
 function waitForDebugger() {
-  notImplemented("inspector.wairForDebugger");
+  if (core.ops.op_inspector_url() === null) {
+    throw new ERR_INSPECTOR_NOT_ACTIVE();
+  }
+  core.ops.op_inspector_wait_for_debugger();
 }
 
 const console = globalThis.console;
@@ -47,6 +47,11 @@ import {
 import { kEmptyObject } from "internal:deno_node/polyfills/internal/util.mjs";
 import { getValidatedPath } from "internal:deno_node/polyfills/internal/fs/utils.mjs";
 import process from "internal:deno_node/polyfills/process.ts";
+import {
+  IpcChannel,
+  setupChannel,
+} from "internal:deno_node/polyfills/internal/child_process/ipc.ts";
+import { kIpc, kIpcRid } from "internal:runtime/js/40_spawn.js";
 
 export function mapValues<T, O>(
   record: Readonly<Record<string, T>>,
@@ -146,7 +151,25 @@ export class ChildProcess extends EventEmitter {
     null,
   ];
 
+  /**
+   * Whether it is still possible to send and receive messages from this
+   * child process. Only defined if it was spawned with an IPC channel.
+   */
+  connected?: boolean;
+
+  /**
+   * Sends a message to the child process. Only defined if it was spawned
+   * with an IPC channel.
+   */
+  send?: (
+    message: unknown,
+    sendHandle?: unknown,
+    options?: unknown,
+    callback?: (err: Error | null) => void,
+  ) => boolean;
+
   #process!: Deno.ChildProcess;
+  #channel: IpcChannel | null = null;
   #spawned = deferred<void>();
 
   constructor(
@@ -168,7 +191,7 @@ export class ChildProcess extends EventEmitter {
       stdin = "pipe",
       stdout = "pipe",
       stderr = "pipe",
-      _channel, // TODO(kt3k): handle this correctly
+      channel,
     ] = normalizeStdioOption(stdio);
     const [cmd, cmdArgs] = buildCommand(
       command,
@@ -188,9 +211,16 @@ export class ChildProcess extends EventEmitter {
         stdout: toDenoStdio(stdout as NodeStdio | number),
         stderr: toDenoStdio(stderr as NodeStdio | number),
         windowsRawArguments: windowsVerbatimArguments,
-      }).spawn();
+        [kIpc]: channel === "ipc",
+      } as Deno.CommandOptions).spawn();
       this.pid = this.#process.pid;
 
+      // deno-lint-ignore no-explicit-any
+      const ipcRid = (this.#process as any)[kIpcRid];
+      if (ipcRid != null) {
+        this.#channel = setupChannel(this, ipcRid);
+      }
+
       if (stdin === "pipe") {
         assert(this.#process.stdin);
         this.stdin = Writable.fromWeb(this.#process.stdin);
@@ -280,13 +310,17 @@ export class ChildProcess extends EventEmitter {
 
   ref() {
     this.#process.ref();
+    this.#channel?.ref();
   }
 
   unref() {
     this.#process.unref();
+    this.#channel?.unref();
   }
 
   disconnect() {
+    // Children with an IPC channel get their own `disconnect()` from
+    // `setupChannel()`.
     warnNotImplemented("ChildProcess.prototype.disconnect");
   }
 
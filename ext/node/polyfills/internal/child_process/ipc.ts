@@ -0,0 +1,188 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+// This module implements the IPC channel between a process and the children
+// it forks. Messages are serialized as JSON, one per line, which matches the
+// default "json" serialization of Node.js.
+
+import { EventEmitter } from "internal:deno_node/polyfills/events.ts";
+import { nextTick } from "internal:deno_node/polyfills/_next_tick.ts";
+import { notImplemented } from "internal:deno_node/polyfills/_utils.ts";
+import {
+  ERR_IPC_CHANNEL_CLOSED,
+  ERR_IPC_DISCONNECTED,
+  ERR_MISSING_ARGS,
+} from "internal:deno_node/polyfills/internal/errors.ts";
+import { validateFunction } from "internal:deno_node/polyfills/internal/validators.mjs";
+
+const { core } = globalThis.__bootstrap;
+
+const promiseIdSymbol = Symbol.for("Deno.core.internalPromiseId");
+
+type SendCallback = (err: Error | null) => void;
+
+/**
+ * Messages with a `cmd` that starts with `NODE_` are used by Node.js itself,
+ * e.g. by `cluster`, and are emitted as "internalMessage" events.
+ */
+function isInternal(message: unknown): boolean {
+  if (message === null || typeof message !== "object") {
+    return false;
+  }
+  const { cmd } = message as { cmd?: unknown };
+  return typeof cmd === "string" && cmd.length > 4 &&
+    cmd.startsWith("NODE_");
+}
+
+export interface IpcTarget extends EventEmitter {
+  connected: boolean;
+  send(
+    message: unknown,
+    sendHandle?: unknown,
+    options?: unknown,
+    callback?: SendCallback,
+  ): boolean;
+  disconnect(): void;
+}
+
+/** Controls whether a pending read keeps the event loop alive. */
+export interface IpcChannel {
+  ref(): void;
+  unref(): void;
+}
+
+/**
+ * Adds `send()`, `disconnect()` and `connected` to `target`, and emits the
+ * "message" and "disconnect" events of the channel with the resource id
+ * `rid`.
+ */
+export function setupChannel(
+  target: EventEmitter,
+  rid: number,
+  refed = true,
+): IpcChannel {
+  const ipc = target as IpcTarget;
+  let readPromiseId: number | undefined;
+
+  const close = () => {
+    if (!ipc.connected) {
+      return;
+    }
+    ipc.connected = false;
+    core.tryClose(rid);
+    nextTick(() => ipc.emit("disconnect"));
+  };
+
+  const readLoop = async () => {
+    while (ipc.connected) {
+      const promise = core.opAsync("op_node_ipc_read", rid);
+      readPromiseId = promise[promiseIdSymbol];
+      if (!refed) {
+        core.unrefOp(readPromiseId);
+      }
+
+      let line: string | null;
+      try {
+        line = await promise;
+      } catch (err) {
+        // The resource is closed by `disconnect()`.
+        if (!ipc.connected) {
+          return;
+        }
+        close();
+        ipc.emit("error", err);
+        return;
+      } finally {
+        readPromiseId = undefined;
+      }
+
+      if (line === null) {
+        close();
+        return;
+      }
+      let message: unknown;
+      try {
+        message = JSON.parse(line);
+      } catch (err) {
+        // the line is skipped, the next one may be a valid message again
+        ipc.emit("error", err);
+        continue;
+      }
+      ipc.emit(isInternal(message) ? "internalMessage" : "message", message);
+    }
+  };
+
+  ipc.connected = true;
+
+  ipc.send = function (
+    message: unknown,
+    sendHandle?: unknown,
+    options?: unknown,
+    callback?: SendCallback,
+  ): boolean {
+    if (typeof sendHandle === "function") {
+      callback = sendHandle as SendCallback;
+      sendHandle = undefined;
+      options = undefined;
+    } else if (typeof options === "function") {
+      callback = options as SendCallback;
+      options = undefined;
+    }
+    if (callback !== undefined) {
+      validateFunction(callback, "callback");
+    }
+
+    if (message === undefined) {
+      throw new ERR_MISSING_ARGS("message");
+    }
+    if (sendHandle !== undefined && sendHandle !== null) {
+      notImplemented("send with sendHandle");
+    }
+
+    if (!ipc.connected) {
+      const err = new ERR_IPC_CHANNEL_CLOSED();
+      if (callback) {
+        nextTick(callback, err);
+      } else {
+        nextTick(() => ipc.emit("error", err));
+      }
+      return false;
+    }
+
+    core.opAsync("op_node_ipc_write", rid, JSON.stringify(message)).then(
+      () => callback?.(null),
+      (err: Error) => {
+        if (callback) {
+          callback(err);
+        } else {
+          ipc.emit("error", err);
+        }
+      },
+    );
+    return true;
+  };
+
+  ipc.disconnect = function () {
+    if (!ipc.connected) {
+      ipc.emit("error", new ERR_IPC_DISCONNECTED());
+      return;
+    }
+    close();
+  };
+
+  readLoop();
+
+  return {
+    ref() {
+      refed = true;
+      if (readPromiseId !== undefined) {
+        core.refOp(readPromiseId);
+      }
+    },
+    unref() {
+      refed = false;
+      if (readPromiseId !== undefined) {
+        core.unrefOp(readPromiseId);
+      }
+    },
+  };
+}
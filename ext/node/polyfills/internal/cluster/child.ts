@@ -0,0 +1,60 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+// Copyright Joyent and Node contributors. All rights reserved. MIT license.
+
+import process from "internal:deno_node/polyfills/process.ts";
+import {
+  sendHelper,
+  setWorkerHooks,
+} from "internal:deno_node/polyfills/internal/cluster/utils.ts";
+import { Worker } from "internal:deno_node/polyfills/internal/cluster/worker.ts";
+
+// deno-lint-ignore no-explicit-any
+type IpcProcess = any;
+
+/**
+ * Creates `cluster.worker` in a worker process and announces it to the
+ * primary.
+ *
+ * Servers of workers don't listen on a handle of the primary like in Node.js.
+ * Every worker listens on its own socket with `SO_REUSEPORT` instead. Linux
+ * and FreeBSD distribute the connections among them, macOS and the other BSDs
+ * only share the port. This isn't supported on Windows.
+ */
+export function initChild(id: number): Worker {
+  const proc = process as IpcProcess;
+  const worker = new Worker({
+    id,
+    process: proc,
+    state: "online",
+  });
+
+  worker.disconnect = function () {
+    this.exitedAfterDisconnect = true;
+    sendHelper(proc, "exitedAfterDisconnect", {}, () => proc.disconnect());
+    return this;
+  };
+  worker.destroy = worker.kill = function () {
+    this.exitedAfterDisconnect = true;
+    if (!proc.connected) {
+      process.exit(0);
+    }
+    sendHelper(proc, "exitedAfterDisconnect", {}, () => process.exit(0));
+  };
+
+  proc.once("disconnect", () => {
+    worker.emit("disconnect");
+    // The primary went away or disconnected the worker.
+    if (!worker.exitedAfterDisconnect) {
+      process.exit(0);
+    }
+  });
+
+  setWorkerHooks({
+    onListening(address) {
+      sendHelper(proc, "listening", { ...address });
+    },
+  });
+
+  sendHelper(proc, "online");
+  return worker;
+}
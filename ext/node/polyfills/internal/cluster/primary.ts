@@ -0,0 +1,179 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+// Copyright Joyent and Node contributors. All rights reserved. MIT license.
+
+import { EventEmitter } from "internal:deno_node/polyfills/events.ts";
+import { nextTick } from "internal:deno_node/polyfills/_next_tick.ts";
+import { fork as forkProcess } from "internal:deno_node/polyfills/child_process.ts";
+import type { ChildProcess } from "internal:deno_node/polyfills/internal/child_process.ts";
+import process from "internal:deno_node/polyfills/process.ts";
+import {
+  isClusterMessage,
+  ListeningAddress,
+} from "internal:deno_node/polyfills/internal/cluster/utils.ts";
+import { Worker } from "internal:deno_node/polyfills/internal/cluster/worker.ts";
+
+export const SCHED_NONE = 1;
+export const SCHED_RR = 2;
+
+export interface ClusterSettings {
+  args?: string[];
+  cwd?: string;
+  exec?: string;
+  execArgv?: string[];
+  silent?: boolean;
+  stdio?: unknown[];
+  uid?: number;
+  gid?: number;
+  windowsHide?: boolean;
+  serialization?: string;
+  inspectPort?: number | (() => number);
+}
+
+export interface Primary extends EventEmitter {
+  settings: ClusterSettings;
+  workers: Record<number, Worker>;
+  fork(env?: Record<string, unknown>): Worker;
+  setupPrimary(settings?: ClusterSettings): void;
+  setupMaster(settings?: ClusterSettings): void;
+  disconnect(callback?: () => void): void;
+}
+
+let ids = 0;
+let initialized = false;
+// The callbacks of `cluster.disconnect()`, called once all workers are gone.
+let disconnectCallbacks: Array<() => void> = [];
+
+/** Adds the primary side of the cluster to `cluster`. */
+export function initPrimary(cluster: Primary) {
+  cluster.settings = {};
+  cluster.workers = {};
+
+  cluster.setupPrimary = function (options?: ClusterSettings) {
+    // The settings are updated in place, `cluster.settings` is exported.
+    const settings = Object.assign(cluster.settings, {
+      args: process.argv.slice(2),
+      exec: process.argv[1],
+      execArgv: process.execArgv,
+      silent: false,
+      ...cluster.settings,
+      ...options,
+    });
+
+    if (initialized) {
+      cluster.emit("setup", settings);
+      return;
+    }
+    initialized = true;
+    nextTick(() => cluster.emit("setup", settings));
+  };
+  cluster.setupMaster = cluster.setupPrimary;
+
+  cluster.fork = function (env?: Record<string, unknown>): Worker {
+    cluster.setupPrimary();
+    const id = ++ids;
+    const childProcess = createWorkerProcess(cluster.settings, id, env);
+    const worker = new Worker({ id, process: childProcess });
+
+    const removeWorker = () => {
+      delete cluster.workers[worker.id];
+      if (Object.keys(cluster.workers).length === 0) {
+        const callbacks = disconnectCallbacks;
+        disconnectCallbacks = [];
+        callbacks.forEach((callback) => callback());
+      }
+    };
+
+    worker.disconnect = function () {
+      this.exitedAfterDisconnect = true;
+      if (childProcess.connected) {
+        childProcess.disconnect();
+      }
+      return this;
+    };
+    worker.destroy = worker.kill = function (signal = "SIGTERM") {
+      this.exitedAfterDisconnect = true;
+      if (childProcess.connected) {
+        childProcess.disconnect();
+      }
+      if (!this.isDead()) {
+        childProcess.kill(signal);
+      }
+    };
+
+    childProcess.on("internalMessage", (message: unknown) => {
+      if (!isClusterMessage(message)) {
+        return;
+      }
+      switch (message.act) {
+        case "online":
+          worker.state = "online";
+          worker.emit("online");
+          cluster.emit("online", worker);
+          break;
+        case "listening": {
+          const { address, port, addressType } = message as unknown as
+            ListeningAddress;
+          const info = { address, port, addressType };
+          worker.state = "listening";
+          worker.emit("listening", info);
+          cluster.emit("listening", worker, info);
+          break;
+        }
+        case "exitedAfterDisconnect":
+          worker.exitedAfterDisconnect = true;
+          break;
+      }
+    });
+    childProcess.on("message", (message: unknown, handle: unknown) => {
+      cluster.emit("message", worker, message, handle);
+    });
+    childProcess.once("disconnect", () => {
+      worker.state = "disconnected";
+      worker.emit("disconnect");
+      cluster.emit("disconnect", worker);
+    });
+    childProcess.once("exit", (code: number | null, signal: string | null) => {
+      worker.state = "dead";
+      worker.emit("exit", code, signal);
+      cluster.emit("exit", worker, code, signal);
+      removeWorker();
+    });
+
+    cluster.workers[worker.id] = worker;
+    nextTick(() => cluster.emit("fork", worker));
+    return worker;
+  };
+
+  cluster.disconnect = function (callback?: () => void) {
+    const workers = Object.values(cluster.workers);
+    if (callback) {
+      if (workers.length === 0) {
+        nextTick(callback);
+      } else {
+        disconnectCallbacks.push(callback);
+      }
+    }
+    for (const worker of workers) {
+      worker.disconnect();
+    }
+  };
+}
+
+function createWorkerProcess(
+  settings: ClusterSettings,
+  id: number,
+  env?: Record<string, unknown>,
+): ChildProcess {
+  // The environment of the primary is inherited.
+  const workerEnv: Record<string, unknown> = {
+    ...env,
+    NODE_UNIQUE_ID: `${id}`,
+  };
+  return forkProcess(settings.exec!, settings.args ?? [], {
+    cwd: settings.cwd,
+    env: workerEnv as Record<string, string>,
+    execArgv: settings.execArgv,
+    silent: settings.silent,
+    stdio: settings.stdio,
+  } as Parameters<typeof forkProcess>[2]);
+}
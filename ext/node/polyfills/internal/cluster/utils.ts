@@ -0,0 +1,63 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+// Copyright Joyent and Node contributors. All rights reserved. MIT license.
+
+/** The `cmd` of the messages between the primary and its workers. */
+export const kClusterCommand = "NODE_CLUSTER";
+
+export type ClusterAction =
+  | "online"
+  | "listening"
+  | "exitedAfterDisconnect";
+
+export interface ClusterMessage {
+  cmd: typeof kClusterCommand;
+  act: ClusterAction;
+  [key: string]: unknown;
+}
+
+export interface ListeningAddress {
+  address: string | null;
+  port: number | null;
+  addressType: number | null;
+}
+
+interface IpcProcess {
+  send?(
+    message: unknown,
+    callback?: (err: Error | null) => void,
+  ): boolean;
+}
+
+/** Sends an internal message of the cluster over the IPC channel. */
+export function sendHelper(
+  proc: IpcProcess,
+  act: ClusterAction,
+  data: Record<string, unknown> = {},
+  callback?: (err: Error | null) => void,
+) {
+  if (!proc.send) {
+    return;
+  }
+  const message: ClusterMessage = { ...data, cmd: kClusterCommand, act };
+  proc.send(message, callback);
+}
+
+export function isClusterMessage(message: unknown): message is ClusterMessage {
+  return message !== null && typeof message === "object" &&
+    (message as { cmd?: unknown }).cmd === kClusterCommand;
+}
+
+interface WorkerHooks {
+  /** Called after a server of the worker started listening. */
+  onListening(address: ListeningAddress): void;
+}
+
+/**
+ * Hooks of the worker side of the cluster, `null` in the primary. They are
+ * kept here so that `net` doesn't have to import `cluster`.
+ */
+export let workerHooks: WorkerHooks | null = null;
+
+export function setWorkerHooks(hooks: WorkerHooks) {
+  workerHooks = hooks;
+}
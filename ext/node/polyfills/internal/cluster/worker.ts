@@ -0,0 +1,83 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+// Copyright Joyent and Node contributors. All rights reserved. MIT license.
+
+import { EventEmitter } from "internal:deno_node/polyfills/events.ts";
+import type { ChildProcess } from "internal:deno_node/polyfills/internal/child_process.ts";
+
+export type WorkerState =
+  | "none"
+  | "online"
+  | "listening"
+  | "disconnected"
+  | "dead";
+
+// The primary holds the `ChildProcess` of the worker, a worker holds its own
+// `process`.
+interface WorkerProcess extends EventEmitter {
+  connected?: boolean;
+  exitCode?: number | null;
+  signalCode?: string | null;
+  send?(message: unknown, ...args: unknown[]): boolean;
+  disconnect?(): void;
+  kill?(signal?: number | string): unknown;
+}
+
+interface WorkerOptions {
+  id?: number;
+  process?: WorkerProcess | ChildProcess;
+  state?: WorkerState;
+}
+
+/** A Worker object contains all public information and method about a worker.
+ * In the primary it can be obtained using cluster.workers. In a worker it can
+ * be obtained using cluster.worker.
+ */
+export class Worker extends EventEmitter {
+  /** Each new worker is given its own unique id, this id is stored in the
+   * id. */
+  id: number;
+  /** The `ChildProcess` of the worker, or `process` in the worker itself. */
+  process: WorkerProcess;
+  /** Set by calling `.disconnect()`, `.kill()` or `.destroy()`. */
+  exitedAfterDisconnect: boolean | undefined = undefined;
+  state: WorkerState;
+
+  // The primary and the worker itself implement these differently.
+  /** Closes the IPC channel and, in the worker, lets the process exit. */
+  declare disconnect: () => this;
+  /** Kills the worker. */
+  declare kill: (signal?: string) => void;
+  /** Alias of `kill()`. */
+  declare destroy: (signal?: string) => void;
+
+  constructor(options: WorkerOptions = {}) {
+    super();
+    this.id = options.id ?? 0;
+    this.process = (options.process ?? null) as WorkerProcess;
+    this.state = options.state ?? "none";
+
+    if (this.process) {
+      this.process.on("error", (err: Error) => this.emit("error", err));
+      this.process.on(
+        "message",
+        (message: unknown, handle: unknown) =>
+          this.emit("message", message, handle),
+      );
+    }
+  }
+
+  /** Sends a message to the worker or the primary. */
+  send(...args: unknown[]): boolean {
+    return this.process.send!(...(args as [unknown]));
+  }
+
+  /** Returns true if the worker is connected to its primary. */
+  isConnected(): boolean {
+    return this.process.connected === true;
+  }
+
+  /** Returns true if the worker's process has terminated. */
+  isDead(): boolean {
+    return this.process.exitCode != null || this.process.signalCode != null;
+  }
+}
@@ -10,14 +10,19 @@ import { Buffer } from "internal:deno_node/polyfills/buffer.ts";
 import { notImplemented } from "internal:deno_node/polyfills/_utils.ts";
 import type { TransformOptions } from "internal:deno_node/polyfills/_stream.d.ts";
 import { Transform } from "internal:deno_node/polyfills/_stream.mjs";
-import { KeyObject } from "internal:deno_node/polyfills/internal/crypto/keys.ts";
+import {
+  KeyObject,
+  prepareSecretKey,
+} from "internal:deno_node/polyfills/internal/crypto/keys.ts";
+import { isAnyArrayBuffer } from "internal:deno_node/polyfills/internal/util/types.ts";
 import type { BufferEncoding } from "internal:deno_node/polyfills/_global.d.ts";
 import type {
   BinaryLike,
   Encoding,
 } from "internal:deno_node/polyfills/internal/crypto/types.ts";
 
-const { ops } = globalThis.__bootstrap.core;
+const { core } = globalThis.__bootstrap;
+const { ops } = core;
 
 export type CipherCCMTypes =
   | "aes-128-ccm"
@@ -123,39 +128,73 @@ export interface DecipherOCB extends Decipher {
   ): this;
 }
 
-export class Cipheriv extends Transform implements Cipher {
-  constructor(
-    _cipher: string,
-    _key: CipherKey,
-    _iv: BinaryLike | null,
-    _options?: TransformOptions,
-  ) {
-    super();
-
-    notImplemented("crypto.Cipheriv");
+function toBuffer(data: BinaryLike, encoding?: Encoding): Buffer {
+  if (typeof data === "string") {
+    return Buffer.from(data, encoding as BufferEncoding);
   }
+  return Buffer.from(data.buffer, data.byteOffset, data.byteLength);
+}
 
-  final(): Buffer;
-  final(outputEncoding: BufferEncoding): string;
-  final(_outputEncoding?: string): Buffer | string {
-    notImplemented("crypto.Cipheriv.prototype.final");
+function encodeOutput(output: Uint8Array, encoding?: string): Buffer | string {
+  const buffer = Buffer.from(
+    output.buffer,
+    output.byteOffset,
+    output.byteLength,
+  );
+  return encoding && encoding !== "buffer"
+    ? buffer.toString(encoding as BufferEncoding)
+    : buffer;
+}
+
+/** The shared state of `Cipheriv` and `Decipheriv`, backed by a native context. */
+class CipherBase extends Transform {
+  #rid: number | null;
+  #autoPadding = true;
+  #authTag: Buffer | undefined;
+
+  constructor(
+    cipher: string,
+    key: CipherKey,
+    iv: BinaryLike | null,
+    decrypt: boolean,
+    options?: TransformOptions,
+  ) {
+    super(options);
+    const keyData = prepareSecretKey(key as string | ArrayBuffer, undefined);
+    const ivData = iv === null ? new Uint8Array() : toBuffer(iv);
+    const keyBytes = isAnyArrayBuffer(keyData)
+      ? new Uint8Array(keyData)
+      : toBuffer(keyData as ArrayBufferView);
+    this.#rid = decrypt
+      ? ops.op_node_create_decipheriv(cipher, keyBytes, ivData)
+      : ops.op_node_create_cipheriv(cipher, keyBytes, ivData);
   }
 
-  getAuthTag(): Buffer {
-    notImplemented("crypto.Cipheriv.prototype.getAuthTag");
+  get #context(): number {
+    if (this.#rid === null) {
+      throw new Error("Unsupported state");
+    }
+    return this.#rid;
   }
 
-  setAAD(
-    _buffer: ArrayBufferView,
-    _options?: {
-      plaintextLength: number;
-    },
-  ): this {
-    notImplemented("crypto.Cipheriv.prototype.setAAD");
+  override _transform(
+    chunk: BinaryLike,
+    encoding: Encoding,
+    callback: (err?: Error | null, data?: Buffer) => void,
+  ) {
+    try {
+      callback(null, this.update(chunk as string, encoding));
+    } catch (err) {
+      callback(err as Error);
+    }
   }
 
-  setAutoPadding(_autoPadding?: boolean): this {
-    notImplemented("crypto.Cipheriv.prototype.setAutoPadding");
+  override _flush(callback: (err?: Error | null, data?: Buffer) => void) {
+    try {
+      callback(null, this.final());
+    } catch (err) {
+      callback(err as Error);
+    }
   }
 
   update(data: BinaryLike): Buffer;
@@ -171,67 +210,104 @@ export class Cipheriv extends Transform implements Cipher {
     outputEncoding: Encoding,
   ): string;
   update(
-    _data: string | BinaryLike | ArrayBufferView,
-    _inputEncoding?: Encoding,
-    _outputEncoding?: Encoding,
+    data: string | BinaryLike | ArrayBufferView,
+    inputEncoding?: Encoding,
+    outputEncoding?: Encoding,
   ): Buffer | string {
-    notImplemented("crypto.Cipheriv.prototype.update");
-  }
-}
-
-export class Decipheriv extends Transform implements Cipher {
-  constructor(
-    _cipher: string,
-    _key: CipherKey,
-    _iv: BinaryLike | null,
-    _options?: TransformOptions,
-  ) {
-    super();
-
-    notImplemented("crypto.Decipheriv");
+    const output = ops.op_node_cipher_update(
+      this.#context,
+      toBuffer(data as BinaryLike, inputEncoding),
+    );
+    return encodeOutput(output, outputEncoding);
   }
 
   final(): Buffer;
   final(outputEncoding: BufferEncoding): string;
-  final(_outputEncoding?: string): Buffer | string {
-    notImplemented("crypto.Decipheriv.prototype.final");
+  final(outputEncoding?: string): Buffer | string {
+    const rid = this.#context;
+    this.#rid = null;
+    const { output, authTag } = ops.op_node_cipher_final(
+      rid,
+      this.#autoPadding,
+      this.#authTag,
+    );
+    if (authTag) {
+      this.#authTag = Buffer.from(authTag);
+    }
+    return encodeOutput(output, outputEncoding);
   }
 
   setAAD(
-    _buffer: ArrayBufferView,
+    buffer: ArrayBufferView,
     _options?: {
       plaintextLength: number;
     },
   ): this {
-    notImplemented("crypto.Decipheriv.prototype.setAAD");
+    ops.op_node_cipher_set_aad(this.#context, toBuffer(buffer));
+    return this;
   }
 
-  setAuthTag(_buffer: BinaryLike, _encoding?: string): this {
-    notImplemented("crypto.Decipheriv.prototype.setAuthTag");
+  setAutoPadding(autoPadding = true): this {
+    this.#autoPadding = autoPadding;
+    return this;
   }
 
-  setAutoPadding(_autoPadding?: boolean): this {
-    notImplemented("crypto.Decipheriv.prototype.setAutoPadding");
+  protected getTag(): Buffer {
+    if (this.#rid !== null || this.#authTag === undefined) {
+      throw new Error(
+        "Attempting to get auth tag in unsupported state",
+      );
+    }
+    return this.#authTag;
   }
 
-  update(data: BinaryLike): Buffer;
-  update(data: string, inputEncoding: Encoding): Buffer;
-  update(
-    data: ArrayBufferView,
-    inputEncoding: undefined,
-    outputEncoding: Encoding,
-  ): string;
-  update(
-    data: string,
-    inputEncoding: Encoding | undefined,
-    outputEncoding: Encoding,
-  ): string;
-  update(
-    _data: string | BinaryLike | ArrayBufferView,
-    _inputEncoding?: Encoding,
-    _outputEncoding?: Encoding,
-  ): Buffer | string {
-    notImplemented("crypto.Decipheriv.prototype.update");
+  protected setTag(buffer: BinaryLike, encoding?: string): this {
+    if (this.#rid === null) {
+      throw new Error("Attempting to set auth tag in unsupported state");
+    }
+    this.#authTag = toBuffer(buffer, encoding as Encoding);
+    return this;
+  }
+
+  override _destroy(
+    err: Error | null,
+    callback: (err: Error | null) => void,
+  ) {
+    if (this.#rid !== null) {
+      core.tryClose(this.#rid);
+      this.#rid = null;
+    }
+    callback(err);
+  }
+}
+
+export class Cipheriv extends CipherBase implements Cipher {
+  constructor(
+    cipher: string,
+    key: CipherKey,
+    iv: BinaryLike | null,
+    options?: TransformOptions,
+  ) {
+    super(cipher, key, iv, false, options);
+  }
+
+  getAuthTag(): Buffer {
+    return this.getTag();
+  }
+}
+
+export class Decipheriv extends CipherBase implements Cipher {
+  constructor(
+    cipher: string,
+    key: CipherKey,
+    iv: BinaryLike | null,
+    options?: TransformOptions,
+  ) {
+    super(cipher, key, iv, true, options);
+  }
+
+  setAuthTag(buffer: BinaryLike, encoding?: string): this {
+    return this.setTag(buffer, encoding);
   }
 }
 
@@ -13,6 +13,7 @@ import {
   forgivingBase64UrlEncode as encodeToBase64Url,
 } from "internal:deno_web/00_infra.js";
 import type { TransformOptions } from "internal:deno_node/polyfills/_stream.d.ts";
+import type { BufferEncoding } from "internal:deno_node/polyfills/_global.d.ts";
 import { validateString } from "internal:deno_node/polyfills/internal/validators.mjs";
 import type {
   BinaryToTextEncoding,
@@ -22,7 +23,6 @@ import {
   KeyObject,
   prepareSecretKey,
 } from "internal:deno_node/polyfills/internal/crypto/keys.ts";
-import { notImplemented } from "internal:deno_node/polyfills/_utils.ts";
 
 const { ops } = globalThis.__bootstrap.core;
 
@@ -140,11 +140,7 @@ export function Hmac(
 type Hmac = HmacImpl;
 
 class HmacImpl extends Transform {
-  #ipad: Uint8Array;
-  #opad: Uint8Array;
-  #ZEROES = Buffer.alloc(128);
-  #algorithm: string;
-  #hash: Hash;
+  #context: number;
 
   constructor(
     hmac: string,
@@ -164,51 +160,33 @@ class HmacImpl extends Transform {
     });
     // deno-lint-ignore no-this-alias
     const self = this;
-    if (key instanceof KeyObject) {
-      notImplemented("Hmac: KeyObject key is not implemented");
-    }
 
     validateString(hmac, "hmac");
-    const u8Key = prepareSecretKey(key, options?.encoding) as Buffer;
-
-    const alg = hmac.toLowerCase();
-    this.#hash = new Hash(alg, options);
-    this.#algorithm = alg;
-    const blockSize = (alg === "sha512" || alg === "sha384") ? 128 : 64;
-    const keySize = u8Key.length;
-
-    let bufKey: Buffer;
-
-    if (keySize > blockSize) {
-      bufKey = this.#hash.update(u8Key).digest() as Buffer;
-    } else {
-      bufKey = Buffer.concat([u8Key, this.#ZEROES], blockSize);
-    }
+    const keyData = prepareSecretKey(key, options?.encoding) as
+      | ArrayBuffer
+      | ArrayBufferView;
 
-    this.#ipad = Buffer.allocUnsafe(blockSize);
-    this.#opad = Buffer.allocUnsafe(blockSize);
-
-    for (let i = 0; i < blockSize; i++) {
-      this.#ipad[i] = bufKey[i] ^ 0x36;
-      this.#opad[i] = bufKey[i] ^ 0x5C;
-    }
-
-    this.#hash = new Hash(alg);
-    this.#hash.update(this.#ipad);
+    this.#context = ops.op_node_create_hmac(
+      hmac.toLowerCase(),
+      coerceToBytes(keyData),
+    );
   }
 
   digest(): Buffer;
   digest(encoding: BinaryToTextEncoding): string;
   digest(encoding?: BinaryToTextEncoding): Buffer | string {
-    const result = this.#hash.digest();
-
-    return new Hash(this.#algorithm).update(this.#opad).update(result).digest(
-      encoding,
-    );
+    const digest = Buffer.from(ops.op_node_hmac_digest(this.#context));
+    if (encoding === undefined || encoding === "buffer") {
+      return digest;
+    }
+    return digest.toString(encoding);
   }
 
   update(data: string | ArrayBuffer, inputEncoding?: Encoding): this {
-    this.#hash.update(data, inputEncoding);
+    const bytes = typeof data === "string"
+      ? Buffer.from(data, inputEncoding as BufferEncoding)
+      : coerceToBytes(data);
+    ops.op_node_hmac_update(this.#context, bytes);
     return this;
   }
 }
@@ -26,7 +26,9 @@ import {
   isAnyArrayBuffer,
   isArrayBufferView,
 } from "internal:deno_node/polyfills/internal/util/types.ts";
-import { notImplemented } from "internal:deno_node/polyfills/_utils.ts";
+
+const { core } = globalThis.__bootstrap;
+const { ops } = core;
 
 const validateParameters = hideStackFrames((hash, key, salt, info, length) => {
   key = prepareKey(key);
@@ -85,6 +87,24 @@ function prepareKey(key: BinaryLike | KeyObject) {
   return createSecretKey(key);
 }
 
+function secretKeyBytes(key: KeyObject): Uint8Array {
+  return toBytes(key.export());
+}
+
+function toBytes(data: BinaryLike | ArrayBuffer): Uint8Array {
+  if (isArrayBufferView(data)) {
+    return new Uint8Array(data.buffer, data.byteOffset, data.byteLength);
+  }
+  return new Uint8Array(data as ArrayBuffer);
+}
+
+function toArrayBuffer(bytes: Uint8Array): ArrayBuffer {
+  return bytes.buffer.slice(
+    bytes.byteOffset,
+    bytes.byteOffset + bytes.byteLength,
+  );
+}
+
 export function hkdf(
   hash: string,
   key: BinaryLike | KeyObject,
@@ -103,7 +123,17 @@ export function hkdf(
 
   validateFunction(callback, "callback");
 
-  notImplemented("crypto.hkdf");
+  core.opAsync(
+    "op_node_hkdf_async",
+    hash,
+    secretKeyBytes(key as KeyObject),
+    toBytes(salt),
+    toBytes(info),
+    length,
+  ).then(
+    (result: Uint8Array) => callback(null, toArrayBuffer(result)),
+    (err: Error) => callback(err, undefined as unknown as ArrayBuffer),
+  );
 }
 
 export function hkdfSync(
@@ -121,7 +151,13 @@ export function hkdfSync(
     length,
   ));
 
-  notImplemented("crypto.hkdfSync");
+  return toArrayBuffer(ops.op_node_hkdf(
+    hash,
+    secretKeyBytes(key as KeyObject),
+    toBytes(salt),
+    toBytes(info),
+    length,
+  ));
 }
 
 export default {
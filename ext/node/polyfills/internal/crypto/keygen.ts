@@ -1,7 +1,21 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 // Copyright Joyent, Inc. and Node.js contributors. All rights reserved. MIT license.
 
-import { KeyObject } from "internal:deno_node/polyfills/internal/crypto/keys.ts";
+import {
+  createAsymmetricKeyObject,
+  createSecretKey,
+  KeyObject,
+} from "internal:deno_node/polyfills/internal/crypto/keys.ts";
+import type { KeyExportOptions } from "internal:deno_node/polyfills/internal/crypto/keys.ts";
+import {
+  validateFunction,
+  validateInteger,
+  validateObject,
+  validateOneOf,
+  validateString,
+  validateUint32,
+} from "internal:deno_node/polyfills/internal/validators.mjs";
+import { ERR_INVALID_ARG_VALUE } from "internal:deno_node/polyfills/internal/errors.ts";
 import { notImplemented } from "internal:deno_node/polyfills/_utils.ts";
 import { Buffer } from "internal:deno_node/polyfills/buffer.ts";
 import {
@@ -9,14 +23,19 @@ import {
   KeyType,
 } from "internal:deno_node/polyfills/internal/crypto/types.ts";
 
+const { core } = globalThis.__bootstrap;
+const { ops } = core;
+
 export function generateKey(
-  _type: "hmac" | "aes",
-  _options: {
+  type: "hmac" | "aes",
+  options: {
     length: number;
   },
-  _callback: (err: Error | null, key: KeyObject) => void,
+  callback: (err: Error | null, key: KeyObject) => void,
 ) {
-  notImplemented("crypto.generateKey");
+  validateFunction(callback, "callback");
+  const key = generateKeySync(type, options);
+  queueMicrotask(() => callback(null, key));
 }
 
 export interface BasePrivateKeyEncodingOptions<T extends KeyFormat> {
@@ -470,9 +489,9 @@ export function generateKeyPair(
   ) => void,
 ): void;
 export function generateKeyPair(
-  _type: KeyType,
-  _options: unknown,
-  _callback: (
+  type: KeyType,
+  options: unknown,
+  callback: (
     err: Error | null,
     // deno-lint-ignore no-explicit-any
     publicKey: any,
@@ -480,7 +499,89 @@ export function generateKeyPair(
     privateKey: any,
   ) => void,
 ) {
-  notImplemented("crypto.generateKeyPair");
+  if (typeof options === "function") {
+    callback = options as typeof callback;
+    options = undefined;
+  }
+  validateFunction(callback, "callback");
+  const params = keyPairParams(type, options);
+
+  core.opAsync("op_node_generate_key_pair_async", params.type, params)
+    .then(
+      ([privateKey, publicKey]: [unknown, unknown]) => {
+        let result;
+        try {
+          result = encodeKeyPair(privateKey, publicKey, options);
+        } catch (err) {
+          return callback(err as Error, undefined, undefined);
+        }
+        callback(null, result.publicKey, result.privateKey);
+      },
+      (err: Error) => callback(err, undefined, undefined),
+    );
+}
+
+// deno-lint-ignore no-explicit-any
+type KeyPairOptions = Record<string, any> | undefined;
+
+/** Validates the options of `generateKeyPair` for the native key generator. */
+function keyPairParams(type: KeyType, options: unknown) {
+  validateString(type, "type");
+  if (options !== undefined) {
+    validateObject(options, "options");
+  }
+  const opts = options as KeyPairOptions;
+  switch (type) {
+    case "rsa": {
+      validateUint32(opts?.modulusLength, "options.modulusLength");
+      const publicExponent = opts?.publicExponent ?? 0x10001;
+      validateUint32(publicExponent, "options.publicExponent");
+      return {
+        type,
+        modulusLength: opts!.modulusLength,
+        publicExponent,
+      };
+    }
+    case "ec":
+      validateString(opts?.namedCurve, "options.namedCurve");
+      return { type, namedCurve: opts!.namedCurve };
+    default:
+      notImplemented(`crypto.generateKeyPair with type ${type}`);
+  }
+}
+
+function encodeKey(
+  handle: unknown,
+  encoding: KeyExportOptions<KeyFormat> | undefined,
+  name: string,
+) {
+  // deno-lint-ignore no-explicit-any
+  const key = createAsymmetricKeyObject(handle as any);
+  if (encoding === undefined) {
+    return key;
+  }
+  validateObject(encoding, name);
+  return key.export(encoding as KeyExportOptions<"pem">);
+}
+
+function encodeKeyPair(
+  privateKey: unknown,
+  publicKey: unknown,
+  options: unknown,
+) {
+  const opts = options as KeyPairOptions;
+  return {
+    publicKey: encodeKey(
+      publicKey,
+      opts?.publicKeyEncoding,
+      "options.publicKeyEncoding",
+    ),
+    privateKey: encodeKey(
+      privateKey,
+      opts?.privateKeyEncoding,
+      "options.privateKeyEncoding",
+    ),
+  };
 }
 
 export interface KeyPairKeyObjectResult {
@@ -657,21 +758,47 @@ export function generateKeyPairSync(
   options?: X448KeyPairKeyObjectOptions,
 ): KeyPairKeyObjectResult;
 export function generateKeyPairSync(
-  _type: KeyType,
-  _options: unknown,
+  type: KeyType,
+  options: unknown,
 ):
   | KeyPairKeyObjectResult
   | KeyPairSyncResult<string | Buffer, string | Buffer> {
-  notImplemented("crypto.generateKeyPairSync");
+  const params = keyPairParams(type, options);
+  const [privateKey, publicKey] = ops.op_node_generate_key_pair(
+    params.type,
+    params,
+  );
+  // deno-lint-ignore no-explicit-any
+  return encodeKeyPair(privateKey, publicKey, options) as any;
 }
 
 export function generateKeySync(
-  _type: "hmac" | "aes",
-  _options: {
+  type: "hmac" | "aes",
+  options: {
     length: number;
   },
 ): KeyObject {
-  notImplemented("crypto.generateKeySync");
+  validateString(type, "type");
+  validateObject(options, "options");
+  const { length } = options;
+  switch (type) {
+    case "hmac":
+      validateInteger(length, "options.length", 8, 2 ** 31 - 1);
+      break;
+    case "aes":
+      validateOneOf(length, "options.length", [128, 192, 256]);
+      break;
+    default:
+      throw new ERR_INVALID_ARG_VALUE(
+        "type",
+        type,
+        "must be a supported key type",
+      );
+  }
+  // only whole bytes are used, like in Node.js
+  const key = new Uint8Array(Math.floor(length / 8));
+  globalThis.crypto.getRandomValues(key);
+  return createSecretKey(key);
 }
 
 export default {
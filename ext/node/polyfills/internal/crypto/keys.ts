@@ -22,6 +22,7 @@ import {
   isAnyArrayBuffer,
   isArrayBufferView,
 } from "internal:deno_node/polyfills/internal/util/types.ts";
+import { validateObject } from "internal:deno_node/polyfills/internal/validators.mjs";
 import { hideStackFrames } from "internal:deno_node/polyfills/internal/errors.ts";
 import {
   isCryptoKey as isCryptoKey_,
@@ -29,6 +30,8 @@ import {
   kKeyType,
 } from "internal:deno_node/polyfills/internal/crypto/_keys.ts";
 
+const { ops } = globalThis.__bootstrap.core;
+
 const getArrayBufferOrView = hideStackFrames(
   (
     buffer,
@@ -130,6 +133,17 @@ export function isCryptoKey(
   return isCryptoKey_(obj);
 }
 
+/** The key material kept by asymmetric `KeyObject`s, as returned by the ops. */
+interface AsymmetricKeyHandle {
+  type: "private" | "public";
+  asymmetricKeyType: KeyType;
+  /** PKCS#8 for private keys, SPKI for public keys. */
+  der: Uint8Array;
+  modulusLength?: number;
+  publicExponent?: number;
+  namedCurve?: string;
+}
+
 export class KeyObject {
   [kKeyType]: KeyObjectType;
   [kHandle]: unknown;
@@ -158,21 +172,31 @@ export class KeyObject {
   }
 
   get asymmetricKeyDetails(): AsymmetricKeyDetails | undefined {
-    notImplemented("crypto.KeyObject.prototype.asymmetricKeyDetails");
-
-    return undefined;
+    if (this.type === "secret") {
+      return undefined;
+    }
+    const handle = this[kHandle] as AsymmetricKeyHandle;
+    if (handle.asymmetricKeyType === "rsa") {
+      return {
+        modulusLength: handle.modulusLength,
+        publicExponent: BigInt(handle.publicExponent!),
+      };
+    }
+    return { namedCurve: handle.namedCurve };
   }
 
   get asymmetricKeyType(): KeyType | undefined {
-    notImplemented("crypto.KeyObject.prototype.asymmetricKeyType");
-
-    return undefined;
+    if (this.type === "secret") {
+      return undefined;
+    }
+    return (this[kHandle] as AsymmetricKeyHandle).asymmetricKeyType;
   }
 
   get symmetricKeySize(): number | undefined {
-    notImplemented("crypto.KeyObject.prototype.symmetricKeySize");
-
-    return undefined;
+    if (this.type !== "secret") {
+      return undefined;
+    }
+    return (this[kHandle] as Uint8Array).byteLength;
   }
 
   static from(key: CryptoKey): KeyObject {
@@ -192,15 +216,149 @@ export class KeyObject {
       );
     }
 
-    notImplemented("crypto.KeyObject.prototype.equals");
+    if (otherKeyObject.type !== this.type) {
+      return false;
+    }
+    const a = keyBytes(this);
+    const b = keyBytes(otherKeyObject);
+    return a.byteLength === b.byteLength &&
+      a.every((byte, i) => byte === b[i]);
   }
 
   export(options: KeyExportOptions<"pem">): string | Buffer;
   export(options?: KeyExportOptions<"der">): Buffer;
   export(options?: JwkKeyExportOptions): JsonWebKey;
-  export(_options?: unknown): string | Buffer | JsonWebKey {
-    notImplemented("crypto.KeyObject.prototype.asymmetricKeyType");
+  export(options?: unknown): string | Buffer | JsonWebKey {
+    if (options !== undefined) {
+      validateObject(options, "options");
+    }
+    const { format, type, cipher, passphrase } = (options ?? {}) as {
+      format?: KeyFormat | "jwk";
+      type?: string;
+      cipher?: string;
+      passphrase?: string | Buffer;
+    };
+
+    if (this.type === "secret") {
+      const key = this[kHandle] as Uint8Array;
+      if (format === "jwk") {
+        return { kty: "oct", k: Buffer.from(key).toString("base64url") };
+      }
+      return Buffer.from(key);
+    }
+
+    if (options === undefined) {
+      throw new ERR_INVALID_ARG_TYPE("options", "object", options);
+    }
+    if (cipher !== undefined || passphrase !== undefined) {
+      notImplemented("crypto.KeyObject.prototype.export with encryption");
+    }
+    if (format !== "pem" && format !== "der" && format !== "jwk") {
+      throw new ERR_INVALID_ARG_VALUE("options.format", format);
+    }
+    const exported = ops.op_node_export_key(asymmetricKeyInput(this), {
+      format,
+      type,
+    });
+    if (format === "der") {
+      return Buffer.from(exported);
+    }
+    return exported;
+  }
+}
+
+function keyBytes(key: KeyObject): Uint8Array {
+  if (key.type === "secret") {
+    return key[kHandle] as Uint8Array;
+  }
+  return (key[kHandle] as AsymmetricKeyHandle).der;
+}
+
+function toUint8Array(buffer: ArrayBufferView | ArrayBufferLike): Uint8Array {
+  if (isArrayBufferView(buffer)) {
+    return new Uint8Array(buffer.buffer, buffer.byteOffset, buffer.byteLength);
+  }
+  return new Uint8Array(buffer);
+}
+
+/** Describes the key material of an asymmetric `KeyObject` for the ops. */
+export function asymmetricKeyInput(key: KeyObject) {
+  const handle = key[kHandle] as AsymmetricKeyHandle;
+  return handle.type === "private"
+    ? { pkcs8: handle.der }
+    : { spki: handle.der };
+}
+
+/**
+ * Normalizes the ways asymmetric keys can be passed to `node:crypto`: as
+ * `KeyObject`s, `CryptoKey`s, PEM strings or buffers, and as objects with
+ * a `key` and its `format` and `type`.
+ */
+export function prepareAsymmetricKey(key: unknown, name = "key"): unknown {
+  if (isKeyObject(key) || isCryptoKey(key)) {
+    const keyObject = isKeyObject(key) ? key : key[kKeyObject];
+    if (keyObject.type === "secret") {
+      throw new ERR_CRYPTO_INVALID_KEY_OBJECT_TYPE("secret", "asymmetric");
+    }
+    return asymmetricKeyInput(keyObject);
   }
+  if (
+    typeof key === "string" || isArrayBufferView(key) || isAnyArrayBuffer(key)
+  ) {
+    return { pem: toUint8Array(getArrayBufferOrView(key, name, "utf8")) };
+  }
+  if (typeof key === "object" && key !== null) {
+    const {
+      key: data,
+      format = "pem",
+      type,
+      encoding,
+      passphrase,
+    } = key as Record<string, unknown>;
+    if (isKeyObject(data) || isCryptoKey(data)) {
+      return prepareAsymmetricKey(data, `${name}.key`);
+    }
+    if (passphrase !== undefined) {
+      notImplemented("crypto encrypted private keys");
+    }
+    if (format === "jwk") {
+      validateObject(data, `${name}.key`);
+      return { jwk: data };
+    }
+    const bytes = toUint8Array(
+      getArrayBufferOrView(data, `${name}.key`, encoding),
+    );
+    if (format === "pem") {
+      return { pem: bytes };
+    }
+    if (format !== "der") {
+      throw new ERR_INVALID_ARG_VALUE(`${name}.format`, format);
+    }
+    if (
+      type !== "pkcs1" && type !== "pkcs8" && type !== "spki" &&
+      type !== "sec1"
+    ) {
+      throw new ERR_INVALID_ARG_VALUE(`${name}.type`, type);
+    }
+    return { [type]: bytes };
+  }
+  throw new ERR_INVALID_ARG_TYPE(name, [
+    "string",
+    "ArrayBuffer",
+    "Buffer",
+    "TypedArray",
+    "DataView",
+    "KeyObject",
+    "CryptoKey",
+    "object",
+  ], key);
+}
+
+/** Creates a `KeyObject` from the key material returned by the ops. */
+export function createAsymmetricKeyObject(
+  handle: AsymmetricKeyHandle,
+): KeyObject {
+  return new KeyObject(handle.type, handle);
 }
 
 export interface JsonWebKeyInput {
@@ -209,15 +367,19 @@ export interface JsonWebKeyInput {
 }
 
 export function createPrivateKey(
-  _key: PrivateKeyInput | string | Buffer | JsonWebKeyInput,
+  key: PrivateKeyInput | string | Buffer | JsonWebKeyInput,
 ): KeyObject {
-  notImplemented("crypto.createPrivateKey");
+  return createAsymmetricKeyObject(
+    ops.op_node_create_private_key(prepareAsymmetricKey(key)),
+  );
 }
 
 export function createPublicKey(
-  _key: PublicKeyInput | string | Buffer | KeyObject | JsonWebKeyInput,
+  key: PublicKeyInput | string | Buffer | KeyObject | JsonWebKeyInput,
 ): KeyObject {
-  notImplemented("crypto.createPublicKey");
+  return createAsymmetricKeyObject(
+    ops.op_node_create_public_key(prepareAsymmetricKey(key)),
+  );
 }
 
 function getKeyTypes(allowKeyObject: boolean, bufferOnly = false) {
@@ -277,18 +439,25 @@ export function createSecretKey(
   encoding: string,
 ): KeyObject;
 export function createSecretKey(
-  _key: string | ArrayBufferView,
-  _encoding?: string,
+  key: string | ArrayBufferView,
+  encoding?: string,
 ): KeyObject {
-  notImplemented("crypto.createSecretKey");
+  const bytes = toUint8Array(
+    prepareSecretKey(key, encoding, true) as ArrayBufferView,
+  );
+  // the key is copied, so later changes to the input don't affect it
+  return new KeyObject("secret", bytes.slice());
 }
 
 export default {
+  asymmetricKeyInput,
+  createAsymmetricKeyObject,
   createPrivateKey,
   createPublicKey,
   createSecretKey,
   isKeyObject,
   isCryptoKey,
   KeyObject,
+  prepareAsymmetricKey,
   prepareSecretKey,
 };
@@ -1,8 +1,13 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 // Copyright Joyent, Inc. and Node.js contributors. All rights reserved. MIT license.
 
-import { notImplemented } from "internal:deno_node/polyfills/_utils.ts";
-import { validateString } from "internal:deno_node/polyfills/internal/validators.mjs";
+import {
+  validateFunction,
+  validateString,
+} from "internal:deno_node/polyfills/internal/validators.mjs";
+import { ERR_INVALID_ARG_TYPE } from "internal:deno_node/polyfills/internal/errors.ts";
+import { isArrayBufferView } from "internal:deno_node/polyfills/internal/util/types.ts";
+import type { BufferEncoding } from "internal:deno_node/polyfills/_global.d.ts";
 import { Buffer } from "internal:deno_node/polyfills/buffer.ts";
 import type { WritableOptions } from "internal:deno_node/polyfills/_stream.d.ts";
 import Writable from "internal:deno_node/polyfills/internal/streams/writable.mjs";
@@ -13,7 +18,13 @@ import type {
   PrivateKeyInput,
   PublicKeyInput,
 } from "internal:deno_node/polyfills/internal/crypto/types.ts";
-import { KeyObject } from "internal:deno_node/polyfills/internal/crypto/keys.ts";
+import {
+  isKeyObject,
+  KeyObject,
+  prepareAsymmetricKey,
+} from "internal:deno_node/polyfills/internal/crypto/keys.ts";
+
+const { ops } = globalThis.__bootstrap.core;
 
 export type DSAEncoding = "der" | "ieee-p1363";
 
@@ -36,13 +47,104 @@ export interface VerifyKeyObjectInput extends SigningOptions {
 
 export type KeyLike = string | Buffer | KeyObject;
 
-export class Sign extends Writable {
-  constructor(algorithm: string, _options?: WritableOptions) {
+/** Maps names like `RSA-SHA256` or `sha256WithRSAEncryption` to the digest. */
+function normalizeDigest(algorithm: string | null | undefined) {
+  if (algorithm === null || algorithm === undefined) {
+    return undefined;
+  }
+  const name = algorithm.toLowerCase();
+  const match = name.match(/^(?:rsa-)?(sha\d+|md5|ripemd160)(?:withrsaencryption)?$/);
+  return match ? match[1] : name;
+}
+
+function toBytes(data: BinaryLike, encoding?: string): Uint8Array {
+  if (typeof data === "string") {
+    return Buffer.from(data, encoding as BufferEncoding);
+  }
+  if (isArrayBufferView(data)) {
+    return new Uint8Array(data.buffer, data.byteOffset, data.byteLength);
+  }
+  throw new ERR_INVALID_ARG_TYPE(
+    "data",
+    ["string", "Buffer", "TypedArray", "DataView"],
+    data,
+  );
+}
+
+function signingOptions(key: unknown): SigningOptions | undefined {
+  if (typeof key !== "object" || key === null || isKeyObject(key)) {
+    return undefined;
+  }
+  const { padding, saltLength, dsaEncoding } = key as SigningOptions;
+  return { padding, saltLength, dsaEncoding };
+}
+
+function sign(
+  algorithm: string | undefined,
+  data: Uint8Array,
+  privateKey: KeyLike | SignKeyObjectInput | SignPrivateKeyInput,
+): Buffer {
+  return Buffer.from(ops.op_node_sign(
+    prepareAsymmetricKey(privateKey),
+    algorithm,
+    data,
+    signingOptions(privateKey),
+  ));
+}
+
+function verify(
+  algorithm: string | undefined,
+  data: Uint8Array,
+  publicKey: KeyLike | VerifyKeyObjectInput | VerifyPublicKeyInput,
+  signature: Uint8Array,
+): boolean {
+  return ops.op_node_verify(
+    prepareAsymmetricKey(publicKey),
+    algorithm,
+    data,
+    signature,
+    signingOptions(publicKey),
+  );
+}
+
+/** The data to sign or verify, which is processed at once by the native side. */
+class SignBase extends Writable {
+  algorithm: string | undefined;
+  #chunks: Uint8Array[] = [];
+
+  constructor(algorithm: string, options?: WritableOptions) {
     validateString(algorithm, "algorithm");
 
-    super();
+    super(options);
+
+    this.algorithm = normalizeDigest(algorithm);
+  }
+
+  override _write(
+    chunk: BinaryLike,
+    encoding: string,
+    callback: (err?: Error | null) => void,
+  ) {
+    this.update(chunk, encoding as Encoding);
+    callback();
+  }
 
-    notImplemented("crypto.Sign");
+  update(data: BinaryLike | string, inputEncoding?: Encoding): this {
+    // the input may be reused by the caller
+    this.#chunks.push(toBytes(data, inputEncoding).slice());
+    return this;
+  }
+
+  protected data(): Uint8Array {
+    const data = Buffer.concat(this.#chunks);
+    this.#chunks = [];
+    return data;
+  }
+}
+
+export class Sign extends SignBase {
+  constructor(algorithm: string, options?: WritableOptions) {
+    super(algorithm, options);
   }
 
   sign(privateKey: KeyLike | SignKeyObjectInput | SignPrivateKeyInput): Buffer;
@@ -51,32 +153,19 @@ export class Sign extends Writable {
     outputFormat: BinaryToTextEncoding,
   ): string;
   sign(
-    _privateKey: KeyLike | SignKeyObjectInput | SignPrivateKeyInput,
-    _outputEncoding?: BinaryToTextEncoding,
+    privateKey: KeyLike | SignKeyObjectInput | SignPrivateKeyInput,
+    outputEncoding?: BinaryToTextEncoding,
   ): Buffer | string {
-    notImplemented("crypto.Sign.prototype.sign");
-  }
-
-  update(data: BinaryLike): this;
-  update(data: string, inputEncoding: Encoding): this;
-  update(_data: BinaryLike | string, _inputEncoding?: Encoding): this {
-    notImplemented("crypto.Sign.prototype.update");
+    const signature = sign(this.algorithm, this.data(), privateKey);
+    return outputEncoding && outputEncoding !== ("buffer" as string)
+      ? signature.toString(outputEncoding)
+      : signature;
   }
 }
 
-export class Verify extends Writable {
-  constructor(algorithm: string, _options?: WritableOptions) {
-    validateString(algorithm, "algorithm");
-
-    super();
-
-    notImplemented("crypto.Verify");
-  }
-
-  update(data: BinaryLike): this;
-  update(data: string, inputEncoding: Encoding): this;
-  update(_data: BinaryLike, _inputEncoding?: string): this {
-    notImplemented("crypto.Sign.prototype.update");
+export class Verify extends SignBase {
+  constructor(algorithm: string, options?: WritableOptions) {
+    super(algorithm, options);
   }
 
   verify(
@@ -89,11 +178,16 @@ export class Verify extends Writable {
     signatureEncoding?: BinaryToTextEncoding,
   ): boolean;
   verify(
-    _object: KeyLike | VerifyKeyObjectInput | VerifyPublicKeyInput,
-    _signature: ArrayBufferView | string,
-    _signatureEncoding?: BinaryToTextEncoding,
+    object: KeyLike | VerifyKeyObjectInput | VerifyPublicKeyInput,
+    signature: ArrayBufferView | string,
+    signatureEncoding?: BinaryToTextEncoding,
   ): boolean {
-    notImplemented("crypto.Sign.prototype.sign");
+    return verify(
+      this.algorithm,
+      this.data(),
+      object,
+      toBytes(signature, signatureEncoding),
+    );
   }
 }
 
@@ -109,12 +203,32 @@ export function signOneShot(
   callback: (error: Error | null, data: Buffer) => void,
 ): void;
 export function signOneShot(
-  _algorithm: string | null | undefined,
-  _data: ArrayBufferView,
-  _key: KeyLike | SignKeyObjectInput | SignPrivateKeyInput,
-  _callback?: (error: Error | null, data: Buffer) => void,
+  algorithm: string | null | undefined,
+  data: ArrayBufferView,
+  key: KeyLike | SignKeyObjectInput | SignPrivateKeyInput,
+  callback?: (error: Error | null, data: Buffer) => void,
 ): Buffer | void {
-  notImplemented("crypto.sign");
+  if (algorithm !== null && algorithm !== undefined) {
+    validateString(algorithm, "algorithm");
+  }
+  if (callback !== undefined) {
+    validateFunction(callback, "callback");
+  }
+  let result: Buffer;
+  try {
+    result = sign(normalizeDigest(algorithm), toBytes(data), key);
+  } catch (err) {
+    if (callback) {
+      return queueMicrotask(() =>
+        callback(err as Error, undefined as unknown as Buffer)
+      );
+    }
+    throw err;
+  }
+  if (callback) {
+    return queueMicrotask(() => callback(null, result));
+  }
+  return result;
 }
 
 export function verifyOneShot(
@@ -131,13 +245,36 @@ export function verifyOneShot(
   callback: (error: Error | null, result: boolean) => void,
 ): void;
 export function verifyOneShot(
-  _algorithm: string | null | undefined,
-  _data: ArrayBufferView,
-  _key: KeyLike | VerifyKeyObjectInput | VerifyPublicKeyInput,
-  _signature: ArrayBufferView,
-  _callback?: (error: Error | null, result: boolean) => void,
+  algorithm: string | null | undefined,
+  data: ArrayBufferView,
+  key: KeyLike | VerifyKeyObjectInput | VerifyPublicKeyInput,
+  signature: ArrayBufferView,
+  callback?: (error: Error | null, result: boolean) => void,
 ): boolean | void {
-  notImplemented("crypto.verify");
+  if (algorithm !== null && algorithm !== undefined) {
+    validateString(algorithm, "algorithm");
+  }
+  if (callback !== undefined) {
+    validateFunction(callback, "callback");
+  }
+  let result: boolean;
+  try {
+    result = verify(
+      normalizeDigest(algorithm),
+      toBytes(data),
+      key,
+      toBytes(signature),
+    );
+  } catch (err) {
+    if (callback) {
+      return queueMicrotask(() => callback(err as Error, false));
+    }
+    throw err;
+  }
+  if (callback) {
+    return queueMicrotask(() => callback(null, result));
+  }
+  return result;
 }
 
 export default {
@@ -902,6 +902,12 @@ export class ERR_CRYPTO_INVALID_KEY_OBJECT_TYPE extends NodeTypeError {
   }
 }
 
+export class ERR_CRYPTO_INVALID_SCRYPT_PARAMS extends NodeRangeError {
+  constructor() {
+    super("ERR_CRYPTO_INVALID_SCRYPT_PARAMS", "Invalid scrypt parameter");
+  }
+}
+
 export class ERR_CRYPTO_INVALID_STATE extends NodeError {
   constructor(x: string) {
     super("ERR_CRYPTO_INVALID_STATE", `Invalid state for operation ${x}`);
@@ -1784,6 +1790,14 @@ export class ERR_SCRIPT_EXECUTION_INTERRUPTED extends NodeError {
     );
   }
 }
+export class ERR_SCRIPT_EXECUTION_TIMEOUT extends NodeError {
+  constructor(timeout: number) {
+    super(
+      "ERR_SCRIPT_EXECUTION_TIMEOUT",
+      `Script execution timed out after ${timeout}ms`,
+    );
+  }
+}
 export class ERR_SERVER_ALREADY_LISTEN extends NodeError {
   constructor() {
     super(
@@ -2626,6 +2640,7 @@ export default {
   ERR_CRYPTO_INCOMPATIBLE_KEY_OPTIONS,
   ERR_CRYPTO_INVALID_DIGEST,
   ERR_CRYPTO_INVALID_KEY_OBJECT_TYPE,
+  ERR_CRYPTO_INVALID_SCRYPT_PARAMS,
   ERR_CRYPTO_INVALID_STATE,
   ERR_CRYPTO_PBKDF2_ERROR,
   ERR_CRYPTO_SCRYPT_INVALID_PARAMETER,
@@ -2777,6 +2792,7 @@ export default {
   ERR_QUICSTREAM_UNSUPPORTED_PUSH,
   ERR_QUIC_TLS13_REQUIRED,
   ERR_SCRIPT_EXECUTION_INTERRUPTED,
+  ERR_SCRIPT_EXECUTION_TIMEOUT,
   ERR_SERVER_ALREADY_LISTEN,
   ERR_SERVER_NOT_RUNNING,
   ERR_SOCKET_ALREADY_BOUND,
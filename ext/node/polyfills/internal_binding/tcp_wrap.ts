@@ -77,6 +77,7 @@ export enum constants {
   SOCKET = socketType.SOCKET,
   SERVER = socketType.SERVER,
   UV_TCP_IPV6ONLY,
+  UV_TCP_REUSEPORT = 4,
 }
 
 export class TCP extends ConnectionWrap {
@@ -85,6 +86,7 @@ export class TCP extends ConnectionWrap {
 
   #address?: string;
   #port?: number;
+  #reusePort = false;
 
   #remoteAddress?: string;
   #remoteFamily?: number;
@@ -151,10 +153,11 @@ export class TCP extends ConnectionWrap {
    * Bind to an IPv4 address.
    * @param address The hostname to bind to.
    * @param port The port to bind to
+   * @param flags The `UV_TCP_*` flags of the socket.
    * @return An error status code.
    */
-  bind(address: string, port: number): number {
-    return this.#bind(address, port, 0);
+  bind(address: string, port: number, flags = 0): number {
+    return this.#bind(address, port, flags);
   }
 
   /**
@@ -201,12 +204,17 @@ export class TCP extends ConnectionWrap {
       hostname: this.#address!,
       port: this.#port!,
       transport: "tcp" as const,
+      reusePort: this.#reusePort,
     };
 
     let listener;
 
+    // `reusePort` is used by the servers of `node:cluster` workers, which
+    // mustn't require `--unstable`.
+    const listen = Deno[Deno.internal]?.nodeUnstable?.listen || Deno.listen;
+
     try {
-      listener = Deno.listen(listenOptions);
+      listener = listen(listenOptions);
     } catch (e) {
       if (e instanceof Deno.errors.AddrInUse) {
         return codeMap.get("EADDRINUSE")!;
@@ -331,7 +339,7 @@ export class TCP extends ConnectionWrap {
    * @param _flags
    * @return An error status code.
    */
-  #bind(address: string, port: number, _flags: number): number {
+  #bind(address: string, port: number, flags: number): number {
     // Deno doesn't currently separate bind from connect etc.
     // REF:
     // - https://doc.deno.land/deno/stable/~/Deno.connect
@@ -345,6 +353,7 @@ export class TCP extends ConnectionWrap {
 
     this.#address = address;
     this.#port = port;
+    this.#reusePort = (flags & constants.UV_TCP_REUSEPORT) !== 0;
 
     return 0;
   }
@@ -16,5 +16,6 @@ export const builtinModules = m.builtinModules;
 export const createRequire = m.createRequire;
 export const globalPaths = m.globalPaths;
 export const Module = m.Module;
+export const register = m.register;
 export const wrap = m.wrap;
 export default m;
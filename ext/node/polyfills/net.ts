@@ -105,6 +105,7 @@ import type { DuplexOptions } from "internal:deno_node/polyfills/_stream.d.ts";
 import type { BufferEncoding } from "internal:deno_node/polyfills/_global.d.ts";
 import type { Abortable } from "internal:deno_node/polyfills/_events.d.ts";
 import { channel } from "internal:deno_node/polyfills/diagnostics_channel.ts";
+import { workerHooks } from "internal:deno_node/polyfills/internal/cluster/utils.ts";
 
 let debug = debuglog("net", (fn) => {
   debug = fn;
@@ -1654,16 +1655,12 @@ function _listenInCluster(
 ) {
   exclusive = !!exclusive;
 
-  // TODO(cmorten): here we deviate somewhat from the Node implementation which
-  // makes use of the https://nodejs.org/api/cluster.html module to run servers
-  // across a "cluster" of Node processes to take advantage of multi-core
-  // systems.
-  //
-  // Though Deno has has a Worker capability from which we could simulate this,
-  // for now we assert that we are _always_ on the primary process.
-  const isPrimary = true;
-
-  if (isPrimary || exclusive) {
+  // Here we deviate from the Node implementation, where the servers of
+  // cluster workers listen on a handle of the primary process. Workers listen
+  // on their own sockets with `SO_REUSEPORT` instead, and the kernel
+  // distributes the connections among them on Linux and FreeBSD. macOS and
+  // the other BSDs only share the port, Windows can't share it at all.
+  if (workerHooks === null || exclusive) {
     // Will create a new handle
     // _listen2 sets up the listened handle, it is still named like this
     // to avoid breaking code that wraps this method
@@ -1671,6 +1668,28 @@ function _listenInCluster(
 
     return;
   }
+
+  if (isWindows) {
+    notImplemented("node:cluster workers sharing a server on Windows");
+  }
+
+  const hooks = workerHooks;
+  server.once("listening", () => {
+    const info = server.address();
+    if (info !== null && typeof info === "object") {
+      hooks.onListening({ address: info.address, port: info.port, addressType });
+    } else {
+      hooks.onListening({ address, port, addressType });
+    }
+  });
+  server._listen2(
+    address,
+    port,
+    addressType,
+    backlog,
+    fd,
+    (flags ?? 0) | TCPConstants.UV_TCP_REUSEPORT,
+  );
 }
 
 function _lookupAndListen(
@@ -1793,7 +1812,7 @@ export function _createServerHandle(
     } else if (addressType === 6) {
       err = (handle as TCP).bind6(address, port ?? 0, flags ?? 0);
     } else {
-      err = (handle as TCP).bind(address, port ?? 0);
+      err = (handle as TCP).bind(address, port ?? 0, flags ?? 0);
     }
   }
 
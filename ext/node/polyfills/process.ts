@@ -64,14 +64,13 @@ import * as constants from "internal:deno_node/polyfills/internal_binding/consta
 import * as uv from "internal:deno_node/polyfills/internal_binding/uv.ts";
 import type { BindingName } from "internal:deno_node/polyfills/internal_binding/mod.ts";
 import { buildAllowedFlags } from "internal:deno_node/polyfills/internal/process/per_thread.mjs";
+import { setupChannel } from "internal:deno_node/polyfills/internal/child_process/ipc.ts";
 
 // @ts-ignore Deno[Deno.internal] is used on purpose here
 const DenoCommand = Deno[Deno.internal]?.nodeUnstable?.Command ||
   Deno.Command;
 
 const notImplementedEvents = [
-  "disconnect",
-  "message",
   "multipleResolves",
   "rejectionHandled",
   "worker",
@@ -766,6 +765,24 @@ internals.__bootstrapNodeProcess = function (
     "stdout",
   );
 
+  // Opens the channel to the parent process if this process was forked.
+  const ipcRid = core.ops.op_node_ipc_pipe();
+  if (ipcRid !== null) {
+    // The channel only keeps the process alive while messages are listened
+    // to.
+    const channel = setupChannel(process, ipcRid, false);
+    process.on("newListener", (event: string) => {
+      if (event === "message") {
+        channel.ref();
+      }
+    });
+    process.on("removeListener", (event: string) => {
+      if (event === "message" && process.listenerCount("message") === 0) {
+        channel.unref();
+      }
+    });
+  }
+
   process.setStartTime(Date.now());
   // @ts-ignore Remove setStartTime and #startTime is not modifiable
   delete process.setStartTime;
@@ -3,23 +3,74 @@
 // deno-lint-ignore-file no-explicit-any
 
 import { notImplemented } from "internal:deno_node/polyfills/_utils.ts";
+import {
+  ERR_INVALID_ARG_TYPE,
+  ERR_SCRIPT_EXECUTION_TIMEOUT,
+} from "internal:deno_node/polyfills/internal/errors.ts";
+import {
+  validateArray,
+  validateObject,
+  validateString,
+  validateUint32,
+} from "internal:deno_node/polyfills/internal/validators.mjs";
+
+const { ops } = globalThis.__bootstrap.core;
+
+const DEFAULT_FILENAME = "evalmachine.<anonymous>";
+
+function getFilename(options: any): string {
+  if (typeof options === "string") {
+    return options;
+  }
+  return options?.filename ?? DEFAULT_FILENAME;
+}
+
+function getTimeout(options: any): number | undefined {
+  const timeout = typeof options === "object" ? options?.timeout : undefined;
+  if (timeout !== undefined) {
+    validateUint32(timeout, "options.timeout", true);
+  }
+  return timeout;
+}
 
 export class Script {
   code: string;
-  constructor(code: string, _options = {}) {
+  #filename: string;
+
+  constructor(code: string, options: any = {}) {
     this.code = `${code}`;
+    this.#filename = getFilename(options);
+  }
+
+  runInThisContext(options: any) {
+    return this.#run(null, options);
   }
 
-  runInThisContext(_options: any) {
-    return eval.call(globalThis, this.code);
+  runInContext(contextifiedObject: any, options: any) {
+    validateContext(contextifiedObject);
+    return this.#run(contextifiedObject, options);
   }
 
-  runInContext(_contextifiedObject: any, _options: any) {
-    notImplemented("Script.prototype.runInContext");
+  #run(contextifiedObject: any, options: any) {
+    const timeout = getTimeout(options);
+    const [result, thrown, timedOut] = ops.op_vm_run_in_context(
+      this.code,
+      contextifiedObject,
+      this.#filename,
+      timeout,
+    );
+    if (timedOut) {
+      throw new ERR_SCRIPT_EXECUTION_TIMEOUT(timeout!);
+    }
+    if (thrown !== null) {
+      throw thrown;
+    }
+    return result;
   }
 
-  runInNewContext(_contextObject: any, _options: any) {
-    notImplemented("Script.prototype.runInNewContext");
+  runInNewContext(contextObject: any, options: any) {
+    const context = createContext(contextObject, options);
+    return this.runInContext(context, options);
   }
 
   createCachedData() {
@@ -27,8 +78,23 @@ export class Script {
   }
 }
 
-export function createContext(_contextObject: any, _options: any) {
-  notImplemented("createContext");
+function validateContext(contextifiedObject: any) {
+  if (!isContext(contextifiedObject)) {
+    throw new ERR_INVALID_ARG_TYPE(
+      "contextifiedObject",
+      "vm.Context",
+      contextifiedObject,
+    );
+  }
+}
+
+export function createContext(contextObject: any = {}, _options: any) {
+  validateObject(contextObject, "contextObject");
+  if (isContext(contextObject)) {
+    return contextObject;
+  }
+  ops.op_vm_create_context(contextObject);
+  return contextObject;
 }
 
 export function createScript(code: string, options: any) {
@@ -36,19 +102,19 @@ export function createScript(code: string, options: any) {
 }
 
 export function runInContext(
-  _code: string,
-  _contextifiedObject: any,
-  _options: any,
+  code: string,
+  contextifiedObject: any,
+  options: any,
 ) {
-  notImplemented("runInContext");
+  return createScript(code, options).runInContext(contextifiedObject, options);
 }
 
 export function runInNewContext(
-  _code: string,
-  _contextObject: any,
-  _options: any,
+  code: string,
+  contextObject: any,
+  options: any,
 ) {
-  notImplemented("runInNewContext");
+  return createScript(code, options).runInNewContext(contextObject, options);
 }
 
 export function runInThisContext(
@@ -58,12 +124,28 @@ export function runInThisContext(
   return createScript(code, options).runInThisContext(options);
 }
 
-export function isContext(_maybeContext: any) {
-  notImplemented("isContext");
+export function isContext(maybeContext: any) {
+  validateObject(maybeContext, "contextifiedObject", { allowArray: true });
+  return ops.op_vm_is_context(maybeContext);
 }
 
-export function compileFunction(_code: string, _params: any, _options: any) {
-  notImplemented("compileFunction");
+export function compileFunction(
+  code: string,
+  params: string[] = [],
+  options: any = {},
+) {
+  validateString(code, "code");
+  validateArray(params, "params");
+  validateObject(options, "options");
+  if (options.contextExtensions?.length > 0) {
+    notImplemented("compileFunction with contextExtensions");
+  }
+  // The function is created by the Function constructor of the parsing
+  // context, so it runs with that context's globals.
+  const FunctionConstructor = options.parsingContext === undefined
+    ? Function
+    : runInContext("Function", options.parsingContext);
+  return new FunctionConstructor(...params, code);
 }
 
 export function measureMemory(_options: any) {
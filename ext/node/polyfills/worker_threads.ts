@@ -1,9 +1,27 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 // Copyright Joyent and Node contributors. All rights reserved. MIT license.
 
+// deno-lint-ignore-file no-explicit-any
+
 import { resolve, toFileUrl } from "internal:deno_node/polyfills/path.ts";
 import { notImplemented } from "internal:deno_node/polyfills/_utils.ts";
 import { EventEmitter } from "internal:deno_node/polyfills/events.ts";
+import { nextTick } from "internal:deno_node/polyfills/_next_tick.ts";
+import process from "internal:deno_node/polyfills/process.ts";
+import { ERR_INVALID_ARG_TYPE } from "internal:deno_node/polyfills/internal/errors.ts";
+import {
+  Worker as WebWorker,
+  workerCloseSymbol,
+  workerMetadataSymbol,
+} from "internal:runtime/js/11_workers.js";
+import {
+  deserializeJsMessageData,
+  MessagePortIdSymbol,
+  serializeJsMessageData,
+} from "internal:deno_web/13_message_port.js";
+
+const { ops } = globalThis.__bootstrap.core;
+const internals = globalThis.__bootstrap.internals;
 
 const environmentData = new Map();
 let threads = 0;
@@ -11,7 +29,7 @@ let threads = 0;
 export interface WorkerOptions {
   // only for typings
   argv?: unknown[];
-  env?: Record<string, unknown>;
+  env?: Record<string, unknown> | typeof SHARE_ENV;
   execArgv?: string[];
   stdin?: boolean;
   stdout?: boolean;
@@ -29,85 +47,133 @@ export interface WorkerOptions {
   workerData?: unknown;
 }
 
+type ResourceLimits = Required<NonNullable<WorkerOptions["resourceLimits"]>>;
+
+/** The data a worker thread receives from its creator on startup. */
+interface WorkerMetadata {
+  environmentData: Map<unknown, unknown>;
+  /** Shared with the worker, which stores its `process.exitCode` here. */
+  exitCode: Int32Array;
+  resourceLimits: ResourceLimits;
+  threadId: number;
+  workerData: unknown;
+}
+
+export const SHARE_ENV = Symbol.for("nodejs.worker_threads.SHARE_ENV");
+
+const untransferableObjects = new WeakSet<object>();
+
+/** Objects marked with `markAsUntransferable()` are cloned instead. */
+function filterTransferList(transferList: Transferable[] = []) {
+  return transferList.filter((transferable) =>
+    !untransferableObjects.has(transferable)
+  );
+}
+
 const kHandle = Symbol("kHandle");
 const PRIVATE_WORKER_THREAD_NAME = "$DENO_STD_NODE_WORKER_THREAD";
 class _Worker extends EventEmitter {
   readonly threadId: number;
-  readonly resourceLimits: Required<
-    NonNullable<WorkerOptions["resourceLimits"]>
-  > = {
-    maxYoungGenerationSizeMb: -1,
-    maxOldGenerationSizeMb: -1,
-    codeRangeSizeMb: -1,
-    stackSizeMb: 4,
-  };
-  private readonly [kHandle]: Worker;
+  readonly resourceLimits: ResourceLimits;
+  private readonly [kHandle]: WebWorker;
+  #exited = false;
 
-  postMessage: Worker["postMessage"];
-
-  constructor(specifier: URL | string, options?: WorkerOptions) {
-    notImplemented("Worker");
+  constructor(specifier: URL | string, options: WorkerOptions = {}) {
     super();
-    if (options?.eval === true) {
+    if (options.eval === true) {
       specifier = `data:text/javascript,${specifier}`;
     } else if (typeof specifier === "string") {
-      // @ts-ignore This API is temporarily disabled
       specifier = toFileUrl(resolve(specifier));
     }
-    const handle = this[kHandle] = new Worker(
-      specifier,
-      {
-        name: PRIVATE_WORKER_THREAD_NAME,
-        type: "module",
-      } as globalThis.WorkerOptions, // bypass unstable type error
-    );
-    handle.addEventListener(
-      "error",
-      (event) => this.emit("error", event.error || event.message),
-    );
+    // The environment variables of a process are shared by all of its
+    // threads in Deno, which is what `SHARE_ENV` asks for. Passing
+    // `process.env` is accepted as well, as it's the common case.
+    if (
+      options.env !== undefined && options.env !== SHARE_ENV &&
+      options.env !== process.env
+    ) {
+      notImplemented("Worker with env other than SHARE_ENV");
+    }
+
+    this.threadId = ++threads;
+    this.resourceLimits = {
+      maxYoungGenerationSizeMb: -1,
+      maxOldGenerationSizeMb: -1,
+      codeRangeSizeMb: -1,
+      stackSizeMb: 4,
+      ...options.resourceLimits,
+    };
+    const maxHeapSize = this.resourceLimits.maxOldGenerationSizeMb;
+
+    const exitCode = new Int32Array(new SharedArrayBuffer(4));
+    const metadata: WorkerMetadata = {
+      environmentData,
+      exitCode,
+      resourceLimits: this.resourceLimits,
+      threadId: this.threadId,
+      workerData: options.workerData,
+    };
+    const handle = this[kHandle] = new WebWorker(specifier, {
+      name: PRIVATE_WORKER_THREAD_NAME,
+      type: "module",
+      deno: maxHeapSize > 0 ? { limits: { maxHeapSize } } : undefined,
+      [workerMetadataSymbol]: serializeJsMessageData(
+        metadata,
+        filterTransferList(options.transferList),
+      ),
+      // the worker finished, or called `process.exit()`
+      [workerCloseSymbol]: () => this.#exit(Atomics.load(exitCode, 0)),
+    });
+    handle.addEventListener("error", (event: ErrorEvent) => {
+      // Without a listener the error is thrown on the main thread, like in
+      // Node.js.
+      if (this.listenerCount("error") > 0) {
+        event.preventDefault();
+        this.emit("error", event.error ?? new Error(event.message));
+      }
+      // An uncaught exception stops the worker thread.
+      handle.terminate();
+      this.#exit(1);
+    });
     handle.addEventListener(
       "messageerror",
-      (event) => this.emit("messageerror", event.data),
+      (event: MessageEvent) => this.emit("messageerror", event.data),
     );
     handle.addEventListener(
       "message",
-      (event) => this.emit("message", event.data),
+      (event: MessageEvent) => this.emit("message", event.data),
     );
-    handle.postMessage({
-      environmentData,
-      threadId: (this.threadId = ++threads),
-      workerData: options?.workerData,
-    }, options?.transferList || []);
-    this.postMessage = handle.postMessage.bind(handle);
-    this.emit("online");
+    nextTick(() => this.emit("online"));
+  }
+
+  postMessage(message: unknown, transferList?: Transferable[]) {
+    this[kHandle].postMessage(message, filterTransferList(transferList));
   }
 
-  terminate() {
+  terminate(): Promise<number> {
     this[kHandle].terminate();
-    this.emit("exit", 0);
+    this.#exit(1);
+    return Promise.resolve(1);
   }
 
+  #exit(code: number) {
+    if (this.#exited) {
+      return;
+    }
+    this.#exited = true;
+    nextTick(() => this.emit("exit", code));
+  }
+
+  // Workers always keep the event loop alive for now.
+  ref() {}
+  unref() {}
+
   readonly getHeapSnapshot = () =>
     notImplemented("Worker.prototype.getHeapSnapshot");
   // fake performance
   readonly performance = globalThis.performance;
 }
 
-export const isMainThread =
-  // deno-lint-ignore no-explicit-any
-  (globalThis as any).name !== PRIVATE_WORKER_THREAD_NAME;
-
-// fake resourceLimits
-export const resourceLimits = isMainThread ? {} : {
-  maxYoungGenerationSizeMb: 48,
-  maxOldGenerationSizeMb: 2048,
-  codeRangeSizeMb: 0,
-  stackSizeMb: 4,
-};
-
-const threadId = 0;
-const workerData: unknown = null;
-
 // Like https://github.com/nodejs/node/blob/48655e17e1d84ba5021d7a94b4b88823f7c9c6cf/lib/internal/event_target.js#L611
 interface NodeEventTarget extends
   Pick<
@@ -116,11 +182,8 @@ interface NodeEventTarget extends
   > {
   setMaxListeners(n: number): void;
   getMaxListeners(): number;
-  // deno-lint-ignore no-explicit-any
   off(eventName: string, listener: (...args: any[]) => void): NodeEventTarget;
-  // deno-lint-ignore no-explicit-any
   on(eventName: string, listener: (...args: any[]) => void): NodeEventTarget;
-  // deno-lint-ignore no-explicit-any
   once(eventName: string, listener: (...args: any[]) => void): NodeEventTarget;
   addListener: NodeEventTarget["on"];
   removeListener: NodeEventTarget["off"];
@@ -128,75 +191,139 @@ interface NodeEventTarget extends
 
 type ParentPort = typeof self & NodeEventTarget;
 
-// deno-lint-ignore no-explicit-any
-const parentPort: ParentPort = null as any;
+export let isMainThread = true;
+export let resourceLimits: ResourceLimits | Record<string, never> = {};
+export let threadId = 0;
+export let workerData: unknown = null;
+export let parentPort: ParentPort | null = null;
 
-/*
-if (!isMainThread) {
-  // deno-lint-ignore no-explicit-any
-  delete (globalThis as any).name;
-  // deno-lint-ignore no-explicit-any
+/** Turns the global scope of a worker into Node's `parentPort`. */
+function createParentPort(): ParentPort {
   const listeners = new WeakMap<(...args: any[]) => void, (ev: any) => any>();
 
-  parentPort = self as ParentPort;
-  parentPort.off = parentPort.removeListener = function (
+  // Like in Node.js, the worker only waits for messages while there are
+  // "message" listeners, otherwise it finishes once it has nothing left to do.
+  let messageListeners = 0;
+  const countMessageListener = (name: string, delta: number) => {
+    if (name !== "message") {
+      return;
+    }
+    messageListeners += delta;
+    internals.setWorkerMessagePollingRef(messageListeners > 0);
+  };
+
+  const port = self as ParentPort;
+  const postMessage = port.postMessage.bind(port);
+  port.postMessage = (message: unknown, transferList?: any) =>
+    postMessage(
+      message,
+      Array.isArray(transferList)
+        ? filterTransferList(transferList)
+        : transferList,
+    );
+  port.off = port.removeListener = function (
     this: ParentPort,
     name,
     listener,
   ) {
-    this.removeEventListener(name, listeners.get(listener)!);
-    listeners.delete(listener);
+    const _listener = listeners.get(listener);
+    if (_listener !== undefined) {
+      this.removeEventListener(name, _listener);
+      listeners.delete(listener);
+      countMessageListener(name, -1);
+    }
     return this;
   };
-  parentPort.on = parentPort.addListener = function (
+  port.on = port.addListener = function (
     this: ParentPort,
     name,
     listener,
   ) {
-    // deno-lint-ignore no-explicit-any
     const _listener = (ev: any) => listener(ev.data);
     listeners.set(listener, _listener);
     this.addEventListener(name, _listener);
+    countMessageListener(name, 1);
     return this;
   };
-  parentPort.once = function (this: ParentPort, name, listener) {
-    // deno-lint-ignore no-explicit-any
-    const _listener = (ev: any) => listener(ev.data);
+  port.once = function (this: ParentPort, name, listener) {
+    const _listener = (ev: any) => {
+      listeners.delete(listener);
+      countMessageListener(name, -1);
+      listener(ev.data);
+    };
     listeners.set(listener, _listener);
-    this.addEventListener(name, _listener);
+    this.addEventListener(name, _listener, { once: true });
+    countMessageListener(name, 1);
     return this;
   };
 
   // mocks
-  parentPort.setMaxListeners = () => {};
-  parentPort.getMaxListeners = () => Infinity;
-  parentPort.eventNames = () => [""];
-  parentPort.listenerCount = () => 0;
+  port.setMaxListeners = () => {};
+  port.getMaxListeners = () => Infinity;
+  port.eventNames = () => [""];
+  port.listenerCount = () => 0;
 
-  parentPort.emit = () => notImplemented("parentPort.emit");
-  parentPort.removeAllListeners = () =>
+  port.emit = () => notImplemented("parentPort.emit");
+  port.removeAllListeners = () =>
     notImplemented("parentPort.removeAllListeners");
 
-  // Receive startup message
-  [{ threadId, workerData, environmentData }] = await once(
-    parentPort,
-    "message",
-  );
+  return port;
+}
 
-  // alias
-  parentPort.addEventListener("offline", () => {
-    parentPort.emit("close");
+/**
+ * Keeps `process.exitCode` of a worker in `exitCode`, for its host to read
+ * once it finished. `process.exit()` sets it as well.
+ */
+function shareExitCode(exitCode: Int32Array) {
+  let code = process.exitCode;
+  Object.defineProperty(process, "exitCode", {
+    get: () => code,
+    set: (value) => {
+      code = value;
+      Atomics.store(exitCode, 0, Number(value) || 0);
+    },
+    configurable: true,
+    enumerable: true,
   });
 }
-*/
+
+// Should be called only once, when the Node.js compatibility layer is
+// initialized, before the main module of a worker is evaluated.
+internals.__initWorkerThreads = function () {
+  isMainThread = (globalThis as any).name !== PRIVATE_WORKER_THREAD_NAME;
+  if (!isMainThread) {
+    delete (globalThis as any).name;
+
+    const data = ops.op_worker_get_metadata();
+    if (data !== null) {
+      const [metadata] = deserializeJsMessageData(data) as [WorkerMetadata];
+      for (const [key, value] of metadata.environmentData) {
+        environmentData.set(key, value);
+      }
+      resourceLimits = metadata.resourceLimits;
+      threadId = metadata.threadId;
+      workerData = metadata.workerData;
+      shareExitCode(metadata.exitCode);
+    }
+    parentPort = createParentPort();
+    internals.setWorkerMessagePollingRef(false);
+  }
+
+  Object.assign(defaultExport, {
+    isMainThread,
+    parentPort,
+    resourceLimits,
+    threadId,
+    workerData,
+  });
+  delete internals.__initWorkerThreads;
+};
 
 export function getEnvironmentData(key: unknown) {
-  notImplemented("getEnvironmentData");
   return environmentData.get(key);
 }
 
 export function setEnvironmentData(key: unknown, value?: unknown) {
-  notImplemented("setEnvironmentData");
   if (value === undefined) {
     environmentData.delete(key);
   } else {
@@ -204,32 +331,48 @@ export function setEnvironmentData(key: unknown, value?: unknown) {
   }
 }
 
-// deno-lint-ignore no-explicit-any
 const _MessagePort: typeof MessagePort = (globalThis as any).MessagePort;
-const _MessageChannel: typeof MessageChannel =
-  // deno-lint-ignore no-explicit-any
-  (globalThis as any).MessageChannel;
+const _MessageChannel: typeof MessageChannel = (globalThis as any)
+  .MessageChannel;
 export const BroadcastChannel = globalThis.BroadcastChannel;
-export const SHARE_ENV = Symbol.for("nodejs.worker_threads.SHARE_ENV");
-export function markAsUntransferable() {
-  notImplemented("markAsUntransferable");
+export function markAsUntransferable(object: object) {
+  if (
+    object !== null && (typeof object === "object" ||
+      typeof object === "function")
+  ) {
+    untransferableObjects.add(object);
+  }
 }
 export function moveMessagePortToContext() {
   notImplemented("moveMessagePortToContext");
 }
-export function receiveMessageOnPort() {
-  notImplemented("receiveMessageOnPort");
+
+/** Synchronously receives a message queued on `port`, if there is one. */
+export function receiveMessageOnPort(
+  port: MessagePort,
+): { message: unknown } | undefined {
+  if (!(port instanceof _MessagePort)) {
+    throw new ERR_INVALID_ARG_TYPE("port", "MessagePort", port);
+  }
+  const rid = (port as any)[MessagePortIdSymbol];
+  // the port was closed or transferred
+  if (rid === null) {
+    return undefined;
+  }
+  const data = ops.op_message_port_recv_message_sync(rid);
+  if (data === null) {
+    return undefined;
+  }
+  const [message] = deserializeJsMessageData(data);
+  return { message };
 }
 export {
   _MessageChannel as MessageChannel,
   _MessagePort as MessagePort,
   _Worker as Worker,
-  parentPort,
-  threadId,
-  workerData,
 };
 
-export default {
+const defaultExport = {
   markAsUntransferable,
   moveMessagePortToContext,
   receiveMessageOnPort,
@@ -246,3 +389,5 @@ export default {
   parentPort,
   isMainThread,
 };
+
+export default defaultExport;
@@ -1,6 +1,16 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 import { notImplemented } from "internal:deno_node/polyfills/_utils.ts";
 import { zlib as constants } from "internal:deno_node/polyfills/internal_binding/constants.ts";
+import {
+  BrotliCompress,
+  brotliCompress,
+  brotliCompressSync,
+  BrotliDecompress,
+  brotliDecompress,
+  brotliDecompressSync,
+  createBrotliCompress,
+  createBrotliDecompress,
+} from "internal:deno_node/polyfills/_brotli.ts";
 import {
   codes,
   createDeflate,
@@ -42,40 +52,12 @@ export class BrotliOptions {
     notImplemented("BrotliOptions.prototype.constructor");
   }
 }
-export class BrotliCompress {
-  constructor() {
-    notImplemented("BrotliCompress.prototype.constructor");
-  }
-}
-export class BrotliDecompress {
-  constructor() {
-    notImplemented("BrotliDecompress.prototype.constructor");
-  }
-}
 export class ZlibBase {
   constructor() {
     notImplemented("ZlibBase.prototype.constructor");
   }
 }
 export { constants };
-export function createBrotliCompress() {
-  notImplemented("createBrotliCompress");
-}
-export function createBrotliDecompress() {
-  notImplemented("createBrotliDecompress");
-}
-export function brotliCompress() {
-  notImplemented("brotliCompress");
-}
-export function brotliCompressSync() {
-  notImplemented("brotliCompressSync");
-}
-export function brotliDecompress() {
-  notImplemented("brotliDecompress");
-}
-export function brotliDecompressSync() {
-  notImplemented("brotliDecompressSync");
-}
 
 export default {
   Options,
@@ -122,7 +104,15 @@ export default {
 };
 
 export {
+  BrotliCompress,
+  brotliCompress,
+  brotliCompressSync,
+  BrotliDecompress,
+  brotliDecompress,
+  brotliDecompressSync,
   codes,
+  createBrotliCompress,
+  createBrotliDecompress,
   createDeflate,
   createDeflateRaw,
   createGunzip,
@@ -21,6 +21,19 @@ use crate::RequireNpmResolver;
 pub static DEFAULT_CONDITIONS: &[&str] = &["deno", "node", "import"];
 pub static REQUIRE_CONDITIONS: &[&str] = &["require", "node"];
 
+/// Adds the custom conditions of `--conditions` or the "conditions" of the
+/// configuration file to `conditions`.
+pub fn with_custom_conditions<'a>(
+  conditions: &[&'a str],
+  custom_conditions: &'a [String],
+) -> Vec<&'a str> {
+  conditions
+    .iter()
+    .copied()
+    .chain(custom_conditions.iter().map(String::as_str))
+    .collect()
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum NodeModuleKind {
   Esm,
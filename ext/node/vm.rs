@@ -0,0 +1,281 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::serde_v8;
+use deno_core::v8;
+use deno_core::JsRealm;
+use serde::Serialize;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Name of the private symbol under which a contextified object keeps the
+/// global proxy of its context.
+const CONTEXT_PRIVATE_NAME: &str = "node:vm:context";
+
+fn context_private<'s>(
+  scope: &mut v8::HandleScope<'s>,
+) -> v8::Local<'s, v8::Private> {
+  let name = v8::String::new(scope, CONTEXT_PRIVATE_NAME).unwrap();
+  v8::Private::for_api(scope, Some(name))
+}
+
+/// Returns the context that `value` was contextified into with
+/// `vm.createContext()`, if any.
+fn contextified_context<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  value: v8::Local<v8::Value>,
+) -> Option<v8::Local<'s, v8::Context>> {
+  let sandbox = v8::Local::<v8::Object>::try_from(value).ok()?;
+  let private = context_private(scope);
+  let global = sandbox.get_private(scope, private)?;
+  let global = v8::Local::<v8::Object>::try_from(global).ok()?;
+  global.get_creation_context(scope)
+}
+
+/// The contextified object is passed as the data of the interceptors of its
+/// context's global object.
+fn sandbox_from_args<'s>(
+  args: &v8::PropertyCallbackArguments<'s>,
+) -> v8::Local<'s, v8::Object> {
+  v8::Local::<v8::Object>::try_from(args.data()).unwrap()
+}
+
+// The interceptors below look up properties on the contextified object
+// first, like Node.js does. Properties it doesn't have are left to the
+// global object of the context, which holds the JavaScript builtins.
+
+fn property_getter<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  key: v8::Local<'s, v8::Name>,
+  args: v8::PropertyCallbackArguments<'s>,
+  mut rv: v8::ReturnValue,
+) {
+  let sandbox = sandbox_from_args(&args);
+  if !sandbox.has(scope, key.into()).unwrap_or(false) {
+    return;
+  }
+  if let Some(value) = sandbox.get(scope, key.into()) {
+    // the contextified object is the global object as seen from inside
+    if value.strict_equals(sandbox.into()) {
+      if let Some(context) = args.holder().get_creation_context(scope) {
+        rv.set(context.global(scope).into());
+        return;
+      }
+    }
+    rv.set(value);
+  }
+}
+
+fn property_setter<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  key: v8::Local<'s, v8::Name>,
+  value: v8::Local<'s, v8::Value>,
+  args: v8::PropertyCallbackArguments<'s>,
+) {
+  let sandbox = sandbox_from_args(&args);
+  // The store isn't intercepted, so the property is also set on the global
+  // object, which keeps declared globals working as expected.
+  sandbox.set(scope, key.into(), value);
+}
+
+fn property_deleter<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  key: v8::Local<'s, v8::Name>,
+  args: v8::PropertyCallbackArguments<'s>,
+  mut rv: v8::ReturnValue,
+) {
+  let sandbox = sandbox_from_args(&args);
+  if !sandbox.delete(scope, key.into()).unwrap_or(false) {
+    rv.set(v8::Boolean::new(scope, false).into());
+  }
+}
+
+fn property_enumerator<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  args: v8::PropertyCallbackArguments<'s>,
+  mut rv: v8::ReturnValue,
+) {
+  let sandbox = sandbox_from_args(&args);
+  if let Some(names) = sandbox.get_own_property_names(scope, Default::default())
+  {
+    rv.set(names.into());
+  }
+}
+
+#[op(v8)]
+fn op_vm_create_context(
+  scope: &mut v8::HandleScope,
+  sandbox: serde_v8::Value,
+) -> Result<(), AnyError> {
+  let sandbox = v8::Local::<v8::Object>::try_from(sandbox.v8_value)
+    .map_err(|_| type_error("Invalid context object"))?;
+
+  let global_template = v8::ObjectTemplate::new(scope);
+  global_template.set_named_property_handler(
+    v8::NamedPropertyHandlerConfiguration::new()
+      .getter(property_getter)
+      .setter(property_setter)
+      .deleter(property_deleter)
+      .enumerator(property_enumerator)
+      .data(sandbox.into()),
+  );
+  let context = v8::Context::new_from_template(scope, global_template);
+
+  // Objects are passed between the contexts, so they have to trust each
+  // other.
+  let current_context = scope.get_current_context();
+  let security_token = current_context.get_security_token(scope);
+  context.set_security_token(security_token);
+  JsRealm::share_state_with(scope, context);
+
+  let global = context.global(scope);
+  let private = context_private(scope);
+  sandbox.set_private(scope, private, global.into());
+  Ok(())
+}
+
+#[op(v8)]
+fn op_vm_is_context(
+  scope: &mut v8::HandleScope,
+  value: serde_v8::Value,
+) -> bool {
+  contextified_context(scope, value.v8_value).is_some()
+}
+
+/// The `v8` crate doesn't expose definer interceptors yet, so declarations
+/// like `var x;` and `function f() {}` define properties on the global object
+/// of the context without reaching the contextified object. They are copied
+/// over after the script ran. The builtins of the context are skipped, as
+/// they aren't enumerable.
+fn copy_declared_globals(
+  scope: &mut v8::HandleScope,
+  context: v8::Local<v8::Context>,
+  sandbox: v8::Local<v8::Object>,
+) {
+  // exceptions thrown by the contextified object are ignored
+  let scope = &mut v8::TryCatch::new(scope);
+  let global = context.global(scope);
+  let names = match global.get_own_property_names(scope, Default::default()) {
+    Some(names) => names,
+    None => return,
+  };
+  for index in 0..names.length() {
+    let name = match names.get_index(scope, index) {
+      Some(name) => name,
+      None => continue,
+    };
+    if sandbox.has(scope, name).unwrap_or(true) {
+      continue;
+    }
+    if let Some(value) = global.get(scope, name) {
+      sandbox.set(scope, name, value);
+    }
+  }
+}
+
+/// Terminates the execution of a script once its timeout expires.
+struct Watchdog {
+  cancel_tx: mpsc::Sender<()>,
+  thread: thread::JoinHandle<bool>,
+}
+
+impl Watchdog {
+  fn start(isolate_handle: v8::IsolateHandle, timeout: u64) -> Self {
+    let (cancel_tx, cancel_rx) = mpsc::channel();
+    let thread = thread::spawn(move || {
+      match cancel_rx.recv_timeout(Duration::from_millis(timeout)) {
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+          isolate_handle.terminate_execution()
+        }
+        _ => false,
+      }
+    });
+    Self { cancel_tx, thread }
+  }
+
+  /// Returns whether the execution was terminated by the watchdog.
+  fn stop(self) -> bool {
+    drop(self.cancel_tx);
+    self.thread.join().unwrap_or(false)
+  }
+}
+
+#[derive(Serialize)]
+struct RunScriptResult<'s>(
+  Option<serde_v8::Value<'s>>,
+  Option<serde_v8::Value<'s>>,
+  bool,
+);
+
+/// Runs `source` as a classic script in the context of the contextified
+/// object `sandbox`, or in the current context if `sandbox` is null.
+/// Exceptions are returned to be rethrown by the caller, like
+/// `Deno.core.evalContext()` does, along with whether `timeout` expired.
+#[op(v8)]
+fn op_vm_run_in_context<'a>(
+  scope: &mut v8::HandleScope<'a>,
+  source: serde_v8::Value<'a>,
+  sandbox: serde_v8::Value<'a>,
+  filename: String,
+  timeout: Option<u64>,
+) -> Result<RunScriptResult<'a>, AnyError> {
+  let source = v8::Local::<v8::String>::try_from(source.v8_value)
+    .map_err(|_| type_error("Invalid source"))?;
+  let (context, maybe_sandbox) = if sandbox.v8_value.is_null() {
+    (scope.get_current_context(), None)
+  } else {
+    let context = contextified_context(scope, sandbox.v8_value)
+      .ok_or_else(|| type_error("The object is not a vm.Context"))?;
+    // contextified objects are always objects
+    let sandbox = v8::Local::<v8::Object>::try_from(sandbox.v8_value).unwrap();
+    (context, Some(sandbox))
+  };
+
+  let scope = &mut v8::ContextScope::new(scope, context);
+  let tc_scope = &mut v8::TryCatch::new(scope);
+  let filename = v8::String::new(tc_scope, &filename).unwrap();
+  let source_map_url = v8::String::new(tc_scope, "").unwrap();
+  let origin = v8::ScriptOrigin::new(
+    tc_scope,
+    filename.into(),
+    0,
+    0,
+    false,
+    0,
+    source_map_url.into(),
+    false,
+    false,
+    false,
+  );
+
+  let maybe_watchdog = timeout
+    .map(|timeout| Watchdog::start(tc_scope.thread_safe_handle(), timeout));
+  let maybe_result = v8::Script::compile(tc_scope, source, Some(&origin))
+    .and_then(|script| script.run(tc_scope));
+  let timed_out = maybe_watchdog.map(Watchdog::stop).unwrap_or(false);
+  if timed_out {
+    // only the script is terminated, not its caller
+    tc_scope.cancel_terminate_execution();
+  } else if tc_scope.has_terminated() {
+    // The termination has to reach the caller, e.g. for
+    // `worker.terminate()`. The result is discarded.
+    tc_scope.rethrow();
+    return Ok(RunScriptResult(None, None, false));
+  }
+
+  if let Some(sandbox) = maybe_sandbox {
+    copy_declared_globals(tc_scope, context, sandbox);
+  }
+  match maybe_result {
+    Some(result) => Ok(RunScriptResult(Some(result.into()), None, false)),
+    None if timed_out => Ok(RunScriptResult(None, None, true)),
+    None => Ok(RunScriptResult(
+      None,
+      tc_scope.exception().map(|exception| exception.into()),
+      false,
+    )),
+  }
+}
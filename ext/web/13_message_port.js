@@ -338,6 +338,7 @@ export {
   deserializeJsMessageData,
   MessageChannel,
   MessagePort,
+  _id as MessagePortIdSymbol,
   MessagePortPrototype,
   serializeJsMessageData,
   structuredClone,
@@ -16,6 +16,7 @@ webidl.converters.CompressionFormat = webidl.createEnumConverter(
     "deflate",
     "deflate-raw",
     "gzip",
+    "br",
   ],
 );
 
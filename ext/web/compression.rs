@@ -1,5 +1,11 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use brotli::enc::backward_references::BrotliEncoderMode;
+use brotli::enc::backward_references::BrotliEncoderParams;
+use brotli::CompressorWriter as BrotliEncoder;
+use brotli::DecompressorWriter as BrotliDecoder;
+use deno_core::error::bad_resource;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op;
 use deno_core::OpState;
@@ -13,16 +19,22 @@ use flate2::write::GzEncoder;
 use flate2::write::ZlibDecoder;
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
+use serde::Deserialize;
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::io::Write;
 use std::rc::Rc;
 
-#[derive(Debug)]
+/// Size of the internal buffers of the Brotli encoder and decoder.
+const BROTLI_BUFFER_SIZE: usize = 4096;
+/// The quality used by `CompressionStream`, same as for HTTP responses.
+const BROTLI_DEFAULT_QUALITY: i32 = 6;
+const BROTLI_DEFAULT_WINDOW: i32 = 22;
+
 struct CompressionResource(RefCell<Inner>);
 
-/// https://wicg.github.io/compression/#supported-formats
-#[derive(Debug)]
+/// https://wicg.github.io/compression/#supported-formats, plus the
+/// non-standard `"br"` format.
 enum Inner {
   DeflateDecoder(ZlibDecoder<Vec<u8>>),
   DeflateEncoder(ZlibEncoder<Vec<u8>>),
@@ -30,6 +42,69 @@ enum Inner {
   DeflateRawEncoder(DeflateEncoder<Vec<u8>>),
   GzDecoder(GzDecoder<Vec<u8>>),
   GzEncoder(GzEncoder<Vec<u8>>),
+  BrotliDecoder(BrotliDecoder<SharedBuf>, SharedBuf),
+  BrotliEncoder(BrotliEncoder<SharedBuf>, SharedBuf),
+}
+
+/// The output of the Brotli encoder and decoder, which only give access to
+/// their writer once finished.
+#[derive(Clone, Default)]
+struct SharedBuf(Rc<RefCell<Vec<u8>>>);
+
+impl SharedBuf {
+  fn take(&self) -> Vec<u8> {
+    std::mem::take(&mut self.0.borrow_mut())
+  }
+}
+
+impl Write for SharedBuf {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.0.borrow_mut().extend_from_slice(buf);
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+/// Parameters of the Brotli encoder, like the `BROTLI_PARAM_*` options of
+/// `node:zlib`.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BrotliParams {
+  mode: Option<u32>,
+  quality: Option<i32>,
+  lgwin: Option<i32>,
+  lgblock: Option<i32>,
+  size_hint: Option<usize>,
+  large_window: Option<bool>,
+}
+
+impl BrotliParams {
+  fn into_encoder_params(self) -> Result<BrotliEncoderParams, AnyError> {
+    let mut params = BrotliEncoderParams::default();
+    params.quality = self.quality.unwrap_or(BROTLI_DEFAULT_QUALITY);
+    params.lgwin = self.lgwin.unwrap_or(BROTLI_DEFAULT_WINDOW);
+    if let Some(lgblock) = self.lgblock {
+      params.lgblock = lgblock;
+    }
+    if let Some(size_hint) = self.size_hint {
+      params.size_hint = size_hint;
+    }
+    if let Some(large_window) = self.large_window {
+      params.large_window = large_window;
+    }
+    if let Some(mode) = self.mode {
+      params.mode = match mode {
+        0 => BrotliEncoderMode::BROTLI_MODE_GENERIC,
+        1 => BrotliEncoderMode::BROTLI_MODE_TEXT,
+        2 => BrotliEncoderMode::BROTLI_MODE_FONT,
+        _ => return Err(type_error(format!("Invalid Brotli mode: {mode}"))),
+      };
+    }
+    Ok(params)
+  }
 }
 
 impl Resource for CompressionResource {
@@ -43,7 +118,8 @@ pub fn op_compression_new(
   state: &mut OpState,
   format: String,
   is_decoder: bool,
-) -> ResourceId {
+  brotli_params: Option<BrotliParams>,
+) -> Result<ResourceId, AnyError> {
   let w = Vec::new();
   let inner = match (format.as_str(), is_decoder) {
     ("deflate", true) => Inner::DeflateDecoder(ZlibDecoder::new(w)),
@@ -58,10 +134,22 @@ pub fn op_compression_new(
     ("gzip", false) => {
       Inner::GzEncoder(GzEncoder::new(w, Compression::default()))
     }
+    ("br", true) => {
+      let out = SharedBuf::default();
+      let decoder = BrotliDecoder::new(out.clone(), BROTLI_BUFFER_SIZE);
+      Inner::BrotliDecoder(decoder, out)
+    }
+    ("br", false) => {
+      let params = brotli_params.unwrap_or_default().into_encoder_params()?;
+      let out = SharedBuf::default();
+      let encoder =
+        BrotliEncoder::with_params(out.clone(), BROTLI_BUFFER_SIZE, &params);
+      Inner::BrotliEncoder(encoder, out)
+    }
     _ => unreachable!(),
   };
   let resource = CompressionResource(RefCell::new(inner));
-  state.resource_table.add(resource)
+  Ok(state.resource_table.add(resource))
 }
 
 #[op]
@@ -103,6 +191,16 @@ pub fn op_compression_write(
       d.flush()?;
       d.get_mut().drain(..)
     }
+    Inner::BrotliDecoder(d, out) => {
+      d.write_all(input)?;
+      d.flush()?;
+      return Ok(out.take().into());
+    }
+    Inner::BrotliEncoder(d, out) => {
+      d.write_all(input)?;
+      d.flush()?;
+      return Ok(out.take().into());
+    }
   }
   .collect();
   Ok(out.into())
@@ -114,7 +212,8 @@ pub fn op_compression_finish(
   rid: ResourceId,
 ) -> Result<ZeroCopyBuf, AnyError> {
   let resource = state.resource_table.take::<CompressionResource>(rid)?;
-  let resource = Rc::try_unwrap(resource).unwrap();
+  let resource = Rc::try_unwrap(resource)
+    .map_err(|_| bad_resource("compression stream is in use"))?;
   let inner = resource.0.into_inner();
   let out: Vec<u8> = match inner {
     Inner::DeflateDecoder(d) => d.finish()?,
@@ -123,6 +222,16 @@ pub fn op_compression_finish(
     Inner::DeflateRawEncoder(d) => d.finish()?,
     Inner::GzDecoder(d) => d.finish()?,
     Inner::GzEncoder(d) => d.finish()?,
+    Inner::BrotliDecoder(d, out) => {
+      if d.into_inner().is_err() {
+        return Err(type_error("Brotli stream is incomplete"));
+      }
+      out.take()
+    }
+    Inner::BrotliEncoder(d, out) => {
+      drop(d.into_inner());
+      out.take()
+    }
   };
   Ok(out.into())
 }
@@ -981,6 +981,9 @@ declare class CompressionStream {
    *
    * Throws a `TypeError` if the format passed to the constructor is not
    * supported.
+   *
+   * Besides the standard formats, the non-standard `"br"` format is
+   * supported for Brotli.
    */
   constructor(format: string);
 
@@ -1010,6 +1013,9 @@ declare class DecompressionStream {
    *
    * Throws a `TypeError` if the format passed to the constructor is not
    * supported.
+   *
+   * Besides the standard formats, the non-standard `"br"` format is
+   * supported for Brotli.
    */
   constructor(format: string);
 
@@ -48,7 +48,9 @@ pub use crate::message_port::create_entangled_message_port;
 use crate::message_port::op_message_port_create_entangled;
 use crate::message_port::op_message_port_post_message;
 use crate::message_port::op_message_port_recv_message;
+use crate::message_port::op_message_port_recv_message_sync;
 pub use crate::message_port::JsMessageData;
+pub use crate::message_port::MessageData;
 pub use crate::message_port::MessagePort;
 
 use crate::timers::op_now;
@@ -106,6 +108,7 @@ pub fn init<P: TimersPermission + 'static>(
       op_message_port_create_entangled::decl(),
       op_message_port_post_message::decl(),
       op_message_port_recv_message::decl(),
+      op_message_port_recv_message_sync::decl(),
       compression::op_compression_new::decl(),
       compression::op_compression_write::decl(),
       compression::op_compression_finish::decl(),
@@ -6,6 +6,7 @@ use std::rc::Rc;
 
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
+use deno_core::futures::future::poll_fn;
 use deno_core::op;
 
 use deno_core::CancelFuture;
@@ -55,12 +56,10 @@ impl MessagePort {
     &self,
     state: Rc<RefCell<OpState>>,
   ) -> Result<Option<JsMessageData>, AnyError> {
-    #![allow(clippy::await_holding_refcell_ref)] // TODO(ry) remove!
-    let mut rx = self
-      .rx
-      .try_borrow_mut()
-      .map_err(|_| type_error("Port receiver is already borrowed"))?;
-    if let Some((data, transferables)) = rx.recv().await {
+    // The receiver is only borrowed while it's polled, so `try_recv` can take
+    // messages while this is waiting.
+    let message = poll_fn(|cx| self.rx.borrow_mut().poll_recv(cx)).await;
+    if let Some((data, transferables)) = message {
       let js_transferables =
         serialize_transferables(&mut state.borrow_mut(), transferables);
       return Ok(Some(JsMessageData {
@@ -71,6 +70,27 @@ impl MessagePort {
     Ok(None)
   }
 
+  /// Receives the next message if one is already queued, without waiting.
+  pub fn try_recv(
+    &self,
+    state: &mut OpState,
+  ) -> Result<Option<JsMessageData>, AnyError> {
+    let mut rx = self
+      .rx
+      .try_borrow_mut()
+      .map_err(|_| type_error("Port receiver is already borrowed"))?;
+    match rx.try_recv() {
+      Ok((data, transferables)) => {
+        let js_transferables = serialize_transferables(state, transferables);
+        Ok(Some(JsMessageData {
+          data,
+          transferables: js_transferables,
+        }))
+      }
+      Err(_) => Ok(None),
+    }
+  }
+
   /// This forcefully disconnects the message port from its paired port. This
   /// will wake up the `.recv` on the paired port, which will return `Ok(None)`.
   pub fn disentangle(&self) {
@@ -191,6 +211,38 @@ pub struct JsMessageData {
   transferables: Vec<JsTransferable>,
 }
 
+impl JsMessageData {
+  /// Takes the transferred message ports out of `state`, so the message can
+  /// be moved to another thread.
+  pub fn detach(self, state: &mut OpState) -> Result<MessageData, AnyError> {
+    let transferables =
+      deserialize_js_transferables(state, self.transferables)?;
+    Ok(MessageData {
+      data: self.data,
+      transferables,
+    })
+  }
+}
+
+/// A message which isn't bound to the resource table of an isolate, like the
+/// ones queued in a message port.
+pub struct MessageData {
+  data: DetachedBuffer,
+  transferables: Vec<Transferable>,
+}
+
+impl MessageData {
+  /// Adds the transferred message ports to `state`, so the message can be
+  /// received by its isolate.
+  pub fn attach(self, state: &mut OpState) -> JsMessageData {
+    let transferables = serialize_transferables(state, self.transferables);
+    JsMessageData {
+      data: self.data,
+      transferables,
+    }
+  }
+}
+
 #[op]
 pub fn op_message_port_post_message(
   state: &mut OpState,
@@ -225,3 +277,12 @@ pub async fn op_message_port_recv_message(
   let cancel = RcRef::map(resource.clone(), |r| &r.cancel);
   resource.port.recv(state).or_cancel(cancel).await?
 }
+
+#[op]
+pub fn op_message_port_recv_message_sync(
+  state: &mut OpState,
+  rid: ResourceId,
+) -> Result<Option<JsMessageData>, AnyError> {
+  let resource = state.resource_table.get::<MessagePortResource>(rid)?;
+  resource.port.try_recv(state)
+}
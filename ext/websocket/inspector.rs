@@ -0,0 +1,88 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::network_headers;
+use deno_core::serde_json::Value;
+use deno_core::NetworkInspector;
+use deno_core::OpState;
+use http::HeaderMap;
+use std::cell::Cell;
+use tokio_tungstenite::tungstenite::handshake::client::Response;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+/// A client WebSocket whose handshake and frames are reported to the
+/// `Network` domain of the inspector.
+pub struct InspectedWebSocket {
+  network: NetworkInspector,
+  request_id: String,
+  request_headers: Value,
+  closed: Cell<bool>,
+}
+
+impl InspectedWebSocket {
+  /// Reports the WebSocket if an inspector session enabled the `Network`
+  /// domain.
+  pub fn new(
+    state: &OpState,
+    url: &str,
+    request_headers: &HeaderMap,
+  ) -> Option<Self> {
+    let network = state.try_borrow::<NetworkInspector>()?;
+    if !network.is_enabled() {
+      return None;
+    }
+    let request_id = network.next_request_id();
+    network.web_socket_created(&request_id, url);
+    Some(Self {
+      network: network.clone(),
+      request_id,
+      request_headers: to_network_headers(request_headers),
+      closed: Cell::new(false),
+    })
+  }
+
+  pub fn handshake(&self, response: &Response) {
+    let status = response.status();
+    self.network.web_socket_handshake(
+      &self.request_id,
+      self.request_headers.clone(),
+      status.as_u16(),
+      status.canonical_reason().unwrap_or(""),
+      to_network_headers(response.headers()),
+    );
+  }
+
+  /// Reports a data frame, or that the WebSocket closed for a close frame.
+  pub fn message(&self, sent: bool, message: &Message) {
+    match message {
+      Message::Text(text) => {
+        self.network.web_socket_frame(
+          &self.request_id,
+          sent,
+          1,
+          text.as_bytes(),
+        );
+      }
+      Message::Binary(data) => {
+        self
+          .network
+          .web_socket_frame(&self.request_id, sent, 2, data);
+      }
+      Message::Close(_) => self.closed(),
+      _ => {}
+    }
+  }
+
+  pub fn closed(&self) {
+    if !self.closed.replace(true) {
+      self.network.web_socket_closed(&self.request_id);
+    }
+  }
+}
+
+fn to_network_headers(headers: &HeaderMap) -> Value {
+  network_headers(
+    headers
+      .iter()
+      .map(|(name, value)| (name.as_str().as_bytes(), value.as_bytes())),
+  )
+}
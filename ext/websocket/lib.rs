@@ -53,8 +53,12 @@ use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
 use tokio_tungstenite::MaybeTlsStream;
 use tokio_tungstenite::WebSocketStream;
 
+use crate::inspector::InspectedWebSocket;
+
 pub use tokio_tungstenite; // Re-export tokio_tungstenite
 
+mod inspector;
+
 #[derive(Clone)]
 pub struct WsRootStore(pub Option<RootCertStore>);
 #[derive(Clone)]
@@ -112,6 +116,7 @@ pub async fn ws_create_server_stream(
       rx: AsyncRefCell::new(ws_rx),
     },
     cancel: Default::default(),
+    inspected: None,
   };
 
   let resource_table = &mut state.borrow_mut().resource_table;
@@ -125,11 +130,15 @@ pub struct WsStreamResource {
   // canceled, while 'write' ops are allowed to complete. Therefore only
   // 'read' futures are attached to this cancel handle.
   pub cancel: CancelHandle,
+  inspected: Option<InspectedWebSocket>,
 }
 
 impl WsStreamResource {
   async fn send(self: &Rc<Self>, message: Message) -> Result<(), AnyError> {
     use tokio_tungstenite::tungstenite::Error;
+    if let Some(inspected) = &self.inspected {
+      inspected.message(true, &message);
+    }
     let res = match self.stream {
       WebSocketStreamType::Client { .. } => {
         let mut tx = RcRef::map(self, |r| match &r.stream {
@@ -315,6 +324,8 @@ where
   }
 
   let request = request.body(())?;
+  let inspected =
+    InspectedWebSocket::new(&state.borrow(), &url, request.headers());
   let domain = &uri.host().unwrap().to_string();
   let port = &uri.port_u16().unwrap_or(match uri.scheme_str() {
     Some("wss") => 443,
@@ -366,6 +377,9 @@ where
   if let Some(cancel_rid) = cancel_handle {
     state.borrow_mut().resource_table.close(cancel_rid).ok();
   }
+  if let Some(inspected) = &inspected {
+    inspected.handshake(&response);
+  }
 
   let (ws_tx, ws_rx) = stream.split();
   let resource = WsStreamResource {
@@ -374,6 +388,7 @@ where
       tx: AsyncRefCell::new(ws_tx),
     },
     cancel: Default::default(),
+    inspected,
   };
   let mut state = state.borrow_mut();
   let rid = state.resource_table.add(resource);
@@ -473,6 +488,12 @@ pub async fn op_ws_next_event(
 
   let cancel = RcRef::map(&resource, |r| &r.cancel);
   let val = resource.next_message(cancel).await?;
+  if let Some(inspected) = &resource.inspected {
+    match &val {
+      Some(Ok(message)) => inspected.message(false, message),
+      _ => inspected.closed(),
+    }
+  }
   let res = match val {
     Some(Ok(Message::Text(text))) => NextEventResponse::String(text),
     Some(Ok(Message::Binary(data))) => NextEventResponse::Binary(data.into()),
@@ -66,6 +66,9 @@ async fn main() -> Result<(), AnyError> {
     shared_array_buffer_store: None,
     compiled_wasm_module_store: None,
     stdio: Default::default(),
+    resource_limits: Default::default(),
+    heap_snapshot_near_limit: None,
+    ops_timeline: false,
   };
 
   let js_path =
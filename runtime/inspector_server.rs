@@ -11,6 +11,9 @@ use deno_core::futures::future::Future;
 use deno_core::futures::pin_mut;
 use deno_core::futures::prelude::*;
 use deno_core::futures::select;
+use deno_core::futures::stream;
+use deno_core::futures::stream::LocalBoxStream;
+use deno_core::futures::stream::SelectAll;
 use deno_core::futures::stream::StreamExt;
 use deno_core::futures::task::Poll;
 use deno_core::serde_json;
@@ -19,6 +22,7 @@ use deno_core::serde_json::Value;
 use deno_core::InspectorMsg;
 use deno_core::InspectorSessionProxy;
 use deno_core::JsRuntime;
+use deno_core::JsRuntimeInspector;
 use deno_websocket::tokio_tungstenite::tungstenite;
 use deno_websocket::tokio_tungstenite::WebSocketStream;
 use std::cell::RefCell;
@@ -40,7 +44,12 @@ pub struct InspectorServer {
 }
 
 impl InspectorServer {
-  pub fn new(host: SocketAddr, name: String) -> Self {
+  /// Binds `host` and starts serving the inspector on a separate thread.
+  pub fn new(host: SocketAddr, name: String) -> Result<Self, AnyError> {
+    let listener = std::net::TcpListener::bind(host)?;
+    listener.set_nonblocking(true)?;
+    let host = listener.local_addr()?;
+
     let (register_inspector_tx, register_inspector_rx) =
       mpsc::unbounded::<InspectorInfo>();
 
@@ -51,26 +60,46 @@ impl InspectorServer {
       let local = tokio::task::LocalSet::new();
       local.block_on(
         &rt,
-        server(host, register_inspector_rx, shutdown_server_rx, name),
+        server(listener, register_inspector_rx, shutdown_server_rx, name),
       )
     });
 
-    Self {
+    Ok(Self {
       host,
       register_inspector_tx,
       shutdown_server_tx: Some(shutdown_server_tx),
       thread_handle: Some(thread_handle),
-    }
+    })
   }
 
+  /// Exposes the inspector of `js_runtime`. Sessions with the inspector of
+  /// the main worker (`is_main`) can auto-attach to the inspectors of all
+  /// other workers through the `Target` domain.
   pub fn register_inspector(
     &self,
     module_url: String,
     js_runtime: &mut JsRuntime,
     wait_for_session: bool,
+    is_main: bool,
   ) {
-    let inspector_rc = js_runtime.inspector();
-    let mut inspector = inspector_rc.borrow_mut();
+    self.register_inspector_handle(
+      module_url,
+      &js_runtime.inspector(),
+      wait_for_session,
+      is_main,
+    );
+  }
+
+  /// Like `register_inspector()`, for when only the inspector is at hand,
+  /// e.g. in ops. Returns the URL of the websocket to debug it.
+  pub fn register_inspector_handle(
+    &self,
+    module_url: String,
+    inspector: &RefCell<JsRuntimeInspector>,
+    wait_for_session: bool,
+    is_main: bool,
+  ) -> String {
+    let mut inspector = inspector.borrow_mut();
     let session_sender = inspector.get_session_sender();
     let deregister_rx = inspector.add_deregister_handler();
     let info = InspectorInfo::new(
@@ -79,8 +108,11 @@ impl InspectorServer {
       deregister_rx,
       module_url,
       wait_for_session,
+      is_main,
     );
+    let url = info.get_websocket_debugger_url();
     self.register_inspector_tx.unbounded_send(info).unwrap();
+    url
   }
 }
 
@@ -115,6 +147,7 @@ where
 fn handle_ws_request(
   req: http::Request<hyper::Body>,
   inspector_map_rc: Rc<RefCell<HashMap<Uuid, InspectorInfo>>>,
+  target_listeners: TargetListeners,
 ) -> http::Result<http::Response<hyper::Body>> {
   let (parts, body) = req.into_parts();
   let req = http::Request::from_parts(parts, ());
@@ -132,7 +165,7 @@ fn handle_ws_request(
   }
 
   // run in a block to not hold borrow to `inspector_map` for too long
-  let new_session_tx = {
+  let (new_session_tx, is_main) = {
     let inspector_map = inspector_map_rc.borrow();
     let maybe_inspector_info = inspector_map.get(&maybe_uuid.unwrap());
 
@@ -143,7 +176,7 @@ fn handle_ws_request(
     }
 
     let info = maybe_inspector_info.unwrap();
-    info.new_session_tx.clone()
+    (info.new_session_tx.clone(), info.is_main)
   };
 
   let resp = tungstenite::handshake::server::create_response(&req)
@@ -187,7 +220,15 @@ fn handle_ws_request(
 
     eprintln!("Debugger session started.");
     let _ = new_session_tx.unbounded_send(inspector_session_proxy);
-    pump_websocket_messages(websocket, inbound_tx, outbound_rx).await;
+    if is_main {
+      let (new_target_tx, new_target_rx) = mpsc::unbounded();
+      target_listeners.borrow_mut().push(new_target_tx);
+      let targets = TargetSessions::new(inspector_map_rc, new_target_rx);
+      pump_main_websocket_messages(websocket, inbound_tx, outbound_rx, targets)
+        .await;
+    } else {
+      pump_websocket_messages(websocket, inbound_tx, outbound_rx).await;
+    }
   });
 
   Ok(resp)
@@ -217,7 +258,7 @@ fn handle_json_version_request(
 }
 
 async fn server(
-  host: SocketAddr,
+  listener: std::net::TcpListener,
   register_inspector_rx: UnboundedReceiver<InspectorInfo>,
   shutdown_server_rx: oneshot::Receiver<()>,
   name: String,
@@ -225,7 +266,10 @@ async fn server(
   let inspector_map_ =
     Rc::new(RefCell::new(HashMap::<Uuid, InspectorInfo>::new()));
 
+  let target_listeners_: TargetListeners = Default::default();
+
   let inspector_map = Rc::clone(&inspector_map_);
+  let target_listeners = Rc::clone(&target_listeners_);
   let register_inspector_handler = register_inspector_rx
     .map(|info| {
       eprintln!(
@@ -236,9 +280,17 @@ async fn server(
       if info.wait_for_session {
         eprintln!("Deno is waiting for debugger to connect.");
       }
-      if inspector_map.borrow_mut().insert(info.uuid, info).is_some() {
+      let uuid = info.uuid;
+      let is_main = info.is_main;
+      if inspector_map.borrow_mut().insert(uuid, info).is_some() {
         panic!("Inspector UUID already in map");
       }
+      // Let sessions with the main worker auto-attach to the new worker.
+      if !is_main {
+        target_listeners
+          .borrow_mut()
+          .retain(|tx| tx.unbounded_send(uuid).is_ok());
+      }
     })
     .collect::<()>();
 
@@ -259,6 +311,7 @@ async fn server(
 
   let make_svc = hyper::service::make_service_fn(|_| {
     let inspector_map = Rc::clone(&inspector_map_);
+    let target_listeners = Rc::clone(&target_listeners_);
     let json_version_response = json_version_response.clone();
 
     future::ok::<_, Infallible>(hyper::service::service_fn(
@@ -266,7 +319,11 @@ async fn server(
         future::ready({
           match (req.method(), req.uri().path()) {
             (&http::Method::GET, path) if path.starts_with("/ws/") => {
-              handle_ws_request(req, Rc::clone(&inspector_map))
+              handle_ws_request(
+                req,
+                Rc::clone(&inspector_map),
+                Rc::clone(&target_listeners),
+              )
             }
             (&http::Method::GET, "/json/version") => {
               handle_json_version_request(json_version_response.clone())
@@ -286,12 +343,14 @@ async fn server(
     ))
   });
 
+  let listener = tokio::net::TcpListener::from_std(listener)
+    .expect("Failed to register the inspector listener");
+  let incoming = hyper::server::conn::AddrIncoming::from_listener(listener)
+    .expect("Failed to get the inspector listener address");
+
   // Create the server manually so it can use the Local Executor
   let server_handler = hyper::server::Builder::new(
-    hyper::server::conn::AddrIncoming::bind(&host).unwrap_or_else(|e| {
-      eprintln!("Cannot start inspector server: {e}.");
-      process::exit(1);
-    }),
+    incoming,
     hyper::server::conn::Http::new().with_executor(LocalExecutor),
   )
   .serve(make_svc)
@@ -361,6 +420,267 @@ async fn pump_websocket_messages(
   let _ = future::try_join(outbound_pump, inbound_pump).await;
 }
 
+/// Like `pump_websocket_messages()`, but for a session with the main worker,
+/// whose messages for the `Target` domain and for auto-attached workers are
+/// handled by `TargetSessions`.
+async fn pump_main_websocket_messages(
+  websocket: WebSocketStream<hyper::upgrade::Upgraded>,
+  inbound_tx: UnboundedSender<String>,
+  mut outbound_rx: UnboundedReceiver<InspectorMsg>,
+  mut targets: TargetSessions,
+) {
+  let (mut websocket_tx, mut websocket_rx) = websocket.split();
+
+  'pump: loop {
+    let messages = tokio::select! {
+      msg = websocket_rx.next() => {
+        let msg_text = match msg {
+          // Messages that cannot be converted to strings are ignored.
+          Some(Ok(msg)) => match msg.into_text() {
+            Ok(msg_text) => msg_text,
+            Err(_) => continue,
+          },
+          _ => break,
+        };
+        match targets.handle_message(&msg_text) {
+          Some(replies) => replies,
+          None => {
+            let _ = inbound_tx.unbounded_send(msg_text);
+            continue;
+          }
+        }
+      }
+      msg = outbound_rx.next() => match msg {
+        Some(msg) => vec![msg.content],
+        None => break,
+      },
+      Some(target_id) = targets.new_target_rx.next() => {
+        targets.attach(target_id).into_iter().collect()
+      }
+      Some((session_id, msg)) = targets.outbound.next() => {
+        targets.handle_outbound(&session_id, msg).into_iter().collect()
+      }
+    };
+    for message in messages {
+      let message = tungstenite::Message::text(message);
+      if websocket_tx.send(message).await.is_err() {
+        break 'pump;
+      }
+    }
+  }
+
+  eprintln!("Debugger session ended");
+}
+
+/// Senders notified of every worker inspector that is registered.
+type TargetListeners = Rc<RefCell<Vec<UnboundedSender<Uuid>>>>;
+
+/// A session with a worker that was auto-attached to.
+struct TargetSession {
+  target_id: Uuid,
+  inbound_tx: UnboundedSender<String>,
+}
+
+/// The `Target` domain of a session with the main worker.
+///
+/// Once the client enables auto-attach with `Target.setAutoAttach`, a session
+/// is opened with every current and future worker. The messages of these
+/// sessions are multiplexed over the session with the main worker, tagged
+/// with a `sessionId`, as in the "flat" mode of the Chrome DevTools Protocol.
+struct TargetSessions {
+  inspector_map: Rc<RefCell<HashMap<Uuid, InspectorInfo>>>,
+  new_target_rx: UnboundedReceiver<Uuid>,
+  auto_attach: bool,
+  sessions: HashMap<String, TargetSession>,
+  outbound: SelectAll<LocalBoxStream<'static, (String, Option<InspectorMsg>)>>,
+}
+
+impl TargetSessions {
+  fn new(
+    inspector_map: Rc<RefCell<HashMap<Uuid, InspectorInfo>>>,
+    new_target_rx: UnboundedReceiver<Uuid>,
+  ) -> Self {
+    Self {
+      inspector_map,
+      new_target_rx,
+      auto_attach: false,
+      sessions: HashMap::new(),
+      outbound: SelectAll::new(),
+    }
+  }
+
+  /// Handles a message from the client. Returns the messages to send back to
+  /// the client, or `None` if the message is meant for the main worker.
+  fn handle_message(&mut self, msg_text: &str) -> Option<Vec<String>> {
+    let mut message: Value = serde_json::from_str(msg_text).ok()?;
+    let id = message.get("id").cloned().unwrap_or_default();
+
+    if let Some(session_id) = message.get("sessionId").cloned() {
+      message.as_object_mut().unwrap().remove("sessionId");
+      let session = session_id.as_str().and_then(|s| self.sessions.get(s));
+      return Some(match session {
+        Some(session) => {
+          let _ = session.inbound_tx.unbounded_send(message.to_string());
+          vec![]
+        }
+        None => vec![error_response(
+          id,
+          -32001,
+          "Session with given id not found.",
+        )],
+      });
+    }
+
+    let method = message.get("method")?.as_str()?;
+    if !method.starts_with("Target.") {
+      return None;
+    }
+    let params = &message["params"];
+    let replies = match method {
+      "Target.setAutoAttach" => {
+        if params["autoAttach"] == true && params["flatten"] != true {
+          return Some(vec![error_response(
+            id,
+            -32602,
+            "Only flat sessions are supported.",
+          )]);
+        }
+        self.auto_attach = params["autoAttach"] == true;
+        let target_ids = self
+          .inspector_map
+          .borrow()
+          .values()
+          .filter(|info| !info.is_main)
+          .map(|info| info.uuid)
+          .collect::<Vec<_>>();
+        let mut replies = vec![json!({ "id": id, "result": {} }).to_string()];
+        for target_id in target_ids {
+          replies.extend(self.attach(target_id));
+        }
+        replies
+      }
+      "Target.getTargets" => {
+        let target_infos = self
+          .inspector_map
+          .borrow()
+          .values()
+          .map(InspectorInfo::get_target_info)
+          .collect::<Vec<_>>();
+        vec![json!({
+          "id": id,
+          "result": { "targetInfos": target_infos },
+        })
+        .to_string()]
+      }
+      "Target.detachFromTarget" => {
+        let session_id = params["sessionId"].as_str().unwrap_or_default();
+        match self.detach(session_id) {
+          Some(event) => {
+            vec![json!({ "id": id, "result": {} }).to_string(), event]
+          }
+          None => vec![error_response(id, -32602, "No session with given id")],
+        }
+      }
+      _ => {
+        let message = format!("'{method}' wasn't found");
+        vec![error_response(id, -32601, &message)]
+      }
+    };
+    Some(replies)
+  }
+
+  /// Opens a session with the worker `target_id` if auto-attach is enabled,
+  /// returning the `Target.attachedToTarget` event for the client.
+  fn attach(&mut self, target_id: Uuid) -> Option<String> {
+    if !self.auto_attach
+      || self.sessions.values().any(|s| s.target_id == target_id)
+    {
+      return None;
+    }
+    let inspector_map = self.inspector_map.borrow();
+    let info = inspector_map.get(&target_id)?;
+
+    let (outbound_tx, outbound_rx) = mpsc::unbounded();
+    let (inbound_tx, inbound_rx) = mpsc::unbounded();
+    let proxy = InspectorSessionProxy {
+      tx: outbound_tx,
+      rx: inbound_rx,
+    };
+    info.new_session_tx.unbounded_send(proxy).ok()?;
+
+    let session_id = Uuid::new_v4().to_string();
+    let msg_session_id = session_id.clone();
+    let end_session_id = session_id.clone();
+    // The end of the stream tells that the worker is gone.
+    self.outbound.push(
+      outbound_rx
+        .map(move |msg| (msg_session_id.clone(), Some(msg)))
+        .chain(stream::once(future::ready((end_session_id, None))))
+        .boxed_local(),
+    );
+    self.sessions.insert(
+      session_id.clone(),
+      TargetSession {
+        target_id,
+        inbound_tx,
+      },
+    );
+
+    let event = json!({
+      "method": "Target.attachedToTarget",
+      "params": {
+        "sessionId": session_id,
+        "targetInfo": info.get_target_info(),
+        "waitingForDebugger": info.wait_for_session,
+      },
+    });
+    Some(event.to_string())
+  }
+
+  /// Closes the session `session_id`, returning the
+  /// `Target.detachedFromTarget` event for the client.
+  fn detach(&mut self, session_id: &str) -> Option<String> {
+    let session = self.sessions.remove(session_id)?;
+    let event = json!({
+      "method": "Target.detachedFromTarget",
+      "params": {
+        "sessionId": session_id,
+        "targetId": session.target_id.to_string(),
+      },
+    });
+    Some(event.to_string())
+  }
+
+  /// Tags a message from the session `session_id` for the client, or
+  /// detaches from the worker if its session ended.
+  fn handle_outbound(
+    &mut self,
+    session_id: &str,
+    msg: Option<InspectorMsg>,
+  ) -> Option<String> {
+    let msg = match msg {
+      Some(msg) => msg,
+      None => return self.detach(session_id),
+    };
+    if !self.sessions.contains_key(session_id) {
+      return None;
+    }
+    let mut message: Value = serde_json::from_str(&msg.content).ok()?;
+    message
+      .as_object_mut()?
+      .insert("sessionId".to_string(), session_id.into());
+    Some(message.to_string())
+  }
+}
+
+fn error_response(id: Value, code: i32, message: &str) -> String {
+  json!({
+    "id": id,
+    "error": { "code": code, "message": message },
+  })
+  .to_string()
+}
+
 /// Inspector information that is sent from the isolate thread to the server
 /// thread when a new inspector is created.
 pub struct InspectorInfo {
@@ -371,6 +691,7 @@ pub struct InspectorInfo {
   pub deregister_rx: oneshot::Receiver<()>,
   pub url: String,
   pub wait_for_session: bool,
+  pub is_main: bool,
 }
 
 impl InspectorInfo {
@@ -380,6 +701,7 @@ impl InspectorInfo {
     deregister_rx: oneshot::Receiver<()>,
     url: String,
     wait_for_session: bool,
+    is_main: bool,
   ) -> Self {
     Self {
       host,
@@ -389,6 +711,7 @@ impl InspectorInfo {
       deregister_rx,
       url,
       wait_for_session,
+      is_main,
     }
   }
 
@@ -416,6 +739,17 @@ impl InspectorInfo {
       )
   }
 
+  fn get_target_info(&self) -> Value {
+    json!({
+      "targetId": self.uuid.to_string(),
+      "type": if self.is_main { "node" } else { "worker" },
+      "title": self.url.to_string(),
+      "url": self.url.to_string(),
+      "attached": true,
+      "canAccessOpener": false,
+    })
+  }
+
   fn get_title(&self) -> String {
     format!(
       "deno{} [pid: {}]",
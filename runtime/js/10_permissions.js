@@ -32,7 +32,7 @@ const illegalConstructorKey = Symbol("illegalConstructorKey");
  * @property {PermissionStatus} status
  */
 
-/** @type {ReadonlyArray<"read" | "write" | "net" | "env" | "sys" | "run" | "ffi" | "hrtime">} */
+/** @type {ReadonlyArray<"read" | "write" | "net" | "env" | "sys" | "run" | "ffi" | "import" | "hrtime">} */
 const permissionNames = [
   "read",
   "write",
@@ -41,6 +41,7 @@ const permissionNames = [
   "sys",
   "run",
   "ffi",
+  "import",
   "hrtime",
 ];
 
@@ -127,7 +128,7 @@ function cache(desc, state) {
     ReflectHas(desc, "path")
   ) {
     key += `-${desc.path}&`;
-  } else if (desc.name === "net" && desc.host) {
+  } else if ((desc.name === "net" || desc.name === "import") && desc.host) {
     key += `-${desc.host}&`;
   } else if (desc.name === "run" && desc.command) {
     key += `-${desc.command}&`;
@@ -6,8 +6,10 @@ const primordials = globalThis.__bootstrap.primordials;
 const {
   Error,
   ObjectPrototypeIsPrototypeOf,
+  PromisePrototypeThen,
   StringPrototypeStartsWith,
   String,
+  Symbol,
   SymbolIterator,
   SymbolToStringTag,
 } = primordials;
@@ -28,22 +30,39 @@ import {
   serializeJsMessageData,
 } from "internal:deno_web/13_message_port.js";
 
+/**
+ * Option key of the serialized message passed to the worker on creation,
+ * which it can read before its main module is evaluated. Internal to
+ * `node:worker_threads`.
+ */
+const workerMetadataSymbol = Symbol("workerMetadata");
+
+/**
+ * Option key of a callback which is called once the worker closed itself or
+ * finished, but not when it's terminated by its host. Internal to
+ * `node:worker_threads`.
+ */
+const workerCloseSymbol = Symbol("workerClose");
+
 function createWorker(
   specifier,
   hasSourceCode,
   sourceCode,
   permissions,
+  limits,
   name,
   workerType,
+  workerMetadata,
 ) {
   return ops.op_create_worker({
     hasSourceCode,
     name,
     permissions: serializePermissions(permissions),
+    limits,
     sourceCode,
     specifier,
     workerType,
-  });
+  }, workerMetadata);
 }
 
 function hostTerminateWorker(id) {
@@ -72,6 +91,8 @@ class Worker extends EventTarget {
   // indicating that the worker is no longer running, but there might
   // still be messages left to receive.
   #status = "RUNNING";
+  #onClose = null;
+  #messagesReceived = null;
 
   constructor(specifier, options = {}) {
     super();
@@ -80,6 +101,8 @@ class Worker extends EventTarget {
       deno,
       name,
       type = "classic",
+      [workerMetadataSymbol]: workerMetadata,
+      [workerCloseSymbol]: onClose = null,
     } = options;
 
     const workerType = webidl.converters["WorkerType"](type);
@@ -96,6 +119,7 @@ class Worker extends EventTarget {
     }
 
     this.#name = name;
+    this.#onClose = onClose;
     let hasSourceCode, sourceCode;
     if (workerType === "classic") {
       hasSourceCode = true;
@@ -110,12 +134,14 @@ class Worker extends EventTarget {
       hasSourceCode,
       sourceCode,
       deno?.permissions,
+      deno?.limits,
       name,
       workerType,
+      workerMetadata,
     );
     this.#id = id;
     this.#pollControl();
-    this.#pollMessages();
+    this.#messagesReceived = this.#pollMessages();
   }
 
   #handleError(e) {
@@ -161,6 +187,10 @@ class Worker extends EventTarget {
         case 3: { // Close
           log(`Host got "close" message from worker: ${this.#name}`);
           this.#status = "CLOSED";
+          if (this.#onClose !== null) {
+            // after the messages the worker sent before it closed
+            PromisePrototypeThen(this.#messagesReceived, this.#onClose);
+          }
           return;
         }
         default: {
@@ -250,4 +280,4 @@ webidl.converters["WorkerType"] = webidl.createEnumConverter("WorkerType", [
   "module",
 ]);
 
-export { Worker };
+export { Worker, workerCloseSymbol, workerMetadataSymbol };
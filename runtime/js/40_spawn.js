@@ -29,6 +29,15 @@ const illegalConstructorKey = Symbol("illegalConstructorKey");
 
 const promiseIdSymbol = SymbolFor("Deno.core.internalPromiseId");
 
+/**
+ * Option key to create an IPC channel to the child. Internal to
+ * `node:child_process`.
+ */
+const kIpc = Symbol("ipc");
+
+/** The resource id of the IPC channel of a child spawned with `kIpc`. */
+const kIpcRid = Symbol("ipcRid");
+
 function spawnChildInner(opFn, command, apiName, {
   args = [],
   cwd = undefined,
@@ -63,7 +72,7 @@ function spawnChildInner(opFn, command, apiName, {
 
 function spawnChild(command, options = {}) {
   return spawnChildInner(
-    ops.op_spawn_child,
+    options[kIpc] ? ops.op_spawn_child_with_ipc : ops.op_spawn_child,
     command,
     "Deno.Command().spawn()",
     options,
@@ -123,6 +132,7 @@ class ChildProcess {
     stdinRid,
     stdoutRid,
     stderrRid,
+    ipcRid,
   } = null) {
     if (key !== illegalConstructorKey) {
       throw new TypeError("Illegal constructor.");
@@ -130,6 +140,7 @@ class ChildProcess {
 
     this.#rid = rid;
     this.#pid = pid;
+    this[kIpcRid] = ipcRid;
 
     if (stdinRid !== null) {
       this.#stdin = writableStreamForRid(stdinRid);
@@ -323,4 +334,4 @@ class Command {
   }
 }
 
-export { ChildProcess, Command };
+export { ChildProcess, Command, kIpc, kIpcRid };
@@ -72,6 +72,8 @@ import {
   workerRuntimeGlobalProperties,
 } from "internal:runtime/js/98_global_scope.js";
 
+const promiseIdSymbol = SymbolFor("Deno.core.internalPromiseId");
+
 let windowIsClosing = false;
 let globalThis_;
 
@@ -132,6 +134,22 @@ function postMessage(message, transferOrOptions = {}) {
 
 let isClosing = false;
 let globalDispatchEvent;
+// Whether waiting for messages keeps the worker alive. Only workers of
+// `node:worker_threads` may finish on their own, see
+// `internals.setWorkerMessagePollingRef()`.
+let isMessagePollingRefed = true;
+let messagePollingPromiseId = null;
+
+function setWorkerMessagePollingRef(ref) {
+  isMessagePollingRefed = ref;
+  if (messagePollingPromiseId !== null) {
+    if (ref) {
+      core.refOp(messagePollingPromiseId);
+    } else {
+      core.unrefOp(messagePollingPromiseId);
+    }
+  }
+}
 
 async function pollForMessages() {
   if (!globalDispatchEvent) {
@@ -141,7 +159,13 @@ async function pollForMessages() {
     );
   }
   while (!isClosing) {
-    const data = await core.opAsync("op_worker_recv_message");
+    const recvMessage = core.opAsync("op_worker_recv_message");
+    messagePollingPromiseId = recvMessage[promiseIdSymbol];
+    if (!isMessagePollingRefed) {
+      core.unrefOp(messagePollingPromiseId);
+    }
+    const data = await recvMessage;
+    messagePollingPromiseId = null;
     if (data === null) break;
     const v = messagePort.deserializeJsMessageData(data);
     const message = v[0];
@@ -467,6 +491,7 @@ function bootstrapMainRuntime(runtimeOptions) {
     nodeUnstable: {
       serve: flash.createServe(ops.op_node_unstable_flash_serve),
       upgradeHttpRaw: flash.upgradeHttpRaw,
+      listen: net.createListen(ops.op_node_unstable_net_listen_tcp),
       listenDatagram: net.createListenDatagram(
         ops.op_node_unstable_net_listen_udp,
         ops.op_node_unstable_net_listen_unixpacket,
@@ -593,9 +618,11 @@ function bootstrapWorkerRuntime(
   // the op function that needs to be passed will be invalidated by creating
   // a snapshot
   ObjectAssign(internals, {
+    setWorkerMessagePollingRef,
     nodeUnstable: {
       serve: flash.createServe(ops.op_node_unstable_flash_serve),
       upgradeHttpRaw: flash.upgradeHttpRaw,
+      listen: net.createListen(ops.op_node_unstable_net_listen_tcp),
       listenDatagram: net.createListenDatagram(
         ops.op_node_unstable_net_listen_udp,
         ops.op_node_unstable_net_listen_unixpacket,
@@ -0,0 +1,124 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Ops to open the inspector of a worker on demand, which back
+//! `inspector.open()` and friends of `node:inspector`.
+
+use crate::inspector_server::InspectorServer;
+use crate::permissions::PermissionsContainer;
+use deno_core::error::custom_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::v8;
+use deno_core::Extension;
+use deno_core::JsRuntime;
+use deno_core::ModuleSpecifier;
+use deno_core::OpState;
+use deno_net::NetPermissions;
+use std::net::SocketAddr;
+use std::net::ToSocketAddrs;
+use std::sync::Arc;
+
+pub fn init(is_main: bool, user_agent: String) -> Extension {
+  Extension::builder("deno_inspector")
+    .ops(vec![
+      op_inspector_open::decl(),
+      op_inspector_close::decl(),
+      op_inspector_url::decl(),
+      op_inspector_wait_for_debugger::decl(),
+    ])
+    .state(move |state| {
+      state.put(InspectorOptions {
+        is_main,
+        user_agent: user_agent.clone(),
+      });
+      Ok(())
+    })
+    .build()
+}
+
+struct InspectorOptions {
+  is_main: bool,
+  /// The name of the inspector server.
+  user_agent: String,
+}
+
+/// The inspector server a worker is exposed on, either because of
+/// `--inspect` or through `op_inspector_open`.
+pub struct ActiveInspectorServer {
+  /// Kept alive for as long as the inspector is active.
+  pub server: Arc<InspectorServer>,
+  /// The URL of the websocket to debug the worker.
+  pub url: String,
+}
+
+#[op(v8)]
+fn op_inspector_open(
+  scope: &mut v8::HandleScope,
+  state: &mut OpState,
+  host: String,
+  port: u16,
+  wait: bool,
+) -> Result<String, AnyError> {
+  if state.has::<ActiveInspectorServer>() {
+    return Err(custom_error(
+      "AlreadyExists",
+      "The inspector is already activated",
+    ));
+  }
+  state
+    .borrow_mut::<PermissionsContainer>()
+    .check_net(&(&host, Some(port)), "inspector.open()")?;
+
+  let addr: SocketAddr = (host.as_str(), port)
+    .to_socket_addrs()?
+    .next()
+    .ok_or_else(|| custom_error("AddrNotAvailable", "Invalid host"))?;
+
+  let options = state.borrow::<InspectorOptions>();
+  let server =
+    Arc::new(InspectorServer::new(addr, options.user_agent.clone())?);
+  let is_main = options.is_main;
+  let inspector = JsRuntime::inspector_from_scope(scope);
+  let url = server.register_inspector_handle(
+    state.borrow::<ModuleSpecifier>().to_string(),
+    &inspector,
+    wait,
+    is_main,
+  );
+  if wait {
+    inspector.borrow_mut().wait_for_session();
+  }
+
+  state.put(ActiveInspectorServer {
+    server,
+    url: url.clone(),
+  });
+  Ok(url)
+}
+
+/// Stops the server opened by `op_inspector_open`. The server of `--inspect`
+/// is shared with other workers and keeps running.
+#[op]
+fn op_inspector_close(state: &mut OpState) {
+  state.try_take::<ActiveInspectorServer>();
+}
+
+#[op]
+fn op_inspector_url(state: &mut OpState) -> Option<String> {
+  state
+    .try_borrow::<ActiveInspectorServer>()
+    .map(|active| active.url.clone())
+}
+
+#[op(v8)]
+fn op_inspector_wait_for_debugger(
+  scope: &mut v8::HandleScope,
+  state: &mut OpState,
+) -> Result<(), AnyError> {
+  if !state.has::<ActiveInspectorServer>() {
+    return Err(custom_error("NotConnected", "The inspector is not active"));
+  }
+  let inspector = JsRuntime::inspector_from_scope(scope);
+  inspector.borrow_mut().wait_for_debugger();
+  Ok(())
+}
@@ -3,6 +3,7 @@
 pub mod fs;
 pub mod fs_events;
 pub mod http;
+pub mod inspector;
 pub mod io;
 pub mod os;
 pub mod permissions;
@@ -1,16 +1,23 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::permissions::calling_modules;
 use crate::permissions::parse_sys_kind;
 use crate::permissions::PermissionsContainer;
+use deno_core::_ops::OpCtx;
 use deno_core::error::custom_error;
 use deno_core::error::uri_error;
 use deno_core::error::AnyError;
 use deno_core::op;
 use deno_core::url;
+use deno_core::v8;
 use deno_core::Extension;
+use deno_core::OpDecl;
 use deno_core::OpState;
 use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::path::Path;
+use std::rc::Rc;
 
 pub fn init() -> Extension {
   Extension::builder("deno_permissions")
@@ -22,6 +29,58 @@ pub fn init() -> Extension {
     .build()
 }
 
+/// The functions of the ops wrapped by `op_with_calling_modules`.
+struct ModuleScopesOps(HashMap<&'static str, v8::FunctionCallback>);
+
+/// Records the modules on the call stack of every op call, so that the
+/// permission checks made by the op also check the module scopes of its
+/// callers. This disables the fast calls of all ops, so it should only be
+/// used when there are module scopes.
+pub fn init_module_scopes() -> Extension {
+  let ops = Rc::new(RefCell::new(HashMap::new()));
+  let state_ops = ops.clone();
+  Extension::builder("deno_permissions_module_scopes")
+    .middleware(move |op| {
+      ops.borrow_mut().insert(op.name, op.v8_fn_ptr);
+      OpDecl {
+        v8_fn_ptr: op_with_calling_modules,
+        fast_fn: None,
+        ..op
+      }
+    })
+    .state(move |state| {
+      state.put(ModuleScopesOps(state_ops.borrow().clone()));
+      Ok(())
+    })
+    .build()
+}
+
+extern "C" fn op_with_calling_modules(info: *const v8::FunctionCallbackInfo) {
+  // SAFETY: V8 always calls function callbacks with a valid info.
+  let info = unsafe { &*info };
+  let (ctx, modules) = {
+    // SAFETY: The scope is dropped before the op creates its own.
+    let scope = &mut unsafe { v8::CallbackScope::new(info) };
+    let args = v8::FunctionCallbackArguments::from_function_callback_info(info);
+    // SAFETY: deno_core guarantees args.data() is a v8 External pointing to
+    // an OpCtx for the isolates lifetime.
+    let ctx = unsafe {
+      &*(v8::Local::<v8::External>::cast(args.data()).value() as *const OpCtx)
+    };
+    (ctx, Some(calling_modules(scope)))
+  };
+  let (op_fn, permissions) = {
+    let state = ctx.state.borrow();
+    (
+      state.borrow::<ModuleScopesOps>().0[ctx.decl.name],
+      state.borrow::<PermissionsContainer>().clone(),
+    )
+  };
+  let previous = permissions.set_calling_modules(modules);
+  op_fn(info);
+  permissions.set_calling_modules(previous);
+}
+
 #[derive(Deserialize)]
 pub struct PermissionArgs {
   name: String,
@@ -55,6 +114,13 @@ pub fn op_query_permission(
       .query(args.kind.as_deref().map(parse_sys_kind).transpose()?),
     "run" => permissions.run.query(args.command.as_deref()),
     "ffi" => permissions.ffi.query(args.path.as_deref().map(Path::new)),
+    "import" => permissions.import.query(
+      match args.host.as_deref() {
+        None => None,
+        Some(h) => Some(parse_host(h)?),
+      }
+      .as_ref(),
+    ),
     "hrtime" => permissions.hrtime.query(),
     n => {
       return Err(custom_error(
@@ -89,6 +155,13 @@ pub fn op_revoke_permission(
       .revoke(args.kind.as_deref().map(parse_sys_kind).transpose()?),
     "run" => permissions.run.revoke(args.command.as_deref()),
     "ffi" => permissions.ffi.revoke(args.path.as_deref().map(Path::new)),
+    "import" => permissions.import.revoke(
+      match args.host.as_deref() {
+        None => None,
+        Some(h) => Some(parse_host(h)?),
+      }
+      .as_ref(),
+    ),
     "hrtime" => permissions.hrtime.revoke(),
     n => {
       return Err(custom_error(
@@ -123,6 +196,13 @@ pub fn op_request_permission(
       .request(args.kind.as_deref().map(parse_sys_kind).transpose()?),
     "run" => permissions.run.request(args.command.as_deref()),
     "ffi" => permissions.ffi.request(args.path.as_deref().map(Path::new)),
+    "import" => permissions.import.request(
+      match args.host.as_deref() {
+        None => None,
+        Some(h) => Some(parse_host(h)?),
+      }
+      .as_ref(),
+    ),
     "hrtime" => permissions.hrtime.request(),
     n => {
       return Err(custom_error(
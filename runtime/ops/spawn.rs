@@ -31,6 +31,7 @@ pub fn init() -> Extension {
   Extension::builder("deno_spawn")
     .ops(vec![
       op_spawn_child::decl(),
+      op_spawn_child_with_ipc::decl(),
       op_spawn_wait::decl(),
       op_spawn_sync::decl(),
     ])
@@ -123,11 +124,19 @@ pub struct SpawnOutput {
   stderr: Option<ZeroCopyBuf>,
 }
 
+/// The parent's end of the IPC channel of a child process, see
+/// `deno_node::IpcJsonStreamResource`.
+#[cfg(unix)]
+type IpcChannel = std::os::unix::net::UnixStream;
+#[cfg(not(unix))]
+type IpcChannel = ();
+
 fn create_command(
   state: &mut OpState,
   args: SpawnArgs,
   api_name: &str,
-) -> Result<std::process::Command, AnyError> {
+  ipc: bool,
+) -> Result<(std::process::Command, Option<IpcChannel>), AnyError> {
   state
     .borrow_mut::<PermissionsContainer>()
     .check_run(&args.cmd, api_name)?;
@@ -183,7 +192,33 @@ fn create_command(
     value => value.as_stdio(),
   });
 
-  Ok(command)
+  if !ipc {
+    return Ok((command, None));
+  }
+
+  #[cfg(unix)]
+  {
+    use std::os::unix::io::AsRawFd;
+
+    let (parent, child) = std::os::unix::net::UnixStream::pair()?;
+    let fd = child.as_raw_fd();
+    command.env(deno_node::CHANNEL_FD_ENV, fd.to_string());
+    // SAFETY: `fcntl` is async-signal-safe. The child's end of the socket is
+    // created with `FD_CLOEXEC`, which is cleared so that it survives `exec`.
+    // `child` is moved into the closure so that it's kept open until the
+    // command is dropped.
+    unsafe {
+      command.pre_exec(move || {
+        if libc::fcntl(child.as_raw_fd(), libc::F_SETFD, 0) == -1 {
+          return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+      });
+    }
+    Ok((command, Some(parent)))
+  }
+  #[cfg(not(unix))]
+  Err(deno_core::error::not_supported())
 }
 
 #[derive(Serialize)]
@@ -194,11 +229,13 @@ struct Child {
   stdin_rid: Option<ResourceId>,
   stdout_rid: Option<ResourceId>,
   stderr_rid: Option<ResourceId>,
+  ipc_rid: Option<ResourceId>,
 }
 
 fn spawn_child(
   state: &mut OpState,
   command: std::process::Command,
+  ipc: Option<IpcChannel>,
 ) -> Result<Child, AnyError> {
   let mut command = tokio::process::Command::from(command);
   // TODO(@crowlkats): allow detaching processes.
@@ -224,6 +261,28 @@ fn spawn_child(
     .take()
     .map(|stderr| state.resource_table.add(ChildStderrResource::from(stderr)));
 
+  // The child's end of the channel is closed in this process together with
+  // `command`.
+  drop(command);
+  #[cfg(unix)]
+  let ipc_rid = match ipc {
+    Some(parent) => {
+      parent.set_nonblocking(true)?;
+      let stream = tokio::net::UnixStream::from_std(parent)?;
+      Some(
+        state
+          .resource_table
+          .add(deno_node::IpcJsonStreamResource::from(stream)),
+      )
+    }
+    None => None,
+  };
+  #[cfg(not(unix))]
+  let ipc_rid = {
+    let _ = ipc;
+    None
+  };
+
   let child_rid = state.resource_table.add(ChildResource(child));
 
   Ok(Child {
@@ -232,6 +291,7 @@ fn spawn_child(
     stdin_rid,
     stdout_rid,
     stderr_rid,
+    ipc_rid,
   })
 }
 
@@ -241,8 +301,20 @@ fn op_spawn_child(
   args: SpawnArgs,
   api_name: String,
 ) -> Result<Child, AnyError> {
-  let command = create_command(state, args, &api_name)?;
-  spawn_child(state, command)
+  let (command, ipc) = create_command(state, args, &api_name, false)?;
+  spawn_child(state, command, ipc)
+}
+
+/// Like `op_spawn_child`, but also creates an IPC channel to the child. Only
+/// used by `node:child_process`.
+#[op]
+fn op_spawn_child_with_ipc(
+  state: &mut OpState,
+  args: SpawnArgs,
+  api_name: String,
+) -> Result<Child, AnyError> {
+  let (command, ipc) = create_command(state, args, &api_name, true)?;
+  spawn_child(state, command, ipc)
 }
 
 #[op]
@@ -270,8 +342,9 @@ fn op_spawn_sync(
 ) -> Result<SpawnOutput, AnyError> {
   let stdout = matches!(args.stdio.stdout, Stdio::Piped);
   let stderr = matches!(args.stdio.stderr, Stdio::Piped);
-  let output =
-    create_command(state, args, "Deno.Command().outputSync()")?.output()?;
+  let (mut command, _) =
+    create_command(state, args, "Deno.Command().outputSync()", false)?;
+  let output = command.output()?;
 
   Ok(SpawnOutput {
     status: output.status.try_into()?,
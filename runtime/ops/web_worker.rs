@@ -2,6 +2,7 @@
 
 mod sync_fetch;
 
+use crate::ops::worker_host::WorkerMetadata;
 use crate::web_worker::WebWorkerInternalHandle;
 use crate::web_worker::WebWorkerType;
 use deno_core::error::AnyError;
@@ -24,6 +25,7 @@ pub fn init() -> Extension {
       // Notify host that guest worker closes.
       op_worker_close::decl(),
       op_worker_get_type::decl(),
+      op_worker_get_metadata::decl(),
       op_worker_sync_fetch::decl(),
     ])
     .build()
@@ -67,3 +69,11 @@ fn op_worker_get_type(state: &mut OpState) -> WebWorkerType {
   let handle = state.borrow::<WebWorkerInternalHandle>().clone();
   handle.worker_type
 }
+
+/// Returns the data the worker was created with, if any. It can only be
+/// taken once.
+#[op]
+fn op_worker_get_metadata(state: &mut OpState) -> Option<JsMessageData> {
+  let WorkerMetadata(metadata) = state.try_take::<WorkerMetadata>()?;
+  Some(metadata.attach(state))
+}
@@ -4,6 +4,7 @@ use crate::ops::TestingFeaturesEnabled;
 use crate::permissions::create_child_permissions;
 use crate::permissions::ChildPermissionsArg;
 use crate::permissions::PermissionsContainer;
+use crate::resource_limits::ResourceLimits;
 use crate::web_worker::run_web_worker;
 use crate::web_worker::SendableWebWorkerHandle;
 use crate::web_worker::WebWorker;
@@ -22,6 +23,7 @@ use deno_core::Extension;
 use deno_core::ModuleSpecifier;
 use deno_core::OpState;
 use deno_web::JsMessageData;
+use deno_web::MessageData;
 use log::debug;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -35,6 +37,7 @@ pub struct CreateWebWorkerArgs {
   pub permissions: PermissionsContainer,
   pub main_module: ModuleSpecifier,
   pub worker_type: WebWorkerType,
+  pub resource_limits: ResourceLimits,
 }
 
 pub type CreateWebWorkerCb = dyn Fn(CreateWebWorkerArgs) -> (WebWorker, SendableWebWorkerHandle)
@@ -61,6 +64,10 @@ struct PreloadModuleCbHolder(Arc<WorkerEventCb>);
 #[derive(Clone)]
 struct PreExecuteModuleCbHolder(Arc<WorkerEventCb>);
 
+/// Data passed by the creator of a worker, available to the worker before its
+/// main module is evaluated. Used by `node:worker_threads` for `workerData`.
+pub struct WorkerMetadata(pub MessageData);
+
 pub struct WorkerThread {
   worker_handle: WebWorkerHandle,
   cancel_handle: Rc<CancelHandle>,
@@ -130,6 +137,7 @@ pub struct CreateWorkerArgs {
   has_source_code: bool,
   name: Option<String>,
   permissions: Option<ChildPermissionsArg>,
+  limits: Option<ResourceLimits>,
   source_code: String,
   specifier: String,
   worker_type: WebWorkerType,
@@ -140,6 +148,7 @@ pub struct CreateWorkerArgs {
 fn op_create_worker(
   state: &mut OpState,
   args: CreateWorkerArgs,
+  maybe_worker_metadata: Option<JsMessageData>,
 ) -> Result<WorkerId, AnyError> {
   let specifier = args.specifier.clone();
   let maybe_source_code = if args.has_source_code {
@@ -174,6 +183,15 @@ fn op_create_worker(
     parent_permissions.clone()
   };
   let parent_permissions = parent_permissions.clone();
+  // `resourceLimits` of Node.js workers are a stable API
+  if args.limits.is_some() && maybe_worker_metadata.is_none() {
+    super::check_unstable(state, "Worker.deno.limits");
+  }
+  let resource_limits = state
+    .try_borrow::<ResourceLimits>()
+    .copied()
+    .unwrap_or_default()
+    .for_child(args.limits);
   let worker_id = state.take::<WorkerId>();
   let create_web_worker_cb = state.take::<CreateWebWorkerCbHolder>();
   state.put::<CreateWebWorkerCbHolder>(create_web_worker_cb.clone());
@@ -187,6 +205,9 @@ fn op_create_worker(
 
   let module_specifier = deno_core::resolve_url(&specifier)?;
   let worker_name = args_name.unwrap_or_default();
+  let maybe_worker_metadata = maybe_worker_metadata
+    .map(|metadata| metadata.detach(state))
+    .transpose()?;
 
   let (handle_sender, handle_receiver) = std::sync::mpsc::sync_channel::<
     Result<SendableWebWorkerHandle, AnyError>,
@@ -202,7 +223,7 @@ fn op_create_worker(
     //  all action done upon it should be noops
     // - newly spawned thread exits
 
-    let (worker, external_handle) =
+    let (mut worker, external_handle) =
       (create_web_worker_cb.0)(CreateWebWorkerArgs {
         name: worker_name,
         worker_id,
@@ -210,7 +231,15 @@ fn op_create_worker(
         permissions: worker_permissions,
         main_module: module_specifier.clone(),
         worker_type,
+        resource_limits,
       });
+    if let Some(metadata) = maybe_worker_metadata {
+      worker
+        .js_runtime
+        .op_state()
+        .borrow_mut()
+        .put(WorkerMetadata(metadata));
+    }
 
     // Send thread safe handle from newly created worker to host thread
     handle_sender.send(Ok(external_handle)).unwrap();
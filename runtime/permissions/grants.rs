@@ -0,0 +1,272 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::PermissionsOptions;
+use super::DEFAULT_IMPORT_ALLOWLIST;
+use crate::colors;
+use deno_core::anyhow::Context;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use once_cell::sync::Lazy;
+use std::path::Path;
+use std::path::PathBuf;
+
+static GRANTS_FILE: Lazy<Mutex<Option<PathBuf>>> =
+  Lazy::new(|| Mutex::new(None));
+
+/// Accesses that were granted with "always allow" at a permission prompt.
+/// They are stored per project and applied on top of the allowlists of every
+/// following run, until they are revoked with `deno permissions revoke`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Grants {
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub env: Vec<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub ffi: Vec<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub import: Vec<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub net: Vec<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub read: Vec<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub run: Vec<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub sys: Vec<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub write: Vec<String>,
+}
+
+impl Grants {
+  /// Reads the grants stored in `path`. A missing file holds no grants.
+  pub fn load(path: &Path) -> Result<Self, AnyError> {
+    let text = match std::fs::read_to_string(path) {
+      Ok(text) => text,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+        return Ok(Self::default())
+      }
+      Err(err) => return Err(err.into()),
+    };
+    serde_json::from_str(&text).with_context(|| {
+      format!(
+        "Failed to parse the permission grants in \"{}\"",
+        path.display()
+      )
+    })
+  }
+
+  /// Writes the grants to `path`, or removes the file if there are none.
+  pub fn save(&self, path: &Path) -> Result<(), AnyError> {
+    if self.is_empty() {
+      return match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+          Err(err.into())
+        }
+        _ => Ok(()),
+      };
+    }
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    let mut text = serde_json::to_string_pretty(self)?;
+    text.push('\n');
+    std::fs::write(path, text)?;
+    Ok(())
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries().iter().all(|(_, values)| values.is_empty())
+  }
+
+  /// The granted values by permission name.
+  pub fn entries(&self) -> [(&'static str, &Vec<String>); 8] {
+    [
+      ("env", &self.env),
+      ("ffi", &self.ffi),
+      ("import", &self.import),
+      ("net", &self.net),
+      ("read", &self.read),
+      ("run", &self.run),
+      ("sys", &self.sys),
+      ("write", &self.write),
+    ]
+  }
+
+  fn values_mut(&mut self, name: &str) -> Result<&mut Vec<String>, AnyError> {
+    Ok(match name {
+      "env" => &mut self.env,
+      "ffi" => &mut self.ffi,
+      "import" => &mut self.import,
+      "net" => &mut self.net,
+      "read" => &mut self.read,
+      "run" => &mut self.run,
+      "sys" => &mut self.sys,
+      "write" => &mut self.write,
+      _ => return Err(type_error(format!("Unknown permission \"{name}\""))),
+    })
+  }
+
+  pub fn add(&mut self, name: &str, value: &str) -> Result<(), AnyError> {
+    let values = self.values_mut(name)?;
+    if !values.iter().any(|v| v == value) {
+      values.push(value.to_string());
+    }
+    Ok(())
+  }
+
+  /// Removes the given values of the permission `name`, all of its values if
+  /// `values` is empty, or every grant if `name` is `None`. Returns the number
+  /// of grants that were removed.
+  pub fn revoke(
+    &mut self,
+    name: Option<&str>,
+    values: &[String],
+  ) -> Result<usize, AnyError> {
+    let name = match name {
+      Some(name) => name,
+      None => {
+        let count = self.entries().iter().map(|(_, v)| v.len()).sum();
+        *self = Self::default();
+        return Ok(count);
+      }
+    };
+    let granted = self.values_mut(name)?;
+    let count = granted.len();
+    if values.is_empty() {
+      granted.clear();
+    } else {
+      granted.retain(|value| !values.contains(value));
+    }
+    Ok(count - granted.len())
+  }
+
+  /// Adds the grants to the allowlists of `options`.
+  pub fn apply(&self, options: &PermissionsOptions) -> PermissionsOptions {
+    // An unset import allowlist stands for the default hosts.
+    let allow_import = match &options.allow_import {
+      None if !self.import.is_empty() => Some(
+        DEFAULT_IMPORT_ALLOWLIST
+          .iter()
+          .map(ToString::to_string)
+          .collect(),
+      ),
+      allow_import => allow_import.clone(),
+    };
+    PermissionsOptions {
+      allow_env: extend_allowlist(&options.allow_env, &self.env),
+      allow_ffi: extend_allowlist(&options.allow_ffi, &self.ffi),
+      allow_import: extend_allowlist(&allow_import, &self.import),
+      allow_net: extend_allowlist(&options.allow_net, &self.net),
+      allow_read: extend_allowlist(&options.allow_read, &self.read),
+      allow_run: extend_allowlist(&options.allow_run, &self.run),
+      allow_sys: extend_allowlist(&options.allow_sys, &self.sys),
+      allow_write: extend_allowlist(&options.allow_write, &self.write),
+      ..options.clone()
+    }
+  }
+}
+
+fn extend_allowlist<T: Clone + From<String>>(
+  allowlist: &Option<Vec<T>>,
+  granted: &[String],
+) -> Option<Vec<T>> {
+  match allowlist {
+    // An empty allowlist already allows everything.
+    Some(list) if list.is_empty() => Some(vec![]),
+    _ if granted.is_empty() => allowlist.clone(),
+    _ => Some(
+      allowlist
+        .iter()
+        .flatten()
+        .cloned()
+        .chain(granted.iter().cloned().map(T::from))
+        .collect(),
+    ),
+  }
+}
+
+/// Sets the file that "always allow" answers at permission prompts are
+/// remembered in. Without one the prompt doesn't offer to remember answers.
+pub fn set_grants_file(path: Option<PathBuf>) {
+  *GRANTS_FILE.lock() = path;
+}
+
+pub(crate) fn can_remember() -> bool {
+  GRANTS_FILE.lock().is_some()
+}
+
+/// Adds a grant to the grants file. The access was granted for the current
+/// run either way, so a failure is only reported.
+pub(crate) fn remember(name: &str, value: &str) {
+  let path = match GRANTS_FILE.lock().clone() {
+    Some(path) => path,
+    None => return,
+  };
+  let result = Grants::load(&path).and_then(|mut grants| {
+    grants.add(name, value)?;
+    grants.save(&path)
+  });
+  if let Err(err) = result {
+    log::warn!(
+      "{}: Failed to remember the permission grant: {err:#}",
+      colors::yellow("Warning")
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_apply() {
+    let grants = Grants {
+      read: vec!["/a".to_string()],
+      net: vec!["example.com".to_string()],
+      import: vec!["example.com".to_string()],
+      ..Default::default()
+    };
+    let options = grants.apply(&PermissionsOptions {
+      allow_net: Some(vec![]),
+      allow_read: Some(vec![PathBuf::from("/b")]),
+      ..Default::default()
+    });
+    assert_eq!(
+      options.allow_read,
+      Some(vec![PathBuf::from("/b"), PathBuf::from("/a")])
+    );
+    assert_eq!(options.allow_net, Some(vec![]));
+    assert_eq!(options.allow_write, None);
+    let allow_import = options.allow_import.unwrap();
+    assert!(allow_import.contains(&"deno.land".to_string()));
+    assert!(allow_import.contains(&"example.com".to_string()));
+  }
+
+  #[test]
+  fn test_save_and_revoke() {
+    let temp_dir = test_util::TempDir::new();
+    let path = temp_dir.path().join("grants").join("project.json");
+    let mut grants = Grants::load(&path).unwrap();
+    assert!(grants.is_empty());
+    grants.add("read", "/a").unwrap();
+    grants.add("read", "/a").unwrap();
+    grants.add("env", "HOME").unwrap();
+    assert!(grants.add("hrtime", "").is_err());
+    grants.save(&path).unwrap();
+
+    let mut grants = Grants::load(&path).unwrap();
+    assert_eq!(grants.read, vec!["/a".to_string()]);
+    assert_eq!(
+      grants.revoke(Some("env"), &["PATH".to_string()]).unwrap(),
+      0
+    );
+    assert_eq!(grants.revoke(Some("env"), &[]).unwrap(), 1);
+    assert_eq!(grants.revoke(None, &[]).unwrap(), 1);
+    grants.save(&path).unwrap();
+    assert!(!path.exists());
+  }
+}
@@ -26,11 +26,25 @@ use std::str::FromStr;
 use std::string::ToString;
 use std::sync::Arc;
 
+mod grants;
+mod module_scopes;
+mod path_pattern;
 mod prompter;
 use prompter::permission_prompt;
+use prompter::permission_prompt_with_choices;
+use prompter::PromptChoices;
 use prompter::PromptResponse;
 use prompter::PERMISSION_EMOJI;
 
+pub use grants::set_grants_file;
+pub use grants::Grants;
+
+pub(crate) use module_scopes::calling_modules;
+pub use module_scopes::ModuleScopeOptions;
+pub use module_scopes::ModuleScopes;
+pub use module_scopes::ROOT_SCOPE;
+use path_pattern::canonicalize_path;
+pub use path_pattern::PathPattern;
 pub use prompter::set_prompt_callbacks;
 pub use prompter::PromptCallback;
 
@@ -102,10 +116,27 @@ impl PermissionState {
     info: impl Fn() -> Option<String>,
     prompt: bool,
   ) -> (Result<(), AnyError>, bool) {
+    let (result, response) =
+      self.check_with_grant(name, api_name, info, prompt, PromptGrant::default);
+    (result, response.is_some())
+  }
+
+  /// As `check2()`, but the prompt also offers the choices of `grant`. The
+  /// response is returned if a prompt was issued, "always allow" answers are
+  /// remembered and reported as `Allow` or `AllowBroader` respectively.
+  #[inline]
+  fn check_with_grant(
+    self,
+    name: &str,
+    api_name: Option<&str>,
+    info: impl Fn() -> Option<String>,
+    prompt: bool,
+    grant: impl FnOnce() -> PromptGrant,
+  ) -> (Result<(), AnyError>, Option<PromptResponse>) {
     match self {
       PermissionState::Granted => {
         Self::log_perm_access(name, info);
-        (Ok(()), false)
+        (Ok(()), None)
       }
       PermissionState::Prompt if prompt => {
         let msg = format!(
@@ -113,14 +144,79 @@ impl PermissionState {
           name,
           info().map_or(String::new(), |info| { format!(" to {info}") }),
         );
-        if PromptResponse::Allow == permission_prompt(&msg, name, api_name) {
-          Self::log_perm_access(name, info);
-          (Ok(()), true)
+        let grant = grant();
+        let choices = PromptChoices {
+          broader: grant.broader.as_ref().map(|(_, display)| display.as_str()),
+          remember: grant.value.is_some() && grants::can_remember(),
+        };
+        let response =
+          permission_prompt_with_choices(&msg, name, api_name, &choices);
+        let response = match (response, grant.value, grant.broader) {
+          (PromptResponse::AllowAlways, Some(value), _) => {
+            grants::remember(name, &value);
+            PromptResponse::Allow
+          }
+          (PromptResponse::AllowBroaderAlways, _, Some((value, _))) => {
+            grants::remember(name, &value);
+            PromptResponse::AllowBroader
+          }
+          (response, _, _) => response,
+        };
+        if response == PromptResponse::Deny {
+          (Err(Self::error(name, info)), Some(response))
         } else {
-          (Err(Self::error(name, info)), true)
+          Self::log_perm_access(name, info);
+          (Ok(()), Some(response))
         }
       }
-      _ => (Err(Self::error(name, info)), false),
+      _ => (Err(Self::error(name, info)), None),
+    }
+  }
+}
+
+/// What a permission prompt offers besides granting the requested access for
+/// the rest of the run.
+#[derive(Default)]
+struct PromptGrant {
+  /// The requested access as it is stored in the grants file, if the answer
+  /// can be remembered.
+  value: Option<String>,
+  /// Broader access that can be granted instead, as it is stored in the
+  /// grants file and as it is described in the prompt.
+  broader: Option<(String, String)>,
+}
+
+impl PromptGrant {
+  /// Offers access to the parent directory of `path`.
+  fn path(path: &Path) -> Self {
+    let path = match resolve_from_cwd(path) {
+      Ok(path) => path,
+      Err(_) => return Self::default(),
+    };
+    Self {
+      value: Some(path.to_string_lossy().to_string()),
+      broader: path.parent().map(|parent| {
+        (
+          parent.to_string_lossy().to_string(),
+          format!("all of \"{}\"", parent.display()),
+        )
+      }),
+    }
+  }
+
+  /// Offers access to all ports of `host`.
+  fn host(host: &str, port: Option<u16>) -> Self {
+    Self {
+      value: Some(NetDescriptor(host.to_string(), port).to_string()),
+      broader: port
+        .map(|_| (host.to_string(), format!("all ports of \"{host}\""))),
+    }
+  }
+
+  fn value(value: &str) -> Self {
+    Self {
+      value: Some(value.to_string()),
+      broader: None,
     }
   }
 }
@@ -228,17 +324,38 @@ pub struct UnaryPermission<T: Eq + Hash> {
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
-pub struct ReadDescriptor(pub PathBuf);
+pub struct ReadDescriptor(pub PathPattern);
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
-pub struct WriteDescriptor(pub PathBuf);
+pub struct WriteDescriptor(pub PathPattern);
+
+/// A descriptor of a host with an optional port. The permissions granted per
+/// host share how they're queried, requested and checked.
+pub trait HostDescriptor: Eq + Hash + fmt::Display + Sized {
+  /// What the permission grants, as shown in prompts.
+  const ACCESS: &'static str;
+
+  fn with_port(host: String, port: Option<u16>) -> Self;
+
+  fn host(&self) -> &str;
+
+  fn from_host<T: AsRef<str>>(host: &(T, Option<u16>)) -> Self {
+    Self::with_port(host.0.as_ref().to_string(), host.1)
+  }
+}
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct NetDescriptor(pub String, pub Option<u16>);
 
-impl NetDescriptor {
-  fn new<T: AsRef<str>>(host: &&(T, Option<u16>)) -> Self {
-    NetDescriptor(host.0.as_ref().to_string(), host.1)
+impl HostDescriptor for NetDescriptor {
+  const ACCESS: &'static str = "network access";
+
+  fn with_port(host: String, port: Option<u16>) -> Self {
+    NetDescriptor(host, port)
+  }
+
+  fn host(&self) -> &str {
+    &self.0
   }
 }
 
@@ -262,6 +379,44 @@ impl fmt::Display for NetDescriptor {
   }
 }
 
+/// Hosts remote modules may be imported from when `--allow-import` is not
+/// specified.
+pub const DEFAULT_IMPORT_ALLOWLIST: &[&str] =
+  &["deno.land", "esm.sh", "registry.npmjs.org"];
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ImportDescriptor(pub String, pub Option<u16>);
+
+impl HostDescriptor for ImportDescriptor {
+  const ACCESS: &'static str = "import access";
+
+  fn with_port(host: String, port: Option<u16>) -> Self {
+    ImportDescriptor(host, port)
+  }
+
+  fn host(&self) -> &str {
+    &self.0
+  }
+}
+
+impl FromStr for ImportDescriptor {
+  type Err = AnyError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let NetDescriptor(hostname, port) = NetDescriptor::from_str(s)?;
+    Ok(ImportDescriptor(hostname, port))
+  }
+}
+
+impl fmt::Display for ImportDescriptor {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&match self.1 {
+      None => self.0.clone(),
+      Some(port) => format!("{}:{}", self.0, port),
+    })
+  }
+}
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct EnvDescriptor(EnvVarName);
 
@@ -326,24 +481,33 @@ impl UnaryPermission<ReadDescriptor> {
     if self.global_state == PermissionState::Granted {
       return PermissionState::Granted;
     }
-    let path = path.map(|p| resolve_from_cwd(p).unwrap());
+    // Resolved once, the entries are matched without touching the file
+    // system again.
+    let path = path.map(|p| canonicalize_path(&resolve_from_cwd(p).unwrap()));
     if self.global_state == PermissionState::Denied
       && match path.as_ref() {
         None => true,
-        Some(path) => self
-          .denied_list
-          .iter()
-          .any(|path_| path_.0.starts_with(path)),
+        Some(path) => {
+          self.denied_list.iter().any(|path_| path_.0.is_below(path))
+        }
       }
     {
       PermissionState::Denied
+    } else if match path.as_ref() {
+      None => false,
+      Some(path) => self
+        .granted_list
+        .iter()
+        .any(|path_| path_.0.is_excluded() && path_.0.matches(path)),
+    } {
+      PermissionState::Denied
     } else if self.global_state == PermissionState::Granted
       || match path.as_ref() {
         None => false,
         Some(path) => self
           .granted_list
           .iter()
-          .any(|path_| path.starts_with(&path_.0)),
+          .any(|path_| !path_.0.is_excluded() && path_.0.matches(path)),
       }
     {
       PermissionState::Granted
@@ -364,15 +528,21 @@ impl UnaryPermission<ReadDescriptor> {
             Some("Deno.permissions.query()"),
           )
         {
-          self.granted_list.insert(ReadDescriptor(resolved_path));
+          self
+            .granted_list
+            .insert(ReadDescriptor(resolved_path.into()));
           PermissionState::Granted
         } else {
-          self.denied_list.insert(ReadDescriptor(resolved_path));
+          self
+            .denied_list
+            .insert(ReadDescriptor(resolved_path.into()));
           self.global_state = PermissionState::Denied;
           PermissionState::Denied
         }
       } else if state == PermissionState::Granted {
-        self.granted_list.insert(ReadDescriptor(resolved_path));
+        self
+          .granted_list
+          .insert(ReadDescriptor(resolved_path.into()));
         PermissionState::Granted
       } else {
         state
@@ -402,10 +572,10 @@ impl UnaryPermission<ReadDescriptor> {
 
   pub fn revoke(&mut self, path: Option<&Path>) -> PermissionState {
     if let Some(path) = path {
-      let path = resolve_from_cwd(path).unwrap();
+      let path = canonicalize_path(&resolve_from_cwd(path).unwrap());
       self
         .granted_list
-        .retain(|path_| !path.starts_with(&path_.0));
+        .retain(|path_| path_.0.is_excluded() || !path_.0.matches(&path));
     } else {
       self.granted_list.clear();
     }
@@ -421,18 +591,26 @@ impl UnaryPermission<ReadDescriptor> {
     path: &Path,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
-    let (result, prompted) = self.query(Some(path)).check2(
+    let (result, response) = self.query(Some(path)).check_with_grant(
       self.name,
       api_name,
       || Some(format!("\"{}\"", path.to_path_buf().display())),
       self.prompt,
+      || PromptGrant::path(path),
     );
-    if prompted {
+    if let Some(response) = response {
       let resolved_path = resolve_from_cwd(path)?;
-      if result.is_ok() {
-        self.granted_list.insert(ReadDescriptor(resolved_path));
+      if response == PromptResponse::AllowBroader {
+        let parent = resolved_path.parent().unwrap().to_path_buf();
+        self.granted_list.insert(ReadDescriptor(parent.into()));
+      } else if result.is_ok() {
+        self
+          .granted_list
+          .insert(ReadDescriptor(resolved_path.into()));
       } else {
-        self.denied_list.insert(ReadDescriptor(resolved_path));
+        self
+          .denied_list
+          .insert(ReadDescriptor(resolved_path.into()));
         self.global_state = PermissionState::Denied;
       }
     }
@@ -456,9 +634,13 @@ impl UnaryPermission<ReadDescriptor> {
     );
     if prompted {
       if result.is_ok() {
-        self.granted_list.insert(ReadDescriptor(resolved_path));
+        self
+          .granted_list
+          .insert(ReadDescriptor(resolved_path.into()));
       } else {
-        self.denied_list.insert(ReadDescriptor(resolved_path));
+        self
+          .denied_list
+          .insert(ReadDescriptor(resolved_path.into()));
         self.global_state = PermissionState::Denied;
       }
     }
@@ -499,24 +681,33 @@ impl UnaryPermission<WriteDescriptor> {
     if self.global_state == PermissionState::Granted {
       return PermissionState::Granted;
     }
-    let path = path.map(|p| resolve_from_cwd(p).unwrap());
+    // Resolved once, the entries are matched without touching the file
+    // system again.
+    let path = path.map(|p| canonicalize_path(&resolve_from_cwd(p).unwrap()));
     if self.global_state == PermissionState::Denied
       && match path.as_ref() {
         None => true,
-        Some(path) => self
-          .denied_list
-          .iter()
-          .any(|path_| path_.0.starts_with(path)),
+        Some(path) => {
+          self.denied_list.iter().any(|path_| path_.0.is_below(path))
+        }
       }
     {
       PermissionState::Denied
+    } else if match path.as_ref() {
+      None => false,
+      Some(path) => self
+        .granted_list
+        .iter()
+        .any(|path_| path_.0.is_excluded() && path_.0.matches(path)),
+    } {
+      PermissionState::Denied
     } else if self.global_state == PermissionState::Granted
       || match path.as_ref() {
         None => false,
         Some(path) => self
           .granted_list
           .iter()
-          .any(|path_| path.starts_with(&path_.0)),
+          .any(|path_| !path_.0.is_excluded() && path_.0.matches(path)),
       }
     {
       PermissionState::Granted
@@ -537,15 +728,21 @@ impl UnaryPermission<WriteDescriptor> {
             Some("Deno.permissions.query()"),
           )
         {
-          self.granted_list.insert(WriteDescriptor(resolved_path));
+          self
+            .granted_list
+            .insert(WriteDescriptor(resolved_path.into()));
           PermissionState::Granted
         } else {
-          self.denied_list.insert(WriteDescriptor(resolved_path));
+          self
+            .denied_list
+            .insert(WriteDescriptor(resolved_path.into()));
           self.global_state = PermissionState::Denied;
           PermissionState::Denied
         }
       } else if state == PermissionState::Granted {
-        self.granted_list.insert(WriteDescriptor(resolved_path));
+        self
+          .granted_list
+          .insert(WriteDescriptor(resolved_path.into()));
         PermissionState::Granted
       } else {
         state
@@ -575,10 +772,10 @@ impl UnaryPermission<WriteDescriptor> {
 
   pub fn revoke(&mut self, path: Option<&Path>) -> PermissionState {
     if let Some(path) = path {
-      let path = resolve_from_cwd(path).unwrap();
+      let path = canonicalize_path(&resolve_from_cwd(path).unwrap());
       self
         .granted_list
-        .retain(|path_| !path.starts_with(&path_.0));
+        .retain(|path_| path_.0.is_excluded() || !path_.0.matches(&path));
     } else {
       self.granted_list.clear();
     }
@@ -594,18 +791,26 @@ impl UnaryPermission<WriteDescriptor> {
     path: &Path,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
-    let (result, prompted) = self.query(Some(path)).check2(
+    let (result, response) = self.query(Some(path)).check_with_grant(
       self.name,
       api_name,
       || Some(format!("\"{}\"", path.to_path_buf().display())),
       self.prompt,
+      || PromptGrant::path(path),
     );
-    if prompted {
+    if let Some(response) = response {
       let resolved_path = resolve_from_cwd(path)?;
-      if result.is_ok() {
-        self.granted_list.insert(WriteDescriptor(resolved_path));
+      if response == PromptResponse::AllowBroader {
+        let parent = resolved_path.parent().unwrap().to_path_buf();
+        self.granted_list.insert(WriteDescriptor(parent.into()));
+      } else if result.is_ok() {
+        self
+          .granted_list
+          .insert(WriteDescriptor(resolved_path.into()));
       } else {
-        self.denied_list.insert(WriteDescriptor(resolved_path));
+        self
+          .denied_list
+          .insert(WriteDescriptor(resolved_path.into()));
         self.global_state = PermissionState::Denied;
       }
     }
@@ -641,7 +846,7 @@ impl Default for UnaryPermission<WriteDescriptor> {
   }
 }
 
-impl UnaryPermission<NetDescriptor> {
+impl<D: HostDescriptor> UnaryPermission<D> {
   pub fn query<T: AsRef<str>>(
     &self,
     host: Option<&(T, Option<u16>)>,
@@ -653,8 +858,8 @@ impl UnaryPermission<NetDescriptor> {
           None => self
             .denied_list
             .iter()
-            .any(|host_| host.0.as_ref() == host_.0),
-          Some(_) => self.denied_list.contains(&NetDescriptor::new(host)),
+            .any(|host_| host.0.as_ref() == host_.host()),
+          Some(_) => self.denied_list.contains(&D::from_host(*host)),
         },
       }
     {
@@ -663,11 +868,10 @@ impl UnaryPermission<NetDescriptor> {
       || match host.as_ref() {
         None => false,
         Some(host) => {
-          self.granted_list.contains(&NetDescriptor::new(&&(
-            host.0.as_ref().to_string(),
-            None,
-          )))
-            || self.granted_list.contains(&NetDescriptor::new(host))
+          self
+            .granted_list
+            .contains(&D::with_port(host.0.as_ref().to_string(), None))
+            || self.granted_list.contains(&D::from_host(*host))
         }
       }
     {
@@ -683,11 +887,11 @@ impl UnaryPermission<NetDescriptor> {
   ) -> PermissionState {
     if let Some(host) = host {
       let state = self.query(Some(host));
-      let host = NetDescriptor::new(&host);
+      let host = D::from_host(host);
       if state == PermissionState::Prompt {
         if PromptResponse::Allow
           == permission_prompt(
-            &format!("network access to \"{host}\""),
+            &format!("{} to \"{host}\"", D::ACCESS),
             self.name,
             Some("Deno.permissions.query()"),
           )
@@ -710,7 +914,7 @@ impl UnaryPermission<NetDescriptor> {
       if state == PermissionState::Prompt {
         if PromptResponse::Allow
           == permission_prompt(
-            "network access",
+            D::ACCESS,
             self.name,
             Some("Deno.permissions.query()"),
           )
@@ -736,11 +940,11 @@ impl UnaryPermission<NetDescriptor> {
       if host.1.is_some() {
         self
           .granted_list
-          .remove(&NetDescriptor(host.0.as_ref().to_string(), host.1));
+          .remove(&D::with_port(host.0.as_ref().to_string(), host.1));
       }
       self
         .granted_list
-        .remove(&NetDescriptor(host.0.as_ref().to_string(), None));
+        .remove(&D::with_port(host.0.as_ref().to_string(), None));
     } else {
       self.granted_list.clear();
     }
@@ -755,15 +959,20 @@ impl UnaryPermission<NetDescriptor> {
     host: &(T, Option<u16>),
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
-    let new_host = NetDescriptor::new(&host);
-    let (result, prompted) = self.query(Some(host)).check(
+    let new_host = D::from_host(host);
+    let (result, response) = self.query(Some(host)).check_with_grant(
       self.name,
       api_name,
-      Some(&format!("\"{new_host}\"")),
+      || Some(format!("\"{new_host}\"")),
       self.prompt,
+      || PromptGrant::host(host.0.as_ref(), host.1),
     );
-    if prompted {
-      if result.is_ok() {
+    if let Some(response) = response {
+      if response == PromptResponse::AllowBroader {
+        self
+          .granted_list
+          .insert(D::with_port(host.0.as_ref().to_string(), None));
+      } else if result.is_ok() {
         self.granted_list.insert(new_host);
       } else {
         self.denied_list.insert(new_host);
@@ -787,17 +996,22 @@ impl UnaryPermission<NetDescriptor> {
       Some(port) => format!("{hostname}:{port}"),
     };
     let host = &(&hostname, url.port_or_known_default());
-    let (result, prompted) = self.query(Some(host)).check(
+    let (result, response) = self.query(Some(host)).check_with_grant(
       self.name,
       api_name,
-      Some(&format!("\"{display_host}\"")),
+      || Some(format!("\"{display_host}\"")),
       self.prompt,
+      || PromptGrant::host(&hostname, url.port_or_known_default()),
     );
-    if prompted {
-      if result.is_ok() {
-        self.granted_list.insert(NetDescriptor::new(&host));
+    if let Some(response) = response {
+      if response == PromptResponse::AllowBroader {
+        self
+          .granted_list
+          .insert(D::with_port(hostname.clone(), None));
+      } else if result.is_ok() {
+        self.granted_list.insert(D::from_host(host));
       } else {
-        self.denied_list.insert(NetDescriptor::new(&host));
+        self.denied_list.insert(D::from_host(host));
         self.global_state = PermissionState::Denied;
       }
     }
@@ -833,6 +1047,19 @@ impl Default for UnaryPermission<NetDescriptor> {
   }
 }
 
+impl Default for UnaryPermission<ImportDescriptor> {
+  fn default() -> Self {
+    UnaryPermission::<ImportDescriptor> {
+      name: "import",
+      description: "import code from remote hosts",
+      global_state: Default::default(),
+      granted_list: Default::default(),
+      denied_list: Default::default(),
+      prompt: false,
+    }
+  }
+}
+
 impl UnaryPermission<EnvDescriptor> {
   pub fn query(&self, env: Option<&str>) -> PermissionState {
     let env = env.map(EnvVarName::new);
@@ -915,13 +1142,14 @@ impl UnaryPermission<EnvDescriptor> {
   }
 
   pub fn check(&mut self, env: &str) -> Result<(), AnyError> {
-    let (result, prompted) = self.query(Some(env)).check(
+    let (result, response) = self.query(Some(env)).check_with_grant(
       self.name,
       None,
-      Some(&format!("\"{env}\"")),
+      || Some(format!("\"{env}\"")),
       self.prompt,
+      || PromptGrant::value(env),
     );
-    if prompted {
+    if response.is_some() {
       if result.is_ok() {
         self.granted_list.insert(EnvDescriptor::new(env));
       } else {
@@ -1041,13 +1269,14 @@ impl UnaryPermission<SysDescriptor> {
     kind: &str,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
-    let (result, prompted) = self.query(Some(kind)).check(
+    let (result, response) = self.query(Some(kind)).check_with_grant(
       self.name,
       api_name,
-      Some(&format!("\"{kind}\"")),
+      || Some(format!("\"{kind}\"")),
       self.prompt,
+      || PromptGrant::value(kind),
     );
-    if prompted {
+    if response.is_some() {
       if result.is_ok() {
         self.granted_list.insert(SysDescriptor(kind.to_string()));
       } else {
@@ -1184,13 +1413,17 @@ impl UnaryPermission<RunDescriptor> {
     cmd: &str,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
-    let (result, prompted) = self.query(Some(cmd)).check(
+    let (result, response) = self.query(Some(cmd)).check_with_grant(
       self.name,
       api_name,
-      Some(&format!("\"{cmd}\"")),
+      || Some(format!("\"{cmd}\"")),
       self.prompt,
+      || {
+        let cmd = RunDescriptor::from_str(cmd).unwrap();
+        PromptGrant::value(&cmd.to_string())
+      },
     );
-    if prompted {
+    if response.is_some() {
       if result.is_ok() {
         self
           .granted_list
@@ -1328,15 +1561,20 @@ impl UnaryPermission<FfiDescriptor> {
   pub fn check(&mut self, path: Option<&Path>) -> Result<(), AnyError> {
     if let Some(path) = path {
       let (resolved_path, display_path) = resolved_and_display_path(path);
-      let (result, prompted) = self.query(Some(&resolved_path)).check(
-        self.name,
-        None,
-        Some(&format!("\"{}\"", display_path.display())),
-        self.prompt,
-      );
+      let (result, response) =
+        self.query(Some(&resolved_path)).check_with_grant(
+          self.name,
+          None,
+          || Some(format!("\"{}\"", display_path.display())),
+          self.prompt,
+          || PromptGrant::path(&resolved_path),
+        );
 
-      if prompted {
-        if result.is_ok() {
+      if let Some(response) = response {
+        if response == PromptResponse::AllowBroader {
+          let parent = resolved_path.parent().unwrap().to_path_buf();
+          self.granted_list.insert(FfiDescriptor(parent));
+        } else if result.is_ok() {
           self.granted_list.insert(FfiDescriptor(resolved_path));
         } else {
           self.denied_list.insert(FfiDescriptor(resolved_path));
@@ -1399,7 +1637,9 @@ pub struct Permissions {
   pub sys: UnaryPermission<SysDescriptor>,
   pub run: UnaryPermission<RunDescriptor>,
   pub ffi: UnaryPermission<FfiDescriptor>,
+  pub import: UnaryPermission<ImportDescriptor>,
   pub hrtime: UnitPermission,
+  pub module_scopes: ModuleScopes,
 }
 
 impl Default for Permissions {
@@ -1412,7 +1652,9 @@ impl Default for Permissions {
       sys: Permissions::new_sys(&None, false).unwrap(),
       run: Permissions::new_run(&None, false).unwrap(),
       ffi: Permissions::new_ffi(&None, false).unwrap(),
+      import: Permissions::new_import(&None, false).unwrap(),
       hrtime: Permissions::new_hrtime(false),
+      module_scopes: Default::default(),
     }
   }
 }
@@ -1423,11 +1665,16 @@ pub struct PermissionsOptions {
   pub allow_hrtime: bool,
   pub allow_net: Option<Vec<String>>,
   pub allow_ffi: Option<Vec<PathBuf>>,
+  pub allow_import: Option<Vec<String>>,
   pub allow_read: Option<Vec<PathBuf>>,
   pub allow_run: Option<Vec<String>>,
   pub allow_sys: Option<Vec<String>>,
   pub allow_write: Option<Vec<PathBuf>>,
   pub prompt: bool,
+  /// Restrictions for the modules in specific scopes, see [`ModuleScopes`].
+  pub module_scopes: Vec<ModuleScopeOptions>,
+  /// The project's file of remembered prompt answers, see [`Grants`].
+  pub grants_file: Option<PathBuf>,
 }
 
 impl Permissions {
@@ -1561,6 +1808,29 @@ impl Permissions {
     })
   }
 
+  /// Unlike other permissions, `None` doesn't mean an empty allowlist but
+  /// the hosts in [`DEFAULT_IMPORT_ALLOWLIST`].
+  pub fn new_import(
+    state: &Option<Vec<String>>,
+    prompt: bool,
+  ) -> Result<UnaryPermission<ImportDescriptor>, AnyError> {
+    Ok(UnaryPermission::<ImportDescriptor> {
+      global_state: global_state_from_option(state),
+      granted_list: match state {
+        Some(v) => v
+          .iter()
+          .map(|x| ImportDescriptor::from_str(x))
+          .collect::<Result<HashSet<ImportDescriptor>, AnyError>>()?,
+        None => DEFAULT_IMPORT_ALLOWLIST
+          .iter()
+          .map(|x| ImportDescriptor(x.to_string(), None))
+          .collect(),
+      },
+      prompt,
+      ..Default::default()
+    })
+  }
+
   pub fn new_hrtime(state: bool) -> UnitPermission {
     unit_permission_from_flag_bool(
       state,
@@ -1571,6 +1841,7 @@ impl Permissions {
   }
 
   pub fn from_options(opts: &PermissionsOptions) -> Result<Self, AnyError> {
+    let opts = &resolve_grants(opts)?;
     Ok(Self {
       read: Permissions::new_read(&opts.allow_read, opts.prompt)?,
       write: Permissions::new_write(&opts.allow_write, opts.prompt)?,
@@ -1579,7 +1850,9 @@ impl Permissions {
       sys: Permissions::new_sys(&opts.allow_sys, opts.prompt)?,
       run: Permissions::new_run(&opts.allow_run, opts.prompt)?,
       ffi: Permissions::new_ffi(&opts.allow_ffi, opts.prompt)?,
+      import: Permissions::new_import(&opts.allow_import, opts.prompt)?,
       hrtime: Permissions::new_hrtime(opts.allow_hrtime),
+      module_scopes: ModuleScopes::from_options(&opts.module_scopes)?,
     })
   }
 
@@ -1592,12 +1865,27 @@ impl Permissions {
       sys: Permissions::new_sys(&Some(vec![]), false).unwrap(),
       run: Permissions::new_run(&Some(vec![]), false).unwrap(),
       ffi: Permissions::new_ffi(&Some(vec![]), false).unwrap(),
+      import: Permissions::new_import(&Some(vec![]), false).unwrap(),
       hrtime: Permissions::new_hrtime(true),
+      module_scopes: Default::default(),
     }
   }
 
+  /// Permissions used to load the static module graph of the main worker.
+  /// Everything is allowed, except for importing code from hosts that aren't
+  /// covered by `--allow-import`.
+  pub fn allow_all_but_import(
+    opts: &PermissionsOptions,
+  ) -> Result<Self, AnyError> {
+    let opts = &resolve_grants(opts)?;
+    Ok(Self {
+      import: Permissions::new_import(&opts.allow_import, opts.prompt)?,
+      ..Permissions::allow_all()
+    })
+  }
+
   /// A helper function that determines if the module specifier is a local or
-  /// remote, and performs a read or net check for the specifier.
+  /// remote, and performs a read or import check for the specifier.
   pub fn check_specifier(
     &mut self,
     specifier: &ModuleSpecifier,
@@ -1611,7 +1899,7 @@ impl Permissions {
       },
       "data" => Ok(()),
       "blob" => Ok(()),
-      _ => self.net.check_url(specifier, Some("import()")),
+      _ => self.import.check_url(specifier, Some("import()")),
     }
   }
 }
@@ -1634,6 +1922,26 @@ impl PermissionsContainer {
     Self::new(Permissions::allow_all())
   }
 
+  /// Sets the modules on the call stack of the current op call, see
+  /// [`ModuleScopes`]. Returns the previously set modules.
+  pub fn set_calling_modules(
+    &self,
+    modules: Option<Vec<String>>,
+  ) -> Option<Vec<String>> {
+    self.0.lock().module_scopes.set_calling_modules(modules)
+  }
+
+  /// Runs `check` against the worker's permissions and the module scopes of
+  /// the modules that called the current op.
+  fn check_scoped(
+    &self,
+    check: impl Fn(&mut Permissions) -> Result<(), AnyError>,
+  ) -> Result<(), AnyError> {
+    let mut permissions = self.0.lock();
+    check(&mut permissions)?;
+    permissions.module_scopes.check(check)
+  }
+
   #[inline(always)]
   pub fn check_specifier(
     &self,
@@ -1648,7 +1956,7 @@ impl PermissionsContainer {
     path: &Path,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().read.check(path, Some(api_name))
+    self.check_scoped(|perms| perms.read.check(path, Some(api_name)))
   }
 
   #[inline(always)]
@@ -1658,12 +1966,12 @@ impl PermissionsContainer {
     display: &str,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().read.check_blind(path, display, api_name)
+    self.check_scoped(|perms| perms.read.check_blind(path, display, api_name))
   }
 
   #[inline(always)]
   pub fn check_read_all(&mut self, api_name: &str) -> Result<(), AnyError> {
-    self.0.lock().read.check_all(Some(api_name))
+    self.check_scoped(|perms| perms.read.check_all(Some(api_name)))
   }
 
   #[inline(always)]
@@ -1672,12 +1980,12 @@ impl PermissionsContainer {
     path: &Path,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().write.check(path, Some(api_name))
+    self.check_scoped(|perms| perms.write.check(path, Some(api_name)))
   }
 
   #[inline(always)]
   pub fn check_write_all(&mut self, api_name: &str) -> Result<(), AnyError> {
-    self.0.lock().write.check_all(Some(api_name))
+    self.check_scoped(|perms| perms.write.check_all(Some(api_name)))
   }
 
   #[inline(always)]
@@ -1686,12 +1994,12 @@ impl PermissionsContainer {
     cmd: &str,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().run.check(cmd, Some(api_name))
+    self.check_scoped(|perms| perms.run.check(cmd, Some(api_name)))
   }
 
   #[inline(always)]
   pub fn check_run_all(&mut self, api_name: &str) -> Result<(), AnyError> {
-    self.0.lock().run.check_all(Some(api_name))
+    self.check_scoped(|perms| perms.run.check_all(Some(api_name)))
   }
 
   #[inline(always)]
@@ -1700,17 +2008,17 @@ impl PermissionsContainer {
     kind: &str,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().sys.check(kind, Some(api_name))
+    self.check_scoped(|perms| perms.sys.check(kind, Some(api_name)))
   }
 
   #[inline(always)]
   pub fn check_env(&mut self, var: &str) -> Result<(), AnyError> {
-    self.0.lock().env.check(var)
+    self.check_scoped(|perms| perms.env.check(var))
   }
 
   #[inline(always)]
   pub fn check_env_all(&mut self) -> Result<(), AnyError> {
-    self.0.lock().env.check_all()
+    self.check_scoped(|perms| perms.env.check_all())
   }
 }
 
@@ -1721,14 +2029,14 @@ impl deno_flash::FlashPermissions for PermissionsContainer {
     host: &(T, Option<u16>),
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().net.check(host, Some(api_name))
+    self.check_scoped(|perms| perms.net.check(host, Some(api_name)))
   }
 }
 
 impl deno_node::NodePermissions for PermissionsContainer {
   #[inline(always)]
   fn check_read(&mut self, path: &Path) -> Result<(), AnyError> {
-    self.0.lock().read.check(path, None)
+    self.check_scoped(|perms| perms.read.check(path, None))
   }
 }
 
@@ -1739,7 +2047,7 @@ impl deno_net::NetPermissions for PermissionsContainer {
     host: &(T, Option<u16>),
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().net.check(host, Some(api_name))
+    self.check_scoped(|perms| perms.net.check(host, Some(api_name)))
   }
 
   #[inline(always)]
@@ -1748,7 +2056,7 @@ impl deno_net::NetPermissions for PermissionsContainer {
     path: &Path,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().read.check(path, Some(api_name))
+    self.check_scoped(|perms| perms.read.check(path, Some(api_name)))
   }
 
   #[inline(always)]
@@ -1757,7 +2065,7 @@ impl deno_net::NetPermissions for PermissionsContainer {
     path: &Path,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().write.check(path, Some(api_name))
+    self.check_scoped(|perms| perms.write.check(path, Some(api_name)))
   }
 }
 
@@ -1768,7 +2076,7 @@ impl deno_fetch::FetchPermissions for PermissionsContainer {
     url: &url::Url,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().net.check_url(url, Some(api_name))
+    self.check_scoped(|perms| perms.net.check_url(url, Some(api_name)))
   }
 
   #[inline(always)]
@@ -1777,14 +2085,14 @@ impl deno_fetch::FetchPermissions for PermissionsContainer {
     path: &Path,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().read.check(path, Some(api_name))
+    self.check_scoped(|perms| perms.read.check(path, Some(api_name)))
   }
 }
 
 impl deno_web::TimersPermission for PermissionsContainer {
   #[inline(always)]
   fn allow_hrtime(&mut self) -> bool {
-    self.0.lock().hrtime.check().is_ok()
+    self.check_scoped(|perms| perms.hrtime.check()).is_ok()
   }
 
   #[inline(always)]
@@ -1800,7 +2108,7 @@ impl deno_websocket::WebSocketPermissions for PermissionsContainer {
     url: &url::Url,
     api_name: &str,
   ) -> Result<(), AnyError> {
-    self.0.lock().net.check_url(url, Some(api_name))
+    self.check_scoped(|perms| perms.net.check_url(url, Some(api_name)))
   }
 }
 
@@ -1809,14 +2117,14 @@ impl deno_websocket::WebSocketPermissions for PermissionsContainer {
 impl deno_napi::NapiPermissions for PermissionsContainer {
   #[inline(always)]
   fn check(&mut self, path: Option<&Path>) -> Result<(), AnyError> {
-    self.0.lock().ffi.check(path)
+    self.check_scoped(|perms| perms.ffi.check(path))
   }
 }
 
 impl deno_ffi::FfiPermissions for PermissionsContainer {
   #[inline(always)]
   fn check(&mut self, path: Option<&Path>) -> Result<(), AnyError> {
-    self.0.lock().ffi.check(path)
+    self.check_scoped(|perms| perms.ffi.check(path))
   }
 }
 
@@ -1838,6 +2146,21 @@ fn unit_permission_from_flag_bool(
   }
 }
 
+/// Adds the grants remembered in the grants file of `opts` to its allowlists
+/// and has prompts remember "always allow" answers in that file.
+fn resolve_grants(
+  opts: &PermissionsOptions,
+) -> Result<PermissionsOptions, AnyError> {
+  match &opts.grants_file {
+    Some(path) => {
+      let opts = Grants::load(path)?.apply(opts);
+      set_grants_file(Some(path.clone()));
+      Ok(opts)
+    }
+    None => Ok(opts.clone()),
+  }
+}
+
 fn global_state_from_option<T>(flag: &Option<Vec<T>>) -> PermissionState {
   if matches!(flag, Some(v) if v.is_empty()) {
     PermissionState::Granted
@@ -1846,36 +2169,28 @@ fn global_state_from_option<T>(flag: &Option<Vec<T>>) -> PermissionState {
   }
 }
 
+/// Resolves the `--allow-read` allowlist. Entries may be glob patterns,
+/// reference environment variables or exclude paths; see [`PathPattern`].
 pub fn resolve_read_allowlist(
   allow: &Option<Vec<PathBuf>>,
 ) -> Result<HashSet<ReadDescriptor>, AnyError> {
   if let Some(v) = allow {
     v.iter()
-      .map(|raw_path| {
-        if raw_path.as_os_str().is_empty() {
-          Err(AnyError::msg("Empty path is not allowed"))
-        } else {
-          resolve_from_cwd(Path::new(&raw_path)).map(ReadDescriptor)
-        }
-      })
+      .map(|raw_path| PathPattern::parse(raw_path).map(ReadDescriptor))
       .collect()
   } else {
     Ok(HashSet::new())
   }
 }
 
+/// Resolves the `--allow-write` allowlist. Entries may be glob patterns,
+/// reference environment variables or exclude paths; see [`PathPattern`].
 pub fn resolve_write_allowlist(
   allow: &Option<Vec<PathBuf>>,
 ) -> Result<HashSet<WriteDescriptor>, AnyError> {
   if let Some(v) = allow {
     v.iter()
-      .map(|raw_path| {
-        if raw_path.as_os_str().is_empty() {
-          Err(AnyError::msg("Empty path is not allowed"))
-        } else {
-          resolve_from_cwd(Path::new(&raw_path)).map(WriteDescriptor)
-        }
-      })
+      .map(|raw_path| PathPattern::parse(raw_path).map(WriteDescriptor))
       .collect()
   } else {
     Ok(HashSet::new())
@@ -2325,14 +2640,23 @@ pub fn create_child_permissions(
         false,
       )?
       .granted_list;
-      if !worker_perms
-        .read
-        .granted_list
-        .iter()
-        .all(|desc| main_perms.read.check(&desc.0, None).is_ok())
-      {
+      if !worker_perms.read.granted_list.iter().all(|desc| {
+        desc.0.is_excluded()
+          || main_perms.read.granted_list.contains(desc)
+          || main_perms.read.check(desc.0.path(), None).is_ok()
+      }) {
         return Err(escalation_error());
       }
+      // Paths excluded from the parent's allowlist stay excluded for the
+      // child.
+      worker_perms.read.granted_list.extend(
+        main_perms
+          .read
+          .granted_list
+          .iter()
+          .filter(|desc| desc.0.is_excluded())
+          .cloned(),
+      );
     }
   }
   worker_perms.read.denied_list = main_perms.read.denied_list.clone();
@@ -2386,14 +2710,23 @@ pub fn create_child_permissions(
         false,
       )?
       .granted_list;
-      if !worker_perms
-        .write
-        .granted_list
-        .iter()
-        .all(|desc| main_perms.write.check(&desc.0, None).is_ok())
-      {
+      if !worker_perms.write.granted_list.iter().all(|desc| {
+        desc.0.is_excluded()
+          || main_perms.write.granted_list.contains(desc)
+          || main_perms.write.check(desc.0.path(), None).is_ok()
+      }) {
         return Err(escalation_error());
       }
+      // Paths excluded from the parent's allowlist stay excluded for the
+      // child.
+      worker_perms.write.granted_list.extend(
+        main_perms
+          .write
+          .granted_list
+          .iter()
+          .filter(|desc| desc.0.is_excluded())
+          .cloned(),
+      );
     }
   }
   worker_perms.write.denied_list = main_perms.write.denied_list.clone();
@@ -2401,6 +2734,11 @@ pub fn create_child_permissions(
     worker_perms.write.global_state = PermissionState::Denied;
   }
   worker_perms.write.prompt = main_perms.write.prompt;
+  // Workers can't be given a different import permission, the hosts a worker
+  // may load code from are always the ones of its parent.
+  worker_perms.import = main_perms.import.clone();
+  worker_perms.module_scopes = main_perms.module_scopes.clone();
+  worker_perms.module_scopes.set_calling_modules(None);
   Ok(worker_perms)
 }
 
@@ -2518,6 +2856,57 @@ mod tests {
     assert!(perms.ffi.check(Some(Path::new("/a/b"))).is_err());
   }
 
+  #[test]
+  fn check_path_patterns() {
+    set_prompter(Box::new(TestPrompter));
+    std::env::set_var("DENO_CHECK_PATH_PATTERNS", "/deno_env");
+    let allowlist = vec![
+      PathBuf::from("glob:/a/**/*.json"),
+      PathBuf::from("/b"),
+      PathBuf::from("!/b/secret"),
+      PathBuf::from("glob:$DENO_CHECK_PATH_PATTERNS/cache"),
+    ];
+
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_read: Some(allowlist.clone()),
+      allow_write: Some(allowlist),
+      ..Default::default()
+    })
+    .unwrap();
+
+    // Glob pattern
+    assert!(perms.read.check(Path::new("/a/b/c.json"), None).is_ok());
+    assert!(perms.write.check(Path::new("/a/c.json"), None).is_ok());
+    assert!(perms.read.check(Path::new("/a/b/c.ts"), None).is_err());
+    assert!(perms.write.check(Path::new("/a"), None).is_err());
+
+    // Path prefix with an exclusion
+    assert!(perms.read.check(Path::new("/b/public/x"), None).is_ok());
+    assert!(perms.write.check(Path::new("/b/public/x"), None).is_ok());
+    assert!(perms.read.check(Path::new("/b/secret"), None).is_err());
+    assert!(perms.write.check(Path::new("/b/secret/x"), None).is_err());
+    assert_eq!(
+      perms.read.query(Some(Path::new("/b/secret/x"))),
+      PermissionState::Denied
+    );
+
+    // Expanded environment variable
+    assert!(perms
+      .read
+      .check(Path::new("/deno_env/cache/x"), None)
+      .is_ok());
+    assert!(perms
+      .write
+      .check(Path::new("/deno_env/other"), None)
+      .is_err());
+
+    assert!(Permissions::new_read(
+      &Some(vec![PathBuf::from("glob:$DENO_CHECK_PATH_PATTERNS_UNSET")]),
+      false
+    )
+    .is_err());
+  }
+
   #[test]
   fn test_check_net_with_values() {
     set_prompter(Box::new(TestPrompter));
@@ -2704,7 +3093,7 @@ mod tests {
     };
     let mut perms = Permissions::from_options(&PermissionsOptions {
       allow_read: Some(read_allowlist),
-      allow_net: Some(svec!["localhost"]),
+      allow_import: Some(svec!["localhost"]),
       ..Default::default()
     })
     .unwrap();
@@ -2739,6 +3128,56 @@ mod tests {
     }
   }
 
+  #[test]
+  fn check_specifiers_import_allowlist() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec!["localhost"]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    let fixtures = vec![
+      ("https://deno.land/std/mod.ts", true),
+      ("https://esm.sh/preact", true),
+      ("https://registry.npmjs.org/chalk", true),
+      // `--allow-net` doesn't grant imports.
+      ("http://localhost:4545/mod.ts", false),
+      ("https://example.com/mod.ts", false),
+    ];
+
+    for (specifier, expected) in fixtures {
+      assert_eq!(
+        perms
+          .check_specifier(&resolve_url_or_path(specifier).unwrap())
+          .is_ok(),
+        expected
+      );
+    }
+
+    let mut perms = Permissions::allow_all_but_import(&PermissionsOptions {
+      allow_import: Some(svec!["example.com:8000"]),
+      ..Default::default()
+    })
+    .unwrap();
+    assert!(perms.net.check_all().is_ok());
+
+    let fixtures = vec![
+      ("http://example.com:8000/a.ts", true),
+      ("http://example.com/a.ts", false),
+      ("https://deno.land/a.ts", false),
+    ];
+
+    for (specifier, expected) in fixtures {
+      assert_eq!(
+        perms
+          .check_specifier(&resolve_url_or_path(specifier).unwrap())
+          .is_ok(),
+        expected
+      );
+    }
+  }
+
   #[test]
   fn check_invalid_specifiers() {
     set_prompter(Box::new(TestPrompter));
@@ -2797,10 +3236,15 @@ mod tests {
         global_state: PermissionState::Prompt,
         ..Permissions::new_run(&Some(svec!["deno"]), false).unwrap()
       },
+      import: UnaryPermission {
+        global_state: PermissionState::Prompt,
+        ..Permissions::new_import(&Some(svec!["deno.land"]), false).unwrap()
+      },
       hrtime: UnitPermission {
         state: PermissionState::Prompt,
         ..Permissions::new_hrtime(false)
       },
+      module_scopes: Default::default(),
     };
     #[rustfmt::skip]
     {
@@ -2835,6 +3279,10 @@ mod tests {
       assert_eq!(perms1.run.query(Some("deno")), PermissionState::Granted);
       assert_eq!(perms2.run.query(None), PermissionState::Prompt);
       assert_eq!(perms2.run.query(Some("deno")), PermissionState::Granted);
+      assert_eq!(perms1.import.query::<&str>(None), PermissionState::Granted);
+      assert_eq!(perms2.import.query::<&str>(None), PermissionState::Prompt);
+      assert_eq!(perms2.import.query(Some(&("deno.land", Some(443)))), PermissionState::Granted);
+      assert_eq!(perms2.import.query(Some(&("esm.sh", Some(443)))), PermissionState::Prompt);
       assert_eq!(perms1.hrtime.query(), PermissionState::Granted);
       assert_eq!(perms2.hrtime.query(), PermissionState::Prompt);
     };
@@ -2881,6 +3329,11 @@ mod tests {
       assert_eq!(perms.run.query(None), PermissionState::Prompt);
       prompt_value.set(false);
       assert_eq!(perms.run.request(Some("deno")), PermissionState::Granted);
+      prompt_value.set(true);
+      assert_eq!(perms.import.request(Some(&("example.com", None))), PermissionState::Granted);
+      assert_eq!(perms.import.query::<&str>(None), PermissionState::Prompt);
+      prompt_value.set(false);
+      assert_eq!(perms.import.request(Some(&("example.com", Some(443)))), PermissionState::Granted);
       prompt_value.set(false);
       assert_eq!(perms.hrtime.request(), PermissionState::Denied);
       prompt_value.set(true);
@@ -2936,10 +3389,15 @@ mod tests {
         global_state: PermissionState::Prompt,
         ..Permissions::new_run(&Some(svec!["deno"]), false).unwrap()
       },
+      import: UnaryPermission {
+        global_state: PermissionState::Prompt,
+        ..Permissions::new_import(&Some(svec!["deno.land"]), false).unwrap()
+      },
       hrtime: UnitPermission {
         state: PermissionState::Denied,
         ..Permissions::new_hrtime(false)
       },
+      module_scopes: Default::default(),
     };
     #[rustfmt::skip]
     {
@@ -2958,6 +3416,8 @@ mod tests {
       assert_eq!(perms.env.revoke(Some("HOME")), PermissionState::Prompt);
       assert_eq!(perms.env.revoke(Some("hostname")), PermissionState::Prompt);
       assert_eq!(perms.run.revoke(Some("deno")), PermissionState::Prompt);
+      assert_eq!(perms.import.revoke(Some(&("deno.land", None))), PermissionState::Prompt);
+      assert_eq!(perms.import.query(Some(&("deno.land", Some(443)))), PermissionState::Prompt);
       assert_eq!(perms.hrtime.revoke(), PermissionState::Denied);
     };
   }
@@ -2973,7 +3433,9 @@ mod tests {
       sys: Permissions::new_sys(&None, true).unwrap(),
       run: Permissions::new_run(&None, true).unwrap(),
       ffi: Permissions::new_ffi(&None, true).unwrap(),
+      import: Permissions::new_import(&None, true).unwrap(),
       hrtime: Permissions::new_hrtime(false),
+      module_scopes: Default::default(),
     };
 
     let prompt_value = PERMISSION_PROMPT_STUB_VALUE_SETTER.lock();
@@ -3037,7 +3499,9 @@ mod tests {
       sys: Permissions::new_sys(&None, true).unwrap(),
       run: Permissions::new_run(&None, true).unwrap(),
       ffi: Permissions::new_ffi(&None, true).unwrap(),
+      import: Permissions::new_import(&None, true).unwrap(),
       hrtime: Permissions::new_hrtime(false),
+      module_scopes: Default::default(),
     };
 
     let prompt_value = PERMISSION_PROMPT_STUB_VALUE_SETTER.lock();
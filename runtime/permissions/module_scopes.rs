@@ -0,0 +1,281 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::Permissions;
+use super::PermissionsOptions;
+use deno_core::error::custom_error;
+use deno_core::error::AnyError;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::v8;
+use deno_core::JsRuntime;
+
+/// The prefix of the scope that applies to every module not covered by a
+/// more specific scope.
+pub const ROOT_SCOPE: &str = "*";
+
+/// Number of frames of the call stack that are inspected to find the modules
+/// a permission check originates from.
+const MAX_STACK_FRAMES: usize = 64;
+
+/// The permissions of the modules whose specifier starts with `prefix`, or of
+/// all modules if the prefix is [`ROOT_SCOPE`]. These only restrict the
+/// permissions a worker has, they can never grant more.
+#[derive(Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct ModuleScopeOptions {
+  pub prefix: String,
+  pub permissions: PermissionsOptions,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct ModuleScope {
+  prefix: String,
+  permissions: Permissions,
+}
+
+/// Per-module restrictions of a worker's permissions. A permission check
+/// made while a module is on the call stack also has to pass the checks of
+/// the most specific scope that covers the module.
+///
+/// The modules on the call stack are recorded by the op layer before every op
+/// call, see `ops::permissions::init_module_scopes`. The call stack is only a
+/// heuristic for who is asking: code that hands a Deno API to another module
+/// as a callback, or that runs after the stack was unwound by the event loop,
+/// is attributed to the modules on the stack when the op was called. Checks
+/// that can't be attributed to any module, e.g. of code created with
+/// `new Function()` or passed to `setTimeout()` as a string, have to pass the
+/// root scope, or all scopes if there is none.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ModuleScopes {
+  scopes: Vec<ModuleScope>,
+  /// `None` outside of op calls, e.g. for the checks of the module loader,
+  /// which aren't made on behalf of JavaScript code.
+  calling_modules: Option<Vec<String>>,
+}
+
+impl ModuleScopes {
+  pub fn from_options(
+    options: &[ModuleScopeOptions],
+  ) -> Result<Self, AnyError> {
+    let mut scopes = options
+      .iter()
+      .map(|scope| {
+        Ok(ModuleScope {
+          prefix: scope.prefix.clone(),
+          permissions: Permissions::from_options(&PermissionsOptions {
+            // Imports are checked when modules are loaded, not by the modules
+            // on the stack.
+            allow_import: Some(vec![]),
+            prompt: false,
+            grants_file: None,
+            ..scope.permissions.clone()
+          })?,
+        })
+      })
+      .collect::<Result<Vec<_>, AnyError>>()?;
+    // Most specific scopes first.
+    scopes.sort_by_key(|scope| std::cmp::Reverse(scope_len(&scope.prefix)));
+    Ok(Self {
+      scopes,
+      calling_modules: None,
+    })
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.scopes.is_empty()
+  }
+
+  /// Sets the modules that subsequent checks are attributed to and returns
+  /// the previous ones.
+  pub fn set_calling_modules(
+    &mut self,
+    modules: Option<Vec<String>>,
+  ) -> Option<Vec<String>> {
+    std::mem::replace(&mut self.calling_modules, modules)
+  }
+
+  fn scope_for_mut(&mut self, specifier: &str) -> Option<&mut ModuleScope> {
+    self.scopes.iter_mut().find(|scope| {
+      scope.prefix == ROOT_SCOPE || specifier.starts_with(&scope.prefix)
+    })
+  }
+
+  /// Runs `check` against the scopes of all calling modules.
+  pub fn check(
+    &mut self,
+    check: impl Fn(&mut Permissions) -> Result<(), AnyError>,
+  ) -> Result<(), AnyError> {
+    let calling_modules = match &self.calling_modules {
+      Some(modules) if !self.is_empty() => modules.clone(),
+      _ => return Ok(()),
+    };
+    if calling_modules.is_empty() {
+      let has_root_scope =
+        self.scopes.iter().any(|scope| scope.prefix == ROOT_SCOPE);
+      for scope in &mut self.scopes {
+        if !has_root_scope || scope.prefix == ROOT_SCOPE {
+          check(&mut scope.permissions).map_err(|err| {
+            scope_error(err, scope, "code outside of a module")
+          })?;
+        }
+      }
+      return Ok(());
+    }
+    for module in calling_modules {
+      if let Some(scope) = self.scope_for_mut(&module) {
+        check(&mut scope.permissions)
+          .map_err(|err| scope_error(err, scope, &module))?;
+      }
+    }
+    Ok(())
+  }
+}
+
+fn scope_error(err: AnyError, scope: &ModuleScope, caller: &str) -> AnyError {
+  let message = err.to_string();
+  let message = message.split(", run again with").next().unwrap();
+  custom_error(
+    "PermissionDenied",
+    format!(
+      "{message}, which is not granted to modules in \"{}\" (called from {caller})",
+      scope.prefix
+    ),
+  )
+}
+
+fn scope_len(prefix: &str) -> usize {
+  if prefix == ROOT_SCOPE {
+    0
+  } else {
+    prefix.len()
+  }
+}
+
+/// The distinct modules on the current call stack, innermost first. Frames
+/// are attributed by the name of their script, never by its `sourceURL`, and
+/// only if the module loader registered a module of that name.
+pub(crate) fn calling_modules(scope: &mut v8::HandleScope) -> Vec<String> {
+  let stack = match v8::StackTrace::current_stack_trace(scope, MAX_STACK_FRAMES)
+  {
+    Some(stack) => stack,
+    None => return vec![],
+  };
+  let mut modules = Vec::new();
+  for i in 0..stack.get_frame_count() {
+    let name = stack
+      .get_frame(scope, i)
+      .and_then(|frame| frame.get_script_name(scope))
+      .map(|name| name.to_rust_string_lossy(scope));
+    if let Some(name) = name {
+      if !name.starts_with("internal:")
+        && !modules.contains(&name)
+        && JsRuntime::is_module_loaded(scope, &name)
+      {
+        modules.push(name);
+      }
+    }
+  }
+  modules
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_core::JsRuntime;
+  use deno_core::RuntimeOptions;
+  use std::path::PathBuf;
+
+  #[test]
+  fn scope_for_module() {
+    let mut scopes = ModuleScopes::from_options(&[
+      ModuleScopeOptions {
+        prefix: ROOT_SCOPE.to_string(),
+        permissions: Default::default(),
+      },
+      ModuleScopeOptions {
+        prefix: "file:///app/".to_string(),
+        permissions: PermissionsOptions {
+          allow_write: Some(vec![]),
+          ..Default::default()
+        },
+      },
+      ModuleScopeOptions {
+        prefix: "https://deno.land/x/somelib/".to_string(),
+        permissions: PermissionsOptions {
+          allow_net: Some(vec!["example.com".to_string()]),
+          ..Default::default()
+        },
+      },
+    ])
+    .unwrap();
+
+    let scope = scopes.scope_for_mut("file:///app/main.ts").unwrap();
+    assert_eq!(scope.prefix, "file:///app/");
+    assert!(scope.permissions.write.check_all(None).is_ok());
+
+    let scope = scopes
+      .scope_for_mut("https://deno.land/x/somelib/mod.ts")
+      .unwrap();
+    assert!(scope
+      .permissions
+      .net
+      .check(&("example.com", None), None)
+      .is_ok());
+    assert!(scope
+      .permissions
+      .net
+      .check(&("deno.land", None), None)
+      .is_err());
+    assert!(scope.permissions.write.check_all(None).is_err());
+
+    let scope = scopes.scope_for_mut("https://esm.sh/other.js").unwrap();
+    assert_eq!(scope.prefix, ROOT_SCOPE);
+  }
+
+  #[test]
+  fn check_without_calling_module() {
+    let mut scopes = ModuleScopes::from_options(&[
+      ModuleScopeOptions {
+        prefix: ROOT_SCOPE.to_string(),
+        permissions: Default::default(),
+      },
+      ModuleScopeOptions {
+        prefix: "file:///app/".to_string(),
+        permissions: PermissionsOptions {
+          allow_read: Some(vec![]),
+          ..Default::default()
+        },
+      },
+    ])
+    .unwrap();
+    let read = |perms: &mut Permissions| {
+      perms
+        .read
+        .check(&PathBuf::from("/a"), Some("Deno.readFile()"))
+    };
+
+    // Checks outside of op calls aren't made on behalf of any module.
+    assert!(scopes.check(read).is_ok());
+
+    // Op calls that can't be attributed to a module are checked against the
+    // root scope.
+    let mut runtime = JsRuntime::new(RuntimeOptions::default());
+    let modules = calling_modules(&mut runtime.handle_scope());
+    assert!(modules.is_empty());
+    scopes.set_calling_modules(Some(modules));
+    assert!(scopes.check(read).is_err());
+
+    scopes.set_calling_modules(Some(vec!["file:///app/main.ts".to_string()]));
+    assert!(scopes.check(read).is_ok());
+
+    // Without a root scope, all scopes have to pass.
+    let mut scopes = ModuleScopes::from_options(&[ModuleScopeOptions {
+      prefix: "file:///app/lib/".to_string(),
+      permissions: Default::default(),
+    }])
+    .unwrap();
+    scopes.set_calling_modules(Some(vec![]));
+    assert!(scopes.check(read).is_err());
+    scopes.set_calling_modules(Some(vec!["file:///app/main.ts".to_string()]));
+    assert!(scopes.check(read).is_ok());
+  }
+}
@@ -0,0 +1,394 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::fs_util::resolve_from_cwd;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use regex::Regex;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// An entry of a path based allowlist, like the ones passed to
+/// `--allow-read` and `--allow-write`.
+///
+/// An entry is either a plain path, which grants access to the path and
+/// everything below it, or a pattern prefixed with `glob:`, such as
+/// `glob:./src/**/*.json`. In patterns, environment variables (`$HOME` or
+/// `${HOME}`) are expanded before the entry is resolved against the current
+/// working directory. Plain paths are taken literally, even if they contain
+/// wildcards or `$`. Entries prefixed with `!` exclude the paths they match
+/// from the rest of the allowlist.
+///
+/// Entries are canonicalized when they're parsed and matched against
+/// canonicalized paths, so a symlink inside of a matching directory can't be
+/// used to reach a path outside of it.
+#[derive(Clone, Debug)]
+pub struct PathPattern {
+  /// For plain paths the resolved path, for globs the resolved literal prefix
+  /// preceding the first wildcard. Canonicalized, as far as it exists.
+  path: PathBuf,
+  glob: Option<Regex>,
+  excluded: bool,
+}
+
+impl PathPattern {
+  pub fn parse(raw: &Path) -> Result<Self, AnyError> {
+    let raw = raw.to_string_lossy();
+    let (excluded, raw) = match raw.strip_prefix('!') {
+      Some(raw) => (true, raw),
+      None => (false, raw.as_ref()),
+    };
+    if raw.is_empty() {
+      return Err(AnyError::msg("Empty path is not allowed"));
+    }
+    let raw = match raw.strip_prefix(GLOB_PREFIX) {
+      Some(raw) => raw,
+      None => {
+        return Ok(Self {
+          path: canonicalize_path(&resolve_from_cwd(Path::new(raw))?),
+          glob: None,
+          excluded,
+        })
+      }
+    };
+    let expanded = expand_env_vars(raw)?;
+    let expanded = Path::new(&expanded);
+
+    let mut prefix = PathBuf::new();
+    let mut glob_components = Vec::new();
+    for component in expanded.components() {
+      match component {
+        Component::Normal(c) if !glob_components.is_empty() => {
+          glob_components.push(c.to_string_lossy().to_string());
+        }
+        Component::Normal(c) if is_glob(&c.to_string_lossy()) => {
+          glob_components.push(c.to_string_lossy().to_string());
+        }
+        component if !glob_components.is_empty() => {
+          return Err(type_error(format!(
+            "Invalid path pattern \"{}\": \"{}\" is not allowed after a wildcard",
+            raw,
+            component.as_os_str().to_string_lossy()
+          )));
+        }
+        component => prefix.push(component),
+      }
+    }
+
+    let path = canonicalize_path(&resolve_from_cwd(&prefix)?);
+    if glob_components.is_empty() {
+      return Ok(Self {
+        path,
+        glob: None,
+        excluded,
+      });
+    }
+
+    let source = glob_to_regex(&glob_components.join("/"));
+    let glob = Regex::new(&source).map_err(|_| {
+      type_error(format!("Invalid glob pattern in path \"{raw}\""))
+    })?;
+    Ok(Self {
+      path,
+      glob: Some(glob),
+      excluded,
+    })
+  }
+
+  /// The resolved path of a plain entry, or the directory a glob pattern is
+  /// rooted in.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  pub fn is_glob(&self) -> bool {
+    self.glob.is_some()
+  }
+
+  pub fn is_excluded(&self) -> bool {
+    self.excluded
+  }
+
+  /// Whether `path`, which must already be canonicalized with
+  /// [`canonicalize_path`], is covered by this entry.
+  pub fn matches(&self, path: &Path) -> bool {
+    match &self.glob {
+      None => path.starts_with(&self.path),
+      Some(glob) => match path.strip_prefix(&self.path) {
+        Ok(rest) => {
+          let rest = rest
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+          glob.is_match(&rest)
+        }
+        Err(_) => false,
+      },
+    }
+  }
+
+  /// Whether the path of this entry is `path` or below it. `path` must
+  /// already be canonicalized with [`canonicalize_path`].
+  pub fn is_below(&self, path: &Path) -> bool {
+    self.path.starts_with(path)
+  }
+}
+
+impl From<PathBuf> for PathPattern {
+  fn from(path: PathBuf) -> Self {
+    Self {
+      path: canonicalize_path(&path),
+      glob: None,
+      excluded: false,
+    }
+  }
+}
+
+impl PartialEq for PathPattern {
+  fn eq(&self, other: &Self) -> bool {
+    self.path == other.path
+      && self.glob.as_ref().map(Regex::as_str)
+        == other.glob.as_ref().map(Regex::as_str)
+      && self.excluded == other.excluded
+  }
+}
+
+impl Eq for PathPattern {}
+
+impl Hash for PathPattern {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.path.hash(state);
+    self.glob.as_ref().map(Regex::as_str).hash(state);
+    self.excluded.hash(state);
+  }
+}
+
+/// The prefix of the entries that are patterns rather than literal paths.
+const GLOB_PREFIX: &str = "glob:";
+
+fn is_glob(component: &str) -> bool {
+  component.contains(['*', '?', '[', '{'])
+}
+
+/// Expands `$NAME` and `${NAME}` references. A `$` that isn't followed by a
+/// variable name is kept as is.
+fn expand_env_vars(raw: &str) -> Result<String, AnyError> {
+  let mut expanded = String::with_capacity(raw.len());
+  let mut chars = raw.chars().peekable();
+  while let Some(c) = chars.next() {
+    if c != '$' {
+      expanded.push(c);
+      continue;
+    }
+    let braced = chars.next_if_eq(&'{').is_some();
+    let mut name = String::new();
+    while let Some(c) =
+      chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_')
+    {
+      name.push(c);
+    }
+    if braced && chars.next_if_eq(&'}').is_none() {
+      return Err(type_error(format!(
+        "Invalid path \"{raw}\": unterminated \"${{\""
+      )));
+    }
+    if name.is_empty() {
+      expanded.push('$');
+      if braced {
+        expanded.push_str("{}");
+      }
+      continue;
+    }
+    match std::env::var(&name) {
+      Ok(value) => expanded.push_str(&value),
+      Err(_) => {
+        return Err(type_error(format!(
+          "Invalid path \"{raw}\": environment variable \"{name}\" is not set"
+        )))
+      }
+    }
+  }
+  Ok(expanded)
+}
+
+/// Translates a `/` separated glob into an anchored regular expression.
+/// A match also covers everything below the matched path, in the same way a
+/// plain path grants access to its descendants.
+fn glob_to_regex(glob: &str) -> String {
+  let mut regex = String::from("^");
+  let mut chars = glob.chars().peekable();
+  let mut in_braces = false;
+  while let Some(c) = chars.next() {
+    match c {
+      '*' if chars.next_if_eq(&'*').is_some() => {
+        if chars.next_if_eq(&'/').is_some() {
+          regex.push_str("(?:[^/]*/)*");
+        } else {
+          regex.push_str(".*");
+        }
+      }
+      '*' => regex.push_str("[^/]*"),
+      '?' => regex.push_str("[^/]"),
+      '[' => {
+        regex.push('[');
+        if chars.next_if(|c| *c == '!' || *c == '^').is_some() {
+          regex.push('^');
+        }
+        for c in chars.by_ref() {
+          if c == ']' {
+            break;
+          }
+          if c == '\\' || c == '[' {
+            regex.push('\\');
+          }
+          regex.push(c);
+        }
+        regex.push(']');
+      }
+      '{' if !in_braces => {
+        in_braces = true;
+        regex.push_str("(?:");
+      }
+      ',' if in_braces => regex.push('|'),
+      '}' if in_braces => {
+        in_braces = false;
+        regex.push(')');
+      }
+      c => regex.push_str(&regex::escape(&c.to_string())),
+    }
+  }
+  if in_braces {
+    regex.push(')');
+  }
+  regex.push_str("(?:/.*)?$");
+  regex
+}
+
+/// Canonicalizes the longest existing ancestor of `path` and appends the
+/// remaining components, so that paths which don't exist yet (for example
+/// the target of a write) can still be matched.
+pub fn canonicalize_path(path: &Path) -> PathBuf {
+  let mut rest = Vec::new();
+  let mut current = path;
+  loop {
+    if let Ok(mut canonical) = current.canonicalize() {
+      canonical.extend(rest.into_iter().rev());
+      return canonical;
+    }
+    match (current.parent(), current.file_name()) {
+      (Some(parent), Some(name)) => {
+        rest.push(name);
+        current = parent;
+      }
+      _ => return path.to_path_buf(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_glob_to_regex() {
+    let cases = [
+      ("*.json", "a.json", true),
+      ("*.json", "a/b.json", false),
+      ("**/*.json", "a.json", true),
+      ("**/*.json", "a/b/c.json", true),
+      ("**/*.json", "a/b/c.ts", false),
+      ("src/*", "src/a/b.ts", true),
+      ("file?.txt", "file1.txt", true),
+      ("file?.txt", "file10.txt", false),
+      ("[!a]*", "abc", false),
+      ("[!a]*", "bcd", true),
+      ("*.{js,ts}", "mod.ts", true),
+      ("*.{js,ts}", "mod.tsx", false),
+      ("a+b.txt", "a+b.txt", true),
+      ("a+b.txt", "aab.txt", false),
+    ];
+    for (glob, path, expected) in cases {
+      let regex = Regex::new(&glob_to_regex(glob)).unwrap();
+      assert_eq!(regex.is_match(path), expected, "{glob} {path}");
+    }
+  }
+
+  #[test]
+  fn test_expand_env_vars() {
+    std::env::set_var("DENO_PATH_PATTERN_TEST", "/home/deno");
+    assert_eq!(
+      expand_env_vars("$DENO_PATH_PATTERN_TEST/.cache").unwrap(),
+      "/home/deno/.cache"
+    );
+    assert_eq!(
+      expand_env_vars("${DENO_PATH_PATTERN_TEST}_x").unwrap(),
+      "/home/deno_x"
+    );
+    assert_eq!(expand_env_vars("/a/$/b").unwrap(), "/a/$/b");
+    assert!(expand_env_vars("${DENO_PATH_PATTERN_TEST").is_err());
+    assert!(expand_env_vars("$DENO_PATH_PATTERN_TEST_UNSET/a").is_err());
+  }
+
+  #[test]
+  fn test_path_pattern_matches() {
+    let pattern = PathPattern::parse(Path::new("/a/b")).unwrap();
+    assert!(!pattern.is_glob());
+    assert!(pattern.matches(Path::new("/a/b/c")));
+    assert!(!pattern.matches(Path::new("/a/c")));
+
+    let pattern =
+      PathPattern::parse(Path::new("!glob:/deno_path_pattern/**/*.json"))
+        .unwrap();
+    assert!(pattern.is_glob());
+    assert!(pattern.is_excluded());
+    assert_eq!(pattern.path(), Path::new("/deno_path_pattern"));
+    assert!(pattern.matches(Path::new("/deno_path_pattern/a/b.json")));
+    assert!(!pattern.matches(Path::new("/deno_path_pattern/a/b.ts")));
+    assert!(!pattern.matches(Path::new("/other/a.json")));
+
+    assert!(PathPattern::parse(Path::new("")).is_err());
+    assert!(PathPattern::parse(Path::new("!")).is_err());
+    assert!(PathPattern::parse(Path::new("glob:/a/*/../b")).is_err());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_path_pattern_symlink() {
+    let temp_dir = test_util::TempDir::new();
+    let root = temp_dir.path().canonicalize().unwrap();
+    std::fs::create_dir_all(root.join("allowed")).unwrap();
+    std::fs::create_dir_all(root.join("secret")).unwrap();
+    std::os::unix::fs::symlink(
+      root.join("secret"),
+      root.join("allowed").join("link"),
+    )
+    .unwrap();
+
+    let glob = root.join("allowed").join("**").join("*.txt");
+    let pattern =
+      PathPattern::parse(Path::new(&format!("glob:{}", glob.display())))
+        .unwrap();
+    assert!(pattern.matches(&canonicalize_path(&root.join("allowed/a.txt"))));
+    assert!(
+      !pattern.matches(&canonicalize_path(&root.join("allowed/link/a.txt")))
+    );
+
+    let pattern = PathPattern::parse(&root.join("allowed")).unwrap();
+    assert!(pattern.matches(&canonicalize_path(&root.join("allowed/a.txt"))));
+    assert!(
+      !pattern.matches(&canonicalize_path(&root.join("allowed/link/a.txt")))
+    );
+  }
+
+  #[test]
+  fn test_path_pattern_literal() {
+    let pattern = PathPattern::parse(Path::new("/a/[b]/$HOME/*")).unwrap();
+    assert!(!pattern.is_glob());
+    assert_eq!(pattern.path(), Path::new("/a/[b]/$HOME/*"));
+    assert!(pattern.matches(Path::new("/a/[b]/$HOME/*/c")));
+    assert!(!pattern.matches(Path::new("/a/b/$HOME/*")));
+  }
+}
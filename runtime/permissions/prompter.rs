@@ -10,9 +10,28 @@ pub const PERMISSION_EMOJI: &str = "⚠️";
 #[derive(Debug, Eq, PartialEq)]
 pub enum PromptResponse {
   Allow,
+  /// Allow the broader access offered by the prompt.
+  AllowBroader,
+  /// Allow the requested access and remember it for future runs of the
+  /// project.
+  AllowAlways,
+  /// Allow the broader access offered by the prompt and remember it for future
+  /// runs of the project.
+  AllowBroaderAlways,
   Deny,
 }
 
+/// The choices a prompt offers besides allowing or denying the requested
+/// access.
+#[derive(Debug, Default)]
+pub struct PromptChoices<'a> {
+  /// Broader access that can be allowed instead, for example
+  /// `all of "/home/user/project"`.
+  pub broader: Option<&'a str>,
+  /// Whether the answer can be remembered for future runs.
+  pub remember: bool,
+}
+
 static PERMISSION_PROMPTER: Lazy<Mutex<Box<dyn PermissionPrompter>>> =
   Lazy::new(|| Mutex::new(Box::new(TtyPrompter)));
 
@@ -26,11 +45,27 @@ pub fn permission_prompt(
   message: &str,
   flag: &str,
   api_name: Option<&str>,
+) -> PromptResponse {
+  permission_prompt_with_choices(
+    message,
+    flag,
+    api_name,
+    &PromptChoices::default(),
+  )
+}
+
+pub fn permission_prompt_with_choices(
+  message: &str,
+  flag: &str,
+  api_name: Option<&str>,
+  choices: &PromptChoices,
 ) -> PromptResponse {
   if let Some(before_callback) = MAYBE_BEFORE_PROMPT_CALLBACK.lock().as_mut() {
     before_callback();
   }
-  let r = PERMISSION_PROMPTER.lock().prompt(message, flag, api_name);
+  let r = PERMISSION_PROMPTER
+    .lock()
+    .prompt_with_choices(message, flag, api_name, choices);
   if let Some(after_callback) = MAYBE_AFTER_PROMPT_CALLBACK.lock().as_mut() {
     after_callback();
   }
@@ -54,6 +89,19 @@ pub trait PermissionPrompter: Send + Sync {
     name: &str,
     api_name: Option<&str>,
   ) -> PromptResponse;
+
+  /// Like [`PermissionPrompter::prompt`], but may also offer the given
+  /// `choices`. Prompters that don't support them only ask for the requested
+  /// access.
+  fn prompt_with_choices(
+    &mut self,
+    message: &str,
+    name: &str,
+    api_name: Option<&str>,
+    _choices: &PromptChoices,
+  ) -> PromptResponse {
+    self.prompt(message, name, api_name)
+  }
 }
 
 pub struct TtyPrompter;
@@ -64,6 +112,16 @@ impl PermissionPrompter for TtyPrompter {
     message: &str,
     name: &str,
     api_name: Option<&str>,
+  ) -> PromptResponse {
+    self.prompt_with_choices(message, name, api_name, &PromptChoices::default())
+  }
+
+  fn prompt_with_choices(
+    &mut self,
+    message: &str,
+    name: &str,
+    api_name: Option<&str>,
+    choices: &PromptChoices,
   ) -> PromptResponse {
     if !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stderr) {
       return PromptResponse::Deny;
@@ -198,7 +256,24 @@ impl PermissionPrompter for TtyPrompter {
     let _stderr_guard = std::io::stderr().lock();
 
     // print to stderr so that if stdout is piped this is still displayed.
-    const OPTS: &str = "[y/n] (y = yes, allow; n = no, deny)";
+    let mut keys = vec!["y", "n"];
+    let mut extra_choices = vec![];
+    if let Some(broader) = choices.broader {
+      keys.push("p");
+      extra_choices.push(format!("p = allow {broader}"));
+    }
+    if choices.remember {
+      keys.push("a");
+      extra_choices.push("a = always allow in this project".to_string());
+      if let Some(broader) = choices.broader {
+        keys.push("b");
+        extra_choices
+          .push(format!("b = always allow {broader} in this project"));
+      }
+    }
+    let opts = format!("[{}] (y = yes, allow; n = no, deny)", keys.join("/"));
+    let prompt_lines =
+      extra_choices.len() + if api_name.is_some() { 4 } else { 3 };
     eprint!("┌ {PERMISSION_EMOJI}  ");
     eprint!("{}", colors::bold("Deno requests "));
     eprint!("{}", colors::bold(message));
@@ -208,8 +283,11 @@ impl PermissionPrompter for TtyPrompter {
     }
     let msg = format!("Run again with --allow-{name} to bypass this prompt.");
     eprintln!("├ {}", colors::italic(&msg));
+    for choice in &extra_choices {
+      eprintln!("├ {choice}");
+    }
     eprint!("└ {}", colors::bold("Allow?"));
-    eprint!(" {OPTS} > ");
+    eprint!(" {opts} > ");
     let value = loop {
       let mut input = String::new();
       let stdin = std::io::stdin();
@@ -223,22 +301,45 @@ impl PermissionPrompter for TtyPrompter {
       };
       match ch.to_ascii_lowercase() {
         'y' => {
-          clear_n_lines(if api_name.is_some() { 4 } else { 3 });
+          clear_n_lines(prompt_lines);
           let msg = format!("Granted {message}.");
           eprintln!("✅ {}", colors::bold(&msg));
           break PromptResponse::Allow;
         }
         'n' => {
-          clear_n_lines(if api_name.is_some() { 4 } else { 3 });
+          clear_n_lines(prompt_lines);
           let msg = format!("Denied {message}.");
           eprintln!("❌ {}", colors::bold(&msg));
           break PromptResponse::Deny;
         }
+        'p' if choices.broader.is_some() => {
+          clear_n_lines(prompt_lines);
+          let broader = choices.broader.unwrap();
+          let msg = format!("Granted {name} access to {broader}.");
+          eprintln!("✅ {}", colors::bold(&msg));
+          break PromptResponse::AllowBroader;
+        }
+        'a' if choices.remember => {
+          clear_n_lines(prompt_lines);
+          let msg =
+            format!("Granted {message}, also for future runs of this project.");
+          eprintln!("✅ {}", colors::bold(&msg));
+          break PromptResponse::AllowAlways;
+        }
+        'b' if choices.remember && choices.broader.is_some() => {
+          clear_n_lines(prompt_lines);
+          let broader = choices.broader.unwrap();
+          let msg = format!(
+            "Granted {name} access to {broader}, also for future runs of this project."
+          );
+          eprintln!("✅ {}", colors::bold(&msg));
+          break PromptResponse::AllowBroaderAlways;
+        }
         _ => {
           // If we don't get a recognized option try again.
           clear_n_lines(1);
           eprint!("└ {}", colors::bold("Unrecognized option. Allow?"));
-          eprint!(" {OPTS} > ");
+          eprint!(" {opts} > ");
         }
       };
     };
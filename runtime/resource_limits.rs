@@ -0,0 +1,458 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::v8;
+use deno_core::JsRuntime;
+use log::debug;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Weak;
+use std::time::Duration;
+
+const MB: usize = 1024 * 1024;
+
+/// How often the CPU time of a worker with a `max_cpu_time` limit is sampled.
+const CPU_TIME_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+const NOT_EXCEEDED: u8 = 0;
+const HEAP_EXCEEDED: u8 = 1;
+const CPU_TIME_EXCEEDED: u8 = 2;
+
+/// Limits on the resources a worker may consume. A worker that exceeds one
+/// of them is terminated.
+#[derive(
+  Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceLimits {
+  /// Maximum size of the V8 heap, in megabytes.
+  pub max_heap_size: Option<usize>,
+  /// Maximum CPU time the thread running the worker may use, in
+  /// milliseconds.
+  pub max_cpu_time: Option<u64>,
+}
+
+impl ResourceLimits {
+  /// The limits of a worker created by a worker with these limits. Limits
+  /// that were not requested are inherited, and a child can never be given
+  /// more than its parent has.
+  pub fn for_child(&self, requested: Option<ResourceLimits>) -> Self {
+    fn min<T: Ord>(parent: Option<T>, child: Option<T>) -> Option<T> {
+      match (parent, child) {
+        (Some(parent), Some(child)) => Some(parent.min(child)),
+        (parent, child) => parent.or(child),
+      }
+    }
+
+    let requested = requested.unwrap_or_default();
+    Self {
+      max_heap_size: min(self.max_heap_size, requested.max_heap_size),
+      max_cpu_time: min(self.max_cpu_time, requested.max_cpu_time),
+    }
+  }
+
+  /// Isolate parameters enforcing `max_heap_size`, to be passed as
+  /// [`deno_core::RuntimeOptions::create_params`].
+  pub fn create_params(&self) -> Option<v8::CreateParams> {
+    self
+      .max_heap_size
+      .map(|size| v8::Isolate::create_params().heap_limits(0, size * MB))
+  }
+
+  /// Starts enforcing the limits on `js_runtime`, which has to be created
+  /// with [`Self::create_params`]. Must be called on the thread that runs
+  /// `js_runtime`, as that's the thread whose CPU time is measured.
+  ///
+  /// If `heap_snapshot_near_limit` is set, a heap snapshot is written to it
+  /// the first time the heap gets close to its limit.
+  ///
+  /// Enforcement stops once the returned value and all of its clones are
+  /// dropped.
+  pub fn enforce(
+    &self,
+    js_runtime: &mut JsRuntime,
+    heap_snapshot_near_limit: Option<PathBuf>,
+  ) -> ResourceLimitsEnforcer {
+    let enforcer = ResourceLimitsEnforcer(Arc::new(EnforcerState {
+      limits: *self,
+      exceeded: AtomicU8::new(NOT_EXCEEDED),
+    }));
+    let isolate_handle = js_runtime.v8_isolate().thread_safe_handle();
+
+    // V8 only keeps a single near heap limit callback, so it handles both
+    // the snapshot and the heap limit.
+    if self.max_heap_size.is_some() || heap_snapshot_near_limit.is_some() {
+      let state = Arc::downgrade(&enforcer.0);
+      let isolate_handle = isolate_handle.clone();
+      let isolate: &mut v8::Isolate = js_runtime.v8_isolate();
+      let isolate = isolate as *mut v8::Isolate;
+      let heap_snapshot_path = RefCell::new(heap_snapshot_near_limit);
+      let enforce_heap_size = self.max_heap_size.is_some();
+      js_runtime.add_near_heap_limit_callback(move |current, _initial| {
+        if let Some(path) = heap_snapshot_path.borrow_mut().take() {
+          // SAFETY: V8 invokes the callback on the isolate's thread while
+          // the isolate is alive.
+          let isolate = unsafe { &mut *isolate };
+          if let Err(err) = write_heap_snapshot(isolate, &path) {
+            eprintln!(
+              "Failed to write the heap snapshot \"{}\": {err}",
+              path.display()
+            );
+          }
+        }
+        if !enforce_heap_size {
+          return current;
+        }
+        if let Some(state) = state.upgrade() {
+          state.set_exceeded(HEAP_EXCEEDED);
+        }
+        isolate_handle.terminate_execution();
+        // Give V8 enough room to unwind the stack instead of aborting the
+        // process with an out of memory error.
+        current * 2
+      });
+    }
+
+    if let Some(max_cpu_time) = self.max_cpu_time {
+      match ThreadCpuClock::current() {
+        Some(clock) => {
+          let max_cpu_time = Duration::from_millis(max_cpu_time);
+          let state = Arc::downgrade(&enforcer.0);
+          std::thread::spawn(move || {
+            watch_cpu_time(clock, max_cpu_time, state, isolate_handle)
+          });
+        }
+        None => {
+          debug!("CPU time limits are not supported on this platform");
+        }
+      }
+    }
+
+    enforcer
+  }
+}
+
+fn write_heap_snapshot(
+  isolate: &mut v8::Isolate,
+  path: &Path,
+) -> Result<(), AnyError> {
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  let mut out = BufWriter::new(File::create(path)?);
+  let mut result = Ok(());
+  isolate.take_heap_snapshot(|chunk| {
+    result = out.write_all(chunk);
+    result.is_ok()
+  });
+  result?;
+  out.flush()?;
+  Ok(())
+}
+
+struct EnforcerState {
+  limits: ResourceLimits,
+  exceeded: AtomicU8,
+}
+
+impl EnforcerState {
+  fn set_exceeded(&self, limit: u8) {
+    // Only the first limit that was hit is reported.
+    let _ = self.exceeded.compare_exchange(
+      NOT_EXCEEDED,
+      limit,
+      Ordering::SeqCst,
+      Ordering::SeqCst,
+    );
+  }
+}
+
+/// Keeps track of whether a worker was terminated because it exceeded one of
+/// its [`ResourceLimits`].
+#[derive(Clone)]
+pub struct ResourceLimitsEnforcer(Arc<EnforcerState>);
+
+impl ResourceLimitsEnforcer {
+  /// The error to report if a limit was exceeded.
+  pub fn exceeded_error(&self) -> Option<AnyError> {
+    let limits = &self.0.limits;
+    match self.0.exceeded.load(Ordering::SeqCst) {
+      HEAP_EXCEEDED => Some(generic_error(format!(
+        "Exceeded the heap limit of {} MB",
+        limits.max_heap_size.unwrap_or_default()
+      ))),
+      CPU_TIME_EXCEEDED => Some(generic_error(format!(
+        "Exceeded the CPU time limit of {} ms",
+        limits.max_cpu_time.unwrap_or_default()
+      ))),
+      _ => None,
+    }
+  }
+
+  /// Replaces the "execution terminated" error of a worker that was stopped
+  /// for exceeding a limit with an error naming that limit.
+  pub fn check<T>(&self, result: Result<T, AnyError>) -> Result<T, AnyError> {
+    match (result, self.exceeded_error()) {
+      (Err(_), Some(err)) => Err(err),
+      (result, _) => result,
+    }
+  }
+}
+
+fn watch_cpu_time(
+  clock: ThreadCpuClock,
+  max_cpu_time: Duration,
+  state: Weak<EnforcerState>,
+  isolate_handle: v8::IsolateHandle,
+) {
+  // Time spent on the thread before the runtime was created doesn't count.
+  let start = clock.elapsed().unwrap_or_default();
+  loop {
+    std::thread::sleep(CPU_TIME_POLL_INTERVAL);
+    let state = match state.upgrade() {
+      Some(state) => state,
+      None => return,
+    };
+    match clock.elapsed() {
+      Some(elapsed) if elapsed.saturating_sub(start) >= max_cpu_time => {
+        state.set_exceeded(CPU_TIME_EXCEEDED);
+        isolate_handle.terminate_execution();
+        return;
+      }
+      Some(_) => {}
+      // The thread is gone.
+      None => return,
+    }
+  }
+}
+
+#[cfg(target_os = "linux")]
+struct ThreadCpuClock(libc::clockid_t);
+
+#[cfg(target_os = "linux")]
+impl ThreadCpuClock {
+  fn current() -> Option<Self> {
+    let mut clock_id: libc::clockid_t = 0;
+    // SAFETY: `pthread_self` always returns a valid handle for the calling
+    // thread and `clock_id` is a valid pointer.
+    let result = unsafe {
+      libc::pthread_getcpuclockid(libc::pthread_self(), &mut clock_id)
+    };
+    (result == 0).then_some(Self(clock_id))
+  }
+
+  fn elapsed(&self) -> Option<Duration> {
+    let mut time = libc::timespec {
+      tv_sec: 0,
+      tv_nsec: 0,
+    };
+    // SAFETY: `time` is a valid pointer. The call fails once the thread the
+    // clock belongs to has exited.
+    let result = unsafe { libc::clock_gettime(self.0, &mut time) };
+    (result == 0)
+      .then(|| Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+  }
+}
+
+#[cfg(target_os = "macos")]
+struct ThreadCpuClock(libc::mach_port_t);
+
+#[cfg(target_os = "macos")]
+impl ThreadCpuClock {
+  fn current() -> Option<Self> {
+    // SAFETY: Returns a send right to the calling thread's port.
+    Some(Self(unsafe { libc::mach_thread_self() }))
+  }
+
+  fn elapsed(&self) -> Option<Duration> {
+    let mut info = std::mem::MaybeUninit::<libc::thread_basic_info>::zeroed();
+    let mut count = libc::THREAD_BASIC_INFO_COUNT;
+    // SAFETY: `info` is large enough for `count` words of thread info.
+    let result = unsafe {
+      libc::thread_info(
+        self.0,
+        libc::THREAD_BASIC_INFO as libc::thread_flavor_t,
+        info.as_mut_ptr() as libc::thread_info_t,
+        &mut count,
+      )
+    };
+    if result != libc::KERN_SUCCESS {
+      return None;
+    }
+    // SAFETY: Initialized by `thread_info`.
+    let info = unsafe { info.assume_init() };
+    let time = |t: libc::time_value_t| {
+      Duration::new(t.seconds as u64, t.microseconds as u32 * 1000)
+    };
+    Some(time(info.user_time) + time(info.system_time))
+  }
+}
+
+#[cfg(target_os = "macos")]
+impl Drop for ThreadCpuClock {
+  fn drop(&mut self) {
+    // SAFETY: The port was obtained with `mach_thread_self`.
+    unsafe {
+      libc::mach_port_deallocate(libc::mach_task_self(), self.0);
+    }
+  }
+}
+
+#[cfg(windows)]
+struct ThreadCpuClock(winapi::um::winnt::HANDLE);
+
+// SAFETY: Thread handles can be used from any thread.
+#[cfg(windows)]
+unsafe impl Send for ThreadCpuClock {}
+
+#[cfg(windows)]
+impl ThreadCpuClock {
+  fn current() -> Option<Self> {
+    use winapi::um::processthreadsapi::GetCurrentThreadId;
+    use winapi::um::processthreadsapi::OpenThread;
+    use winapi::um::winnt::THREAD_QUERY_LIMITED_INFORMATION;
+
+    // SAFETY: Opens a new handle to the calling thread, which is closed
+    // when the clock is dropped.
+    let handle = unsafe {
+      OpenThread(THREAD_QUERY_LIMITED_INFORMATION, 0, GetCurrentThreadId())
+    };
+    (!handle.is_null()).then_some(Self(handle))
+  }
+
+  fn elapsed(&self) -> Option<Duration> {
+    use winapi::shared::minwindef::FILETIME;
+    use winapi::um::minwinbase::STILL_ACTIVE;
+    use winapi::um::processthreadsapi::GetExitCodeThread;
+    use winapi::um::processthreadsapi::GetThreadTimes;
+
+    let mut exit_code = 0;
+    // SAFETY: `self.0` is a valid thread handle.
+    let result = unsafe { GetExitCodeThread(self.0, &mut exit_code) };
+    if result == 0 || exit_code != STILL_ACTIVE {
+      return None;
+    }
+
+    let empty = || FILETIME {
+      dwLowDateTime: 0,
+      dwHighDateTime: 0,
+    };
+    let (mut creation, mut exit, mut kernel, mut user) =
+      (empty(), empty(), empty(), empty());
+    // SAFETY: `self.0` is a valid thread handle and all out pointers are
+    // valid.
+    let result = unsafe {
+      GetThreadTimes(self.0, &mut creation, &mut exit, &mut kernel, &mut user)
+    };
+    if result == 0 {
+      return None;
+    }
+    // FILETIME values count 100 nanosecond intervals.
+    let time = |t: FILETIME| {
+      let ticks = ((t.dwHighDateTime as u64) << 32) | t.dwLowDateTime as u64;
+      Duration::from_nanos(ticks * 100)
+    };
+    Some(time(kernel) + time(user))
+  }
+}
+
+#[cfg(windows)]
+impl Drop for ThreadCpuClock {
+  fn drop(&mut self) {
+    // SAFETY: The handle was opened with `OpenThread`.
+    unsafe {
+      winapi::um::handleapi::CloseHandle(self.0);
+    }
+  }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+struct ThreadCpuClock;
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+impl ThreadCpuClock {
+  fn current() -> Option<Self> {
+    None
+  }
+
+  fn elapsed(&self) -> Option<Duration> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_core::RuntimeOptions;
+
+  #[test]
+  fn test_for_child() {
+    let parent = ResourceLimits {
+      max_heap_size: Some(64),
+      max_cpu_time: None,
+    };
+    assert_eq!(parent.for_child(None), parent);
+    assert_eq!(
+      parent.for_child(Some(ResourceLimits {
+        max_heap_size: Some(128),
+        max_cpu_time: Some(1000),
+      })),
+      ResourceLimits {
+        max_heap_size: Some(64),
+        max_cpu_time: Some(1000),
+      }
+    );
+    assert_eq!(
+      parent
+        .for_child(Some(ResourceLimits {
+          max_heap_size: Some(16),
+          max_cpu_time: None,
+        }))
+        .max_heap_size,
+      Some(16)
+    );
+  }
+
+  #[test]
+  fn test_max_heap_size() {
+    let limits = ResourceLimits {
+      max_heap_size: Some(16),
+      max_cpu_time: None,
+    };
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      create_params: limits.create_params(),
+      ..Default::default()
+    });
+    let enforcer = limits.enforce(&mut runtime, None);
+    let result = runtime.execute_script(
+      "script name",
+      r#"let s = ""; while(true) { s += "Hello"; }"#,
+    );
+    let err = enforcer.check(result).unwrap_err();
+    assert_eq!(err.to_string(), "Exceeded the heap limit of 16 MB");
+  }
+
+  #[cfg(any(target_os = "linux", target_os = "macos", windows))]
+  #[test]
+  fn test_max_cpu_time() {
+    let limits = ResourceLimits {
+      max_heap_size: None,
+      max_cpu_time: Some(100),
+    };
+    let mut runtime = JsRuntime::new(RuntimeOptions::default());
+    let enforcer = limits.enforce(&mut runtime, None);
+    let result = runtime.execute_script("script name", "while(true) {}");
+    let err = enforcer.check(result).unwrap_err();
+    assert_eq!(err.to_string(), "Exceeded the CPU time limit of 100 ms");
+  }
+}
@@ -4,6 +4,8 @@ use crate::inspector_server::InspectorServer;
 use crate::ops;
 use crate::ops::io::Stdio;
 use crate::permissions::PermissionsContainer;
+use crate::resource_limits::ResourceLimits;
+use crate::resource_limits::ResourceLimitsEnforcer;
 use crate::tokio_util::run_local;
 use crate::worker::FormatJsErrorFn;
 use crate::BootstrapOptions;
@@ -318,6 +320,9 @@ pub struct WebWorker {
   pub worker_type: WebWorkerType,
   pub main_module: ModuleSpecifier,
   poll_for_messages_fn: Option<v8::Global<v8::Value>>,
+  should_break_on_first_statement: bool,
+  should_wait_for_inspector_session: bool,
+  resource_limits: ResourceLimitsEnforcer,
 }
 
 pub struct WebWorkerOptions {
@@ -336,6 +341,12 @@ pub struct WebWorkerOptions {
   pub source_map_getter: Option<Box<dyn SourceMapGetter>>,
   pub worker_type: WebWorkerType,
   pub maybe_inspector_server: Option<Arc<InspectorServer>>,
+  // If true, the worker will wait for a debugger and break on the first
+  // statement of its code. Takes higher precedence than
+  // `should_wait_for_inspector_session`.
+  pub should_break_on_first_statement: bool,
+  // If true, the worker will wait for a debugger before executing its code.
+  pub should_wait_for_inspector_session: bool,
   pub get_error_class_fn: Option<GetErrorClassFn>,
   pub blob_store: BlobStore,
   pub broadcast_channel: InMemoryBroadcastChannel,
@@ -343,6 +354,7 @@ pub struct WebWorkerOptions {
   pub compiled_wasm_module_store: Option<CompiledWasmModuleStore>,
   pub cache_storage_dir: Option<std::path::PathBuf>,
   pub stdio: Stdio,
+  pub resource_limits: ResourceLimits,
 }
 
 impl WebWorker {
@@ -370,11 +382,14 @@ impl WebWorker {
     // Permissions: many ops depend on this
     let unstable = options.bootstrap.unstable;
     let enable_testing_features = options.bootstrap.enable_testing_features;
+    let resource_limits = options.resource_limits;
+    let has_module_scopes = !permissions.0.lock().module_scopes.is_empty();
     let perm_ext = Extension::builder("deno_permissions_web_worker")
       .state(move |state| {
         state.put::<PermissionsContainer>(permissions.clone());
         state.put(ops::UnstableChecker { unstable });
         state.put(ops::TestingFeaturesEnabled(enable_testing_features));
+        state.put(resource_limits);
         Ok(())
       })
       .build();
@@ -440,6 +455,7 @@ impl WebWorker {
       ops::permissions::init(),
       ops::process::init(),
       ops::spawn::init(),
+      ops::inspector::init(false, options.bootstrap.user_agent.clone()),
       ops::signal::init(),
       ops::tty::init(),
       deno_http::init(),
@@ -448,6 +464,9 @@ impl WebWorker {
       // Permissions ext (worker specific state)
       perm_ext,
     ];
+    if has_module_scopes {
+      extensions.push(ops::permissions::init_module_scopes());
+    }
 
     // Append exts
     extensions.extend(std::mem::take(&mut options.extensions));
@@ -468,22 +487,25 @@ impl WebWorker {
       shared_array_buffer_store: options.shared_array_buffer_store.clone(),
       compiled_wasm_module_store: options.compiled_wasm_module_store.clone(),
       extensions,
+      // Otherwise the inspector is created on demand, e.g. by `node:inspector`.
       inspector: options.maybe_inspector_server.is_some(),
+      create_params: resource_limits.create_params(),
       ..Default::default()
     });
+    let resource_limits = resource_limits.enforce(&mut js_runtime, None);
 
     if let Some(server) = options.maybe_inspector_server.clone() {
-      server.register_inspector(
+      let url = server.register_inspector_handle(
         main_module.to_string(),
-        &mut js_runtime,
+        &js_runtime.inspector(),
+        options.should_break_on_first_statement
+          || options.should_wait_for_inspector_session,
         false,
       );
-
-      // Put inspector handle into the op state so we can put a breakpoint when
-      // executing a CJS entrypoint.
-      let op_state = js_runtime.op_state();
-      let inspector = js_runtime.inspector();
-      op_state.borrow_mut().put(inspector);
+      js_runtime
+        .op_state()
+        .borrow_mut()
+        .put(ops::inspector::ActiveInspectorServer { server, url });
     }
 
     let (internal_handle, external_handle) = {
@@ -505,11 +527,37 @@ impl WebWorker {
         worker_type: options.worker_type,
         main_module,
         poll_for_messages_fn: None,
+        should_break_on_first_statement: options
+          .should_break_on_first_statement,
+        should_wait_for_inspector_session: options
+          .should_wait_for_inspector_session,
+        resource_limits,
       },
       external_handle,
     )
   }
 
+  /// Unlike the main worker, which starts running as soon as a debugger
+  /// connects, a worker waits until the debugger asks it to run. Clients that
+  /// auto-attach to workers through the `Target` domain rely on that to set
+  /// up breakpoints in time.
+  fn wait_for_inspector_session(&mut self) {
+    if !self.should_break_on_first_statement
+      && !self.should_wait_for_inspector_session
+    {
+      return;
+    }
+    let inspector = self.js_runtime.inspector();
+    let mut inspector = inspector.borrow_mut();
+    inspector.wait_for_debugger();
+    if self.should_break_on_first_statement {
+      inspector.wait_for_session_and_break_on_next_statement();
+    }
+    // Only the first code the worker executes waits.
+    self.should_break_on_first_statement = false;
+    self.should_wait_for_inspector_session = false;
+  }
+
   pub fn bootstrap(&mut self, options: &BootstrapOptions) {
     // Instead of using name for log we use `worker-${id}` because
     // WebWorkers can have empty string as name.
@@ -601,6 +649,8 @@ impl WebWorker {
     &mut self,
     id: ModuleId,
   ) -> Result<(), AnyError> {
+    self.wait_for_inspector_session();
+    let resource_limits = self.resource_limits.clone();
     let mut receiver = self.js_runtime.mod_evaluate(id);
     tokio::select! {
       biased;
@@ -610,7 +660,7 @@ impl WebWorker {
         // If `None` is returned it means that runtime was destroyed before
         // evaluation was complete. This can happen in Web Worker when `self.close()`
         // is called at top level.
-        maybe_result.unwrap_or(Ok(()))
+        resource_limits.check(maybe_result.unwrap_or(Ok(())))
       }
 
       event_loop_result = self.run_event_loop(false) => {
@@ -619,7 +669,7 @@ impl WebWorker {
         }
         event_loop_result?;
         let maybe_result = receiver.await;
-        maybe_result.unwrap_or(Ok(()))
+        resource_limits.check(maybe_result.unwrap_or(Ok(())))
       }
     }
   }
@@ -644,12 +694,14 @@ impl WebWorker {
         }
 
         if let Err(e) = r {
-          return Poll::Ready(Err(e));
+          return Poll::Ready(self.resource_limits.check(Err(e)));
         }
 
-        panic!(
-          "coding error: either js is polling or the worker is terminated"
-        );
+        // Waiting for messages keeps a worker alive, unless JS opted out of
+        // it like `node:worker_threads` does. Then the worker closes once
+        // there is nothing left to do, like with `self.close()`.
+        self.internal_handle.terminate();
+        Poll::Ready(Ok(()))
       }
       Poll::Pending => Poll::Pending,
     }
@@ -729,9 +781,10 @@ pub fn run_web_worker(
 
     // Execute provided source code immediately
     let result = if let Some(source_code) = maybe_source_code {
+      worker.wait_for_inspector_session();
       let r = worker.execute_script(&located_script_name!(), &source_code);
       worker.start_polling_for_messages();
-      r
+      worker.resource_limits.check(r)
     } else {
       // TODO(bartlomieju): add "type": "classic", ie. ability to load
       // script instead of module
@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::sync::atomic::AtomicI32;
@@ -38,6 +39,8 @@ use crate::inspector_server::InspectorServer;
 use crate::ops;
 use crate::ops::io::Stdio;
 use crate::permissions::PermissionsContainer;
+use crate::resource_limits::ResourceLimits;
+use crate::resource_limits::ResourceLimitsEnforcer;
 use crate::BootstrapOptions;
 
 pub type FormatJsErrorFn = dyn Fn(&JsError) -> String + Sync + Send;
@@ -66,6 +69,7 @@ pub struct MainWorker {
   should_break_on_first_statement: bool,
   should_wait_for_inspector_session: bool,
   exit_code: ExitCode,
+  resource_limits: ResourceLimitsEnforcer,
 }
 
 pub struct WorkerOptions {
@@ -141,6 +145,17 @@ pub struct WorkerOptions {
   /// `WebAssembly.Module` objects cannot be serialized.
   pub compiled_wasm_module_store: Option<CompiledWasmModuleStore>,
   pub stdio: Stdio,
+
+  /// Limits on the heap size and CPU time of the worker. Web workers created
+  /// by this worker can't exceed them.
+  pub resource_limits: ResourceLimits,
+
+  /// If set, a heap snapshot is written to this path the first time the heap
+  /// gets close to its limit.
+  pub heap_snapshot_near_limit: Option<PathBuf>,
+
+  /// Record every op call in a timeline, see `OpsTracker::write_timeline()`.
+  pub ops_timeline: bool,
 }
 
 impl Default for WorkerOptions {
@@ -177,6 +192,9 @@ impl Default for WorkerOptions {
       startup_snapshot: Default::default(),
       bootstrap: Default::default(),
       stdio: Default::default(),
+      resource_limits: Default::default(),
+      heap_snapshot_near_limit: Default::default(),
+      ops_timeline: Default::default(),
     }
   }
 }
@@ -201,11 +219,14 @@ impl MainWorker {
     // Permissions: many ops depend on this
     let unstable = options.bootstrap.unstable;
     let enable_testing_features = options.bootstrap.enable_testing_features;
+    let resource_limits = options.resource_limits;
+    let has_module_scopes = !permissions.0.lock().module_scopes.is_empty();
     let perm_ext = Extension::builder("deno_permissions_worker")
       .state(move |state| {
         state.put::<PermissionsContainer>(permissions.clone());
         state.put(ops::UnstableChecker { unstable });
         state.put(ops::TestingFeaturesEnabled(enable_testing_features));
+        state.put(resource_limits);
         Ok(())
       })
       .build();
@@ -255,6 +276,7 @@ impl MainWorker {
         options.format_js_error_fn.clone(),
       ),
       ops::spawn::init(),
+      ops::inspector::init(true, options.bootstrap.user_agent.clone()),
       ops::fs_events::init(),
       ops::fs::init(),
       ops::io::init(),
@@ -279,6 +301,9 @@ impl MainWorker {
       // Permissions ext (worker specific state)
       perm_ext,
     ];
+    if has_module_scopes {
+      extensions.push(ops::permissions::init_module_scopes());
+    }
     extensions.extend(std::mem::take(&mut options.extensions));
 
     #[cfg(not(feature = "dont_create_runtime_snapshot"))]
@@ -298,24 +323,28 @@ impl MainWorker {
       compiled_wasm_module_store: options.compiled_wasm_module_store.clone(),
       extensions,
       extensions_with_js: options.extensions_with_js,
+      // Otherwise the inspector is created on demand, e.g. by `node:inspector`.
       inspector: options.maybe_inspector_server.is_some(),
       is_main: true,
+      create_params: resource_limits.create_params(),
+      ops_timeline: options.ops_timeline,
       ..Default::default()
     });
+    let resource_limits = resource_limits
+      .enforce(&mut js_runtime, options.heap_snapshot_near_limit.clone());
 
     if let Some(server) = options.maybe_inspector_server.clone() {
-      server.register_inspector(
+      let url = server.register_inspector_handle(
         main_module.to_string(),
-        &mut js_runtime,
+        &js_runtime.inspector(),
         options.should_break_on_first_statement
           || options.should_wait_for_inspector_session,
+        true,
       );
-
-      // Put inspector handle into the op state so we can put a breakpoint when
-      // executing a CJS entrypoint.
-      let op_state = js_runtime.op_state();
-      let inspector = js_runtime.inspector();
-      op_state.borrow_mut().put(inspector);
+      js_runtime
+        .op_state()
+        .borrow_mut()
+        .put(ops::inspector::ActiveInspectorServer { server, url });
     }
 
     Self {
@@ -324,6 +353,7 @@ impl MainWorker {
       should_wait_for_inspector_session: options
         .should_wait_for_inspector_session,
       exit_code,
+      resource_limits,
     }
   }
 
@@ -371,6 +401,7 @@ impl MainWorker {
     id: ModuleId,
   ) -> Result<(), AnyError> {
     self.wait_for_inspector_session();
+    let resource_limits = self.resource_limits.clone();
     let mut receiver = self.js_runtime.mod_evaluate(id);
     tokio::select! {
       // Not using biased mode leads to non-determinism for relatively simple
@@ -379,13 +410,17 @@ impl MainWorker {
 
       maybe_result = &mut receiver => {
         debug!("received module evaluate {:#?}", maybe_result);
-        maybe_result.expect("Module evaluation result not provided.")
+        resource_limits.check(
+          maybe_result.expect("Module evaluation result not provided."),
+        )
       }
 
       event_loop_result = self.run_event_loop(false) => {
         event_loop_result?;
         let maybe_result = receiver.await;
-        maybe_result.expect("Module evaluation result not provided.")
+        resource_limits.check(
+          maybe_result.expect("Module evaluation result not provided."),
+        )
       }
     }
   }
@@ -434,14 +469,18 @@ impl MainWorker {
     cx: &mut Context,
     wait_for_inspector: bool,
   ) -> Poll<Result<(), AnyError>> {
-    self.js_runtime.poll_event_loop(cx, wait_for_inspector)
+    self
+      .js_runtime
+      .poll_event_loop(cx, wait_for_inspector)
+      .map(|result| self.resource_limits.check(result))
   }
 
   pub async fn run_event_loop(
     &mut self,
     wait_for_inspector: bool,
   ) -> Result<(), AnyError> {
-    self.js_runtime.run_event_loop(wait_for_inspector).await
+    let result = self.js_runtime.run_event_loop(wait_for_inspector).await;
+    self.resource_limits.check(result)
   }
 
   /// A utility function that runs provided future concurrently with the event loop.